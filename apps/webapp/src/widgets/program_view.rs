@@ -39,7 +39,12 @@ pub fn ProgramView() -> impl IntoView {
 
                 {move || {
                     if core_exists() {
-                        view! { <StepButton instruction_map=instruction_map/> }
+                        view! {
+                            <div class="flex gap-2">
+                                <StepBackButton instruction_map=instruction_map/>
+                                <StepButton instruction_map=instruction_map/>
+                            </div>
+                        }
                     } else {
                         view! {
                             <StartButton
@@ -86,24 +91,40 @@ fn ResetButton() -> impl IntoView {
 #[component]
 fn StartButton(
     code: ReadSignal<String>,
-    set_instruction_map: WriteSignal<Vec<usize>>,
+    set_instruction_map: WriteSignal<Vec<(u64, usize)>>,
 ) -> impl IntoView {
     let core = expect_context::<RwSignal<global_state::Machine>>();
     let vlen = expect_context::<RwSignal<Vlen>>();
     let highlighted_line = expect_context::<RwSignal<global_state::Highlight>>();
     let errors = expect_context::<RwSignal<global_state::Errors>>();
 
-    let build_machine = create_write_slice(core, move |machine, (instructions, memory)| {
-        let vu = VectorEngineBuilder::default().vlen(vlen()).build();
+    let build_machine = create_write_slice(
+        core,
+        move |machine, (instructions, memory, text_base): (Vec<Instruction>, Memory, u64)| {
+            // V needs VLEN >= 128, smaller units run the Zve64d subset
+            let extension = match vlen().bit_length() {
+                ..=64 => VectorExtension::Zve64d,
+                _ => VectorExtension::V,
+            };
+            let vu = VectorEngineBuilder::default()
+                .vlen(vlen())
+                .extension(extension)
+                .build()
+                .unwrap();
 
-        let core = RvCoreBuilder::default()
-            .vec_engine(vu)
-            .instructions(instructions)
-            .memory(memory)
-            .build();
+            let mut core = RvCoreBuilder::default()
+                .vec_engine(vu)
+                .memory(memory)
+                .text_base(text_base)
+                .history(History::new(10_000))
+                .build();
 
-        *machine = global_state::Machine::On(core);
-    });
+            // Interpreter only emits encodable instructions
+            core.load_program(&instructions).unwrap();
+
+            *machine = global_state::Machine::On(core);
+        },
+    );
 
     view! {
         <button
@@ -113,9 +134,13 @@ fn StartButton(
                 match compile_result {
                     Err(vec) => errors.set(global_state::Errors(vec.into_iter().collect())),
                     Ok(result) => {
-                        build_machine((result.instructions, result.memory));
-                        let map = result.instructions_addresses;
-                        if let Some(first) = map.first() {
+                        build_machine((result.instructions, result.memory, result.text_base));
+                        let map: Vec<_> = result
+                            .instructions_pcs
+                            .into_iter()
+                            .zip(result.instructions_addresses)
+                            .collect();
+                        if let Some((_, first)) = map.first() {
                             highlighted_line.set(global_state::Highlight::On(*first + 1));
                         }
                         set_instruction_map(map);
@@ -130,7 +155,7 @@ fn StartButton(
 }
 
 #[component]
-fn StepButton(instruction_map: ReadSignal<Vec<usize>>) -> impl IntoView {
+fn StepButton(instruction_map: ReadSignal<Vec<(u64, usize)>>) -> impl IntoView {
     let core = expect_context::<RwSignal<global_state::Machine>>();
     let highlighted_line = expect_context::<RwSignal<global_state::Highlight>>();
     let errors = expect_context::<RwSignal<global_state::Errors>>();
@@ -144,10 +169,11 @@ fn StepButton(instruction_map: ReadSignal<Vec<usize>>) -> impl IntoView {
         match (machine.read_core(), option) {
             // Machine executed step properly
             (Some(machine), Some(Ok(()))) => {
-                let instruction_line = machine.registers.pc / 4;
+                let pc = machine.registers.pc;
                 let instruction_index = instruction_map()
-                    .get(instruction_line as usize)
-                    .map(|el| el + 1);
+                    .into_iter()
+                    .find(|(address, _)| *address == pc)
+                    .map(|(_, line)| line + 1);
 
                 match instruction_index {
                     Some(index) => {
@@ -158,9 +184,12 @@ fn StepButton(instruction_map: ReadSignal<Vec<usize>>) -> impl IntoView {
                 }
             }
             // Machine exists, but step returned error
-            (Some(_), Some(Err(msg))) => {
+            (Some(_), Some(Err(trap))) => {
                 *machine = global_state::Machine::Off;
-                errors.set(global_state::Errors(vec![(last_executed_line() - 1, msg)]));
+                errors.set(global_state::Errors(vec![(
+                    last_executed_line() - 1,
+                    trap.to_string(),
+                )]));
                 highlighted_line.set(global_state::Highlight::Off);
             }
             // Machine exists, but RvCore::step() returned nothing, probably impossible since
@@ -186,3 +215,46 @@ fn StepButton(instruction_map: ReadSignal<Vec<usize>>) -> impl IntoView {
         </button>
     }
 }
+
+#[component]
+fn StepBackButton(instruction_map: ReadSignal<Vec<(u64, usize)>>) -> impl IntoView {
+    let core = expect_context::<RwSignal<global_state::Machine>>();
+    let highlighted_line = expect_context::<RwSignal<global_state::Highlight>>();
+
+    let (can_step_back, step_back) = create_slice(
+        core,
+        |machine| {
+            machine
+                .read_core()
+                .is_some_and(|core| !core.history.is_empty())
+        },
+        move |machine, _: ()| {
+            let core = machine.rw_core().unwrap();
+
+            if !core.step_back() {
+                return;
+            }
+
+            let pc = core.registers.pc;
+            if let Some((_, line)) = instruction_map()
+                .into_iter()
+                .find(|(address, _)| *address == pc)
+            {
+                highlighted_line.set(global_state::Highlight::On(line + 1));
+            }
+        },
+    );
+
+    view! {
+        <button
+            prop:disabled=move || !can_step_back()
+            class="rounded-md px-3 text-sm font-semibold text-white shadow-sm"
+            class=("bg-zinc-400", move || !can_step_back())
+            class=("bg-zinc-600", can_step_back)
+            class=("hover:bg-zinc-500", can_step_back)
+            on:click=move |_| step_back(())
+        >
+            Back
+        </button>
+    }
+}
@@ -74,7 +74,29 @@ fn csr_name(index: usize) -> Option<&'static str> {
         0x0003 => "fcsr",
         0x0001 => "fflags",
         0x0002 => "frm",
+        0x0100 => "sstatus",
+        0x0104 => "sie",
+        0x0105 => "stvec",
+        0x0140 => "sscratch",
+        0x0141 => "sepc",
+        0x0142 => "scause",
+        0x0143 => "stval",
+        0x0144 => "sip",
+        0x0180 => "satp",
         0x0300 => "mstatus",
+        0x0301 => "misa",
+        0x0302 => "medeleg",
+        0x0303 => "mideleg",
+        0x0304 => "mie",
+        0x0305 => "mtvec",
+        0x0340 => "mscratch",
+        0x0341 => "mepc",
+        0x0342 => "mcause",
+        0x0343 => "mtval",
+        0x0344 => "mip",
+        0x0f11 => "mvendorid",
+        0x0f13 => "mimpid",
+        0x0f14 => "mhartid",
         0x0200 => "vsstatus",
         0x0C20 => "vl",
         0x0C21 => "vtype",
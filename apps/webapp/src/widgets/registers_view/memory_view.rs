@@ -1,5 +1,3 @@
-use std::collections::VecDeque;
-
 use eeric_core::prelude::*;
 use leptos::*;
 
@@ -8,11 +6,23 @@ use crate::widgets::global_state;
 #[component]
 pub fn MemoryView() -> impl IntoView {
     let core = expect_context::<RwSignal<global_state::Machine>>();
+    // Regions can span most of the address space, only their beginning is shown
     let memory = create_read_slice(core, |state| {
         state
             .read_core()
-            .map(|machine| machine.memory.snapshot())
-            .unwrap_or(VecDeque::from_iter((0..0x100).map(|_| 0)))
+            .map(|machine| {
+                let memory = &machine.memory;
+
+                memory
+                    .regions()
+                    .iter()
+                    .flat_map(|region| {
+                        let len = region.size.min(0x1000);
+                        (region.start..).zip(memory.bytes(region.start, len as usize))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or((0..0x100).map(|address| (address, 0)).collect())
     });
 
     view! {
@@ -25,8 +35,7 @@ pub fn MemoryView() -> impl IntoView {
             }
             {move || {
                 memory()
-                    .iter()
-                    .enumerate()
+                    .into_iter()
                     .map(|(address, byte)| {
                         view! {
                             <>
@@ -25,7 +25,7 @@ pub fn FloatRegisters() -> impl IntoView {
                         .enumerate()
                         .map(|(index, value)| {
                             view! {
-                                <ScalarRegister name=freg_name(index) value=format!("{:.2}", value)/>
+                                <ScalarRegister name=freg_name(index) value=format!("{:.2}", f64::from_bits(value))/>
                             }
                         })
                         .collect::<Vec<_>>()
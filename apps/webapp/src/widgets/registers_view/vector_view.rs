@@ -24,13 +24,7 @@ pub struct FrontEndVLEN(Vlen);
 
 impl ToString for FrontEndVLEN {
     fn to_string(&self) -> String {
-        match self.0 {
-            Vlen::V64 => "64b",
-            Vlen::V128 => "128b",
-            Vlen::V256 => "256b",
-            Vlen::V512 => "512b",
-        }
-        .to_string()
+        format!("{}b", self.0.bit_length())
     }
 }
 
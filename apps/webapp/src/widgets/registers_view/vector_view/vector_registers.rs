@@ -35,11 +35,9 @@ pub fn VectorRegisters() -> impl IntoView {
     let (sew, _set_sew) = create_signal(FrontEndSEW::Default);
 
     let grid_cols = move || {
-        (match vlen_view() {
-            Vlen::V512 => 1,
-            Vlen::V256 => 1,
-            Vlen::V128 => 2,
-            Vlen::V64 => 2,
+        (match vlen_view().bit_length() {
+            ..=128 => 2,
+            _ => 1,
         }) * (vlen_view().byte_length() / sew().map_default(vec_engine().sew).0.byte_length() + 1)
     };
 
@@ -22,56 +22,57 @@ fn main() {
 
     use alias::*;
 
-    let mut core = RvCoreBuilder::default()
-        .instructions(vec![
-            I::Vsetvli(F::Vsetvli {
-                rd: T0,
-                rs1: A2,
-                vtypei: vtype!(e8, m8, ta, ma),
-            }),
-            I::Vlv {
-                eew: BaseSew::E8,
-                data: F::Vl {
-                    vd: 0,
-                    rs1: A1,
-                    vm: false,
-                },
-            },
-            I::Add(F::R {
-                rd: A1,
+    let mut core = RvCoreBuilder::default().build();
+
+    core.load_program(&[
+        I::Vsetvli(F::Vsetvli {
+            rd: T0,
+            rs1: A2,
+            vtypei: vtype!(e8, m8, ta, ma),
+        }),
+        I::Vlv {
+            eew: BaseSew::E8,
+            data: F::Vl {
+                vd: 0,
                 rs1: A1,
-                rs2: T0,
-            }),
-            I::Sub(F::R {
-                rd: A2,
-                rs1: A2,
-                rs2: T0,
-            }),
-            I::Vsv {
-                eew: BaseSew::E8,
-                data: F::Vs {
-                    vs3: 0,
-                    rs1: A3,
-                    vm: false,
-                },
+                vm: false,
             },
-            I::Add(F::R {
-                rd: A3,
+        },
+        I::Add(F::R {
+            rd: A1,
+            rs1: A1,
+            rs2: T0,
+        }),
+        I::Sub(F::R {
+            rd: A2,
+            rs1: A2,
+            rs2: T0,
+        }),
+        I::Vsv {
+            eew: BaseSew::E8,
+            data: F::Vs {
+                vs3: 0,
                 rs1: A3,
-                rs2: T0,
-            }),
-            I::Bne(F::S {
-                rs1: A2,
-                rs2: ZERO,
-                imm12: -24,
-            }),
-            I::Jalr(F::I {
-                rd: ZERO,
-                rs1: RA,
-                imm12: 0,
-            }),
-        ])
-        .build();
+                vm: false,
+            },
+        },
+        I::Add(F::R {
+            rd: A3,
+            rs1: A3,
+            rs2: T0,
+        }),
+        I::Bne(F::S {
+            rs1: A2,
+            rs2: ZERO,
+            imm12: -24,
+        }),
+        I::Jalr(F::I {
+            rd: ZERO,
+            rs1: RA,
+            imm12: 0,
+        }),
+    ])
+    .unwrap();
 
     for machine_state in core.run() {
         println!("{:?}", machine_state);
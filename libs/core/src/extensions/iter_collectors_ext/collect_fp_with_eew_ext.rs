@@ -15,6 +15,10 @@ where
 
         let raw = self
             .flat_map(|fp| match fp {
+                ArbitraryFloat::F16(f) => {
+                    eew = BaseSew::E16;
+                    f.to_le_bytes().to_vec()
+                }
                 ArbitraryFloat::F32(f) => {
                     eew = BaseSew::E32;
                     f.to_le_bytes().to_vec()
@@ -15,7 +15,7 @@ where
         Vreg {
             raw: self
                 .map(u128::to_le_bytes)
-                .flat_map(|bytes| bytes[0..eew.byte_length()].to_owned())
+                .flat_map(|bytes| bytes[0..eew.double().byte_length()].to_owned())
                 .collect(),
             eew,
         }
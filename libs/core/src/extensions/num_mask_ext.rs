@@ -1,6 +1,5 @@
 pub trait NumMaskExt {
     fn with_mask_bit(self, value: u64) -> u64;
-    fn get_mask_bit(&self) -> u64;
 }
 
 impl NumMaskExt for u64 {
@@ -11,8 +10,4 @@ impl NumMaskExt for u64 {
             self & !1
         }
     }
-
-    fn get_mask_bit(&self) -> u64 {
-        *self & 1
-    }
 }
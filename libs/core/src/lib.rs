@@ -2,6 +2,7 @@
 #![feature(fn_traits)]
 #![feature(iter_next_chunk)]
 #![feature(iter_array_chunks)]
+#![feature(f16)]
 
 mod extensions;
 mod rv_core;
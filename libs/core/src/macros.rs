@@ -33,6 +33,7 @@ macro_rules! fuse {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     #[test]
     fn vtype_works() {
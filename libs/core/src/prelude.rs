@@ -1,11 +1,30 @@
 pub use crate::rv_core::{
-    instruction::{format, Instruction},
-    memory::Memory,
-    registers::{CsrPrivilege, Registers, RegistersSnapshot},
+    debugger::{
+        Breakpoint, Debugger, Predicate, RegisterRef, StopCondition, StopReason, WatchHit,
+        WatchKind, Watchpoint,
+    },
+    device::{Clint, Device, SharedDevice, Uart16550},
+    elf::{Elf, Segment, SegmentFlags, Symbol},
+    environment::{EcallOutcome, Environment, EnvironmentHandler, RarsEnvironment},
+    history::History,
+    instruction::{
+        decoder::{decode, DecodeError},
+        encoder::{
+            compress, encode, encode_bytes, encode_compressed, encode_compressed_as, encode_fused,
+            EncodeError,
+        },
+        format, Disassembly, Instruction,
+    },
+    memory::{Memory, Permissions, Region},
+    mmu::{Access, Mmu},
+    registers::{CsrPrivilege, PrivilegeMode, Registers, RegistersSnapshot},
     snapshot::Snapshotable,
+    softfloat::RoundingMode,
+    trace::{MemoryAccess, Trace, TraceEntry, VectorConfig},
+    trap::{Exception, Interrupt, Trap},
     vector_engine::{
         sew::{BaseSew, Sew},
-        Lmul, MaskBehavior, VectorEngine, VectorEngineBuilder, Vlen,
+        AgnosticFill, Lmul, MaskBehavior, VectorEngine, VectorEngineBuilder, VectorExtension, Vlen,
     },
     RvCore, RvCoreBuilder,
 };
@@ -14,5 +33,5 @@ pub mod alias {
     pub use crate::rv_core::registers::aliases::csr::*;
     pub use crate::rv_core::registers::aliases::float::*;
     pub use crate::rv_core::registers::aliases::integer::*;
-    pub use crate::rv_core::registers::aliases::vector::*;
+    pub use crate::rv_core::registers::aliases::{fflags, interrupt, mstatus, vtype};
 }
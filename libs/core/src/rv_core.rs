@@ -1,15 +1,40 @@
 pub mod arbitrary_float;
+pub mod debugger;
+pub mod device;
+pub mod elf;
+pub mod environment;
+pub mod history;
 pub mod instruction;
 pub mod memory;
+pub mod mmu;
 pub mod registers;
 pub mod snapshot;
+pub mod softfloat;
+pub mod trace;
+pub mod trap;
 pub mod vector_engine;
 
 use derive_builder::Builder;
+use itertools::Itertools;
 
-use instruction::{executor::Executor, Instruction};
-use memory::Memory;
-use registers::Registers;
+use debugger::{Debugger, StopCondition, StopReason};
+use elf::{Elf, SegmentFlags};
+use environment::{EcallOutcome, Environment};
+use history::{Checkpoint, History};
+use instruction::{
+    decode_cache::DecodeCache,
+    encoder::{encode_bytes, EncodeError},
+    executor::Executor,
+    Instruction,
+};
+use memory::{Memory, Permissions, Region};
+use mmu::{Access, Mmu};
+use registers::{
+    aliases::{csr::*, integer::SP, interrupt, mstatus},
+    PrivilegeMode, Registers,
+};
+use trace::Trace;
+use trap::{Exception, Interrupt, Trap};
 
 use self::vector_engine::VectorEngine;
 
@@ -17,49 +42,339 @@ use self::vector_engine::VectorEngine;
 #[builder(build_fn(skip))]
 pub struct RvCore {
     pub memory: Memory,
-    pub instructions: Vec<Instruction>,
+    /// Address the program is loaded at, defaults to the first word past the initial memory
+    pub text_base: u64,
+    /// First address past the loaded program, running stops once pc leaves the text section
+    #[builder(setter(skip))]
+    pub text_end: u64,
     #[builder(setter(skip))]
     pub registers: Registers,
     pub vec_engine: VectorEngine,
+    /// Services ecalls before they trap
+    #[builder(setter(into))]
+    pub environment: Environment,
+    /// Code the program exited with through the environment, running stops once it's set
+    #[builder(setter(skip))]
+    pub exit_code: Option<i32>,
+    /// Steps that `step_back` can undo, nothing is recorded by default
+    pub history: History,
+    /// Retired instructions, nothing is recorded by default
+    pub trace: Trace,
+    /// Breakpoints and watchpoints `run_until` stops at
+    pub debugger: Debugger,
+    #[builder(setter(skip))]
+    decode_cache: DecodeCache,
 }
 
 impl RvCore {
-    pub fn step(&mut self) -> Option<Result<(), String>> {
+    pub fn step(&mut self) -> Option<Result<(), Trap>> {
         self.run().next()
     }
 
     pub fn run(&mut self) -> RunningRvCore<'_> {
         RunningRvCore { core: self }
     }
+
+    /// Undoes the most recent recorded step, returns false when the history is empty
+    pub fn step_back(&mut self) -> bool {
+        History::undo(self)
+    }
+
+    /// Steps back at least once and until pc reaches `pc`, returns false when the history runs out first
+    pub fn run_back_to(&mut self, pc: u64) -> bool {
+        while self.step_back() {
+            if self.registers.pc == pc {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Runs until a breakpoint, a watchpoint or the condition fires, a trap is left unhandled or the program ends.
+    /// Breakpoints at the starting pc are skipped so that a stopped run can be resumed.
+    pub fn run_until(&mut self, condition: StopCondition) -> StopReason {
+        let mut steps = 0;
+
+        loop {
+            if steps > 0 {
+                if let Some(id) = self.debugger.breakpoint_at(&self.registers) {
+                    return StopReason::Breakpoint(id);
+                }
+            }
+
+            match &condition {
+                StopCondition::Pc(pc) if steps > 0 && self.registers.pc == *pc => {
+                    return StopReason::Condition
+                }
+                StopCondition::Steps(limit) if steps >= *limit => return StopReason::Condition,
+                _ => {}
+            }
+
+            match self.step() {
+                None => return StopReason::Finished,
+                Some(Err(trap)) => return StopReason::Trap(trap),
+                Some(Ok(())) => steps += 1,
+            }
+
+            if let Some((id, hit)) = self.debugger.take_hit() {
+                return StopReason::Watchpoint { id, hit };
+            }
+
+            if let StopCondition::Registers(predicate) = &condition {
+                if predicate.holds(&self.registers) {
+                    return StopReason::Condition;
+                }
+            }
+        }
+    }
+
+    /// Encodes instructions into memory at `text_base` and jumps to the first one.
+    /// Memory is grown when the program doesn't fit.
+    pub fn load_program(&mut self, instructions: &[Instruction]) -> Result<(), EncodeError> {
+        let text: Vec<u8> = instructions
+            .iter()
+            .map(encode_bytes)
+            .flatten_ok()
+            .try_collect()?;
+
+        let start = self.text_base;
+        let end = start + text.len() as u64;
+
+        if end > self.memory.len() {
+            self.memory.resize(end);
+        }
+
+        self.memory.load(start, &text);
+        self.text_end = end;
+        self.registers.pc = self.text_base;
+        self.exit_code = None;
+        self.decode_cache.clear();
+        self.history.clear();
+
+        Ok(())
+    }
+
+    /// Copies PT_LOAD segments into memory and jumps to the entry point.
+    /// Segments outside of mapped memory get a text, rodata or data region carrying their permissions.
+    /// When the image doesn't fit below the end of memory, a stack region of the default memory size is mapped above it.
+    /// Text section spans all executable segments.
+    pub fn load_elf(&mut self, elf: &Elf) -> Result<(), String> {
+        // Segments built by hand skip the checks parsing does
+        if let Some(segment) = elf
+            .segments
+            .iter()
+            .find(|segment| (segment.data.len() as u64) > segment.mem_size)
+        {
+            return Err(format!(
+                "Segment at {:#x} is larger in file than in memory",
+                segment.vaddr
+            ));
+        }
+
+        let fits = elf.end() <= self.memory.len();
+
+        for segment in &elf.segments {
+            let start = segment.vaddr;
+
+            if segment.mem_size > 0 && self.memory.region_at(segment.vaddr).is_none() {
+                let SegmentFlags {
+                    read,
+                    write,
+                    execute,
+                } = segment.flags;
+
+                let name = match (execute, write) {
+                    (true, _) => "text",
+                    (false, true) => "data",
+                    (false, false) => "rodata",
+                };
+                let permissions = Permissions::new(read, write, execute);
+
+                self.memory
+                    .map(Region::new(
+                        name,
+                        segment.vaddr,
+                        segment.mem_size,
+                        permissions,
+                    ))
+                    .map_err(|exception| exception.to_string())?;
+            }
+            let file_size = segment.data.len() as u64;
+
+            self.memory.load(start, &segment.data);
+            self.memory
+                .zero(start + file_size, segment.mem_size - file_size);
+        }
+
+        if !fits {
+            let stack = Region::new("stack", elf.end(), Memory::default().len(), Permissions::RW);
+
+            self.memory.unmap("stack");
+            self.memory
+                .map(stack)
+                .map_err(|exception| exception.to_string())?;
+            self.registers.x[SP] = self.memory.len() - 1;
+        }
+
+        let executable = elf.segments.iter().filter(|segment| segment.flags.execute);
+
+        (self.text_base, self.text_end) = executable
+            .map(|segment| (segment.vaddr, segment.end()))
+            .reduce(|(start, end), (vaddr, segment_end)| (start.min(vaddr), end.max(segment_end)))
+            .unwrap_or((elf.entry, elf.entry));
+
+        self.registers.pc = elf.entry;
+        self.exit_code = None;
+        self.decode_cache.clear();
+        self.history.clear();
+
+        Ok(())
+    }
+
+    /// Saves the trapped context and jumps to the handler at mtvec, or at stvec when the trap is delegated to S-mode.
+    /// Interrupts are vectored to `base + 4 * code` when the trap vector is in vectored mode.
+    /// Returns false without touching the state if the trap vector is zero, leaving the trap to the embedder.
+    fn enter_handler(&mut self, code: u64, interrupt: bool, epc: u64, tval: u64) -> bool {
+        let c = &mut self.registers.c;
+        let mode = self.registers.mode;
+
+        let delegation = if interrupt {
+            c[MIDELEG].read()
+        } else {
+            c[MEDELEG].read()
+        };
+        let delegated = mode != PrivilegeMode::Machine && delegation & (1 << code) != 0;
+
+        let (tvec, epc_csr, cause_csr, tval_csr) = if delegated {
+            (c[STVEC].read(), SEPC, SCAUSE, STVAL)
+        } else {
+            (c[MTVEC].read(), MEPC, MCAUSE, MTVAL)
+        };
+
+        if tvec == 0 {
+            return false;
+        }
+
+        let status = c[MSTATUS].read();
+        let status = if delegated {
+            let spp = if mode == PrivilegeMode::Supervisor {
+                mstatus::SPP
+            } else {
+                0
+            };
+
+            stack_interrupt_enable(status, mstatus::SIE, mstatus::SPIE) & !mstatus::SPP | spp
+        } else {
+            stack_interrupt_enable(status, mstatus::MIE, mstatus::MPIE) & !mstatus::MPP
+                | (mode as u64) << 11
+        };
+
+        unsafe {
+            c[MSTATUS].set(status);
+            c[epc_csr].set(epc);
+            c[cause_csr].set(code | (interrupt as u64) << 63);
+            c[tval_csr].set(tval);
+        }
+
+        let base = tvec & !0b11;
+        let vectored = tvec & 0b11 == 1;
+
+        self.registers.mode = if delegated {
+            PrivilegeMode::Supervisor
+        } else {
+            PrivilegeMode::Machine
+        };
+        self.registers.pc = if interrupt && vectored {
+            base.wrapping_add(4 * code)
+        } else {
+            base
+        };
+
+        true
+    }
+
+    /// Lets the environment service an ecall, returns false when it leaves the call to trap
+    fn call_environment(&mut self) -> Result<bool, Exception> {
+        let Some(handler) = self.environment.handler() else {
+            return Ok(false);
+        };
+
+        let outcome = handler.borrow_mut().ecall(self)?;
+
+        match outcome {
+            EcallOutcome::Resume => {}
+            EcallOutcome::Exit(code) => self.exit_code = Some(code),
+            EcallOutcome::Trap => return Ok(false),
+        }
+
+        self.registers.pc = self.registers.pc.wrapping_add(4);
+
+        Ok(true)
+    }
+
+    /// Memory as seen by the program at the current privilege mode
+    pub fn mmu(&mut self) -> Mmu<'_> {
+        Mmu::new(&mut self.memory, &self.registers.c, self.registers.mode)
+    }
+
+    /// Fetches and decodes the instruction at pc, along with its raw encoding
+    fn fetch(&mut self) -> Result<(u32, Instruction), Trap> {
+        let pc = self.registers.pc;
+
+        if !pc.is_multiple_of(2) {
+            return Err(Exception::InstructionAddressMisaligned(pc).at(pc));
+        }
+
+        let raw = self.mmu().fetch(pc).map_err(|exception| exception.at(pc))?;
+
+        self.decode_cache
+            .decode(pc, raw)
+            .map(|instruction| (raw, instruction))
+            .map_err(|_| Exception::IllegalInstruction(raw as u64).at(pc))
+    }
+}
+
+/// Moves the interrupt enable bit to its previous-enable slot and disables interrupts
+fn stack_interrupt_enable(status: u64, enable: u64, previous_enable: u64) -> u64 {
+    let previous = if status & enable != 0 {
+        previous_enable
+    } else {
+        0
+    };
+
+    status & !(enable | previous_enable) | previous
 }
 
 impl Default for RvCore {
     fn default() -> Self {
-        let vec_engine = VectorEngine::default();
-        let memory = Memory::default();
-
-        Self {
-            instructions: Vec::new(),
-            registers: Registers::new(&vec_engine, &memory),
-            memory,
-            vec_engine,
-        }
+        RvCoreBuilder::default().build()
     }
 }
 
 impl RvCoreBuilder {
     pub fn build(&self) -> RvCore {
         let memory = self.memory.clone().unwrap_or_default();
-        let instructions = self.instructions.clone().unwrap_or_default();
         let vec_engine = self.vec_engine.unwrap_or_default();
         let registers = Registers::new(&vec_engine, &memory);
+        let text_base = self.text_base.unwrap_or(memory.len().next_multiple_of(4));
 
-        RvCore {
+        let mut core = RvCore {
             memory,
-            instructions,
+            text_base,
+            text_end: text_base,
             vec_engine,
             registers,
-        }
+            environment: self.environment.clone().unwrap_or_default(),
+            exit_code: None,
+            history: self.history.clone().unwrap_or_default(),
+            trace: self.trace.clone().unwrap_or_default(),
+            debugger: self.debugger.clone().unwrap_or_default(),
+            decode_cache: DecodeCache::default(),
+        };
+        core.registers.pc = text_base;
+
+        core
     }
 }
 
@@ -68,31 +383,114 @@ pub struct RunningRvCore<'core> {
 }
 
 impl Iterator for RunningRvCore<'_> {
-    type Item = Result<(), String>;
+    type Item = Result<(), Trap>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let instruction_pointer = self.core.registers.pc / 4;
-        let instruction = self
-            .core
-            .instructions
-            .get(instruction_pointer as usize)?
-            .clone();
-
-        Some(
-            Executor::new(
-                &mut self.core.registers,
-                &mut self.core.memory,
-                &mut self.core.vec_engine,
-            )
-            .execute(instruction),
-        )
+        let core = &mut *self.core;
+
+        let watching = core.debugger.is_watching();
+
+        if core.history.depth() == 0 && !core.trace.is_enabled() && !watching {
+            return Self::advance(core);
+        }
+
+        let checkpoint = Checkpoint::new(core);
+        let pending = Trace::begin(core);
+
+        if pending.is_some() || watching {
+            core.memory.accesses().start();
+        }
+
+        let result = Self::advance(core);
+        let diff = checkpoint.diff(core);
+        let accesses = core.memory.accesses().stop();
+
+        if watching {
+            core.debugger.observe(&core.registers, &diff, &accesses);
+        }
+
+        if let Some(pending) = pending {
+            Trace::finish(core, pending, &diff, accesses);
+        }
+
+        if result.is_some() {
+            core.history.push(diff);
+        }
+
+        result
+    }
+}
+
+impl RunningRvCore<'_> {
+    fn advance(core: &mut RvCore) -> Option<Result<(), Trap>> {
+        if core.exit_code.is_some() {
+            return None;
+        }
+
+        let pc = core.registers.pc;
+
+        // Text section is physical, pages that fail to translate are left for the fetch to fault on
+        if let Ok(address) = core.mmu().translate(pc, Access::Fetch) {
+            if !(core.text_base..core.text_end).contains(&address) {
+                return None;
+            }
+        }
+
+        // Devices drive the machine interrupt lines, software can't set them
+        if let Some(raised) = core.memory.tick_devices() {
+            let lines = interrupt::MSI | interrupt::MTI | interrupt::MEI;
+            let c = &mut core.registers.c;
+            let pending = c[MIP].read() & !lines | raised;
+
+            if pending != c[MIP].read() {
+                unsafe { c[MIP].set(pending) }
+            }
+        }
+
+        if let Some(interrupt) = Interrupt::pending(&core.registers.c, core.registers.mode) {
+            if core.enter_handler(interrupt.code(), true, pc, 0) {
+                return Some(Ok(()));
+            }
+        }
+
+        let result = core.fetch().and_then(|(raw, instruction)| {
+            Executor::new(&mut core.registers, &mut core.memory, &mut core.vec_engine)
+                .execute(instruction)
+                .map_err(|trap| match trap.exception {
+                    // Executors don't see the encoding, mtval gets the fetched word
+                    Exception::IllegalInstruction(0) => {
+                        Exception::IllegalInstruction(raw as u64).at(trap.pc)
+                    }
+                    _ => trap,
+                })
+        });
+
+        let result = match result {
+            Err(Trap {
+                exception: Exception::EnvironmentCall(_),
+                pc,
+            }) => match core.call_environment() {
+                Ok(true) => return Some(Ok(())),
+                Ok(false) => result,
+                Err(exception) => Err(exception.at(pc)),
+            },
+            result => result,
+        };
+
+        if let Err(trap) = result {
+            if core.enter_handler(trap.code(), false, trap.pc, trap.tval()) {
+                return Some(Ok(()));
+            }
+        }
+
+        Some(result)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::rv_core::{
-        registers::aliases::{csr::VLENB, integer::SP},
+        registers::aliases::csr::VLENB,
         snapshot::Snapshotable,
         vector_engine::{VectorEngineBuilder, Vlen},
     };
@@ -112,14 +510,20 @@ mod tests {
     fn custom_vlen_works() {
         use Instruction::*;
 
-        let core = RvCoreBuilder::default()
-            .instructions(vec![Vsetvli(instruction::format::Vsetvli {
-                rd: 5,
-                rs1: 12,
-                vtypei: 195,
-            })])
-            .vec_engine(VectorEngineBuilder::default().vlen(Vlen::V256).build())
+        let mut core = RvCoreBuilder::default()
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .vlen(Vlen::V256)
+                    .build()
+                    .unwrap(),
+            )
             .build();
+        core.load_program(&[Vsetvli(instruction::format::Vsetvli {
+            rd: 5,
+            rs1: 12,
+            vtypei: 195,
+        })])
+        .unwrap();
         assert_eq!(
             core.registers.snapshot().v.len(),
             32 * Vlen::V256.byte_length()
@@ -129,7 +533,12 @@ mod tests {
     #[test]
     fn vlenb_csr_works() {
         let core = RvCoreBuilder::default()
-            .vec_engine(VectorEngineBuilder::default().vlen(Vlen::V256).build())
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .vlen(Vlen::V256)
+                    .build()
+                    .unwrap(),
+            )
             .build();
         assert_eq!(
             core.registers.snapshot().c[VLENB].read(),
@@ -137,6 +546,685 @@ mod tests {
         );
     }
 
+    #[test]
+    fn elf_segments_get_regions() {
+        use instruction::format::{S, U};
+        use Instruction::*;
+
+        let text: Vec<u8> = [
+            Auipc(U { rd: 5, imm20: 0 }),
+            Sw(S {
+                rs1: 5,
+                rs2: 0,
+                imm12: 0,
+            }),
+        ]
+        .iter()
+        .flat_map(|instruction| encode_bytes(instruction).unwrap())
+        .collect();
+        let elf = Elf::parse(&elf::tests::sample_elf(
+            0x8000_0000,
+            0x8000_0000,
+            &text,
+            0x10,
+        ))
+        .unwrap();
+
+        let mut core = RvCore::default();
+        core.load_elf(&elf).unwrap();
+
+        let regions: Vec<_> = core
+            .memory
+            .regions()
+            .iter()
+            .map(|region| (region.name.as_str(), region.start, region.permissions))
+            .collect();
+        assert_eq!(
+            regions,
+            [
+                ("memory", 0, Permissions::RWX),
+                ("text", 0x8000_0000, Permissions::RX),
+                ("stack", 0x8000_0010, Permissions::RW),
+            ]
+        );
+        assert_eq!(core.registers.x[SP], 0x8000_100f);
+
+        assert_eq!(core.step(), Some(Ok(())));
+        assert_eq!(
+            core.step(),
+            Some(Err(Exception::StoreAccessFault(0x8000_0000).at(0x8000_0004)))
+        );
+    }
+
+    #[test]
+    fn load_elf_works() {
+        let elf = Elf::parse(&elf::tests::sample_elf(0x800, 0x804, &[1, 2, 3, 4], 0x10)).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0xff; 0x1000].into_iter()))
+            .build();
+        core.load_elf(&elf).unwrap();
+
+        assert_eq!(core.registers.pc, 0x804);
+        assert_eq!((core.text_base, core.text_end), (0x800, 0x810));
+        assert_eq!(
+            core.memory.get(0x800).unwrap(),
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff]
+        );
+        assert_eq!(core.registers.x[SP], 0xfff);
+
+        let elf = Elf::parse(&elf::tests::sample_elf(0x2000, 0x2000, &[1, 2, 3, 4], 0x10)).unwrap();
+        core.load_elf(&elf).unwrap();
+
+        assert_eq!(core.memory.len(), 0x3010);
+        assert_eq!(core.registers.x[SP], 0x300f);
+    }
+
+    #[test]
+    fn load_elf_zeroes_bss_in_place() {
+        let mut elf = Elf::parse(&elf::tests::sample_elf(
+            0x800,
+            0x800,
+            &[1, 2, 3, 4],
+            1 << 40,
+        ))
+        .unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0xff; 0x1000].into_iter()))
+            .build();
+        core.load_elf(&elf).unwrap();
+
+        assert_eq!(core.memory.get(0x802).unwrap(), [3, 4, 0, 0]);
+        assert_eq!(core.memory.bytes(0xffc, 4), [0; 4]);
+
+        elf.segments[0].mem_size = 2;
+
+        assert!(core.load_elf(&elf).is_err());
+    }
+
+    #[test]
+    fn program_runs_from_memory() {
+        use instruction::format::{I, R, U};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            // Function pointer to `double`
+            Auipc(U { rd: 10, imm20: 0 }),
+            Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 20,
+            }),
+            Addi(I {
+                rd: 11,
+                rs1: 0,
+                imm12: 21,
+            }),
+            Jalr(I {
+                rd: 1,
+                rs1: 10,
+                imm12: 0,
+            }),
+            Jal(U { rd: 0, imm20: 12 }),
+            // double:
+            Add(R {
+                rd: 11,
+                rs1: 11,
+                rs2: 11,
+            }),
+            Jalr(I {
+                rd: 0,
+                rs1: 1,
+                imm12: 0,
+            }),
+        ])
+        .unwrap();
+
+        assert_eq!(core.text_base, 0x100);
+        assert_eq!(core.memory.get(0x100).unwrap(), 0x00000517u32.to_le_bytes());
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[10], 0x114);
+        assert_eq!(core.registers.x[11], 42);
+        assert_eq!(core.registers.x[1], 0x110);
+        assert_eq!(core.registers.pc, 0x11c);
+    }
+
+    #[test]
+    fn compressed_program_advances_by_halfwords() {
+        use instruction::format::{I, R, S, U};
+        use Instruction::*;
+
+        let c = |instruction| instruction::encoder::compress(&instruction);
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            c(Addi(I {
+                rd: 11,
+                rs1: 0,
+                imm12: 5,
+            })),
+            c(Addi(I {
+                rd: 12,
+                rs1: 0,
+                imm12: 0,
+            })),
+            // loop:
+            Add(R {
+                rd: 12,
+                rs1: 12,
+                rs2: 11,
+            }),
+            c(Addi(I {
+                rd: 11,
+                rs1: 11,
+                imm12: -1,
+            })),
+            c(Bne(S {
+                rs1: 11,
+                rs2: 0,
+                imm12: -6,
+            })),
+            Auipc(U { rd: 10, imm20: 0 }),
+            c(Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 10,
+            })),
+            c(Jalr(I {
+                rd: 1,
+                rs1: 10,
+                imm12: 0,
+            })),
+            c(Ebreak),
+            c(Add(R {
+                rd: 13,
+                rs1: 0,
+                rs2: 1,
+            })),
+        ])
+        .unwrap();
+
+        assert_eq!(core.text_end, 0x118);
+        assert_eq!(core.memory.get(0x100).unwrap(), 0x4595u16.to_le_bytes());
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[12], 15);
+        assert_eq!(core.registers.x[1], 0x114);
+        assert_eq!(core.registers.x[13], 0x114);
+        assert_eq!(core.registers.pc, 0x118);
+        assert_eq!(core.registers.c[INSTRET].read(), 21);
+    }
+
+    #[test]
+    fn traps_report_cause_and_pc() {
+        use instruction::format::{I, U};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Lui(U { rd: 10, imm20: 1 }),
+            Ld(I {
+                rd: 11,
+                rs1: 10,
+                imm12: 0,
+            }),
+        ])
+        .unwrap();
+
+        assert_eq!(core.step(), Some(Ok(())));
+
+        let trap = core.step().unwrap().unwrap_err();
+        assert_eq!(trap, Exception::LoadAccessFault(0x1000).at(0x104));
+        assert_eq!((trap.code(), trap.tval()), (5, 0x1000));
+        assert_eq!(core.registers.pc, 0x104);
+
+        core.memory.set(0x104, 0xffffffffu32.to_le_bytes()).unwrap();
+
+        assert_eq!(
+            core.step(),
+            Some(Err(Exception::IllegalInstruction(0xffffffff).at(0x104)))
+        );
+
+        // csrrw zero, mvendorid, zero decodes, but the executor rejects it
+        core.memory.set(0x104, 0xf1101073u32.to_le_bytes()).unwrap();
+
+        assert_eq!(
+            core.step(),
+            Some(Err(Exception::IllegalInstruction(0xf1101073).at(0x104)))
+        );
+    }
+
+    #[test]
+    fn traps_enter_handler_at_mtvec() {
+        use instruction::format::{Csrr, I, U};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Auipc(U { rd: 5, imm20: 0 }),
+            Addi(I {
+                rd: 5,
+                rs1: 5,
+                imm12: 28,
+            }),
+            Csrrw(Csrr {
+                rd: 0,
+                rs1: 5,
+                csr: MTVEC,
+            }),
+            Lui(U { rd: 10, imm20: 16 }),
+            Ld(I {
+                rd: 11,
+                rs1: 10,
+                imm12: 0,
+            }),
+            Addi(I {
+                rd: 12,
+                rs1: 0,
+                imm12: 1,
+            }),
+            Jal(U { rd: 0, imm20: 24 }),
+            // handler:
+            Csrrs(Csrr {
+                rd: 6,
+                rs1: 0,
+                csr: MCAUSE,
+            }),
+            Csrrs(Csrr {
+                rd: 7,
+                rs1: 0,
+                csr: MEPC,
+            }),
+            Addi(I {
+                rd: 7,
+                rs1: 7,
+                imm12: 4,
+            }),
+            Csrrw(Csrr {
+                rd: 0,
+                rs1: 7,
+                csr: MEPC,
+            }),
+            Mret,
+        ])
+        .unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[6], 5);
+        assert_eq!(core.registers.x[7], 0x114);
+        assert_eq!(core.registers.x[12], 1);
+        assert_eq!(core.registers.c[MTVAL].read(), 0x10000);
+        assert_eq!(
+            core.registers.c[MSTATUS].read() & mstatus::MPIE,
+            mstatus::MPIE
+        );
+        assert_eq!(core.registers.pc, 0x130);
+    }
+
+    #[test]
+    fn interrupts_are_vectored() {
+        use registers::aliases::{
+            csr::{MIE, MIP},
+            interrupt,
+        };
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&vec![Instruction::Wfi; 16]).unwrap();
+
+        core.registers.c[MTVEC].write(0x101).unwrap();
+        core.registers.c[MIE].write(interrupt::MTI).unwrap();
+
+        unsafe { core.registers.c[MIP].set(interrupt::MTI) }
+
+        // Globally disabled
+        assert_eq!(core.step(), Some(Ok(())));
+        assert_eq!(core.registers.pc, 0x104);
+
+        core.registers.c[MSTATUS].write(mstatus::MIE).unwrap();
+
+        assert_eq!(core.step(), Some(Ok(())));
+        assert_eq!(core.registers.pc, 0x11c);
+        assert_eq!(core.registers.c[MCAUSE].read(), 1 << 63 | 7);
+        assert_eq!(core.registers.c[MEPC].read(), 0x104);
+        assert_eq!(
+            core.registers.c[MSTATUS].read() & (mstatus::MIE | mstatus::MPIE),
+            mstatus::MPIE
+        );
+    }
+
+    #[test]
+    fn steps_are_undone() {
+        use instruction::format::{Opivi, Vsetvli, I, S};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .history(History::new(16))
+            .build();
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 3,
+            }),
+            Vsetvli(Vsetvli {
+                rd: 6,
+                rs1: 5,
+                vtypei: 0,
+            }),
+            Vaddvi(Opivi {
+                dest: 1,
+                imm5: 5,
+                vs2: 0,
+                vm: false,
+            }),
+            Sd(S {
+                rs1: 0,
+                rs2: 5,
+                imm12: 0x10,
+            }),
+            Addi(I {
+                rd: 5,
+                rs1: 5,
+                imm12: 1,
+            }),
+        ])
+        .unwrap();
+
+        let initial = (
+            core.registers.snapshot(),
+            core.memory.bytes(0, 0x100),
+            core.vec_engine,
+        );
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.history.len(), 5);
+        assert_eq!(core.registers.x[5], 4);
+        assert_eq!(core.memory.bytes(0x10, 1), [3]);
+
+        assert!(core.run_back_to(0x108));
+        assert_eq!(core.registers.x[6], 3);
+        assert_eq!(core.registers.snapshot().v[16..19], [0; 3]);
+        assert_eq!(core.memory.bytes(0x10, 1), [0]);
+
+        while core.step_back() {}
+
+        assert_eq!(
+            (
+                core.registers.snapshot(),
+                core.memory.bytes(0, 0x100),
+                core.vec_engine
+            ),
+            initial
+        );
+        assert!(!core.run_back_to(0x100));
+
+        // Depth bounds the history
+        core.history.set_depth(2);
+        for result in core.run() {
+            result.unwrap();
+        }
+        assert_eq!(core.history.len(), 2);
+        assert!(!core.run_back_to(0x100));
+        assert_eq!(core.registers.pc, 0x10c);
+    }
+
+    #[test]
+    fn trapped_vector_loads_are_undone() {
+        use instruction::format::{Vl, Vsetivli};
+        use vector_engine::{sew::BaseSew, AgnosticFill};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .agnostic_fill(AgnosticFill::Ones)
+                    .build()
+                    .unwrap(),
+            )
+            .history(History::new(16))
+            .build();
+        core.load_program(&[
+            // e8, m1, ta, ma
+            Vsetivli(Vsetivli {
+                rd: 0,
+                uimm: 8,
+                vtypei: 0b1100_0000,
+            }),
+            Vlv {
+                data: Vl {
+                    vd: 1,
+                    rs1: 10,
+                    vm: false,
+                },
+                eew: BaseSew::E8,
+            },
+        ])
+        .unwrap();
+
+        // Three elements fit before the end of the text section
+        core.registers.x[10] = 0x105;
+
+        let trap = core.run().find_map(Result::err).unwrap();
+        let vlenb = core.vec_engine.vlen.byte_length();
+
+        assert_eq!(trap, Exception::LoadAccessFault(0x108).at(0x104));
+        assert_eq!(core.registers.c[VSTART].read(), 3);
+        assert_eq!(
+            core.registers.snapshot().v[vlenb..vlenb + 3],
+            core.memory.bytes(0x105, 3)
+        );
+        // Tail past vl is filled even though the load stopped early
+        assert!(core.registers.snapshot().v[vlenb + 8..2 * vlenb]
+            .iter()
+            .all(|&byte| byte == 0xff));
+        assert_eq!(core.history.len(), 2);
+
+        assert!(core.step_back());
+        assert_eq!(core.registers.pc, 0x104);
+        assert_eq!(core.registers.c[VSTART].read(), 0);
+        assert!(core.registers.snapshot().v[vlenb..2 * vlenb]
+            .iter()
+            .all(|&byte| byte == 0));
+
+        assert!(core.step_back());
+        assert_eq!(core.registers.c[VL].read(), 0);
+        assert!(!core.step_back());
+    }
+
+    #[test]
+    fn devices_print_and_raise_timer_interrupts() {
+        use device::{Clint, Uart16550};
+        use instruction::format::{I, S, U};
+        use registers::aliases::csr::MIE;
+        use std::{cell::RefCell, rc::Rc};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Lui(U {
+                rd: 5,
+                imm20: (Uart16550::BASE >> 12) as i32,
+            }),
+            Addi(I {
+                rd: 6,
+                rs1: 0,
+                imm12: b'A' as i32,
+            }),
+            Sb(S {
+                rs1: 5,
+                rs2: 6,
+                imm12: 0,
+            }),
+            Lui(U {
+                rd: 7,
+                imm20: ((Clint::BASE + Clint::MTIMECMP) >> 12) as i32,
+            }),
+            Addi(I {
+                rd: 8,
+                rs1: 0,
+                imm12: 10,
+            }),
+            Sd(S {
+                rs1: 7,
+                rs2: 8,
+                imm12: 0,
+            }),
+            Jal(U { rd: 0, imm20: 0 }),
+            // handler:
+            Addi(I {
+                rd: 9,
+                rs1: 0,
+                imm12: 1,
+            }),
+        ])
+        .unwrap();
+
+        let uart = Rc::new(RefCell::new(Uart16550::default()));
+        let clint = Rc::new(RefCell::new(Clint::default()));
+        core.memory
+            .attach(
+                Region::new("uart", Uart16550::BASE, Uart16550::SIZE, Permissions::RW),
+                uart.clone(),
+            )
+            .unwrap();
+        core.memory
+            .attach(
+                Region::new("clint", Clint::BASE, Clint::SIZE, Permissions::RW),
+                clint.clone(),
+            )
+            .unwrap();
+        assert_eq!(core.memory.len(), 0x120);
+
+        core.registers.c[MTVEC].write(0x11c).unwrap();
+        core.registers.c[MIE].write(interrupt::MTI).unwrap();
+        core.registers.c[MSTATUS].write(mstatus::MIE).unwrap();
+
+        for _ in 0..16 {
+            core.step().unwrap().unwrap();
+
+            if core.registers.x[9] == 1 {
+                break;
+            }
+        }
+
+        assert_eq!(uart.borrow().output_string(), "A");
+        assert_eq!(clint.borrow().mtimecmp, 10);
+        assert_eq!(clint.borrow().mtime, 11);
+        assert_eq!(core.registers.c[MCAUSE].read(), 1 << 63 | 7);
+        assert_eq!(core.registers.c[MEPC].read(), 0x118);
+    }
+
+    #[test]
+    fn supervisor_traps_are_delegated() {
+        use instruction::format::{Csrr, I, R, U};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x3000].into_iter()))
+            .text_base(0x100)
+            .build();
+        core.load_program(&[
+            Csrrs(Csrr {
+                rd: 10,
+                rs1: 0,
+                csr: MSTATUS,
+            }),
+            Lui(U {
+                rd: 11,
+                imm20: 0x40000,
+            }),
+            Ld(I {
+                rd: 12,
+                rs1: 11,
+                imm12: 0,
+            }),
+            Addi(I {
+                rd: 13,
+                rs1: 0,
+                imm12: 1,
+            }),
+            Jal(U { rd: 0, imm20: 28 }),
+            // handler:
+            Csrrs(Csrr {
+                rd: 5,
+                rs1: 0,
+                csr: SCAUSE,
+            }),
+            Add(R {
+                rd: 6,
+                rs1: 6,
+                rs2: 5,
+            }),
+            Csrrs(Csrr {
+                rd: 7,
+                rs1: 0,
+                csr: SEPC,
+            }),
+            Addi(I {
+                rd: 7,
+                rs1: 7,
+                imm12: 4,
+            }),
+            Csrrw(Csrr {
+                rd: 0,
+                rs1: 7,
+                csr: SEPC,
+            }),
+            Sret,
+        ])
+        .unwrap();
+
+        // Sv39 root table at 0x2000 maps the first gigapage to itself, the second one is unmapped
+        core.memory.load(0x2000, &0xcfu64.to_le_bytes());
+
+        let c = &mut core.registers.c;
+        c[SATP].write(8 << 60 | 0x2).unwrap();
+        c[STVEC].write(0x114).unwrap();
+        c[MEDELEG].write(1 << 2 | 1 << 13).unwrap();
+        c[MTVEC].write(0x200).unwrap();
+        core.registers.mode = PrivilegeMode::Supervisor;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.mode, PrivilegeMode::Supervisor);
+        assert_eq!(core.registers.x[6], 2 + 13);
+        assert_eq!(core.registers.x[13], 1);
+        assert_eq!(core.registers.c[STVAL].read(), 0x4000_0000);
+        assert_eq!(core.registers.c[MCAUSE].read(), 0);
+        assert_eq!(core.registers.pc, 0x12c);
+    }
+
     #[test]
     fn sp_points_to_stack() {
         let memory = Memory::new([5, 2, 1, 3, 4].into_iter());
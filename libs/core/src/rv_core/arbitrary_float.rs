@@ -6,27 +6,11 @@ use core::{
 
 use num_traits::{Float, Num, NumCast, One, ToPrimitive, Zero};
 
-// Note: RVV 1.1 spec defines a f16 type, maybe in the future :)
-
-// Single precision and rest decomposition
-pub fn decompose(f: f64) -> (f32, u32) {
-    let bits = f.to_bits();
-    let low_bits = (bits & 0xFFFFFFFF) as u32;
-    let high_bits = (bits >> 32) as u32;
-
-    (f32::from_bits(low_bits), high_bits)
-}
-
-// Double precision recombination
-pub fn compose(float: f32, int: u32) -> f64 {
-    let float_bits = float.to_bits();
-    let combined = ((int as u64) << 32) | float_bits as u64;
-
-    f64::from_bits(combined)
-}
+use super::{registers::unbox, trap::Exception};
 
 #[derive(Clone, Copy)]
 pub enum ArbitraryFloat {
+    F16(f16),
     F32(f32),
     F64(f64),
 }
@@ -37,35 +21,59 @@ pub enum RoundingMode {
 }
 
 impl ArbitraryFloat {
-    pub fn copy_type(other: &Self, value: f64) -> Self {
+    /// Reads raw scalar register bits in the format of `other`
+    pub fn copy_type(other: &Self, bits: u64) -> Self {
         match other {
-            Self::F32(_) => Self::F32(decompose(value).0),
-            Self::F64(_) => Self::F64(value),
+            Self::F16(_) => Self::F16(f16::from_bits(unbox(bits, 16) as u16)),
+            Self::F32(_) => Self::F32(f32::from_bits(unbox(bits, 32) as u32)),
+            Self::F64(_) => Self::F64(f64::from_bits(bits)),
         }
     }
 
     pub fn double_precision(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F32(fp as f32),
             Self::F32(fp) => Self::F64(fp as f64),
             Self::F64(_) => unimplemented!("No f128 support"),
         }
     }
 
-    pub fn half_precision(self, mode: RoundingMode) -> Self {
-        match self {
-            Self::F32(_) => unimplemented!("No f16 support"),
+    /// Narrows to the format of half the width, there's no 8-bit one to narrow halves to
+    pub fn half_precision(self, mode: RoundingMode) -> Result<Self, Exception> {
+        Ok(match self {
+            Self::F16(_) => return Err(Exception::IllegalInstruction(0)),
+            Self::F32(fp) => {
+                let hp = fp as f16;
+                Self::F16(match mode {
+                    RoundingMode::Nearest => hp,
+                    RoundingMode::TowardsOdd => f16::from_bits(round_to_odd(
+                        hp.to_bits() as u64,
+                        hp as f32 == fp,
+                        (hp as f32).abs() > fp.abs(),
+                    ) as u16),
+                })
+            }
             Self::F64(fp) => {
                 let hp = fp as f32;
                 Self::F32(match mode {
                     RoundingMode::Nearest => hp,
-                    RoundingMode::TowardsOdd => {
-                        let mut bits = hp.to_bits();
-                        bits |= 1;
-                        f32::from_bits(bits)
-                    }
+                    RoundingMode::TowardsOdd => f32::from_bits(round_to_odd(
+                        hp.to_bits() as u64,
+                        hp as f64 == fp,
+                        (hp as f64).abs() > fp.abs(),
+                    ) as u32),
                 })
             }
-        }
+        })
+    }
+}
+
+// Bits of a value rounded to nearest, turned into bits of the same value rounded to odd
+fn round_to_odd(bits: u64, exact: bool, rounded_away_from_zero: bool) -> u64 {
+    match (exact, rounded_away_from_zero) {
+        (true, _) => bits,
+        (false, true) => (bits - 1) | 1,
+        (false, false) => bits | 1,
     }
 }
 
@@ -74,6 +82,7 @@ impl Neg for ArbitraryFloat {
 
     fn neg(self) -> Self::Output {
         match self {
+            Self::F16(fp) => Self::F16(-fp),
             Self::F32(fp) => Self::F32(-fp),
             Self::F64(fp) => Self::F64(-fp),
         }
@@ -83,6 +92,8 @@ impl Neg for ArbitraryFloat {
 impl PartialEq for ArbitraryFloat {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F32(*fp1 as f32).eq(fp2),
+            (fp1, Self::F16(fp2)) => fp1.eq(&Self::F32(*fp2 as f32)),
             (Self::F32(fp1), Self::F32(fp2)) => fp1.eq(fp2),
             (Self::F32(fp1), Self::F64(fp2)) => fp1.eq(&(*fp2 as f32)),
             (Self::F64(fp1), Self::F32(fp2)) => fp1.eq(&(*fp2 as f64)),
@@ -94,6 +105,8 @@ impl PartialEq for ArbitraryFloat {
 impl PartialOrd for ArbitraryFloat {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F32(*fp1 as f32).partial_cmp(fp2),
+            (fp1, Self::F16(fp2)) => fp1.partial_cmp(&Self::F32(*fp2 as f32)),
             (Self::F32(fp1), Self::F32(fp2)) => fp1.partial_cmp(fp2),
             (Self::F32(fp1), Self::F64(fp2)) => fp1.partial_cmp(&(*fp2 as f32)),
             (Self::F64(fp1), Self::F32(fp2)) => fp1.partial_cmp(&(*fp2 as f64)),
@@ -105,6 +118,7 @@ impl PartialOrd for ArbitraryFloat {
 impl ToPrimitive for ArbitraryFloat {
     fn to_i64(&self) -> Option<i64> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_i64(),
             Self::F32(fp) => fp.to_i64(),
             Self::F64(fp) => fp.to_i64(),
         }
@@ -112,6 +126,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_u64(&self) -> Option<u64> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_u64(),
             Self::F32(fp) => fp.to_u64(),
             Self::F64(fp) => fp.to_u64(),
         }
@@ -119,6 +134,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_i128(&self) -> Option<i128> {
         match self {
+            Self::F16(fp) => Some(*fp as i128),
             Self::F32(fp) => Some(*fp as i128),
             Self::F64(fp) => Some(*fp as i128),
         }
@@ -126,6 +142,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_u128(&self) -> Option<u128> {
         match self {
+            Self::F16(fp) => Some(*fp as u128),
             Self::F32(fp) => Some(*fp as u128),
             Self::F64(fp) => Some(*fp as u128),
         }
@@ -133,6 +150,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_isize(&self) -> Option<isize> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_isize(),
             Self::F32(fp) => fp.to_isize(),
             Self::F64(fp) => fp.to_isize(),
         }
@@ -140,6 +158,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_i8(&self) -> Option<i8> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_i8(),
             Self::F32(fp) => fp.to_i8(),
             Self::F64(fp) => fp.to_i8(),
         }
@@ -147,6 +166,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_i16(&self) -> Option<i16> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_i16(),
             Self::F32(fp) => fp.to_i16(),
             Self::F64(fp) => fp.to_i16(),
         }
@@ -154,6 +174,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_i32(&self) -> Option<i32> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_i32(),
             Self::F32(fp) => fp.to_i32(),
             Self::F64(fp) => fp.to_i32(),
         }
@@ -161,6 +182,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_usize(&self) -> Option<usize> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_usize(),
             Self::F32(fp) => fp.to_usize(),
             Self::F64(fp) => fp.to_usize(),
         }
@@ -168,6 +190,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_u8(&self) -> Option<u8> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_u8(),
             Self::F32(fp) => fp.to_u8(),
             Self::F64(fp) => fp.to_u8(),
         }
@@ -175,6 +198,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_u16(&self) -> Option<u16> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_u16(),
             Self::F32(fp) => fp.to_u16(),
             Self::F64(fp) => fp.to_u16(),
         }
@@ -182,6 +206,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_u32(&self) -> Option<u32> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_u32(),
             Self::F32(fp) => fp.to_u32(),
             Self::F64(fp) => fp.to_u32(),
         }
@@ -189,6 +214,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_f32(&self) -> Option<f32> {
         match self {
+            Self::F16(fp) => Some(*fp as f32),
             Self::F32(fp) => Some(*fp),
             Self::F64(fp) => fp.to_f32(),
         }
@@ -196,6 +222,7 @@ impl ToPrimitive for ArbitraryFloat {
 
     fn to_f64(&self) -> Option<f64> {
         match self {
+            Self::F16(fp) => (*fp as f32).to_f64(),
             Self::F32(fp) => fp.to_f64(),
             Self::F64(fp) => Some(*fp),
         }
@@ -213,6 +240,8 @@ impl Add for ArbitraryFloat {
 
     fn add(self, other: Self) -> Self::Output {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F16(fp1 + fp2.to_f32().unwrap() as f16),
+            (fp1, Self::F16(fp2)) => fp1 + Self::F32(fp2 as f32),
             (Self::F32(fp1), Self::F32(fp2)) => Self::F32(fp1 + fp2),
             (Self::F32(fp1), Self::F64(fp2)) => Self::F32(fp1 + fp2 as f32),
             (Self::F64(fp1), Self::F32(fp2)) => Self::F64(fp1 + fp2 as f64),
@@ -226,6 +255,8 @@ impl Sub for ArbitraryFloat {
 
     fn sub(self, other: Self) -> Self::Output {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F16(fp1 - fp2.to_f32().unwrap() as f16),
+            (fp1, Self::F16(fp2)) => fp1 - Self::F32(fp2 as f32),
             (Self::F32(fp1), Self::F32(fp2)) => Self::F32(fp1 - fp2),
             (Self::F32(fp1), Self::F64(fp2)) => Self::F32(fp1 - fp2 as f32),
             (Self::F64(fp1), Self::F32(fp2)) => Self::F64(fp1 - fp2 as f64),
@@ -239,6 +270,8 @@ impl Mul for ArbitraryFloat {
 
     fn mul(self, other: Self) -> Self::Output {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F16(fp1 * fp2.to_f32().unwrap() as f16),
+            (fp1, Self::F16(fp2)) => fp1 * Self::F32(fp2 as f32),
             (Self::F32(fp1), Self::F32(fp2)) => Self::F32(fp1 * fp2),
             (Self::F32(fp1), Self::F64(fp2)) => Self::F32(fp1 * fp2 as f32),
             (Self::F64(fp1), Self::F32(fp2)) => Self::F64(fp1 * fp2 as f64),
@@ -252,6 +285,8 @@ impl Div for ArbitraryFloat {
 
     fn div(self, other: Self) -> Self::Output {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F16(fp1 / fp2.to_f32().unwrap() as f16),
+            (fp1, Self::F16(fp2)) => fp1 / Self::F32(fp2 as f32),
             (Self::F32(fp1), Self::F32(fp2)) => Self::F32(fp1 / fp2),
             (Self::F32(fp1), Self::F64(fp2)) => Self::F32(fp1 / fp2 as f32),
             (Self::F64(fp1), Self::F32(fp2)) => Self::F64(fp1 / fp2 as f64),
@@ -265,6 +300,8 @@ impl Rem for ArbitraryFloat {
 
     fn rem(self, other: Self) -> Self::Output {
         match (self, other) {
+            (Self::F16(fp1), fp2) => Self::F16(fp1 % fp2.to_f32().unwrap() as f16),
+            (fp1, Self::F16(fp2)) => fp1 % Self::F32(fp2 as f32),
             (Self::F32(fp1), Self::F32(fp2)) => Self::F32(fp1 % fp2),
             (Self::F32(fp1), Self::F64(fp2)) => Self::F32(fp1 % fp2 as f32),
             (Self::F64(fp1), Self::F32(fp2)) => Self::F64(fp1 % fp2 as f64),
@@ -280,6 +317,7 @@ impl Zero for ArbitraryFloat {
 
     fn is_zero(&self) -> bool {
         match self {
+            Self::F16(fp) => (*fp as f32).is_zero(),
             Self::F32(fp) => fp.is_zero(),
             Self::F64(fp) => fp.is_zero(),
         }
@@ -334,6 +372,7 @@ impl Float for ArbitraryFloat {
 
     fn is_nan(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_nan(),
             Self::F32(fp) => fp.is_nan(),
             Self::F64(fp) => fp.is_nan(),
         }
@@ -341,6 +380,7 @@ impl Float for ArbitraryFloat {
 
     fn is_infinite(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_infinite(),
             Self::F32(fp) => fp.is_infinite(),
             Self::F64(fp) => fp.is_infinite(),
         }
@@ -348,6 +388,7 @@ impl Float for ArbitraryFloat {
 
     fn is_finite(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_finite(),
             Self::F32(fp) => fp.is_finite(),
             Self::F64(fp) => fp.is_finite(),
         }
@@ -355,6 +396,7 @@ impl Float for ArbitraryFloat {
 
     fn is_normal(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_normal(),
             Self::F32(fp) => fp.is_normal(),
             Self::F64(fp) => fp.is_normal(),
         }
@@ -362,6 +404,7 @@ impl Float for ArbitraryFloat {
 
     fn classify(self) -> FpCategory {
         match self {
+            Self::F16(fp) => fp.classify(),
             Self::F32(fp) => fp.classify(),
             Self::F64(fp) => fp.classify(),
         }
@@ -369,6 +412,7 @@ impl Float for ArbitraryFloat {
 
     fn floor(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).floor() as f16),
             Self::F32(fp) => Self::F32(fp.floor()),
             Self::F64(fp) => Self::F64(fp.floor()),
         }
@@ -376,6 +420,7 @@ impl Float for ArbitraryFloat {
 
     fn ceil(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).ceil() as f16),
             Self::F32(fp) => Self::F32(fp.ceil()),
             Self::F64(fp) => Self::F64(fp.ceil()),
         }
@@ -383,6 +428,7 @@ impl Float for ArbitraryFloat {
 
     fn round(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).round() as f16),
             Self::F32(fp) => Self::F32(fp.round()),
             Self::F64(fp) => Self::F64(fp.round()),
         }
@@ -390,6 +436,7 @@ impl Float for ArbitraryFloat {
 
     fn trunc(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).trunc() as f16),
             Self::F32(fp) => Self::F32(fp.trunc()),
             Self::F64(fp) => Self::F64(fp.trunc()),
         }
@@ -397,6 +444,7 @@ impl Float for ArbitraryFloat {
 
     fn fract(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).fract() as f16),
             Self::F32(fp) => Self::F32(fp.fract()),
             Self::F64(fp) => Self::F64(fp.fract()),
         }
@@ -404,6 +452,7 @@ impl Float for ArbitraryFloat {
 
     fn abs(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).abs() as f16),
             Self::F32(fp) => Self::F32(fp.abs()),
             Self::F64(fp) => Self::F64(fp.abs()),
         }
@@ -411,6 +460,7 @@ impl Float for ArbitraryFloat {
 
     fn signum(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).signum() as f16),
             Self::F32(fp) => Self::F32(fp.signum()),
             Self::F64(fp) => Self::F64(fp.signum()),
         }
@@ -418,6 +468,7 @@ impl Float for ArbitraryFloat {
 
     fn is_sign_positive(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_sign_positive(),
             Self::F32(fp) => fp.is_sign_positive(),
             Self::F64(fp) => fp.is_sign_positive(),
         }
@@ -425,6 +476,7 @@ impl Float for ArbitraryFloat {
 
     fn is_sign_negative(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_sign_negative(),
             Self::F32(fp) => fp.is_sign_negative(),
             Self::F64(fp) => fp.is_sign_negative(),
         }
@@ -432,6 +484,9 @@ impl Float for ArbitraryFloat {
 
     fn mul_add(self, a: Self, b: Self) -> Self {
         match self {
+            Self::F16(fp) => {
+                Self::F16((fp as f64).mul_add(a.to_f64().unwrap(), b.to_f64().unwrap()) as f16)
+            }
             Self::F32(fp) => Self::F32(fp.mul_add(a.to_f32().unwrap(), b.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.mul_add(a.to_f64().unwrap(), b.to_f64().unwrap())),
         }
@@ -439,6 +494,7 @@ impl Float for ArbitraryFloat {
 
     fn recip(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).recip() as f16),
             Self::F32(fp) => Self::F32(fp.recip()),
             Self::F64(fp) => Self::F64(fp.recip()),
         }
@@ -446,6 +502,7 @@ impl Float for ArbitraryFloat {
 
     fn powi(self, n: i32) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).powi(n) as f16),
             Self::F32(fp) => Self::F32(fp.powi(n)),
             Self::F64(fp) => Self::F64(fp.powi(n)),
         }
@@ -453,6 +510,7 @@ impl Float for ArbitraryFloat {
 
     fn powf(self, n: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).powf(n.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.powf(n.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.powf(n.to_f64().unwrap())),
         }
@@ -460,6 +518,7 @@ impl Float for ArbitraryFloat {
 
     fn sqrt(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).sqrt() as f16),
             Self::F32(fp) => Self::F32(fp.sqrt()),
             Self::F64(fp) => Self::F64(fp.sqrt()),
         }
@@ -467,6 +526,7 @@ impl Float for ArbitraryFloat {
 
     fn exp(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).exp() as f16),
             Self::F32(fp) => Self::F32(fp.exp()),
             Self::F64(fp) => Self::F64(fp.exp()),
         }
@@ -474,6 +534,7 @@ impl Float for ArbitraryFloat {
 
     fn exp2(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).exp2() as f16),
             Self::F32(fp) => Self::F32(fp.exp2()),
             Self::F64(fp) => Self::F64(fp.exp2()),
         }
@@ -481,6 +542,7 @@ impl Float for ArbitraryFloat {
 
     fn ln(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).ln() as f16),
             Self::F32(fp) => Self::F32(fp.ln()),
             Self::F64(fp) => Self::F64(fp.ln()),
         }
@@ -488,6 +550,7 @@ impl Float for ArbitraryFloat {
 
     fn log(self, base: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).log(base.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.log(base.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.log(base.to_f64().unwrap())),
         }
@@ -495,6 +558,7 @@ impl Float for ArbitraryFloat {
 
     fn log2(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).log2() as f16),
             Self::F32(fp) => Self::F32(fp.log2()),
             Self::F64(fp) => Self::F64(fp.log2()),
         }
@@ -502,6 +566,7 @@ impl Float for ArbitraryFloat {
 
     fn log10(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).log10() as f16),
             Self::F32(fp) => Self::F32(fp.log10()),
             Self::F64(fp) => Self::F64(fp.log10()),
         }
@@ -509,6 +574,7 @@ impl Float for ArbitraryFloat {
 
     fn max(self, other: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).max(other.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.max(other.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.max(other.to_f64().unwrap())),
         }
@@ -516,6 +582,7 @@ impl Float for ArbitraryFloat {
 
     fn min(self, other: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).min(other.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.min(other.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.min(other.to_f64().unwrap())),
         }
@@ -527,6 +594,7 @@ impl Float for ArbitraryFloat {
 
     fn cbrt(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).cbrt() as f16),
             Self::F32(fp) => Self::F32(fp.cbrt()),
             Self::F64(fp) => Self::F64(fp.cbrt()),
         }
@@ -534,6 +602,7 @@ impl Float for ArbitraryFloat {
 
     fn hypot(self, other: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).hypot(other.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.hypot(other.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.hypot(other.to_f64().unwrap())),
         }
@@ -541,6 +610,7 @@ impl Float for ArbitraryFloat {
 
     fn sin(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).sin() as f16),
             Self::F32(fp) => Self::F32(fp.sin()),
             Self::F64(fp) => Self::F64(fp.sin()),
         }
@@ -548,6 +618,7 @@ impl Float for ArbitraryFloat {
 
     fn cos(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).cos() as f16),
             Self::F32(fp) => Self::F32(fp.cos()),
             Self::F64(fp) => Self::F64(fp.cos()),
         }
@@ -555,6 +626,7 @@ impl Float for ArbitraryFloat {
 
     fn tan(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).tan() as f16),
             Self::F32(fp) => Self::F32(fp.tan()),
             Self::F64(fp) => Self::F64(fp.tan()),
         }
@@ -562,6 +634,7 @@ impl Float for ArbitraryFloat {
 
     fn asin(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).asin() as f16),
             Self::F32(fp) => Self::F32(fp.asin()),
             Self::F64(fp) => Self::F64(fp.asin()),
         }
@@ -569,6 +642,7 @@ impl Float for ArbitraryFloat {
 
     fn acos(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).acos() as f16),
             Self::F32(fp) => Self::F32(fp.acos()),
             Self::F64(fp) => Self::F64(fp.acos()),
         }
@@ -576,6 +650,7 @@ impl Float for ArbitraryFloat {
 
     fn atan(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).atan() as f16),
             Self::F32(fp) => Self::F32(fp.atan()),
             Self::F64(fp) => Self::F64(fp.atan()),
         }
@@ -583,6 +658,7 @@ impl Float for ArbitraryFloat {
 
     fn atan2(self, other: Self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).atan2(other.to_f32().unwrap()) as f16),
             Self::F32(fp) => Self::F32(fp.atan2(other.to_f32().unwrap())),
             Self::F64(fp) => Self::F64(fp.atan2(other.to_f64().unwrap())),
         }
@@ -590,6 +666,10 @@ impl Float for ArbitraryFloat {
 
     fn sin_cos(self) -> (Self, Self) {
         match self {
+            Self::F16(fp) => {
+                let (sin, cos) = (fp as f32).sin_cos();
+                (Self::F16(sin as f16), Self::F16(cos as f16))
+            }
             Self::F32(fp) => {
                 let (sin, cos) = fp.sin_cos();
                 (Self::F32(sin), Self::F32(cos))
@@ -603,6 +683,7 @@ impl Float for ArbitraryFloat {
 
     fn exp_m1(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).exp_m1() as f16),
             Self::F32(fp) => Self::F32(fp.exp_m1()),
             Self::F64(fp) => Self::F64(fp.exp_m1()),
         }
@@ -610,6 +691,7 @@ impl Float for ArbitraryFloat {
 
     fn ln_1p(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).ln_1p() as f16),
             Self::F32(fp) => Self::F32(fp.ln_1p()),
             Self::F64(fp) => Self::F64(fp.ln_1p()),
         }
@@ -617,6 +699,7 @@ impl Float for ArbitraryFloat {
 
     fn sinh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).sinh() as f16),
             Self::F32(fp) => Self::F32(fp.sinh()),
             Self::F64(fp) => Self::F64(fp.sinh()),
         }
@@ -624,6 +707,7 @@ impl Float for ArbitraryFloat {
 
     fn cosh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).cosh() as f16),
             Self::F32(fp) => Self::F32(fp.cosh()),
             Self::F64(fp) => Self::F64(fp.cosh()),
         }
@@ -631,6 +715,7 @@ impl Float for ArbitraryFloat {
 
     fn tanh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).tanh() as f16),
             Self::F32(fp) => Self::F32(fp.tanh()),
             Self::F64(fp) => Self::F64(fp.tanh()),
         }
@@ -638,6 +723,7 @@ impl Float for ArbitraryFloat {
 
     fn asinh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).asinh() as f16),
             Self::F32(fp) => Self::F32(fp.asinh()),
             Self::F64(fp) => Self::F64(fp.asinh()),
         }
@@ -645,6 +731,7 @@ impl Float for ArbitraryFloat {
 
     fn acosh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).acosh() as f16),
             Self::F32(fp) => Self::F32(fp.acosh()),
             Self::F64(fp) => Self::F64(fp.acosh()),
         }
@@ -652,6 +739,7 @@ impl Float for ArbitraryFloat {
 
     fn atanh(self) -> Self {
         match self {
+            Self::F16(fp) => Self::F16((fp as f32).atanh() as f16),
             Self::F32(fp) => Self::F32(fp.atanh()),
             Self::F64(fp) => Self::F64(fp.atanh()),
         }
@@ -659,6 +747,7 @@ impl Float for ArbitraryFloat {
 
     fn integer_decode(self) -> (u64, i16, i8) {
         match self {
+            Self::F16(fp) => (fp as f32).integer_decode(),
             Self::F32(fp) => fp.integer_decode(),
             Self::F64(fp) => fp.integer_decode(),
         }
@@ -670,6 +759,7 @@ impl Float for ArbitraryFloat {
 
     fn is_subnormal(self) -> bool {
         match self {
+            Self::F16(fp) => fp.is_subnormal(),
             Self::F32(fp) => fp.is_subnormal(),
             Self::F64(fp) => fp.is_subnormal(),
         }
@@ -0,0 +1,497 @@
+use std::{collections::BTreeMap, fmt, ops::Range, rc::Rc};
+
+use super::{history::Diff, registers::Registers, trace::MemoryAccess, trap::Trap};
+
+/// Condition on the register state, e.g. `|r| r.x[A0] == 0`
+#[derive(Clone)]
+pub struct Predicate(Rc<dyn Fn(&Registers) -> bool>);
+
+impl Predicate {
+    pub fn new(predicate: impl Fn(&Registers) -> bool + 'static) -> Self {
+        Self(Rc::new(predicate))
+    }
+
+    pub fn holds(&self, registers: &Registers) -> bool {
+        (self.0)(registers)
+    }
+}
+
+impl fmt::Debug for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Predicate")
+    }
+}
+
+// Closures can't be compared, only clones of the same predicate are equal
+impl PartialEq for Predicate {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Breakpoint {
+    pub pc: u64,
+    /// Breakpoint only fires when the condition holds, unconditional when there's none
+    pub condition: Option<Predicate>,
+}
+
+/// Register a watchpoint observes, CSRs are addressed by their number
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RegisterRef {
+    X(usize),
+    F(usize),
+    V(usize),
+    Csr(usize),
+}
+
+/// Accesses a memory watchpoint fires on
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Memory is watched by virtual address, registers are only watched for writes
+#[derive(Clone, PartialEq, Debug)]
+pub enum Watchpoint {
+    Memory { range: Range<u64>, kind: WatchKind },
+    Register(RegisterRef),
+}
+
+/// What fired a watchpoint
+#[derive(Clone, PartialEq, Debug)]
+pub enum WatchHit {
+    Memory(MemoryAccess),
+    Register(RegisterRef),
+}
+
+/// Extra place to stop at on top of breakpoints and watchpoints
+#[derive(Clone, PartialEq, Debug)]
+pub enum StopCondition {
+    /// Runs until the program ends
+    End,
+    /// Stops before the instruction at the address runs
+    Pc(u64),
+    /// Stops after the given number of steps
+    Steps(usize),
+    /// Stops once a step leaves the registers satisfying the predicate
+    Registers(Predicate),
+}
+
+/// Why `RvCore::run_until` returned
+#[derive(Clone, PartialEq, Debug)]
+pub enum StopReason {
+    /// Breakpoint with the given id is at pc, its instruction hasn't run yet
+    Breakpoint(usize),
+    /// Instruction that just ran triggered the watchpoint with the given id
+    Watchpoint { id: usize, hit: WatchHit },
+    /// Stop condition was met
+    Condition,
+    /// Trap without a handler
+    Trap(Trap),
+    /// Program exited or pc left the text section
+    Finished,
+}
+
+/// Breakpoints and watchpoints of a core, each one gets an id to remove it by
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Debugger {
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    watchpoints: BTreeMap<usize, Watchpoint>,
+    next_id: usize,
+    /// First watchpoint the last step triggered
+    hit: Option<(usize, WatchHit)>,
+}
+
+impl Debugger {
+    pub fn add_breakpoint(&mut self, pc: u64) -> usize {
+        self.insert_breakpoint(Breakpoint {
+            pc,
+            condition: None,
+        })
+    }
+
+    pub fn add_conditional_breakpoint(&mut self, pc: u64, condition: Predicate) -> usize {
+        self.insert_breakpoint(Breakpoint {
+            pc,
+            condition: Some(condition),
+        })
+    }
+
+    pub fn watch_memory(&mut self, range: Range<u64>, kind: WatchKind) -> usize {
+        self.insert_watchpoint(Watchpoint::Memory { range, kind })
+    }
+
+    pub fn watch_register(&mut self, register: RegisterRef) -> usize {
+        self.insert_watchpoint(Watchpoint::Register(register))
+    }
+
+    /// Removes a breakpoint or watchpoint, returns false when the id is unknown
+    pub fn remove(&mut self, id: usize) -> bool {
+        self.breakpoints.remove(&id).is_some() || self.watchpoints.remove(&id).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = (usize, &Breakpoint)> {
+        self.breakpoints
+            .iter()
+            .map(|(id, breakpoint)| (*id, breakpoint))
+    }
+
+    pub fn watchpoints(&self) -> impl Iterator<Item = (usize, &Watchpoint)> {
+        self.watchpoints
+            .iter()
+            .map(|(id, watchpoint)| (*id, watchpoint))
+    }
+
+    /// First breakpoint at pc whose condition holds
+    pub fn breakpoint_at(&self, registers: &Registers) -> Option<usize> {
+        self.breakpoints
+            .iter()
+            .find(|(_, breakpoint)| {
+                breakpoint.pc == registers.pc
+                    && breakpoint
+                        .condition
+                        .as_ref()
+                        .is_none_or(|condition| condition.holds(registers))
+            })
+            .map(|(id, _)| *id)
+    }
+
+    pub(super) fn is_watching(&self) -> bool {
+        !self.watchpoints.is_empty()
+    }
+
+    /// Checks the writes and accesses of a step against the watchpoints, `registers` are the ones after the step
+    pub(super) fn observe(
+        &mut self,
+        registers: &Registers,
+        diff: &Diff,
+        accesses: &[MemoryAccess],
+    ) {
+        self.hit = self.watchpoints.iter().find_map(|(id, watchpoint)| {
+            let hit = match watchpoint {
+                Watchpoint::Memory { range, kind } => accesses
+                    .iter()
+                    .find(|access| watched(access, range, *kind))
+                    .cloned()
+                    .map(WatchHit::Memory),
+                Watchpoint::Register(register) => {
+                    let written = |journal: &[(usize, u64)], index: usize| {
+                        journal.iter().any(|(written, _)| *written == index)
+                    };
+
+                    let hit = match *register {
+                        // x0 slot swallows discarded writes
+                        RegisterRef::X(0) => false,
+                        RegisterRef::X(index) => written(&diff.x, index),
+                        RegisterRef::F(index) => written(&diff.f, index),
+                        RegisterRef::Csr(csr) => csr_written(registers, &diff.c, csr),
+                        RegisterRef::V(index) => diff.v.iter().any(|(nth, _)| *nth == index),
+                    };
+
+                    hit.then_some(WatchHit::Register(*register))
+                }
+            };
+
+            hit.map(|hit| (*id, hit))
+        });
+    }
+
+    pub(super) fn take_hit(&mut self) -> Option<(usize, WatchHit)> {
+        self.hit.take()
+    }
+
+    fn insert_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        let id = self.next_id();
+        self.breakpoints.insert(id, breakpoint);
+
+        id
+    }
+
+    fn insert_watchpoint(&mut self, watchpoint: Watchpoint) -> usize {
+        let id = self.next_id();
+        self.watchpoints.insert(id, watchpoint);
+
+        id
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+
+        self.next_id
+    }
+}
+
+/// Views fire once a write to their backing register changes the fields they show
+fn csr_written(registers: &Registers, journal: &[(usize, u64)], csr: usize) -> bool {
+    registers
+        .c
+        .backing(csr)
+        .into_iter()
+        .any(|(backing, fields)| {
+            journal.iter().any(|(written, old)| {
+                *written == backing
+                    && (fields == u64::MAX || (old ^ registers.c[backing].read()) & fields != 0)
+            })
+        })
+}
+
+fn watched(access: &MemoryAccess, range: &Range<u64>, kind: WatchKind) -> bool {
+    let (address, data, matches) = match access {
+        MemoryAccess::Load { address, data } => (address, data, kind != WatchKind::Write),
+        MemoryAccess::Store { address, data } => (address, data, kind != WatchKind::Read),
+    };
+
+    let end = address.saturating_add(data.len() as u64);
+
+    matches && *address < range.end && range.start < end
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rv_core::{
+        instruction::{
+            format::{self, Csri, Opivi, I, S, U},
+            Instruction::*,
+        },
+        memory::Memory,
+        registers::aliases::csr::{FCSR, FFLAGS, FRM, MSTATUS, SSTATUS},
+        trap::Exception,
+        vector_engine::sew::BaseSew,
+        RvCoreBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn run_until_reports_what_fired() {
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 3,
+            }),
+            Sd(S {
+                rs1: 0,
+                rs2: 5,
+                imm12: 0x10,
+            }),
+            Ld(I {
+                rd: 6,
+                rs1: 0,
+                imm12: 0x14,
+            }),
+            Addi(I {
+                rd: 5,
+                rs1: 5,
+                imm12: 1,
+            }),
+            Addi(I {
+                rd: 7,
+                rs1: 5,
+                imm12: 0,
+            }),
+        ])
+        .unwrap();
+
+        let breakpoint = core.debugger.add_breakpoint(0x104);
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Breakpoint(breakpoint)
+        );
+
+        // Loads don't fire write watchpoints, stores do
+        let write = core.debugger.watch_memory(0x14..0x20, WatchKind::Write);
+        let read = core.debugger.watch_memory(0x18..0x20, WatchKind::Read);
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: write,
+                hit: WatchHit::Memory(MemoryAccess::Store {
+                    address: 0x10,
+                    data: 3u64.to_le_bytes().to_vec(),
+                }),
+            }
+        );
+        assert_eq!(core.registers.pc, 0x108);
+
+        assert!(matches!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint { id, .. } if id == read
+        ));
+        core.debugger.clear();
+
+        let register = core.debugger.watch_register(RegisterRef::X(5));
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: register,
+                hit: WatchHit::Register(RegisterRef::X(5)),
+            }
+        );
+        assert!(core.debugger.remove(register));
+        assert!(!core.debugger.remove(register));
+
+        assert_eq!(core.run_until(StopCondition::End), StopReason::Finished);
+        assert_eq!(core.registers.x[7], 4);
+
+        // Conditional breakpoints only fire once their predicate holds
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 5,
+                imm12: 1,
+            }),
+            Jal(U { rd: 0, imm20: -4 }),
+        ])
+        .unwrap();
+        core.registers.x[5] = 0;
+        core.debugger
+            .add_conditional_breakpoint(0x100, Predicate::new(|registers| registers.x[5] == 3));
+        assert!(matches!(
+            core.run_until(StopCondition::End),
+            StopReason::Breakpoint(_)
+        ));
+        assert_eq!(core.registers.x[5], 3);
+
+        core.debugger.clear();
+        assert_eq!(
+            core.run_until(StopCondition::Steps(4)),
+            StopReason::Condition
+        );
+        assert_eq!(core.registers.x[5], 5);
+        assert_eq!(
+            core.run_until(StopCondition::Registers(Predicate::new(|registers| {
+                registers.x[5] == 10
+            }))),
+            StopReason::Condition
+        );
+        assert_eq!(
+            core.run_until(StopCondition::Pc(0x104)),
+            StopReason::Condition
+        );
+        assert_eq!(core.registers.x[5], 11);
+    }
+
+    #[test]
+    fn csr_views_are_watched_through_their_backing_register() {
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Csrrwi(Csri {
+                rd: 0,
+                uimm: 1,
+                csr: FFLAGS,
+            }),
+            Csrrwi(Csri {
+                rd: 0,
+                uimm: 2,
+                csr: FRM,
+            }),
+            Csrrsi(Csri {
+                rd: 0,
+                uimm: 2,
+                csr: SSTATUS,
+            }),
+        ])
+        .unwrap();
+
+        let frm = core.debugger.watch_register(RegisterRef::Csr(FRM));
+        let fflags = core.debugger.watch_register(RegisterRef::Csr(FFLAGS));
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: fflags,
+                hit: WatchHit::Register(RegisterRef::Csr(FFLAGS)),
+            }
+        );
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: frm,
+                hit: WatchHit::Register(RegisterRef::Csr(FRM)),
+            }
+        );
+        core.debugger.clear();
+
+        let fcsr = core.debugger.watch_register(RegisterRef::Csr(FCSR));
+        let mstatus = core.debugger.watch_register(RegisterRef::Csr(MSTATUS));
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: mstatus,
+                hit: WatchHit::Register(RegisterRef::Csr(MSTATUS)),
+            }
+        );
+        assert!(core.debugger.remove(fcsr));
+    }
+
+    #[test]
+    fn vector_watchpoints_only_see_active_elements() {
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            // e8, m1, ta, ma with vl = 0, then vl = 4
+            Vsetivli(format::Vsetivli {
+                rd: 0,
+                uimm: 0,
+                vtypei: 0b1100_0000,
+            }),
+            Vaddvi(Opivi {
+                dest: 1,
+                imm5: 5,
+                vs2: 0,
+                vm: false,
+            }),
+            Vsetivli(format::Vsetivli {
+                rd: 0,
+                uimm: 4,
+                vtypei: 0b1100_0000,
+            }),
+            Vlv {
+                data: format::Vl {
+                    vd: 1,
+                    rs1: 0,
+                    vm: true,
+                },
+                eew: BaseSew::E8,
+            },
+            Ld(I {
+                rd: 5,
+                rs1: 0,
+                imm12: -8,
+            }),
+        ])
+        .unwrap();
+        core.registers.v.bytes_mut(0..1)[0] = 0b0101;
+
+        // Element 1 is masked off and never read
+        core.debugger.watch_memory(1..2, WatchKind::Read);
+        let v1 = core.debugger.watch_register(RegisterRef::V(1));
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Watchpoint {
+                id: v1,
+                hit: WatchHit::Register(RegisterRef::V(1)),
+            }
+        );
+        assert_eq!(core.registers.pc, 0x110);
+
+        assert_eq!(
+            core.run_until(StopCondition::End),
+            StopReason::Trap(Exception::LoadAccessFault(u64::MAX - 7).at(0x110))
+        );
+    }
+}
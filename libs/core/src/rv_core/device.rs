@@ -0,0 +1,27 @@
+mod clint;
+mod uart;
+
+use std::{cell::RefCell, fmt, rc::Rc};
+
+pub use clint::Clint;
+pub use uart::Uart16550;
+
+/// Peripheral attached to the bus at a memory region, see `Memory::attach`.
+/// Loads and stores falling into the region are split into single bytes and handed to the device.
+pub trait Device: fmt::Debug {
+    /// Reads the byte at `offset` from the start of the region, reads may have side effects such as popping a FIFO
+    fn read(&mut self, offset: u64) -> u8;
+
+    fn write(&mut self, offset: u64, value: u8);
+
+    /// Advances the device by one step of the core
+    fn tick(&mut self) {}
+
+    /// mip bits the device currently raises
+    fn interrupts(&self) -> u64 {
+        0
+    }
+}
+
+/// Device shared between the bus and the embedder, who can keep a handle to feed it input or inspect its output
+pub type SharedDevice = Rc<RefCell<dyn Device>>;
@@ -0,0 +1,102 @@
+use super::Device;
+use crate::rv_core::registers::aliases::interrupt;
+
+/// Core-local interruptor of a single hart, laid out like SiFive's CLINT.
+/// `mtime` advances by one every step, a timer interrupt is pending while it's at least `mtimecmp`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Clint {
+    pub msip: bool,
+    pub mtimecmp: u64,
+    pub mtime: u64,
+}
+
+impl Clint {
+    /// Base address on QEMU's virt machine
+    pub const BASE: u64 = 0x200_0000;
+    pub const SIZE: u64 = 0x1_0000;
+
+    pub const MSIP: u64 = 0x0;
+    pub const MTIMECMP: u64 = 0x4000;
+    pub const MTIME: u64 = 0xbff8;
+}
+
+impl Default for Clint {
+    fn default() -> Self {
+        Self {
+            msip: false,
+            mtimecmp: u64::MAX,
+            mtime: 0,
+        }
+    }
+}
+
+impl Device for Clint {
+    fn read(&mut self, offset: u64) -> u8 {
+        match offset {
+            Self::MSIP => self.msip as u8,
+            Self::MTIMECMP..=0x4007 => {
+                self.mtimecmp.to_le_bytes()[(offset - Self::MTIMECMP) as usize]
+            }
+            Self::MTIME..=0xbfff => self.mtime.to_le_bytes()[(offset - Self::MTIME) as usize],
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u64, value: u8) {
+        match offset {
+            Self::MSIP => self.msip = value & 1 != 0,
+            Self::MTIMECMP..=0x4007 => {
+                replace_byte(&mut self.mtimecmp, offset - Self::MTIMECMP, value)
+            }
+            Self::MTIME..=0xbfff => replace_byte(&mut self.mtime, offset - Self::MTIME, value),
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self) {
+        self.mtime = self.mtime.wrapping_add(1);
+    }
+
+    fn interrupts(&self) -> u64 {
+        let software = if self.msip { interrupt::MSI } else { 0 };
+        let timer = if self.mtime >= self.mtimecmp {
+            interrupt::MTI
+        } else {
+            0
+        };
+
+        software | timer
+    }
+}
+
+fn replace_byte(register: &mut u64, index: u64, value: u8) {
+    let mut bytes = register.to_le_bytes();
+    bytes[index as usize] = value;
+    *register = u64::from_le_bytes(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_fires_at_mtimecmp() {
+        let mut clint = Clint::default();
+
+        for (index, byte) in 3u64.to_le_bytes().into_iter().enumerate() {
+            clint.write(Clint::MTIMECMP + index as u64, byte);
+        }
+        assert_eq!(clint.mtimecmp, 3);
+
+        clint.tick();
+        clint.tick();
+        assert_eq!(clint.interrupts(), 0);
+
+        clint.tick();
+        assert_eq!(clint.interrupts(), interrupt::MTI);
+        assert_eq!(clint.read(Clint::MTIME), 3);
+
+        clint.write(Clint::MSIP, 1);
+        assert_eq!(clint.interrupts(), interrupt::MSI | interrupt::MTI);
+    }
+}
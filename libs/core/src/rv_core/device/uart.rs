@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+
+use super::Device;
+use crate::rv_core::registers::aliases::interrupt;
+
+/// 16550 compatible serial port with FIFOs, bytes are transmitted the moment they're written.
+/// Both of its interrupts are wired straight to the machine external interrupt, there's no interrupt controller in between.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Uart16550 {
+    /// Everything the program transmitted so far
+    pub output: Vec<u8>,
+    /// Bytes waiting to be received by the program
+    pub input: VecDeque<u8>,
+    ier: u8,
+    lcr: u8,
+    mcr: u8,
+    scr: u8,
+    divisor: u16,
+    /// Transmitter empty interrupt, cleared by reading IIR or writing THR
+    thre_pending: bool,
+}
+
+impl Uart16550 {
+    /// Base address on QEMU's virt machine
+    pub const BASE: u64 = 0x1000_0000;
+    pub const SIZE: u64 = 0x8;
+
+    pub const RBR: u64 = 0;
+    pub const THR: u64 = 0;
+    pub const IER: u64 = 1;
+    pub const IIR: u64 = 2;
+    pub const FCR: u64 = 2;
+    pub const LCR: u64 = 3;
+    pub const MCR: u64 = 4;
+    pub const LSR: u64 = 5;
+    pub const MSR: u64 = 6;
+    pub const SCR: u64 = 7;
+
+    const IER_RDA: u8 = 1 << 0;
+    const IER_THRE: u8 = 1 << 1;
+    const LCR_DLAB: u8 = 1 << 7;
+    const LSR_DR: u8 = 1 << 0;
+    const LSR_THRE: u8 = 1 << 5;
+    const LSR_TEMT: u8 = 1 << 6;
+
+    /// Text the program transmitted so far
+    pub fn output_string(&self) -> String {
+        String::from_utf8_lossy(&self.output).into_owned()
+    }
+
+    fn dlab(&self) -> bool {
+        self.lcr & Self::LCR_DLAB != 0
+    }
+
+    fn receive_pending(&self) -> bool {
+        self.ier & Self::IER_RDA != 0 && !self.input.is_empty()
+    }
+
+    fn transmit_pending(&self) -> bool {
+        self.ier & Self::IER_THRE != 0 && self.thre_pending
+    }
+}
+
+impl Device for Uart16550 {
+    fn read(&mut self, offset: u64) -> u8 {
+        match offset {
+            Self::RBR if self.dlab() => self.divisor as u8,
+            Self::RBR => self.input.pop_front().unwrap_or(0),
+            Self::IER if self.dlab() => (self.divisor >> 8) as u8,
+            Self::IER => self.ier,
+            Self::IIR => {
+                // Top bits report enabled FIFOs
+                let id = if self.receive_pending() {
+                    0b0100
+                } else if self.transmit_pending() {
+                    self.thre_pending = false;
+                    0b0010
+                } else {
+                    0b0001
+                };
+
+                0xc0 | id
+            }
+            Self::LCR => self.lcr,
+            Self::MCR => self.mcr,
+            Self::LSR => {
+                let data_ready = if self.input.is_empty() {
+                    0
+                } else {
+                    Self::LSR_DR
+                };
+
+                data_ready | Self::LSR_THRE | Self::LSR_TEMT
+            }
+            Self::SCR => self.scr,
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u64, value: u8) {
+        match offset {
+            Self::THR if self.dlab() => self.divisor = self.divisor & 0xff00 | value as u16,
+            Self::THR => {
+                self.output.push(value);
+                self.thre_pending = true;
+            }
+            Self::IER if self.dlab() => self.divisor = self.divisor & 0x00ff | (value as u16) << 8,
+            Self::IER => {
+                // Enabling the interrupt raises it right away, the holding register is always empty
+                if value & !self.ier & Self::IER_THRE != 0 {
+                    self.thre_pending = true;
+                }
+                self.ier = value & 0x0f;
+            }
+            Self::FCR => {
+                // Receiver FIFO reset
+                if value & 0b10 != 0 {
+                    self.input.clear();
+                }
+            }
+            Self::LCR => self.lcr = value,
+            Self::MCR => self.mcr = value & 0x1f,
+            Self::SCR => self.scr = value,
+            _ => {}
+        }
+    }
+
+    fn interrupts(&self) -> u64 {
+        if self.receive_pending() || self.transmit_pending() {
+            interrupt::MEI
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_are_transmitted_and_received() {
+        let mut uart = Uart16550::default();
+        uart.input.extend(b"ok");
+
+        for byte in b"hi\n" {
+            uart.write(Uart16550::THR, *byte);
+        }
+        assert_eq!(uart.output_string(), "hi\n");
+
+        assert_eq!(uart.read(Uart16550::LSR) & 1, 1);
+        assert_eq!(uart.read(Uart16550::RBR), b'o');
+        assert_eq!(uart.read(Uart16550::RBR), b'k');
+        assert_eq!(uart.read(Uart16550::LSR), 0x60);
+
+        // Divisor latch shadows the data registers
+        uart.write(Uart16550::LCR, 0x80);
+        uart.write(Uart16550::THR, 0x03);
+        assert_eq!(uart.read(Uart16550::RBR), 0x03);
+        assert_eq!(uart.output_string(), "hi\n");
+    }
+
+    #[test]
+    fn interrupts_follow_ier() {
+        let mut uart = Uart16550::default();
+        uart.input.push_back(b'x');
+        assert_eq!(uart.interrupts(), 0);
+
+        uart.write(Uart16550::IER, 0b11);
+        assert_eq!(uart.interrupts(), interrupt::MEI);
+        assert_eq!(uart.read(Uart16550::IIR), 0xc4);
+
+        uart.read(Uart16550::RBR);
+        assert_eq!(uart.read(Uart16550::IIR), 0xc2);
+        assert_eq!(uart.read(Uart16550::IIR), 0xc1);
+        assert_eq!(uart.interrupts(), 0);
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const EM_RISCV: u16 = 0xf3;
+
+const PT_LOAD: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+
+const PROGRAM_HEADER_SIZE: usize = 56;
+const SECTION_HEADER_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SegmentFlags {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl From<u32> for SegmentFlags {
+    fn from(flags: u32) -> Self {
+        Self {
+            execute: flags & 0b001 != 0,
+            write: flags & 0b010 != 0,
+            read: flags & 0b100 != 0,
+        }
+    }
+}
+
+/// PT_LOAD segment, bytes past `data` up to `mem_size` are zero-filled (.bss)
+#[derive(Clone, PartialEq, Debug)]
+pub struct Segment {
+    pub vaddr: u64,
+    pub mem_size: u64,
+    pub flags: SegmentFlags,
+    pub data: Vec<u8>,
+}
+
+impl Segment {
+    /// Saturates for segments built by hand, parsed ones never wrap around
+    pub fn end(&self) -> u64 {
+        self.vaddr.saturating_add(self.mem_size)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Elf {
+    pub entry: u64,
+    pub segments: Vec<Segment>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Elf {
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let reader = Reader(bytes);
+
+        if reader.bytes::<4>(0)? != ELF_MAGIC {
+            return Err("Not an ELF file".to_owned());
+        }
+
+        if reader.u8(4)? != ELFCLASS64 {
+            return Err("Only ELF64 files are supported".to_owned());
+        }
+
+        if reader.u8(5)? != ELFDATA2LSB {
+            return Err("Only little-endian ELF files are supported".to_owned());
+        }
+
+        let machine = reader.u16(0x12)?;
+        if machine != EM_RISCV {
+            return Err(format!("Unsupported ELF machine: {:#x}", machine));
+        }
+
+        let entry = reader.u64(0x18)?;
+        let program_headers_offset = reader.u64(0x20)? as usize;
+        let section_headers_offset = reader.u64(0x28)? as usize;
+        let program_headers_count = reader.u16(0x38)? as usize;
+        let section_headers_count = reader.u16(0x3c)? as usize;
+
+        let mut segments = Vec::new();
+
+        for index in 0..program_headers_count {
+            let header = reader.entry(program_headers_offset, index, PROGRAM_HEADER_SIZE)?;
+
+            if header.u32(0)? != PT_LOAD {
+                continue;
+            }
+
+            let flags = header.u32(4)?;
+            let offset = header.u64(8)? as usize;
+            let vaddr = header.u64(16)?;
+            let file_size = header.u64(32)? as usize;
+            let mem_size = header.u64(40)?;
+
+            if file_size as u64 > mem_size {
+                return Err(format!(
+                    "Segment at {:#x} is larger in file than in memory",
+                    vaddr
+                ));
+            }
+
+            if vaddr.checked_add(mem_size).is_none() {
+                return Err(format!(
+                    "Segment at {:#x} runs past the end of the address space",
+                    vaddr
+                ));
+            }
+
+            segments.push(Segment {
+                vaddr,
+                mem_size,
+                flags: flags.into(),
+                data: reader.slice(offset, file_size)?.to_vec(),
+            });
+        }
+
+        let mut symbols = Vec::new();
+
+        for index in 0..section_headers_count {
+            let header = reader.entry(section_headers_offset, index, SECTION_HEADER_SIZE)?;
+
+            if header.u32(4)? != SHT_SYMTAB {
+                continue;
+            }
+
+            let offset = header.u64(24)? as usize;
+            let size = header.u64(32)? as usize;
+            let link = header.u32(40)? as usize;
+
+            let strtab_header = reader.entry(section_headers_offset, link, SECTION_HEADER_SIZE)?;
+            let strtab_offset = strtab_header.u64(24)? as usize;
+            let strtab_size = strtab_header.u64(32)? as usize;
+            let strtab = reader.slice(strtab_offset, strtab_size)?;
+
+            // Entry 0 is always the undefined symbol
+            for index in 1..size / SYMBOL_SIZE {
+                let symbol = reader.entry(offset, index, SYMBOL_SIZE)?;
+                let name_offset = symbol.u32(0)? as usize;
+                let name = strtab
+                    .get(name_offset..)
+                    .and_then(|name| name.split(|&byte| byte == 0).next())
+                    .ok_or_else(|| format!("Invalid symbol name offset: {}", name_offset))?;
+
+                if name.is_empty() {
+                    continue;
+                }
+
+                symbols.push(Symbol {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    value: symbol.u64(8)?,
+                    size: symbol.u64(16)?,
+                });
+            }
+        }
+
+        Ok(Self {
+            entry,
+            segments,
+            symbols,
+        })
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    pub fn symbol_table(&self) -> HashMap<String, u64> {
+        self.symbols
+            .iter()
+            .map(|symbol| (symbol.name.clone(), symbol.value))
+            .collect()
+    }
+
+    /// First address past the highest loaded segment
+    pub fn end(&self) -> u64 {
+        self.segments.iter().map(Segment::end).max().unwrap_or(0)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn slice(&self, offset: usize, length: usize) -> Result<&[u8], String> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.0.get(offset..end))
+            .ok_or_else(|| format!("Truncated ELF file at offset {:#x}", offset))
+    }
+
+    /// Entry `index` of a table of `size` byte entries starting at `offset`
+    fn entry(&self, offset: usize, index: usize, size: usize) -> Result<Reader<'_>, String> {
+        index
+            .checked_mul(size)
+            .and_then(|start| offset.checked_add(start))
+            .ok_or_else(|| format!("ELF table at {:#x} runs past the end of the file", offset))
+            .and_then(|start| self.slice(start, size))
+            .map(Reader)
+    }
+
+    fn bytes<const BYTES: usize>(&self, offset: usize) -> Result<[u8; BYTES], String> {
+        Ok(self.slice(offset, BYTES)?.try_into().unwrap())
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, String> {
+        Ok(self.bytes::<1>(offset)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.bytes(offset)?))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Minimal RISC-V ELF64 with a single RX segment, a .bss tail and a symbol table
+    pub(crate) fn sample_elf(vaddr: u64, entry: u64, text: &[u8], mem_size: u64) -> Vec<u8> {
+        let strtab = b"\0_start\0buffer\0";
+        let text_offset = 0x40 + PROGRAM_HEADER_SIZE;
+        let strtab_offset = text_offset + text.len();
+        let symtab_offset = strtab_offset + strtab.len();
+        let section_headers_offset = symtab_offset + 3 * SYMBOL_SIZE;
+
+        let mut elf = vec![0; section_headers_offset + 3 * SECTION_HEADER_SIZE];
+
+        let mut put = |offset: usize, bytes: &[u8]| {
+            elf[offset..offset + bytes.len()].copy_from_slice(bytes);
+        };
+
+        put(0, &ELF_MAGIC);
+        put(4, &[ELFCLASS64, ELFDATA2LSB, 1]);
+        put(0x10, &2u16.to_le_bytes());
+        put(0x12, &EM_RISCV.to_le_bytes());
+        put(0x18, &entry.to_le_bytes());
+        put(0x20, &0x40u64.to_le_bytes());
+        put(0x28, &(section_headers_offset as u64).to_le_bytes());
+        put(0x36, &(PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+        put(0x38, &1u16.to_le_bytes());
+        put(0x3a, &(SECTION_HEADER_SIZE as u16).to_le_bytes());
+        put(0x3c, &3u16.to_le_bytes());
+
+        put(0x40, &PT_LOAD.to_le_bytes());
+        put(0x44, &0b101u32.to_le_bytes());
+        put(0x48, &(text_offset as u64).to_le_bytes());
+        put(0x50, &vaddr.to_le_bytes());
+        put(0x60, &(text.len() as u64).to_le_bytes());
+        put(0x68, &mem_size.to_le_bytes());
+
+        put(text_offset, text);
+        put(strtab_offset, strtab);
+
+        put(symtab_offset + SYMBOL_SIZE, &1u32.to_le_bytes());
+        put(symtab_offset + SYMBOL_SIZE + 8, &entry.to_le_bytes());
+        put(symtab_offset + 2 * SYMBOL_SIZE, &8u32.to_le_bytes());
+        put(
+            symtab_offset + 2 * SYMBOL_SIZE + 8,
+            &(vaddr + text.len() as u64).to_le_bytes(),
+        );
+        put(symtab_offset + 2 * SYMBOL_SIZE + 16, &16u64.to_le_bytes());
+
+        let strtab_header = section_headers_offset + SECTION_HEADER_SIZE;
+        put(strtab_header + 4, &3u32.to_le_bytes());
+        put(strtab_header + 24, &(strtab_offset as u64).to_le_bytes());
+        put(strtab_header + 32, &(strtab.len() as u64).to_le_bytes());
+
+        let symtab_header = section_headers_offset + 2 * SECTION_HEADER_SIZE;
+        put(symtab_header + 4, &SHT_SYMTAB.to_le_bytes());
+        put(symtab_header + 24, &(symtab_offset as u64).to_le_bytes());
+        put(symtab_header + 32, &(3 * SYMBOL_SIZE as u64).to_le_bytes());
+        put(symtab_header + 40, &1u32.to_le_bytes());
+
+        elf
+    }
+
+    #[test]
+    fn parsing_works() {
+        let elf = Elf::parse(&sample_elf(0x1000, 0x1004, &[1, 2, 3, 4, 5, 6, 7, 8], 0x20)).unwrap();
+
+        assert_eq!(elf.entry, 0x1004);
+        assert_eq!(
+            elf.segments,
+            vec![Segment {
+                vaddr: 0x1000,
+                mem_size: 0x20,
+                flags: SegmentFlags {
+                    read: true,
+                    write: false,
+                    execute: true
+                },
+                data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+            }]
+        );
+        assert_eq!(elf.end(), 0x1020);
+        assert_eq!(elf.symbol("_start").unwrap().value, 0x1004);
+        assert_eq!(
+            elf.symbol("buffer"),
+            Some(&Symbol {
+                name: "buffer".to_owned(),
+                value: 0x1008,
+                size: 16
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(Elf::parse(b"#!/bin/sh").is_err());
+
+        let mut elf = sample_elf(0x1000, 0x1000, &[0; 4], 4);
+        elf[0x12] = 0x3e;
+
+        assert_eq!(
+            Elf::parse(&elf),
+            Err("Unsupported ELF machine: 0x3e".to_owned())
+        );
+    }
+
+    #[test]
+    fn overflowing_offsets_are_rejected() {
+        let sample = sample_elf(0x1000, 0x1000, &[0; 4], 4);
+
+        let mut elf = sample.clone();
+        elf[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Elf::parse(&elf).is_err());
+
+        let mut elf = sample.clone();
+        elf[0x50..0x58].copy_from_slice(&(u64::MAX - 2).to_le_bytes());
+        assert_eq!(
+            Elf::parse(&elf),
+            Err("Segment at 0xfffffffffffffffd runs past the end of the address space".to_owned())
+        );
+
+        // Symbol table reaching past the end of the address space
+        let symtab_header = sample.len() - SECTION_HEADER_SIZE;
+        let mut elf = sample;
+        elf[symtab_header + 24..symtab_header + 32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Elf::parse(&elf).is_err());
+    }
+}
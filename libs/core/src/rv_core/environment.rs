@@ -0,0 +1,299 @@
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use super::{
+    memory::{Permissions, Region},
+    registers::aliases::{
+        float::FA0,
+        integer::{A0, A7},
+    },
+    trap::Exception,
+    RvCore,
+};
+
+/// What the core does after handing an ecall to its environment
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EcallOutcome {
+    /// Call was serviced, execution continues after the ecall
+    Resume,
+    /// Program exited with the given code, running stops after the ecall
+    Exit(i32),
+    /// Call is left to the trap handler, or to the embedder when there's none
+    Trap,
+}
+
+/// Services ecalls in place of an operating system
+pub trait EnvironmentHandler: fmt::Debug {
+    /// Called with pc still pointing at the ecall, arguments and results are passed in the core's registers.
+    /// Returned exceptions are raised by the ecall, e.g. a page fault on a string passed by the program.
+    fn ecall(&mut self, core: &mut RvCore) -> Result<EcallOutcome, Exception>;
+}
+
+/// Handler attached to a core, ecalls trap as usual when there's none.
+/// Handler is shared, so the embedder can keep a handle to it and inspect it while the core runs.
+#[derive(Clone, Default, Debug)]
+pub struct Environment(Option<Rc<RefCell<dyn EnvironmentHandler>>>);
+
+impl Environment {
+    pub fn new(handler: impl EnvironmentHandler + 'static) -> Self {
+        Self::from(Rc::new(RefCell::new(handler)))
+    }
+
+    pub fn handler(&self) -> Option<Rc<RefCell<dyn EnvironmentHandler>>> {
+        self.0.clone()
+    }
+}
+
+impl<H: EnvironmentHandler + 'static> From<Rc<RefCell<H>>> for Environment {
+    fn from(handler: Rc<RefCell<H>>) -> Self {
+        Self(Some(handler))
+    }
+}
+
+// Environment lives outside of the hart, cores holding the same state are equal regardless of it
+impl PartialEq for Environment {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// System calls of the RARS and Venus simulators, call number is passed in a7 and arguments from a0.
+/// Calls it doesn't know and reads past the end of input are left to trap,
+/// so the embedder can supply more input and step the ecall again.
+#[derive(Clone, Default, Debug)]
+pub struct RarsEnvironment {
+    /// Everything the program printed so far
+    pub output: String,
+    /// Text consumed by read calls
+    pub input: String,
+    /// Program break, placed at the end of memory by the first sbrk
+    brk: Option<u64>,
+}
+
+impl RarsEnvironment {
+    pub const PRINT_INT: u64 = 1;
+    pub const PRINT_FLOAT: u64 = 2;
+    pub const PRINT_DOUBLE: u64 = 3;
+    pub const PRINT_STRING: u64 = 4;
+    pub const READ_INT: u64 = 5;
+    pub const SBRK: u64 = 9;
+    pub const EXIT: u64 = 10;
+    pub const PRINT_CHAR: u64 = 11;
+    pub const EXIT2: u64 = 17;
+    /// Linux exit, used by programs built with a regular toolchain
+    pub const LINUX_EXIT: u64 = 93;
+
+    fn print_string(&mut self, core: &mut RvCore) -> Result<(), Exception> {
+        let start = core.registers.x[A0];
+        let mmu = core.mmu();
+        let mut bytes = Vec::new();
+
+        for address in start.. {
+            match mmu.get(address as usize)? {
+                [0] => break,
+                [byte] => bytes.push(byte),
+            }
+        }
+
+        self.output.push_str(&String::from_utf8_lossy(&bytes));
+
+        Ok(())
+    }
+
+    fn read_int(&mut self) -> Option<i64> {
+        let input = self.input.trim_start();
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        let value = input[..end].parse().ok()?;
+
+        self.input = input[end..].to_owned();
+
+        Some(value)
+    }
+
+    fn sbrk(&mut self, core: &mut RvCore) -> Option<u64> {
+        let brk = self.brk.unwrap_or(core.memory.len().next_multiple_of(8));
+        let new_brk = brk.checked_add_signed(core.registers.x[A0] as i64)?;
+
+        let end = core.memory.len();
+
+        if new_brk > end {
+            let highest = end
+                .checked_sub(1)
+                .and_then(|last| core.memory.region_at(last));
+
+            // Text may be the highest region, the heap gets a writable one of its own then
+            if highest.is_some_and(|region| !region.permissions.write) {
+                let heap = Region::new("heap", end, new_brk - end, Permissions::RW);
+                core.memory.map(heap).ok()?;
+            } else {
+                core.memory.resize(new_brk);
+            }
+        }
+
+        self.brk = Some(new_brk);
+
+        Some(brk)
+    }
+}
+
+impl EnvironmentHandler for RarsEnvironment {
+    fn ecall(&mut self, core: &mut RvCore) -> Result<EcallOutcome, Exception> {
+        let x = &core.registers.x;
+        let f = &core.registers.f;
+
+        match x[A7] {
+            Self::PRINT_INT => self.output.push_str(&(x[A0] as i64).to_string()),
+            Self::PRINT_FLOAT => self.output.push_str(&f.single(FA0).to_string()),
+            Self::PRINT_DOUBLE => self.output.push_str(&f.double(FA0).to_string()),
+            Self::PRINT_STRING => self.print_string(core)?,
+            Self::PRINT_CHAR => self.output.push(x[A0] as u8 as char),
+            Self::READ_INT => match self.read_int() {
+                Some(value) => core.registers.x[A0] = value as u64,
+                None => return Ok(EcallOutcome::Trap),
+            },
+            Self::SBRK => match self.sbrk(core) {
+                Some(brk) => core.registers.x[A0] = brk,
+                None => return Ok(EcallOutcome::Trap),
+            },
+            Self::EXIT => return Ok(EcallOutcome::Exit(0)),
+            Self::EXIT2 | Self::LINUX_EXIT => return Ok(EcallOutcome::Exit(x[A0] as i32)),
+            _ => return Ok(EcallOutcome::Trap),
+        }
+
+        Ok(EcallOutcome::Resume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::{
+        instruction::{
+            format::{I, S},
+            Instruction,
+        },
+        memory::Memory,
+        registers::PrivilegeMode,
+        trap::Trap,
+        RvCoreBuilder,
+    };
+
+    fn li(rd: usize, imm12: i32) -> Instruction {
+        Instruction::Addi(I { rd, rs1: 0, imm12 })
+    }
+
+    fn mv(rd: usize, rs1: usize) -> Instruction {
+        Instruction::Addi(I { rd, rs1, imm12: 0 })
+    }
+
+    #[test]
+    fn rars_calls_work() {
+        let environment = Rc::new(RefCell::new(RarsEnvironment {
+            input: " 17\n".to_owned(),
+            ..Default::default()
+        }));
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(b"Hi \0".iter().copied()))
+            .environment(environment.clone())
+            .build();
+        core.load_program(&[
+            li(A7, 4),
+            li(A0, 0),
+            Instruction::Ecall,
+            li(A7, 1),
+            li(A0, -42),
+            Instruction::Ecall,
+            li(A7, 5),
+            Instruction::Ecall,
+            mv(8, A0),
+            li(A7, 9),
+            li(A0, 16),
+            Instruction::Ecall,
+            mv(9, A0),
+            li(A7, 17),
+            li(A0, 3),
+            Instruction::Ecall,
+            li(A0, 0),
+        ])
+        .unwrap();
+
+        let text_end = core.text_end;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(environment.borrow().output, "Hi -42");
+        assert_eq!(core.registers.x[8], 17);
+        assert_eq!(core.registers.x[9], text_end);
+        assert_eq!(core.memory.len(), text_end + 16);
+        assert_eq!(core.exit_code, Some(3));
+        assert_eq!(core.registers.x[A0], 3);
+    }
+
+    #[test]
+    fn heap_is_mapped_above_text() {
+        let mut memory = Memory::empty();
+        memory
+            .map(Region::new("data", 0, 0x10, Permissions::RW))
+            .unwrap();
+        memory
+            .map(Region::new("text", 0x10, 0x14, Permissions::RX))
+            .unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(memory)
+            .environment(Rc::new(RefCell::new(RarsEnvironment::default())))
+            .text_base(0x10)
+            .build();
+        core.load_program(&[
+            li(A7, 9),
+            li(A0, 16),
+            Instruction::Ecall,
+            mv(9, A0),
+            Instruction::Sd(S {
+                rs1: 9,
+                rs2: A7,
+                imm12: 0,
+            }),
+        ])
+        .unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[9], 0x28);
+        assert_eq!(
+            core.memory.region("heap"),
+            Some(&Region::new("heap", 0x24, 0x14, Permissions::RW))
+        );
+        assert_eq!(core.memory.get(0x28), Ok(9u64.to_le_bytes()));
+    }
+
+    #[test]
+    fn missing_input_is_left_to_embedder() {
+        let environment = Rc::new(RefCell::new(RarsEnvironment::default()));
+
+        let mut core = RvCoreBuilder::default()
+            .environment(environment.clone())
+            .build();
+        core.load_program(&[li(A7, 5), Instruction::Ecall]).unwrap();
+
+        let ecall = core.text_base + 4;
+
+        assert_eq!(core.step(), Some(Ok(())));
+        assert_eq!(
+            core.step(),
+            Some(Err(Trap {
+                exception: Exception::EnvironmentCall(PrivilegeMode::Machine),
+                pc: ecall
+            }))
+        );
+
+        environment.borrow_mut().input.push_str("-5");
+
+        assert_eq!(core.step(), Some(Ok(())));
+        assert_eq!(core.registers.x[A0] as i64, -5);
+    }
+}
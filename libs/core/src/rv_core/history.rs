@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use super::{registers::PrivilegeMode, vector_engine::VectorEngine, RvCore};
+
+/// Old values overwritten by the step being recorded, stays empty while nothing is recorded
+#[derive(Clone, Debug)]
+pub(crate) struct Journal<T> {
+    recording: bool,
+    entries: Vec<T>,
+}
+
+impl<T> Journal<T> {
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn entries(&self) -> &[T] {
+        &self.entries
+    }
+
+    pub fn record(&mut self, entry: T) {
+        if self.recording {
+            self.entries.push(entry);
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.entries.clear();
+    }
+
+    pub fn stop(&mut self) -> Vec<T> {
+        self.recording = false;
+        std::mem::take(&mut self.entries)
+    }
+}
+
+impl<T> Default for Journal<T> {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            entries: Vec::new(),
+        }
+    }
+}
+
+// Journal only lives for the duration of a step, it isn't part of the state
+impl<T> PartialEq for Journal<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Everything a step overwrote, applying it in reverse undoes the step
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct Diff {
+    pc: u64,
+    mode: PrivilegeMode,
+    reservation: Option<u64>,
+    exit_code: Option<i32>,
+    pub x: Vec<(usize, u64)>,
+    pub f: Vec<(usize, u64)>,
+    pub c: Vec<(usize, u64)>,
+    pub v: Vec<(usize, Vec<u8>)>,
+    memory: Vec<(u64, Vec<u8>)>,
+    /// Only kept when vset* or the agnostic fill changed it
+    vec_engine: Option<VectorEngine>,
+}
+
+/// State ahead of a step, registers and memory journal their own writes until it's turned into a diff
+pub(super) struct Checkpoint {
+    pc: u64,
+    mode: PrivilegeMode,
+    reservation: Option<u64>,
+    exit_code: Option<i32>,
+    vec_engine: VectorEngine,
+}
+
+impl Checkpoint {
+    pub fn new(core: &mut RvCore) -> Self {
+        let registers = &mut core.registers;
+
+        registers.x.journal().start();
+        registers.f.journal().start();
+        registers.c.journal().start();
+        registers.v.journal().start();
+        core.memory.journal().start();
+
+        Self {
+            pc: registers.pc,
+            mode: registers.mode,
+            reservation: registers.reservation,
+            exit_code: core.exit_code,
+            vec_engine: core.vec_engine,
+        }
+    }
+
+    pub fn diff(self, core: &mut RvCore) -> Diff {
+        let registers = &mut core.registers;
+
+        Diff {
+            pc: self.pc,
+            mode: self.mode,
+            reservation: self.reservation,
+            exit_code: self.exit_code,
+            x: registers.x.journal().stop(),
+            f: registers.f.journal().stop(),
+            c: registers.c.journal().stop(),
+            v: registers.v.journal().stop(),
+            memory: core.memory.journal().stop(),
+            vec_engine: (self.vec_engine != core.vec_engine).then_some(self.vec_engine),
+        }
+    }
+}
+
+/// Undo log of the most recent steps, each step is kept as a diff of the state it overwrote.
+/// Memory writes are undone, devices and the environment are not, e.g. transmitted UART bytes stay transmitted.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct History {
+    /// Steps to keep, the oldest ones are dropped first, zero disables recording
+    depth: usize,
+    steps: VecDeque<Diff>,
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            steps: VecDeque::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.truncate();
+    }
+
+    /// Steps that can be undone
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    /// Keeps the diff of a step that ran, nothing is kept while recording is disabled
+    pub(super) fn push(&mut self, diff: Diff) {
+        if self.depth > 0 {
+            self.steps.push_back(diff);
+            self.truncate();
+        }
+    }
+
+    /// Reverts the most recent step, returns false when there is none
+    pub(super) fn undo(core: &mut RvCore) -> bool {
+        let Some(diff) = core.history.steps.pop_back() else {
+            return false;
+        };
+
+        let registers = &mut core.registers;
+
+        registers.x.undo(diff.x);
+        registers.f.undo(diff.f);
+        registers.c.undo(diff.c);
+        registers.v.undo(diff.v);
+        core.memory.undo(diff.memory);
+
+        registers.pc = diff.pc;
+        registers.mode = diff.mode;
+        registers.reservation = diff.reservation;
+        core.exit_code = diff.exit_code;
+
+        if let Some(vec_engine) = diff.vec_engine {
+            core.vec_engine = vec_engine;
+        }
+
+        true
+    }
+
+    fn truncate(&mut self) {
+        while self.steps.len() > self.depth {
+            self.steps.pop_front();
+        }
+    }
+}
@@ -1,6 +1,12 @@
+pub mod decode_cache;
+pub mod decoder;
+mod disassembler;
+pub mod encoder;
 pub mod executor;
 pub mod format;
 
+pub(crate) use disassembler::Csr;
+pub use disassembler::Disassembly;
 use format::*;
 
 use super::vector_engine::sew::BaseSew;
@@ -65,6 +71,16 @@ pub enum Instruction {
     Jal(U),
     Jalr(I),
 
+    // Environment
+    Ecall,
+    Ebreak,
+
+    // Memory ordering
+    Fence(Fence),
+
+    /// Zifencei extension
+    FenceI(I),
+
     /// Zicsr extension
     // RV32/64Zicsr
     Csrrw(Csrr),
@@ -74,6 +90,15 @@ pub enum Instruction {
     Csrrsi(Csri),
     Csrrci(Csri),
 
+    /// Privileged instructions
+    // Machine-mode
+    Mret,
+    Wfi,
+
+    // Supervisor-mode
+    Sret,
+    SfenceVma(R),
+
     /// M extension
     // RV32M
     Mul(R),
@@ -92,6 +117,33 @@ pub enum Instruction {
     Remw(R),
     Remuw(R),
 
+    /// A extension
+    // RV32A
+    Lrw(Atomic),
+    Scw(Atomic),
+    Amoswapw(Atomic),
+    Amoaddw(Atomic),
+    Amoxorw(Atomic),
+    Amoandw(Atomic),
+    Amoorw(Atomic),
+    Amominw(Atomic),
+    Amomaxw(Atomic),
+    Amominuw(Atomic),
+    Amomaxuw(Atomic),
+
+    // RV64A
+    Lrd(Atomic),
+    Scd(Atomic),
+    Amoswapd(Atomic),
+    Amoaddd(Atomic),
+    Amoxord(Atomic),
+    Amoandd(Atomic),
+    Amoord(Atomic),
+    Amomind(Atomic),
+    Amomaxd(Atomic),
+    Amominud(Atomic),
+    Amomaxud(Atomic),
+
     /// F extension
     // RV32F
     Flw(I),
@@ -100,32 +152,32 @@ pub enum Instruction {
     Fmsubs(R4),
     Fnmsubs(R4),
     Fnmadds(R4),
-    Fadds(R),
-    Fsubs(R),
-    Fmuls(R),
-    Fdivs(R),
-    Fsqrts(R),
+    Fadds(Rfp),
+    Fsubs(Rfp),
+    Fmuls(Rfp),
+    Fdivs(Rfp),
+    Fsqrts(Rfp),
     Fsgnjs(R),
     Fsgnjns(R),
     Fsgnjxs(R),
     Fmins(R),
     Fmaxs(R),
-    Fcvtws(R),
-    Fcvtwus(R),
+    Fcvtws(Rfp),
+    Fcvtwus(Rfp),
     Fmvxw(R),
     Feqs(R),
     Flts(R),
     Fles(R),
     Fclasss(R),
-    Fcvtsw(R),
-    Fcvtswu(R),
+    Fcvtsw(Rfp),
+    Fcvtswu(Rfp),
     Fmvwx(R),
 
     // RV64F
-    Fcvtls(R),
-    Fcvtlus(R),
-    Fcvtsl(R),
-    Fcvtslu(R),
+    Fcvtls(Rfp),
+    Fcvtlus(Rfp),
+    Fcvtsl(Rfp),
+    Fcvtslu(Rfp),
 
     /// D extension
     // RV32D
@@ -135,35 +187,131 @@ pub enum Instruction {
     Fmsubd(R4),
     Fnmsubd(R4),
     Fnmaddd(R4),
-    Faddd(R),
-    Fsubd(R),
-    Fmuld(R),
-    Fdivd(R),
-    Fsqrtd(R),
+    Faddd(Rfp),
+    Fsubd(Rfp),
+    Fmuld(Rfp),
+    Fdivd(Rfp),
+    Fsqrtd(Rfp),
     Fsgnjd(R),
     Fsgnjnd(R),
     Fsgnjxd(R),
     Fmind(R),
     Fmaxd(R),
-    Fcvtsd(R),
-    Fcvtds(R),
+    Fcvtsd(Rfp),
+    Fcvtds(Rfp),
     Feqd(R),
     Fltd(R),
     Fled(R),
     Fclassd(R),
-    Fcvtwd(R),
-    Fcvtwud(R),
-    Fcvtdw(R),
-    Fcvtdwu(R),
+    Fcvtwd(Rfp),
+    Fcvtwud(Rfp),
+    Fcvtdw(Rfp),
+    Fcvtdwu(Rfp),
 
     // RV64D
-    Fcvtld(R),
-    Fcvtlud(R),
+    Fcvtld(Rfp),
+    Fcvtlud(Rfp),
     Fmvxd(R),
-    Fcvtdl(R),
-    Fcvtdlu(R),
+    Fcvtdl(Rfp),
+    Fcvtdlu(Rfp),
     Fmvdx(R),
 
+    /// Zfh extension
+    // RV32Zfh
+    Flh(I),
+    Fsh(S),
+    Fmaddh(R4),
+    Fmsubh(R4),
+    Fnmsubh(R4),
+    Fnmaddh(R4),
+    Faddh(Rfp),
+    Fsubh(Rfp),
+    Fmulh(Rfp),
+    Fdivh(Rfp),
+    Fsqrth(Rfp),
+    Fsgnjh(R),
+    Fsgnjnh(R),
+    Fsgnjxh(R),
+    Fminh(R),
+    Fmaxh(R),
+    Fcvtsh(Rfp),
+    Fcvths(Rfp),
+    Fcvtdh(Rfp),
+    Fcvthd(Rfp),
+    Fcvtwh(Rfp),
+    Fcvtwuh(Rfp),
+    Fmvxh(R),
+    Feqh(R),
+    Flth(R),
+    Fleh(R),
+    Fclassh(R),
+    Fcvthw(Rfp),
+    Fcvthwu(Rfp),
+    Fmvhx(R),
+
+    // RV64Zfh
+    Fcvtlh(Rfp),
+    Fcvtluh(Rfp),
+    Fcvthl(Rfp),
+    Fcvthlu(Rfp),
+
+    /// Zba extension
+    Adduw(R),
+    Sh1add(R),
+    Sh2add(R),
+    Sh3add(R),
+    Sh1adduw(R),
+    Sh2adduw(R),
+    Sh3adduw(R),
+    Slliuw(I),
+
+    /// Zbb extension, rs2 of unary instructions is unused
+    // Logic with negate
+    Andn(R),
+    Orn(R),
+    Xnor(R),
+
+    // Bit counting
+    Clz(R),
+    Clzw(R),
+    Ctz(R),
+    Ctzw(R),
+    Cpop(R),
+    Cpopw(R),
+
+    // Minimum/maximum
+    Max(R),
+    Maxu(R),
+    Min(R),
+    Minu(R),
+
+    // Sign and zero extension
+    Sextb(R),
+    Sexth(R),
+    Zexth(R),
+
+    // Bitwise rotation
+    Rol(R),
+    Rolw(R),
+    Ror(R),
+    Rori(I),
+    Roriw(I),
+    Rorw(R),
+
+    // Byte operations
+    Orcb(R),
+    Rev8(R),
+
+    /// Zbs extension
+    Bclr(R),
+    Bclri(I),
+    Bext(R),
+    Bexti(I),
+    Binv(R),
+    Binvi(I),
+    Bset(R),
+    Bseti(I),
+
     /// V extension
     // VCFG
     Vsetvli(Vsetvli),
@@ -219,6 +367,11 @@ pub enum Instruction {
         eew: BaseSew,
         nf: usize,
     },
+    Vlsegffv {
+        data: Vl,
+        eew: BaseSew,
+        nf: usize,
+    },
     Vssegv {
         data: Vs,
         eew: BaseSew,
@@ -731,6 +884,21 @@ pub enum Instruction {
     Vfwnmsacvv(Opfvv),
     Vfwnmsacvf(Opfvf),
 
+    /// C extension, the wrapped instruction along with the 16-bit form it was decoded from or compressed into.
+    /// Some instructions have several forms, e.g. `c.addi sp, 16` and `c.addi16sp sp, 16`, so the encoding is kept.
+    Compressed(Box<Instruction>, u16),
+
     // Instruction fusion for pseudo instructions
     Fusion(Box<[Instruction]>),
 }
+
+impl Instruction {
+    /// Bytes taken in memory, fused instructions span all of their parts
+    pub fn size(&self) -> u64 {
+        match self {
+            Self::Compressed(..) => 2,
+            Self::Fusion(instructions) => instructions.iter().map(Self::size).sum(),
+            _ => 4,
+        }
+    }
+}
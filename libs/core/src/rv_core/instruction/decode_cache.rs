@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use super::{
+    decoder::{decode, DecodeError},
+    Instruction,
+};
+
+/// Decoded instructions keyed by address.
+/// Entries remember the raw word they were decoded from, so code rewritten in memory is decoded again.
+#[derive(Clone, Default, Debug)]
+pub struct DecodeCache(HashMap<u64, (u32, Instruction)>);
+
+impl DecodeCache {
+    pub fn decode(&mut self, address: u64, raw: u32) -> Result<Instruction, DecodeError> {
+        if let Some((cached_raw, instruction)) = self.0.get(&address) {
+            if *cached_raw == raw {
+                return Ok(instruction.clone());
+            }
+        }
+
+        let instruction = decode(raw)?;
+        self.0.insert(address, (raw, instruction.clone()));
+
+        Ok(instruction)
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+// Cache is transparent, cores holding the same state are equal regardless of what they decoded so far
+impl PartialEq for DecodeCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::instruction::format::I;
+
+    #[test]
+    fn rewritten_words_are_decoded_again() {
+        let mut cache = DecodeCache::default();
+
+        assert_eq!(
+            cache.decode(0x100, 0x00150513),
+            Ok(Instruction::Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 1
+            }))
+        );
+        assert_eq!(
+            cache.decode(0x100, 0x00250513),
+            Ok(Instruction::Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 2
+            }))
+        );
+        assert_eq!(cache.decode(0x104, 0), Err(DecodeError::Reserved(0)));
+    }
+}
@@ -0,0 +1,1811 @@
+use std::fmt;
+
+use super::{format, format::*, Instruction, Instruction::*};
+use crate::rv_core::vector_engine::sew::BaseSew;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecodeError {
+    /// Opcode and function fields don't match any supported instruction
+    Unknown(u32),
+    /// Instruction is recognized but one of its fields holds a reserved value
+    Reserved(u32),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(raw) => write!(f, "Unknown instruction {:#010x}", raw),
+            Self::Reserved(raw) => write!(f, "Reserved encoding in instruction {:#010x}", raw),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl TryFrom<u32> for Instruction {
+    type Error = DecodeError;
+
+    fn try_from(raw: u32) -> Result<Self, Self::Error> {
+        decode(raw)
+    }
+}
+
+/// Decodes the instruction at the start of `raw`, upper half is ignored when it holds a compressed one
+pub fn decode(raw: u32) -> Result<Instruction, DecodeError> {
+    let word = Word(raw);
+
+    if raw & 0b11 != 0b11 {
+        return decode_compressed(Word(raw & 0xffff))
+            .map(|instruction| Compressed(Box::new(instruction), raw as u16));
+    }
+
+    let instruction = match word.opcode() {
+        0b0000011 => decode_load(word),
+        0b0000111 => decode_load_fp(word)?,
+        0b0001111 => decode_misc_mem(word),
+        0b0010011 => decode_op_imm(word),
+        0b0010111 => Some(Auipc(word.u())),
+        0b0011011 => decode_op_imm_32(word),
+        0b0100011 => decode_store(word),
+        0b0100111 => decode_store_fp(word)?,
+        0b0101111 => decode_amo(word),
+        0b0110011 => decode_op(word),
+        0b0110111 => Some(Lui(word.u())),
+        0b0111011 => decode_op_32(word),
+        0b1000011 | 0b1000111 | 0b1001011 | 0b1001111 => decode_fused_multiply_add(word),
+        0b1010011 => decode_op_fp(word),
+        0b1010111 => decode_op_v(word)?,
+        0b1100011 => decode_branch(word),
+        0b1100111 if word.funct3() == 0 => Some(Jalr(word.i())),
+        0b1101111 => Some(Jal(word.j())),
+        0b1110011 => decode_system(word),
+        _ => None,
+    };
+
+    instruction.ok_or(DecodeError::Unknown(raw))
+}
+
+/// Expands a 16-bit instruction into its 32-bit equivalent
+fn decode_compressed(half: Word) -> Result<Instruction, DecodeError> {
+    let reserved = Err(DecodeError::Reserved(half.0));
+    let rd = half.rd();
+    let rs2 = half.bits(6, 2) as usize;
+    let imm = half.imm_ci();
+
+    let instruction = match (half.bits(1, 0), half.bits(15, 13)) {
+        (0b00, 0b000) => {
+            let imm12 = (half.bits(10, 7) << 6
+                | half.bits(12, 11) << 4
+                | half.bits(5, 5) << 3
+                | half.bits(6, 6) << 2) as i32;
+
+            if imm12 == 0 {
+                return reserved;
+            }
+
+            Addi(I {
+                rd: half.creg(2),
+                rs1: SP,
+                imm12,
+            })
+        }
+        (0b00, 0b001) => Fld(half.cl(half.uimm_cl_d())),
+        (0b00, 0b010) => Lw(half.cl(half.uimm_cl_w())),
+        (0b00, 0b011) => Ld(half.cl(half.uimm_cl_d())),
+        (0b00, 0b101) => Fsd(half.cs(half.uimm_cl_d())),
+        (0b00, 0b110) => Sw(half.cs(half.uimm_cl_w())),
+        (0b00, 0b111) => Sd(half.cs(half.uimm_cl_d())),
+        (0b01, 0b000) => Addi(I {
+            rd,
+            rs1: rd,
+            imm12: imm,
+        }),
+        (0b01, 0b001) if rd == 0 => return reserved,
+        (0b01, 0b001) => Addiw(I {
+            rd,
+            rs1: rd,
+            imm12: imm,
+        }),
+        (0b01, 0b010) => Addi(I {
+            rd,
+            rs1: 0,
+            imm12: imm,
+        }),
+        (0b01, 0b011) if rd == SP => {
+            let imm12 = (half.bits(12, 12) << 31) as i32 >> 22
+                | (half.bits(4, 3) << 7
+                    | half.bits(5, 5) << 6
+                    | half.bits(2, 2) << 5
+                    | half.bits(6, 6) << 4) as i32;
+
+            if imm12 == 0 {
+                return reserved;
+            }
+
+            Addi(I {
+                rd: SP,
+                rs1: SP,
+                imm12,
+            })
+        }
+        (0b01, 0b011) if imm == 0 => return reserved,
+        (0b01, 0b011) => Lui(U { rd, imm20: imm }),
+        (0b01, 0b100) => {
+            let rd = half.creg(7);
+            let shamt = imm & 0b111111;
+
+            match (half.bits(11, 10), half.bits(12, 12), half.bits(6, 5)) {
+                (0b00, ..) => Srli(I {
+                    rd,
+                    rs1: rd,
+                    imm12: shamt,
+                }),
+                (0b01, ..) => Srai(I {
+                    rd,
+                    rs1: rd,
+                    imm12: shamt,
+                }),
+                (0b10, ..) => Andi(I {
+                    rd,
+                    rs1: rd,
+                    imm12: imm,
+                }),
+                (0b11, 0, funct2) => {
+                    let r = half.ca();
+
+                    match funct2 {
+                        0b00 => Sub(r),
+                        0b01 => Xor(r),
+                        0b10 => Or(r),
+                        _ => And(r),
+                    }
+                }
+                (0b11, _, 0b00) => Subw(half.ca()),
+                (0b11, _, 0b01) => Addw(half.ca()),
+                _ => return reserved,
+            }
+        }
+        (0b01, 0b101) => Jal(U {
+            rd: 0,
+            imm20: (half.bits(12, 12) << 31) as i32 >> 20
+                | (half.bits(8, 8) << 10
+                    | half.bits(10, 9) << 8
+                    | half.bits(6, 6) << 7
+                    | half.bits(7, 7) << 6
+                    | half.bits(2, 2) << 5
+                    | half.bits(11, 11) << 4
+                    | half.bits(5, 3) << 1) as i32,
+        }),
+        (0b01, funct3 @ (0b110 | 0b111)) => {
+            let branch = S {
+                rs1: half.creg(7),
+                rs2: 0,
+                imm12: (half.bits(12, 12) << 31) as i32 >> 23
+                    | (half.bits(6, 5) << 6
+                        | half.bits(2, 2) << 5
+                        | half.bits(11, 10) << 3
+                        | half.bits(4, 3) << 1) as i32,
+            };
+
+            if funct3 == 0b110 {
+                Beq(branch)
+            } else {
+                Bne(branch)
+            }
+        }
+        (0b10, 0b000) => Slli(I {
+            rd,
+            rs1: rd,
+            imm12: imm & 0b111111,
+        }),
+        (0b10, 0b001) => Fld(half.ci_sp(half.uimm_ci_d())),
+        (0b10, 0b010) if rd == 0 => return reserved,
+        (0b10, 0b010) => Lw(half.ci_sp(half.uimm_ci_w())),
+        (0b10, 0b011) if rd == 0 => return reserved,
+        (0b10, 0b011) => Ld(half.ci_sp(half.uimm_ci_d())),
+        (0b10, 0b100) => match (half.bits(12, 12), rd, rs2) {
+            (0, 0, 0) => return reserved,
+            (0, rs1, 0) => Jalr(I {
+                rd: 0,
+                rs1,
+                imm12: 0,
+            }),
+            (0, rd, rs2) => Add(R { rd, rs1: 0, rs2 }),
+            (_, 0, 0) => Ebreak,
+            (_, rs1, 0) => Jalr(I {
+                rd: RA,
+                rs1,
+                imm12: 0,
+            }),
+            (_, rd, rs2) => Add(R { rd, rs1: rd, rs2 }),
+        },
+        (0b10, 0b101) => Fsd(half.css(half.uimm_css_d())),
+        (0b10, 0b110) => Sw(half.css(half.uimm_css_w())),
+        (0b10, 0b111) => Sd(half.css(half.uimm_css_d())),
+        _ => return Err(DecodeError::Unknown(half.0)),
+    };
+
+    Ok(instruction)
+}
+
+const RA: usize = 1;
+const SP: usize = 2;
+
+#[derive(Clone, Copy)]
+struct Word(u32);
+
+impl Word {
+    fn bits(self, high: u32, low: u32) -> u32 {
+        (self.0 >> low) & ((1 << (high - low + 1)) - 1)
+    }
+
+    fn opcode(self) -> u32 {
+        self.bits(6, 0)
+    }
+
+    fn rd(self) -> usize {
+        self.bits(11, 7) as usize
+    }
+
+    fn funct3(self) -> u32 {
+        self.bits(14, 12)
+    }
+
+    fn rs1(self) -> usize {
+        self.bits(19, 15) as usize
+    }
+
+    fn rs2(self) -> usize {
+        self.bits(24, 20) as usize
+    }
+
+    fn rs3(self) -> usize {
+        self.bits(31, 27) as usize
+    }
+
+    fn funct7(self) -> u32 {
+        self.bits(31, 25)
+    }
+
+    fn funct6(self) -> u32 {
+        self.bits(31, 26)
+    }
+
+    /// Vector mask bit, `true` means the instruction is masked by v0
+    fn vm(self) -> bool {
+        self.bits(25, 25) == 0
+    }
+
+    fn imm_i(self) -> i32 {
+        self.0 as i32 >> 20
+    }
+
+    fn imm_s(self) -> i32 {
+        (self.0 as i32 >> 25) << 5 | self.bits(11, 7) as i32
+    }
+
+    fn imm_b(self) -> i32 {
+        (self.0 as i32 >> 31) << 12
+            | (self.bits(7, 7) << 11) as i32
+            | (self.bits(30, 25) << 5) as i32
+            | (self.bits(11, 8) << 1) as i32
+    }
+
+    fn imm_j(self) -> i32 {
+        (self.0 as i32 >> 31) << 20
+            | (self.bits(19, 12) << 12) as i32
+            | (self.bits(20, 20) << 11) as i32
+            | (self.bits(30, 21) << 1) as i32
+    }
+
+    fn simm5(self) -> i32 {
+        ((self.bits(19, 15) as i32) << 27) >> 27
+    }
+
+    fn uimm5(self) -> i32 {
+        self.bits(19, 15) as i32
+    }
+
+    fn r(self) -> R {
+        R {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+        }
+    }
+
+    /// R-type with function code in rs2 field
+    fn r_unary(self) -> R {
+        R {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: 0,
+        }
+    }
+
+    fn r4(self) -> R4 {
+        R4 {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+            rs3: self.rs3(),
+            rm: self.funct3(),
+        }
+    }
+
+    fn rfp(self) -> Rfp {
+        Rfp {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+            rm: self.funct3(),
+        }
+    }
+
+    /// Rounding R-type with function code in rs2 field
+    fn rfp_unary(self) -> Rfp {
+        Rfp {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: 0,
+            rm: self.funct3(),
+        }
+    }
+
+    fn i(self) -> I {
+        I {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            imm12: self.imm_i(),
+        }
+    }
+
+    fn shift(self, shamt_bits: u32) -> I {
+        I {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            imm12: self.bits(19 + shamt_bits, 20) as i32,
+        }
+    }
+
+    fn s(self) -> S {
+        S {
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+            imm12: self.imm_s(),
+        }
+    }
+
+    fn b(self) -> S {
+        S {
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+            imm12: self.imm_b(),
+        }
+    }
+
+    fn u(self) -> U {
+        U {
+            rd: self.rd(),
+            imm20: self.0 as i32 >> 12,
+        }
+    }
+
+    fn j(self) -> U {
+        U {
+            rd: self.rd(),
+            imm20: self.imm_j(),
+        }
+    }
+
+    fn atomic(self) -> Atomic {
+        Atomic {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            rs2: self.rs2(),
+            aq: self.bits(26, 26) == 1,
+            rl: self.bits(25, 25) == 1,
+        }
+    }
+
+    fn csrr(self) -> Csrr {
+        Csrr {
+            rd: self.rd(),
+            rs1: self.rs1(),
+            csr: self.bits(31, 20) as usize,
+        }
+    }
+
+    fn csri(self) -> Csri {
+        Csri {
+            rd: self.rd(),
+            uimm: self.rs1(),
+            csr: self.bits(31, 20) as usize,
+        }
+    }
+
+    fn opivv(self) -> Opivv {
+        Opivv {
+            dest: self.rd(),
+            vs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    fn opivx(self) -> Opivx {
+        Opivx {
+            dest: self.rd(),
+            rs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    fn opivi(self, imm5: i32) -> Opivi {
+        Opivi {
+            dest: self.rd(),
+            imm5,
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    fn opmvv(self) -> Opmvv {
+        Opmvv {
+            dest: self.rd(),
+            vs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    /// OPMVV with function code in vs1 field
+    fn opmvv_unary(self) -> Opmvv {
+        Opmvv {
+            vs1: 0,
+            ..self.opmvv()
+        }
+    }
+
+    fn opmvx(self) -> Opmvx {
+        Opmvx {
+            dest: self.rd(),
+            rs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    fn opfvv(self) -> Opfvv {
+        Opfvv {
+            dest: self.rd(),
+            vs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    /// OPFVV with function code in vs1 field
+    fn opfvv_unary(self) -> Opfvv {
+        Opfvv {
+            vs1: 0,
+            ..self.opfvv()
+        }
+    }
+
+    fn opfvf(self) -> Opfvf {
+        Opfvf {
+            vd: self.rd(),
+            rs1: self.rs1(),
+            vs2: self.rs2(),
+            vm: self.vm(),
+        }
+    }
+
+    /// Element width of vector loads and stores, None for scalar floating-point widths
+    /// One of x8-x15 in the 3-bit register field of a compressed instruction starting at `low`
+    fn creg(self, low: u32) -> usize {
+        self.bits(low + 2, low) as usize + 8
+    }
+
+    /// Sign-extended 6-bit immediate of the CI format
+    fn imm_ci(self) -> i32 {
+        (self.bits(12, 12) << 31) as i32 >> 26 | self.bits(6, 2) as i32
+    }
+
+    fn uimm_ci_w(self) -> i32 {
+        (self.bits(3, 2) << 6 | self.bits(12, 12) << 5 | self.bits(6, 4) << 2) as i32
+    }
+
+    fn uimm_ci_d(self) -> i32 {
+        (self.bits(4, 2) << 6 | self.bits(12, 12) << 5 | self.bits(6, 5) << 3) as i32
+    }
+
+    fn uimm_css_w(self) -> i32 {
+        (self.bits(8, 7) << 6 | self.bits(12, 9) << 2) as i32
+    }
+
+    fn uimm_css_d(self) -> i32 {
+        (self.bits(9, 7) << 6 | self.bits(12, 10) << 3) as i32
+    }
+
+    fn uimm_cl_w(self) -> i32 {
+        (self.bits(5, 5) << 6 | self.bits(12, 10) << 3 | self.bits(6, 6) << 2) as i32
+    }
+
+    fn uimm_cl_d(self) -> i32 {
+        (self.bits(6, 5) << 6 | self.bits(12, 10) << 3) as i32
+    }
+
+    fn ci_sp(self, imm12: i32) -> I {
+        I {
+            rd: self.rd(),
+            rs1: SP,
+            imm12,
+        }
+    }
+
+    fn css(self, imm12: i32) -> S {
+        S {
+            rs1: SP,
+            rs2: self.bits(6, 2) as usize,
+            imm12,
+        }
+    }
+
+    fn cl(self, imm12: i32) -> I {
+        I {
+            rd: self.creg(2),
+            rs1: self.creg(7),
+            imm12,
+        }
+    }
+
+    fn cs(self, imm12: i32) -> S {
+        S {
+            rs1: self.creg(7),
+            rs2: self.creg(2),
+            imm12,
+        }
+    }
+
+    fn ca(self) -> R {
+        R {
+            rd: self.creg(7),
+            rs1: self.creg(7),
+            rs2: self.creg(2),
+        }
+    }
+
+    fn vector_eew(self) -> Option<BaseSew> {
+        match self.funct3() {
+            0b000 => Some(BaseSew::E8),
+            0b101 => Some(BaseSew::E16),
+            0b110 => Some(BaseSew::E32),
+            0b111 => Some(BaseSew::E64),
+            _ => None,
+        }
+    }
+
+    fn nf(self) -> usize {
+        self.bits(31, 29) as usize + 1
+    }
+}
+
+fn decode_load(word: Word) -> Option<Instruction> {
+    let i = word.i();
+
+    Some(match word.funct3() {
+        0b000 => Lb(i),
+        0b001 => Lh(i),
+        0b010 => Lw(i),
+        0b011 => Ld(i),
+        0b100 => Lbu(i),
+        0b101 => Lhu(i),
+        0b110 => Lwu(i),
+        _ => return None,
+    })
+}
+
+fn decode_store(word: Word) -> Option<Instruction> {
+    let s = word.s();
+
+    Some(match word.funct3() {
+        0b000 => Sb(s),
+        0b001 => Sh(s),
+        0b010 => Sw(s),
+        0b011 => Sd(s),
+        _ => return None,
+    })
+}
+
+fn decode_branch(word: Word) -> Option<Instruction> {
+    let b = word.b();
+
+    Some(match word.funct3() {
+        0b000 => Beq(b),
+        0b001 => Bne(b),
+        0b100 => Blt(b),
+        0b101 => Bge(b),
+        0b110 => Bltu(b),
+        0b111 => Bgeu(b),
+        _ => return None,
+    })
+}
+
+fn decode_op_imm(word: Word) -> Option<Instruction> {
+    Some(match (word.funct3(), word.funct6()) {
+        (0b000, _) => Addi(word.i()),
+        (0b010, _) => Slti(word.i()),
+        (0b011, _) => Sltiu(word.i()),
+        (0b100, _) => Xori(word.i()),
+        (0b110, _) => Ori(word.i()),
+        (0b111, _) => Andi(word.i()),
+        (0b001, 0b000000) => Slli(word.shift(6)),
+        (0b101, 0b000000) => Srli(word.shift(6)),
+        (0b101, 0b010000) => Srai(word.shift(6)),
+        (0b001, 0b011000) => match word.bits(25, 20) {
+            0b000000 => Clz(word.r_unary()),
+            0b000001 => Ctz(word.r_unary()),
+            0b000010 => Cpop(word.r_unary()),
+            0b000100 => Sextb(word.r_unary()),
+            0b000101 => Sexth(word.r_unary()),
+            _ => return None,
+        },
+        (0b101, 0b011000) => Rori(word.shift(6)),
+        (0b101, 0b001010) if word.bits(25, 20) == 0b000111 => Orcb(word.r_unary()),
+        (0b101, 0b011010) if word.bits(25, 20) == 0b111000 => Rev8(word.r_unary()),
+        (0b001, 0b010010) => Bclri(word.shift(6)),
+        (0b101, 0b010010) => Bexti(word.shift(6)),
+        (0b001, 0b011010) => Binvi(word.shift(6)),
+        (0b001, 0b001010) => Bseti(word.shift(6)),
+        _ => return None,
+    })
+}
+
+fn decode_op_imm_32(word: Word) -> Option<Instruction> {
+    Some(match (word.funct3(), word.funct7()) {
+        (0b000, _) => Addiw(word.i()),
+        (0b001, 0b0000000) => Slliw(word.shift(5)),
+        (0b101, 0b0000000) => Srliw(word.shift(5)),
+        (0b101, 0b0100000) => Sraiw(word.shift(5)),
+        (0b001, 0b0000100 | 0b0000101) => Slliuw(word.shift(6)),
+        (0b001, 0b0110000) => match word.rs2() {
+            0b00000 => Clzw(word.r_unary()),
+            0b00001 => Ctzw(word.r_unary()),
+            0b00010 => Cpopw(word.r_unary()),
+            _ => return None,
+        },
+        (0b101, 0b0110000) => Roriw(word.shift(5)),
+        _ => return None,
+    })
+}
+
+fn decode_op(word: Word) -> Option<Instruction> {
+    let r = word.r();
+
+    Some(match (word.funct7(), word.funct3()) {
+        (0b0000000, 0b000) => Add(r),
+        (0b0100000, 0b000) => Sub(r),
+        (0b0000000, 0b001) => Sll(r),
+        (0b0000000, 0b010) => Slt(r),
+        (0b0000000, 0b011) => Sltu(r),
+        (0b0000000, 0b100) => Xor(r),
+        (0b0000000, 0b101) => Srl(r),
+        (0b0100000, 0b101) => Sra(r),
+        (0b0000000, 0b110) => Or(r),
+        (0b0000000, 0b111) => And(r),
+        (0b0000001, 0b000) => Mul(r),
+        (0b0000001, 0b001) => Mulh(r),
+        (0b0000001, 0b010) => Mulhsu(r),
+        (0b0000001, 0b011) => Mulhu(r),
+        (0b0000001, 0b100) => Div(r),
+        (0b0000001, 0b101) => Divu(r),
+        (0b0000001, 0b110) => Rem(r),
+        (0b0000001, 0b111) => Remu(r),
+        (0b0010000, 0b010) => Sh1add(r),
+        (0b0010000, 0b100) => Sh2add(r),
+        (0b0010000, 0b110) => Sh3add(r),
+        (0b0100000, 0b111) => Andn(r),
+        (0b0100000, 0b110) => Orn(r),
+        (0b0100000, 0b100) => Xnor(r),
+        (0b0000101, 0b110) => Max(r),
+        (0b0000101, 0b111) => Maxu(r),
+        (0b0000101, 0b100) => Min(r),
+        (0b0000101, 0b101) => Minu(r),
+        (0b0110000, 0b001) => Rol(r),
+        (0b0110000, 0b101) => Ror(r),
+        (0b0100100, 0b001) => Bclr(r),
+        (0b0100100, 0b101) => Bext(r),
+        (0b0110100, 0b001) => Binv(r),
+        (0b0010100, 0b001) => Bset(r),
+        _ => return None,
+    })
+}
+
+fn decode_op_32(word: Word) -> Option<Instruction> {
+    let r = word.r();
+
+    Some(match (word.funct7(), word.funct3()) {
+        (0b0000000, 0b000) => Addw(r),
+        (0b0100000, 0b000) => Subw(r),
+        (0b0000000, 0b001) => Sllw(r),
+        (0b0000000, 0b101) => Srlw(r),
+        (0b0100000, 0b101) => Sraw(r),
+        (0b0000001, 0b000) => Mulw(r),
+        (0b0000001, 0b100) => Divw(r),
+        (0b0000001, 0b101) => Divuw(r),
+        (0b0000001, 0b110) => Remw(r),
+        (0b0000001, 0b111) => Remuw(r),
+        (0b0000100, 0b000) => Adduw(r),
+        (0b0000100, 0b100) if r.rs2 == 0 => Zexth(r),
+        (0b0010000, 0b010) => Sh1adduw(r),
+        (0b0010000, 0b100) => Sh2adduw(r),
+        (0b0010000, 0b110) => Sh3adduw(r),
+        (0b0110000, 0b001) => Rolw(r),
+        (0b0110000, 0b101) => Rorw(r),
+        _ => return None,
+    })
+}
+
+fn decode_amo(word: Word) -> Option<Instruction> {
+    let atomic = word.atomic();
+
+    Some(match (word.bits(31, 27), word.funct3()) {
+        (0b00010, 0b010) if word.rs2() == 0 => Lrw(atomic),
+        (0b00011, 0b010) => Scw(atomic),
+        (0b00001, 0b010) => Amoswapw(atomic),
+        (0b00000, 0b010) => Amoaddw(atomic),
+        (0b00100, 0b010) => Amoxorw(atomic),
+        (0b01100, 0b010) => Amoandw(atomic),
+        (0b01000, 0b010) => Amoorw(atomic),
+        (0b10000, 0b010) => Amominw(atomic),
+        (0b10100, 0b010) => Amomaxw(atomic),
+        (0b11000, 0b010) => Amominuw(atomic),
+        (0b11100, 0b010) => Amomaxuw(atomic),
+        (0b00010, 0b011) if word.rs2() == 0 => Lrd(atomic),
+        (0b00011, 0b011) => Scd(atomic),
+        (0b00001, 0b011) => Amoswapd(atomic),
+        (0b00000, 0b011) => Amoaddd(atomic),
+        (0b00100, 0b011) => Amoxord(atomic),
+        (0b01100, 0b011) => Amoandd(atomic),
+        (0b01000, 0b011) => Amoord(atomic),
+        (0b10000, 0b011) => Amomind(atomic),
+        (0b10100, 0b011) => Amomaxd(atomic),
+        (0b11000, 0b011) => Amominud(atomic),
+        (0b11100, 0b011) => Amomaxud(atomic),
+        _ => return None,
+    })
+}
+
+// Unknown fence modes and unused rd, rs1 and imm fields are kept, but ignored when executing
+// as the specification requires for forward compatibility
+fn decode_misc_mem(word: Word) -> Option<Instruction> {
+    Some(match word.funct3() {
+        0b000 => Fence(format::Fence {
+            fm: word.bits(31, 28),
+            pred: word.bits(27, 24),
+            succ: word.bits(23, 20),
+            rs1: word.rs1(),
+            rd: word.rd(),
+        }),
+        0b001 => FenceI(word.i()),
+        _ => return None,
+    })
+}
+
+fn decode_system(word: Word) -> Option<Instruction> {
+    Some(match word.funct3() {
+        0b000 => match word.0 {
+            0x00000073 => Ecall,
+            0x00100073 => Ebreak,
+            0x30200073 => Mret,
+            0x10500073 => Wfi,
+            0x10200073 => Sret,
+            _ if word.bits(31, 25) == 0b0001001 && word.rd() == 0 => SfenceVma(word.r()),
+            _ => return None,
+        },
+        0b001 => Csrrw(word.csrr()),
+        0b010 => Csrrs(word.csrr()),
+        0b011 => Csrrc(word.csrr()),
+        0b101 => Csrrwi(word.csri()),
+        0b110 => Csrrsi(word.csri()),
+        0b111 => Csrrci(word.csri()),
+        _ => return None,
+    })
+}
+
+/// Rounding modes 0b101 and 0b110 are reserved
+fn has_reserved_rm(word: Word) -> bool {
+    matches!(word.funct3(), 0b101 | 0b110)
+}
+
+fn decode_fused_multiply_add(word: Word) -> Option<Instruction> {
+    if has_reserved_rm(word) {
+        return None;
+    }
+
+    let r4 = word.r4();
+
+    Some(match (word.opcode(), word.bits(26, 25)) {
+        (0b1000011, 0b00) => Fmadds(r4),
+        (0b1000111, 0b00) => Fmsubs(r4),
+        (0b1001011, 0b00) => Fnmsubs(r4),
+        (0b1001111, 0b00) => Fnmadds(r4),
+        (0b1000011, 0b01) => Fmaddd(r4),
+        (0b1000111, 0b01) => Fmsubd(r4),
+        (0b1001011, 0b01) => Fnmsubd(r4),
+        (0b1001111, 0b01) => Fnmaddd(r4),
+        (0b1000011, 0b10) => Fmaddh(r4),
+        (0b1000111, 0b10) => Fmsubh(r4),
+        (0b1001011, 0b10) => Fnmsubh(r4),
+        (0b1001111, 0b10) => Fnmaddh(r4),
+        _ => return None,
+    })
+}
+
+fn decode_op_fp(word: Word) -> Option<Instruction> {
+    // Instructions without a rounding mode only use the low funct3 values
+    if has_reserved_rm(word) {
+        return None;
+    }
+
+    let r = word.r();
+    let unary = word.r_unary();
+    let rfp = word.rfp();
+    let rfp_unary = word.rfp_unary();
+
+    Some(match (word.funct7(), word.rs2(), word.funct3()) {
+        (0b0000000, _, _) => Fadds(rfp),
+        (0b0000100, _, _) => Fsubs(rfp),
+        (0b0001000, _, _) => Fmuls(rfp),
+        (0b0001100, _, _) => Fdivs(rfp),
+        (0b0101100, 0b00000, _) => Fsqrts(rfp_unary),
+        (0b0010000, _, 0b000) => Fsgnjs(r),
+        (0b0010000, _, 0b001) => Fsgnjns(r),
+        (0b0010000, _, 0b010) => Fsgnjxs(r),
+        (0b0010100, _, 0b000) => Fmins(r),
+        (0b0010100, _, 0b001) => Fmaxs(r),
+        (0b1100000, 0b00000, _) => Fcvtws(rfp_unary),
+        (0b1100000, 0b00001, _) => Fcvtwus(rfp_unary),
+        (0b1100000, 0b00010, _) => Fcvtls(rfp_unary),
+        (0b1100000, 0b00011, _) => Fcvtlus(rfp_unary),
+        (0b1110000, 0b00000, 0b000) => Fmvxw(unary),
+        (0b1110000, 0b00000, 0b001) => Fclasss(unary),
+        (0b1010000, _, 0b010) => Feqs(r),
+        (0b1010000, _, 0b001) => Flts(r),
+        (0b1010000, _, 0b000) => Fles(r),
+        (0b1101000, 0b00000, _) => Fcvtsw(rfp_unary),
+        (0b1101000, 0b00001, _) => Fcvtswu(rfp_unary),
+        (0b1101000, 0b00010, _) => Fcvtsl(rfp_unary),
+        (0b1101000, 0b00011, _) => Fcvtslu(rfp_unary),
+        (0b1111000, 0b00000, 0b000) => Fmvwx(unary),
+
+        (0b0000001, _, _) => Faddd(rfp),
+        (0b0000101, _, _) => Fsubd(rfp),
+        (0b0001001, _, _) => Fmuld(rfp),
+        (0b0001101, _, _) => Fdivd(rfp),
+        (0b0101101, 0b00000, _) => Fsqrtd(rfp_unary),
+        (0b0010001, _, 0b000) => Fsgnjd(r),
+        (0b0010001, _, 0b001) => Fsgnjnd(r),
+        (0b0010001, _, 0b010) => Fsgnjxd(r),
+        (0b0010101, _, 0b000) => Fmind(r),
+        (0b0010101, _, 0b001) => Fmaxd(r),
+        (0b0100000, 0b00001, _) => Fcvtsd(rfp_unary),
+        (0b0100001, 0b00000, _) => Fcvtds(rfp_unary),
+        (0b1100001, 0b00000, _) => Fcvtwd(rfp_unary),
+        (0b1100001, 0b00001, _) => Fcvtwud(rfp_unary),
+        (0b1100001, 0b00010, _) => Fcvtld(rfp_unary),
+        (0b1100001, 0b00011, _) => Fcvtlud(rfp_unary),
+        (0b1110001, 0b00000, 0b000) => Fmvxd(unary),
+        (0b1110001, 0b00000, 0b001) => Fclassd(unary),
+        (0b1010001, _, 0b010) => Feqd(r),
+        (0b1010001, _, 0b001) => Fltd(r),
+        (0b1010001, _, 0b000) => Fled(r),
+        (0b1101001, 0b00000, _) => Fcvtdw(rfp_unary),
+        (0b1101001, 0b00001, _) => Fcvtdwu(rfp_unary),
+        (0b1101001, 0b00010, _) => Fcvtdl(rfp_unary),
+        (0b1101001, 0b00011, _) => Fcvtdlu(rfp_unary),
+        (0b1111001, 0b00000, 0b000) => Fmvdx(unary),
+
+        (0b0000010, _, _) => Faddh(rfp),
+        (0b0000110, _, _) => Fsubh(rfp),
+        (0b0001010, _, _) => Fmulh(rfp),
+        (0b0001110, _, _) => Fdivh(rfp),
+        (0b0101110, 0b00000, _) => Fsqrth(rfp_unary),
+        (0b0010010, _, 0b000) => Fsgnjh(r),
+        (0b0010010, _, 0b001) => Fsgnjnh(r),
+        (0b0010010, _, 0b010) => Fsgnjxh(r),
+        (0b0010110, _, 0b000) => Fminh(r),
+        (0b0010110, _, 0b001) => Fmaxh(r),
+        (0b0100000, 0b00010, _) => Fcvtsh(rfp_unary),
+        (0b0100010, 0b00000, _) => Fcvths(rfp_unary),
+        (0b0100001, 0b00010, _) => Fcvtdh(rfp_unary),
+        (0b0100010, 0b00001, _) => Fcvthd(rfp_unary),
+        (0b1100010, 0b00000, _) => Fcvtwh(rfp_unary),
+        (0b1100010, 0b00001, _) => Fcvtwuh(rfp_unary),
+        (0b1100010, 0b00010, _) => Fcvtlh(rfp_unary),
+        (0b1100010, 0b00011, _) => Fcvtluh(rfp_unary),
+        (0b1110010, 0b00000, 0b000) => Fmvxh(unary),
+        (0b1110010, 0b00000, 0b001) => Fclassh(unary),
+        (0b1010010, _, 0b010) => Feqh(r),
+        (0b1010010, _, 0b001) => Flth(r),
+        (0b1010010, _, 0b000) => Fleh(r),
+        (0b1101010, 0b00000, _) => Fcvthw(rfp_unary),
+        (0b1101010, 0b00001, _) => Fcvthwu(rfp_unary),
+        (0b1101010, 0b00010, _) => Fcvthl(rfp_unary),
+        (0b1101010, 0b00011, _) => Fcvthlu(rfp_unary),
+        (0b1111010, 0b00000, 0b000) => Fmvhx(unary),
+        _ => return None,
+    })
+}
+
+fn decode_load_fp(word: Word) -> Result<Option<Instruction>, DecodeError> {
+    let Some(eew) = word.vector_eew() else {
+        return Ok(match word.funct3() {
+            0b001 => Some(Flh(word.i())),
+            0b010 => Some(Flw(word.i())),
+            0b011 => Some(Fld(word.i())),
+            _ => None,
+        });
+    };
+
+    // mew = 1 is reserved for EEW > 64
+    if word.bits(28, 28) == 1 {
+        return Err(DecodeError::Reserved(word.0));
+    }
+
+    let nf = word.nf();
+    let vl = Vl {
+        vd: word.rd(),
+        rs1: word.rs1(),
+        vm: word.vm(),
+    };
+    let vls = Vls {
+        vd: word.rd(),
+        rs1: word.rs1(),
+        rs2: word.rs2(),
+        vm: word.vm(),
+    };
+    let vlx = Vlx {
+        vd: word.rd(),
+        rs1: word.rs1(),
+        vs2: word.rs2(),
+        vm: word.vm(),
+    };
+
+    Ok(Some(match (word.bits(27, 26), word.rs2(), nf) {
+        (0b00, 0b00000, 1) => Vlv { data: vl, eew },
+        (0b00, 0b00000, _) => Vlsegv { data: vl, eew, nf },
+        (0b00, 0b01000, _) => {
+            // Register group has to be aligned to its size
+            if !matches!(nf, 1 | 2 | 4 | 8) || word.vm() || !word.rd().is_multiple_of(nf) {
+                return Err(DecodeError::Reserved(word.0));
+            }
+
+            Vlrv {
+                data: Vlr {
+                    vd: word.rd(),
+                    rs1: word.rs1(),
+                },
+                eew,
+                nf,
+            }
+        }
+        (0b00, 0b01011, 1) if eew == BaseSew::E8 && !word.vm() => Vlmv(vl),
+        (0b00, 0b10000, 1) => Vlffv { data: vl, eew },
+        (0b00, 0b10000, _) => Vlsegffv { data: vl, eew, nf },
+        (0b00, _, _) => return Ok(None),
+        (0b01, _, 1) => Vluxv { data: vlx, eew },
+        (0b01, _, _) => Vluxsegv { data: vlx, eew, nf },
+        (0b10, _, 1) => Vlsv { data: vls, eew },
+        (0b10, _, _) => Vlssegv { data: vls, eew, nf },
+        (0b11, _, 1) => Vloxv { data: vlx, eew },
+        (0b11, _, _) => Vloxsegv { data: vlx, eew, nf },
+        _ => unreachable!(),
+    }))
+}
+
+fn decode_store_fp(word: Word) -> Result<Option<Instruction>, DecodeError> {
+    let Some(eew) = word.vector_eew() else {
+        return Ok(match word.funct3() {
+            0b001 => Some(Fsh(word.s())),
+            0b010 => Some(Fsw(word.s())),
+            0b011 => Some(Fsd(word.s())),
+            _ => None,
+        });
+    };
+
+    if word.bits(28, 28) == 1 {
+        return Err(DecodeError::Reserved(word.0));
+    }
+
+    let nf = word.nf();
+    let vs = Vs {
+        vs3: word.rd(),
+        rs1: word.rs1(),
+        vm: word.vm(),
+    };
+    let vss = Vss {
+        vs3: word.rd(),
+        rs1: word.rs1(),
+        rs2: word.rs2(),
+        vm: word.vm(),
+    };
+    let vsx = Vsx {
+        vs3: word.rd(),
+        rs1: word.rs1(),
+        vs2: word.rs2(),
+        vm: word.vm(),
+    };
+
+    Ok(Some(match (word.bits(27, 26), word.rs2(), nf) {
+        (0b00, 0b00000, 1) => Vsv { data: vs, eew },
+        (0b00, 0b00000, _) => Vssegv { data: vs, eew, nf },
+        (0b00, 0b01000, _) => {
+            if !matches!(nf, 1 | 2 | 4 | 8)
+                || word.vm()
+                || eew != BaseSew::E8
+                || !word.rd().is_multiple_of(nf)
+            {
+                return Err(DecodeError::Reserved(word.0));
+            }
+
+            Vsrv {
+                data: Vsr {
+                    vs3: word.rd(),
+                    rs1: word.rs1(),
+                },
+                nf,
+            }
+        }
+        (0b00, 0b01011, 1) if eew == BaseSew::E8 && !word.vm() => Vsmv(vs),
+        (0b00, _, _) => return Ok(None),
+        (0b01, _, 1) => Vsuxv { data: vsx, eew },
+        (0b01, _, _) => Vsuxsegv { data: vsx, eew, nf },
+        (0b10, _, 1) => Vssv { data: vss, eew },
+        (0b10, _, _) => Vsssegv { data: vss, eew, nf },
+        (0b11, _, 1) => Vsoxv { data: vsx, eew },
+        (0b11, _, _) => Vsoxsegv { data: vsx, eew, nf },
+        _ => unreachable!(),
+    }))
+}
+
+fn decode_op_v(word: Word) -> Result<Option<Instruction>, DecodeError> {
+    Ok(match word.funct3() {
+        0b000 => decode_opivv(word),
+        0b001 => decode_opfvv(word),
+        0b010 => decode_opmvv(word),
+        0b011 => decode_opivi(word),
+        0b100 => decode_opivx(word),
+        0b101 => decode_opfvf(word),
+        0b110 => decode_opmvx(word),
+        _ => return decode_vset(word),
+    })
+}
+
+fn decode_vset(word: Word) -> Result<Option<Instruction>, DecodeError> {
+    Ok(Some(match (word.bits(31, 31), word.bits(30, 30)) {
+        (0, _) => Vsetvli(format::Vsetvli {
+            rd: word.rd(),
+            rs1: word.rs1(),
+            vtypei: word.bits(30, 20),
+        }),
+        (1, 1) => Vsetivli(format::Vsetivli {
+            rd: word.rd(),
+            uimm: word.bits(19, 15),
+            vtypei: word.bits(29, 20),
+        }),
+        _ if word.funct7() == 0b1000000 => Vsetvl(format::Vsetvl {
+            rd: word.rd(),
+            rs1: word.rs1(),
+            rs2: word.rs2(),
+        }),
+        _ => return Ok(None),
+    }))
+}
+
+fn decode_opivv(word: Word) -> Option<Instruction> {
+    let op = word.opivv();
+
+    Some(match word.funct6() {
+        0b000000 => Vaddvv(op),
+        0b000010 => Vsubvv(op),
+        0b000100 => Vminuvv(op),
+        0b000101 => Vminvv(op),
+        0b000110 => Vmaxuvv(op),
+        0b000111 => Vmaxvv(op),
+        0b001001 => Vandvv(op),
+        0b001010 => Vorvv(op),
+        0b001011 => Vxorvv(op),
+        0b001100 => Vrgathervv(op),
+        0b001110 => Vrgatherei16vv(op),
+        0b010000 if op.vm => Vadcvvm(op),
+        0b010001 if op.vm => Vmadcvvm(op),
+        0b010001 => Vmadcvv(op),
+        0b010010 if op.vm => Vsbcvvm(op),
+        0b010011 if op.vm => Vmsbcvvm(op),
+        0b010011 => Vmsbcvv(op),
+        0b010111 if op.vm => Vmergevvm(op),
+        0b010111 if op.vs2 == 0 => Vmvvv(op),
+        0b011000 => Vmseqvv(op),
+        0b011001 => Vmsnevv(op),
+        0b011010 => Vmsltuvv(op),
+        0b011011 => Vmsltvv(op),
+        0b011100 => Vmsleuvv(op),
+        0b011101 => Vmslevv(op),
+        0b100000 => Vsadduvv(op),
+        0b100001 => Vsaddvv(op),
+        0b100010 => Vssubuvv(op),
+        0b100011 => Vssubvv(op),
+        0b100101 => Vsllvv(op),
+        0b100111 => Vsmulvv(op),
+        0b101000 => Vsrlvv(op),
+        0b101001 => Vsravv(op),
+        0b101010 => Vssrlvv(op),
+        0b101011 => Vssravv(op),
+        0b101100 => Vnsrlwv(op),
+        0b101101 => Vnsrawv(op),
+        0b101110 => Vnclipuwv(op),
+        0b101111 => Vnclipwv(op),
+        0b110000 => Vwredsumuvs(op),
+        0b110001 => Vwredsumvs(op),
+        _ => return None,
+    })
+}
+
+fn decode_opivx(word: Word) -> Option<Instruction> {
+    let op = word.opivx();
+
+    Some(match word.funct6() {
+        0b000000 => Vaddvx(op),
+        0b000010 => Vsubvx(op),
+        0b000011 => Vrsubvx(op),
+        0b000100 => Vminuvx(op),
+        0b000101 => Vminvx(op),
+        0b000110 => Vmaxuvx(op),
+        0b000111 => Vmaxvx(op),
+        0b001001 => Vandvx(op),
+        0b001010 => Vorvx(op),
+        0b001011 => Vxorvx(op),
+        0b001100 => Vrgathervx(op),
+        0b001110 => Vslideupvx(op),
+        0b001111 => Vslidedownvx(op),
+        0b010000 if op.vm => Vadcvxm(op),
+        0b010001 if op.vm => Vmadcvxm(op),
+        0b010001 => Vmadcvx(op),
+        0b010010 if op.vm => Vsbcvxm(op),
+        0b010011 if op.vm => Vmsbcvxm(op),
+        0b010011 => Vmsbcvx(op),
+        0b010111 if op.vm => Vmergevxm(op),
+        0b010111 if op.vs2 == 0 => Vmvvx(op),
+        0b011000 => Vmseqvx(op),
+        0b011001 => Vmsnevx(op),
+        0b011010 => Vmsltuvx(op),
+        0b011011 => Vmsltvx(op),
+        0b011100 => Vmsleuvx(op),
+        0b011101 => Vmslevx(op),
+        0b011110 => Vmsgtuvx(op),
+        0b011111 => Vmsgtvx(op),
+        0b100000 => Vsadduvx(op),
+        0b100001 => Vsaddvx(op),
+        0b100010 => Vssubuvx(op),
+        0b100011 => Vssubvx(op),
+        0b100101 => Vsllvx(op),
+        0b100111 => Vsmulvx(op),
+        0b101000 => Vsrlvx(op),
+        0b101001 => Vsravx(op),
+        0b101010 => Vssrlvx(op),
+        0b101011 => Vssravx(op),
+        0b101100 => Vnsrlwx(op),
+        0b101101 => Vnsrawx(op),
+        0b101110 => Vnclipuwx(op),
+        0b101111 => Vnclipwx(op),
+        _ => return None,
+    })
+}
+
+fn decode_opivi(word: Word) -> Option<Instruction> {
+    let signed = word.opivi(word.simm5());
+    let unsigned = word.opivi(word.uimm5());
+
+    Some(match word.funct6() {
+        0b000000 => Vaddvi(signed),
+        0b000011 => Vrsubvi(signed),
+        0b001001 => Vandvi(signed),
+        0b001010 => Vorvi(signed),
+        0b001011 => Vxorvi(signed),
+        0b001100 => Vrgathervi(unsigned),
+        0b001110 => Vslideupvi(unsigned),
+        0b001111 => Vslidedownvi(unsigned),
+        0b010000 if signed.vm => Vadcvim(signed),
+        0b010001 if signed.vm => Vmadcvim(signed),
+        0b010001 => Vmadcvi(signed),
+        0b010111 if signed.vm => Vmergevim(signed),
+        0b010111 if signed.vs2 == 0 => Vmvvi(signed),
+        0b011000 => Vmseqvi(signed),
+        0b011001 => Vmsnevi(signed),
+        0b011100 => Vmsleuvi(signed),
+        0b011101 => Vmslevi(signed),
+        0b011110 => Vmsgtuvi(signed),
+        0b011111 => Vmsgtvi(signed),
+        0b100000 => Vsadduvi(signed),
+        0b100001 => Vsaddvi(signed),
+        0b100101 => Vsllvi(unsigned),
+        0b100111 if !unsigned.vm => {
+            let nreg = unsigned.imm5 as usize + 1;
+
+            // Source and destination groups have to be aligned to their size
+            if !unsigned.dest.is_multiple_of(nreg) || !unsigned.vs2.is_multiple_of(nreg) {
+                return None;
+            }
+
+            match unsigned.imm5 {
+                0 => Vmv1rv(unsigned),
+                1 => Vmv2rv(unsigned),
+                3 => Vmv4rv(unsigned),
+                7 => Vmv8rv(unsigned),
+                _ => return None,
+            }
+        }
+        0b101000 => Vsrlvi(unsigned),
+        0b101001 => Vsravi(unsigned),
+        0b101010 => Vssrlvi(unsigned),
+        0b101011 => Vssravi(unsigned),
+        0b101100 => Vnsrlwi(unsigned),
+        0b101101 => Vnsrawi(unsigned),
+        0b101110 => Vnclipuwi(unsigned),
+        0b101111 => Vnclipwi(unsigned),
+        _ => return None,
+    })
+}
+
+fn decode_opmvv(word: Word) -> Option<Instruction> {
+    let op = word.opmvv();
+    let unary = word.opmvv_unary();
+
+    Some(match word.funct6() {
+        0b000000 => Vredsumvs(op),
+        0b000001 => Vredandvs(op),
+        0b000010 => Vredorvs(op),
+        0b000011 => Vredxorvs(op),
+        0b000100 => Vredminuvs(op),
+        0b000101 => Vredminvs(op),
+        0b000110 => Vredmaxuvs(op),
+        0b000111 => Vredmaxvs(op),
+        0b001000 => Vaadduvv(op),
+        0b001001 => Vaaddvv(op),
+        0b001010 => Vasubuvv(op),
+        0b001011 => Vasubvv(op),
+        0b010000 => match op.vs1 {
+            0b00000 if !op.vm => Vmvxs(unary),
+            0b10000 => Vcpopm(unary),
+            0b10001 => Vfirstm(unary),
+            _ => return None,
+        },
+        0b010010 => match op.vs1 {
+            0b00010 => Vzextvf8(unary),
+            0b00011 => Vsextvf8(unary),
+            0b00100 => Vzextvf4(unary),
+            0b00101 => Vsextvf4(unary),
+            0b00110 => Vzextvf2(unary),
+            0b00111 => Vsextvf2(unary),
+            _ => return None,
+        },
+        0b010100 => match op.vs1 {
+            0b00001 => Vmsbfm(unary),
+            0b00010 => Vmsofm(unary),
+            0b00011 => Vmsifm(unary),
+            0b10000 => Viotam(unary),
+            0b10001 if op.vs2 == 0 => Vidv(unary),
+            _ => return None,
+        },
+        0b010111 if !op.vm => Vcompressvm(op),
+        0b011000 if !op.vm => Vmandnmm(op),
+        0b011001 if !op.vm => Vmandmm(op),
+        0b011010 if !op.vm => Vmormm(op),
+        0b011011 if !op.vm => Vmxormm(op),
+        0b011100 if !op.vm => Vmornmm(op),
+        0b011101 if !op.vm => Vmnandmm(op),
+        0b011110 if !op.vm => Vmnormm(op),
+        0b011111 if !op.vm => Vmxnormm(op),
+        0b100000 => Vdivuvv(op),
+        0b100001 => Vdivvv(op),
+        0b100010 => Vremuvv(op),
+        0b100011 => Vremvv(op),
+        0b100100 => Vmulhuvv(op),
+        0b100101 => Vmulvv(op),
+        0b100110 => Vmulhsuvv(op),
+        0b100111 => Vmulhvv(op),
+        0b101001 => Vmaddvv(op),
+        0b101011 => Vnmsubvv(op),
+        0b101101 => Vmaccvv(op),
+        0b101111 => Vnmsacvv(op),
+        0b110000 => Vwadduvv(op),
+        0b110001 => Vwaddvv(op),
+        0b110010 => Vwsubuvv(op),
+        0b110011 => Vwsubvv(op),
+        0b110100 => Vwadduwv(op),
+        0b110101 => Vwaddwv(op),
+        0b110110 => Vwsubuwv(op),
+        0b110111 => Vwsubwv(op),
+        0b111000 => Vwmuluvv(op),
+        0b111010 => Vwmulsuvv(op),
+        0b111011 => Vwmulvv(op),
+        0b111100 => Vwmaccuvv(op),
+        0b111101 => Vwmaccvv(op),
+        0b111111 => Vwmaccsuvv(op),
+        _ => return None,
+    })
+}
+
+fn decode_opmvx(word: Word) -> Option<Instruction> {
+    let op = word.opmvx();
+
+    Some(match word.funct6() {
+        0b001000 => Vaadduvx(op),
+        0b001001 => Vaaddvx(op),
+        0b001010 => Vasubuvx(op),
+        0b001011 => Vasubvx(op),
+        0b001110 => Vslide1upvx(op),
+        0b001111 => Vslide1downvx(op),
+        0b010000 if op.vs2 == 0 && !op.vm => Vmvsx(op),
+        0b100000 => Vdivuvx(op),
+        0b100001 => Vdivvx(op),
+        0b100010 => Vremuvx(op),
+        0b100011 => Vremvx(op),
+        0b100100 => Vmulhuvx(op),
+        0b100101 => Vmulvx(op),
+        0b100110 => Vmulhsuvx(op),
+        0b100111 => Vmulhvx(op),
+        0b101001 => Vmaddvx(op),
+        0b101011 => Vnmsubvx(op),
+        0b101101 => Vmaccvx(op),
+        0b101111 => Vnmsacvx(op),
+        0b110000 => Vwadduvx(op),
+        0b110001 => Vwaddvx(op),
+        0b110010 => Vwsubuvx(op),
+        0b110011 => Vwsubvx(op),
+        0b110100 => Vwadduwx(op),
+        0b110101 => Vwaddwx(op),
+        0b110110 => Vwsubuwx(op),
+        0b110111 => Vwsubwx(op),
+        0b111000 => Vwmuluvx(op),
+        0b111010 => Vwmulsuvx(op),
+        0b111011 => Vwmulvx(op),
+        0b111100 => Vwmaccuvx(op),
+        0b111101 => Vwmaccvx(op),
+        0b111110 => Vwmaccusvx(op),
+        0b111111 => Vwmaccsuvx(op),
+        _ => return None,
+    })
+}
+
+fn decode_opfvv(word: Word) -> Option<Instruction> {
+    let op = word.opfvv();
+    let unary = word.opfvv_unary();
+
+    Some(match word.funct6() {
+        0b000000 => Vfaddvv(op),
+        0b000001 => Vfredusumvs(op),
+        0b000010 => Vfsubvv(op),
+        0b000011 => Vfredosumvs(op),
+        0b000100 => Vfminvv(op),
+        0b000101 => Vfredminvs(op),
+        0b000110 => Vfmaxvv(op),
+        0b000111 => Vfredmaxvs(op),
+        0b001000 => Vfsgnjvv(op),
+        0b001001 => Vfsgnjnvv(op),
+        0b001010 => Vfsgnjxvv(op),
+        0b010000 if op.vs1 == 0 && !op.vm => Vfmvfs(unary),
+        0b010010 => match op.vs1 {
+            0b00000 => Vfcvtxufv(unary),
+            0b00001 => Vfcvtxfv(unary),
+            0b00010 => Vfcvtfxuv(unary),
+            0b00011 => Vfcvtfxv(unary),
+            0b00110 => VfcvtRtzxufv(unary),
+            0b00111 => VfcvtRtzxfv(unary),
+            0b01000 => Vfwcvtxufv(unary),
+            0b01001 => Vfwcvtxfv(unary),
+            0b01010 => Vfwcvtfxuv(unary),
+            0b01011 => Vfwcvtfxv(unary),
+            0b01100 => Vfwcvtffv(unary),
+            0b01110 => VfwcvtRtzxufv(unary),
+            0b01111 => VfwcvtRtzxfv(unary),
+            0b10000 => Vfncvtxufw(unary),
+            0b10001 => Vfncvtxfw(unary),
+            0b10010 => Vfncvtfxuw(unary),
+            0b10011 => Vfncvtfxw(unary),
+            0b10100 => Vfncvtffw(unary),
+            0b10101 => VfncvtRodffw(unary),
+            0b10110 => VfncvtRtzxufw(unary),
+            0b10111 => VfncvtRtzxfw(unary),
+            _ => return None,
+        },
+        0b010011 => match op.vs1 {
+            0b00000 => Vfsqrtv(unary),
+            0b00100 => Vfrsqrt7v(unary),
+            0b00101 => Vfrec7v(unary),
+            0b10000 => Vfclassv(unary),
+            _ => return None,
+        },
+        0b011000 => Vmfeqvv(op),
+        0b011001 => Vmflevv(op),
+        0b011011 => Vmfltvv(op),
+        0b011100 => Vmfnevv(op),
+        0b100000 => Vfdivvv(op),
+        0b100100 => Vfmulvv(op),
+        0b101000 => Vfmaddvv(op),
+        0b101001 => Vfnmaddvv(op),
+        0b101010 => Vfmsubvv(op),
+        0b101011 => Vfnmsubvv(op),
+        0b101100 => Vfmaccvv(op),
+        0b101101 => Vfnmaccvv(op),
+        0b101110 => Vfmsacvv(op),
+        0b101111 => Vfnmsacvv(op),
+        0b110000 => Vfwaddvv(op),
+        0b110001 => Vfwredusumvs(op),
+        0b110010 => Vfwsubvv(op),
+        0b110011 => Vfwredosumvs(op),
+        0b110100 => Vfwaddwv(op),
+        0b110110 => Vfwsubwv(op),
+        0b111000 => Vfwmulvv(op),
+        0b111100 => Vfwmaccvv(op),
+        0b111101 => Vfwnmaccvv(op),
+        0b111110 => Vfwmsacvv(op),
+        0b111111 => Vfwnmsacvv(op),
+        _ => return None,
+    })
+}
+
+fn decode_opfvf(word: Word) -> Option<Instruction> {
+    let op = word.opfvf();
+
+    Some(match word.funct6() {
+        0b000000 => Vfaddvf(op),
+        0b000010 => Vfsubvf(op),
+        0b000100 => Vfminvf(op),
+        0b000110 => Vfmaxvf(op),
+        0b001000 => Vfsgnjvf(op),
+        0b001001 => Vfsgnjnvf(op),
+        0b001010 => Vfsgnjxvf(op),
+        0b001110 => Vfslide1upvf(op),
+        0b001111 => Vfslide1downvf(op),
+        0b010000 if op.vs2 == 0 && !op.vm => Vfmvsf(op),
+        0b010111 if op.vm => Vfmergevfm(op),
+        0b010111 if op.vs2 == 0 => Vfmvvf(op),
+        0b011000 => Vmfeqvf(op),
+        0b011001 => Vmflevf(op),
+        0b011011 => Vmfltvf(op),
+        0b011100 => Vmfnevf(op),
+        0b011101 => Vmfgtvf(op),
+        0b011111 => Vmfgevf(op),
+        0b100000 => Vfdivvf(op),
+        0b100001 => Vfrdivvf(op),
+        0b100100 => Vfmulvf(op),
+        0b100111 => Vfrsubvf(op),
+        0b101000 => Vfmaddvf(op),
+        0b101001 => Vfnmaddvf(op),
+        0b101010 => Vfmsubvf(op),
+        0b101011 => Vfnmsubvf(op),
+        0b101100 => Vfmaccvf(op),
+        0b101101 => Vfnmaccvf(op),
+        0b101110 => Vfmsacvf(op),
+        0b101111 => Vfnmsacvf(op),
+        0b110000 => Vfwaddvf(op),
+        0b110010 => Vfwsubvf(op),
+        0b110100 => Vfwaddwf(op),
+        0b110110 => Vfwsubwf(op),
+        0b111000 => Vfwmulvf(op),
+        0b111100 => Vfwmaccvf(op),
+        0b111101 => Vfwnmaccvf(op),
+        0b111110 => Vfwmsacvf(op),
+        0b111111 => Vfwnmsacvf(op),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_decoding_works() {
+        // addi a0, a0, 1
+        assert_eq!(
+            decode(0x00150513),
+            Ok(Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 1
+            }))
+        );
+        // sd ra, -8(sp)
+        assert_eq!(
+            decode(0xfe113c23),
+            Ok(Sd(S {
+                rs1: 2,
+                rs2: 1,
+                imm12: -8
+            }))
+        );
+        // bne a0, zero, -16
+        assert_eq!(
+            decode(0xfe0518e3),
+            Ok(Bne(S {
+                rs1: 10,
+                rs2: 0,
+                imm12: -16
+            }))
+        );
+        // jal ra, 2048
+        assert_eq!(decode(0x001000ef), Ok(Jal(U { rd: 1, imm20: 2048 })));
+        // lui a5, 0xfffff
+        assert_eq!(decode(0xfffff7b7), Ok(Lui(U { rd: 15, imm20: -1 })));
+        // srai a0, a0, 63
+        assert_eq!(
+            decode(0x43f55513),
+            Ok(Srai(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 63
+            }))
+        );
+        // csrrs a0, vlenb, zero
+        assert_eq!(
+            decode(0xc2202573),
+            Ok(Csrrs(Csrr {
+                rd: 10,
+                rs1: 0,
+                csr: 0xc22
+            }))
+        );
+        // fence rw, w
+        assert_eq!(
+            decode(0x0310000f),
+            Ok(Fence(format::Fence {
+                fm: 0,
+                pred: 0b0011,
+                succ: 0b0001,
+                rs1: 0,
+                rd: 0
+            }))
+        );
+        // ecall
+        assert_eq!(decode(0x00000073), Ok(Ecall));
+        // sc.w.rl a0, a2, (a1)
+        assert_eq!(
+            decode(0x1ac5a52f),
+            Ok(Scw(Atomic {
+                rd: 10,
+                rs1: 11,
+                rs2: 12,
+                aq: false,
+                rl: true
+            }))
+        );
+    }
+
+    #[test]
+    fn vector_decoding_works() {
+        // vsetvli t0, a0, e32, m1, ta, ma
+        assert_eq!(
+            decode(0x0d0572d7),
+            Ok(Vsetvli(format::Vsetvli {
+                rd: 5,
+                rs1: 10,
+                vtypei: 0b11010000
+            }))
+        );
+        // vle8.v v8, (a0)
+        assert_eq!(
+            decode(0x02050407),
+            Ok(Vlv {
+                data: Vl {
+                    vd: 8,
+                    rs1: 10,
+                    vm: false
+                },
+                eew: BaseSew::E8
+            })
+        );
+        // vlseg3e32.v v4, (a1), v0.t
+        assert_eq!(
+            decode(0x4005e207),
+            Ok(Vlsegv {
+                data: Vl {
+                    vd: 4,
+                    rs1: 11,
+                    vm: true
+                },
+                eew: BaseSew::E32,
+                nf: 3
+            })
+        );
+        // vl2re16.v v2, (a0)
+        assert_eq!(
+            decode(0x22855107),
+            Ok(Vlrv {
+                data: Vlr { vd: 2, rs1: 10 },
+                eew: BaseSew::E16,
+                nf: 2
+            })
+        );
+        // vadd.vv v1, v2, v3
+        assert_eq!(
+            decode(0x022180d7),
+            Ok(Vaddvv(Opivv {
+                dest: 1,
+                vs1: 3,
+                vs2: 2,
+                vm: false
+            }))
+        );
+        // vadd.vi v1, v2, -1, v0.t
+        assert_eq!(
+            decode(0x002fb0d7),
+            Ok(Vaddvi(Opivi {
+                dest: 1,
+                imm5: -1,
+                vs2: 2,
+                vm: true
+            }))
+        );
+        // vcpop.m a0, v4
+        assert_eq!(
+            decode(0x42482557),
+            Ok(Vcpopm(Opmvv {
+                dest: 10,
+                vs1: 0,
+                vs2: 4,
+                vm: false
+            }))
+        );
+    }
+
+    #[test]
+    fn compressed_instructions_are_expanded() {
+        // c.addi4spn a0, sp, 16
+        assert_eq!(
+            decode(0x0808),
+            Ok(Compressed(
+                Box::new(Addi(I {
+                    rd: 10,
+                    rs1: 2,
+                    imm12: 16
+                })),
+                0x0808
+            ))
+        );
+        // c.ld a0, 8(a1), upper half belongs to the next instruction
+        assert_eq!(
+            decode(0x0513_6588),
+            Ok(Compressed(
+                Box::new(Ld(I {
+                    rd: 10,
+                    rs1: 11,
+                    imm12: 8
+                })),
+                0x6588
+            ))
+        );
+        // c.addi16sp sp, -64
+        assert_eq!(
+            decode(0x7139),
+            Ok(Compressed(
+                Box::new(Addi(I {
+                    rd: 2,
+                    rs1: 2,
+                    imm12: -64
+                })),
+                0x7139
+            ))
+        );
+        // c.lui a0, 0xfffff
+        assert_eq!(
+            decode(0x757d),
+            Ok(Compressed(Box::new(Lui(U { rd: 10, imm20: -1 })), 0x757d))
+        );
+        // c.j -16
+        assert_eq!(
+            decode(0xbfc5),
+            Ok(Compressed(Box::new(Jal(U { rd: 0, imm20: -16 })), 0xbfc5))
+        );
+        // c.bnez a0, -8
+        assert_eq!(
+            decode(0xfd65),
+            Ok(Compressed(
+                Box::new(Bne(S {
+                    rs1: 10,
+                    rs2: 0,
+                    imm12: -8
+                })),
+                0xfd65
+            ))
+        );
+        // c.sdsp a0, 8(sp)
+        assert_eq!(
+            decode(0xe42a),
+            Ok(Compressed(
+                Box::new(Sd(S {
+                    rs1: 2,
+                    rs2: 10,
+                    imm12: 8
+                })),
+                0xe42a
+            ))
+        );
+        // c.jalr a0
+        assert_eq!(
+            decode(0x9502),
+            Ok(Compressed(
+                Box::new(Jalr(I {
+                    rd: 1,
+                    rs1: 10,
+                    imm12: 0
+                })),
+                0x9502
+            ))
+        );
+        assert_eq!(decode(0x9002), Ok(Compressed(Box::new(Ebreak), 0x9002)));
+        // c.lwsp zero, 0(sp) and c.jr zero
+        assert_eq!(decode(0x4002), Err(DecodeError::Reserved(0x4002)));
+        assert_eq!(decode(0x8002), Err(DecodeError::Reserved(0x8002)));
+    }
+
+    #[test]
+    fn compressed_encodings_round_trip() {
+        use crate::rv_core::instruction::encoder::encode;
+
+        for half in (0..=u16::MAX).filter(|half| half & 0b11 != 0b11) {
+            if let Ok(instruction) = decode(half as u32) {
+                assert_eq!(encode(&instruction), Ok(half as u32), "{:#06x}", half);
+            }
+        }
+
+        // c.addi sp, 16 and c.addi16sp sp, 16 expand to the same addi
+        let (Ok(Compressed(addi, _)), Ok(Compressed(addi16sp, _))) =
+            (decode(0x0141), decode(0x6141))
+        else {
+            panic!("c.addi and c.addi16sp are valid");
+        };
+        assert_eq!(addi, addi16sp);
+        // HINT, c.li zero, 10
+        assert_eq!(encode(&decode(0x4029).unwrap()), Ok(0x4029));
+    }
+
+    #[test]
+    fn ignored_fields_round_trip() {
+        use crate::rv_core::instruction::encoder::encode;
+
+        let words = [
+            // fence.i with rd, rs1 and imm set
+            0x36339f0f, // fence with rd and rs1 set
+            0x0ff2838f, // fence.tso
+            0x8330000f, // fcvt.d.s fa0, fa1, rtz
+            0x42059553, // fcvt.d.wu fa0, a0, dyn
+            0xd2157553, // vlseg2e32ff.v v4, (a0)
+            0x23056207,
+        ];
+
+        for word in words {
+            assert_eq!(encode(&decode(word).unwrap()), Ok(word), "{:#010x}", word);
+        }
+    }
+
+    #[test]
+    fn invalid_words_are_rejected() {
+        assert_eq!(decode(0x0000_0000), Err(DecodeError::Reserved(0)));
+        assert_eq!(decode(0xffff_ffff), Err(DecodeError::Unknown(0xffff_ffff)));
+        // vle8.v with mew set
+        assert_eq!(decode(0x12050407), Err(DecodeError::Reserved(0x12050407)));
+        // fadd.s with the reserved rounding mode 0b101
+        assert_eq!(decode(0x00c5d553), Err(DecodeError::Unknown(0x00c5d553)));
+        // vl4re16.v v30, (a0) with a misaligned register group
+        assert_eq!(decode(0x62855f07), Err(DecodeError::Reserved(0x62855f07)));
+        // vmv2r.v v3, v4 and vmv2r.v v2, v5
+        assert_eq!(decode(0x9e40b1d7), Err(DecodeError::Unknown(0x9e40b1d7)));
+        assert_eq!(decode(0x9e50b157), Err(DecodeError::Unknown(0x9e50b157)));
+    }
+}
@@ -0,0 +1,1692 @@
+use std::fmt::{Display, Formatter, Result};
+
+use super::{format, format::*, Instruction, Instruction::*};
+use crate::rv_core::{
+    registers::aliases::csr,
+    softfloat::RoundingMode,
+    vector_engine::sew::{BaseSew, Sew},
+};
+
+/// Instruction as placed at `pc`, branch and jump targets are printed as addresses
+pub struct Disassembly<'a> {
+    instruction: &'a Instruction,
+    pc: u64,
+}
+
+impl Instruction {
+    pub fn disassemble(&self, pc: u64) -> Disassembly<'_> {
+        Disassembly {
+            instruction: self,
+            pc,
+        }
+    }
+}
+
+// Targets are printed as if the instruction was placed at address 0
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.disassemble(0).fmt(f)
+    }
+}
+
+impl Display for Disassembly<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let pc = self.pc;
+
+        match self.instruction {
+            Add(op) => r(f, "add", op),
+            Addw(op) => r(f, "addw", op),
+            Sub(op) => r(f, "sub", op),
+            Subw(op) => r(f, "subw", op),
+            Addi(op) => i(f, "addi", op),
+            Addiw(op) => i(f, "addiw", op),
+            Slt(op) => r(f, "slt", op),
+            Slti(op) => i(f, "slti", op),
+            Sltu(op) => r(f, "sltu", op),
+            Sltiu(op) => i(f, "sltiu", op),
+            Lui(op) => u(f, "lui", op),
+            Auipc(op) => u(f, "auipc", op),
+            And(op) => r(f, "and", op),
+            Or(op) => r(f, "or", op),
+            Xor(op) => r(f, "xor", op),
+            Andi(op) => i(f, "andi", op),
+            Ori(op) => i(f, "ori", op),
+            Xori(op) => i(f, "xori", op),
+            Sll(op) => r(f, "sll", op),
+            Sllw(op) => r(f, "sllw", op),
+            Srl(op) => r(f, "srl", op),
+            Srlw(op) => r(f, "srlw", op),
+            Sra(op) => r(f, "sra", op),
+            Sraw(op) => r(f, "sraw", op),
+            Slli(op) => i(f, "slli", op),
+            Slliw(op) => i(f, "slliw", op),
+            Srli(op) => i(f, "srli", op),
+            Srliw(op) => i(f, "srliw", op),
+            Srai(op) => i(f, "srai", op),
+            Sraiw(op) => i(f, "sraiw", op),
+            Ld(op) => load(f, "ld", X(op.rd), op),
+            Lw(op) => load(f, "lw", X(op.rd), op),
+            Lh(op) => load(f, "lh", X(op.rd), op),
+            Lb(op) => load(f, "lb", X(op.rd), op),
+            Lwu(op) => load(f, "lwu", X(op.rd), op),
+            Lhu(op) => load(f, "lhu", X(op.rd), op),
+            Lbu(op) => load(f, "lbu", X(op.rd), op),
+            Sd(op) => store(f, "sd", X(op.rs2), op),
+            Sw(op) => store(f, "sw", X(op.rs2), op),
+            Sh(op) => store(f, "sh", X(op.rs2), op),
+            Sb(op) => store(f, "sb", X(op.rs2), op),
+            Beq(op) => branch(f, "beq", op, pc),
+            Bne(op) => branch(f, "bne", op, pc),
+            Bge(op) => branch(f, "bge", op, pc),
+            Bgeu(op) => branch(f, "bgeu", op, pc),
+            Blt(op) => branch(f, "blt", op, pc),
+            Bltu(op) => branch(f, "bltu", op, pc),
+            Csrrw(op) => csrr(f, "csrrw", op),
+            Csrrs(op) => csrr(f, "csrrs", op),
+            Csrrc(op) => csrr(f, "csrrc", op),
+            Csrrwi(op) => csri(f, "csrrwi", op),
+            Csrrsi(op) => csri(f, "csrrsi", op),
+            Csrrci(op) => csri(f, "csrrci", op),
+            Mret => write!(f, "mret"),
+            Wfi => write!(f, "wfi"),
+            Sret => write!(f, "sret"),
+            SfenceVma(op) => sfence_vma(f, op),
+            Mul(op) => r(f, "mul", op),
+            Mulh(op) => r(f, "mulh", op),
+            Mulhsu(op) => r(f, "mulhsu", op),
+            Mulhu(op) => r(f, "mulhu", op),
+            Div(op) => r(f, "div", op),
+            Divu(op) => r(f, "divu", op),
+            Rem(op) => r(f, "rem", op),
+            Remu(op) => r(f, "remu", op),
+            Mulw(op) => r(f, "mulw", op),
+            Divw(op) => r(f, "divw", op),
+            Divuw(op) => r(f, "divuw", op),
+            Remw(op) => r(f, "remw", op),
+            Remuw(op) => r(f, "remuw", op),
+
+            Adduw(op) => r(f, "add.uw", op),
+            Sh1add(op) => r(f, "sh1add", op),
+            Sh2add(op) => r(f, "sh2add", op),
+            Sh3add(op) => r(f, "sh3add", op),
+            Sh1adduw(op) => r(f, "sh1add.uw", op),
+            Sh2adduw(op) => r(f, "sh2add.uw", op),
+            Sh3adduw(op) => r(f, "sh3add.uw", op),
+            Slliuw(op) => i(f, "slli.uw", op),
+
+            Andn(op) => r(f, "andn", op),
+            Orn(op) => r(f, "orn", op),
+            Xnor(op) => r(f, "xnor", op),
+            Clz(op) => unary(f, "clz", op),
+            Clzw(op) => unary(f, "clzw", op),
+            Ctz(op) => unary(f, "ctz", op),
+            Ctzw(op) => unary(f, "ctzw", op),
+            Cpop(op) => unary(f, "cpop", op),
+            Cpopw(op) => unary(f, "cpopw", op),
+            Max(op) => r(f, "max", op),
+            Maxu(op) => r(f, "maxu", op),
+            Min(op) => r(f, "min", op),
+            Minu(op) => r(f, "minu", op),
+            Sextb(op) => unary(f, "sext.b", op),
+            Sexth(op) => unary(f, "sext.h", op),
+            Zexth(op) => unary(f, "zext.h", op),
+            Rol(op) => r(f, "rol", op),
+            Rolw(op) => r(f, "rolw", op),
+            Ror(op) => r(f, "ror", op),
+            Rori(op) => i(f, "rori", op),
+            Roriw(op) => i(f, "roriw", op),
+            Rorw(op) => r(f, "rorw", op),
+            Orcb(op) => unary(f, "orc.b", op),
+            Rev8(op) => unary(f, "rev8", op),
+
+            Bclr(op) => r(f, "bclr", op),
+            Bclri(op) => i(f, "bclri", op),
+            Bext(op) => r(f, "bext", op),
+            Bexti(op) => i(f, "bexti", op),
+            Binv(op) => r(f, "binv", op),
+            Binvi(op) => i(f, "binvi", op),
+            Bset(op) => r(f, "bset", op),
+            Bseti(op) => i(f, "bseti", op),
+            Lrw(op) => lr(f, "lr.w", op),
+            Scw(op) => amo(f, "sc.w", op),
+            Amoswapw(op) => amo(f, "amoswap.w", op),
+            Amoaddw(op) => amo(f, "amoadd.w", op),
+            Amoxorw(op) => amo(f, "amoxor.w", op),
+            Amoandw(op) => amo(f, "amoand.w", op),
+            Amoorw(op) => amo(f, "amoor.w", op),
+            Amominw(op) => amo(f, "amomin.w", op),
+            Amomaxw(op) => amo(f, "amomax.w", op),
+            Amominuw(op) => amo(f, "amominu.w", op),
+            Amomaxuw(op) => amo(f, "amomaxu.w", op),
+            Lrd(op) => lr(f, "lr.d", op),
+            Scd(op) => amo(f, "sc.d", op),
+            Amoswapd(op) => amo(f, "amoswap.d", op),
+            Amoaddd(op) => amo(f, "amoadd.d", op),
+            Amoxord(op) => amo(f, "amoxor.d", op),
+            Amoandd(op) => amo(f, "amoand.d", op),
+            Amoord(op) => amo(f, "amoor.d", op),
+            Amomind(op) => amo(f, "amomin.d", op),
+            Amomaxd(op) => amo(f, "amomax.d", op),
+            Amominud(op) => amo(f, "amominu.d", op),
+            Amomaxud(op) => amo(f, "amomaxu.d", op),
+            Flw(op) => load(f, "flw", F(op.rd), op),
+            Fsw(op) => store(f, "fsw", F(op.rs2), op),
+            Fmadds(op) => r4(f, "fmadd.s", op),
+            Fmsubs(op) => r4(f, "fmsub.s", op),
+            Fnmsubs(op) => r4(f, "fnmsub.s", op),
+            Fnmadds(op) => r4(f, "fnmadd.s", op),
+            Fadds(op) => fr_rm(f, "fadd.s", op),
+            Fsubs(op) => fr_rm(f, "fsub.s", op),
+            Fmuls(op) => fr_rm(f, "fmul.s", op),
+            Fdivs(op) => fr_rm(f, "fdiv.s", op),
+            Fsqrts(op) => frs_rm(f, "fsqrt.s", op),
+            Fsgnjs(op) => fr(f, "fsgnj.s", op),
+            Fsgnjns(op) => fr(f, "fsgnjn.s", op),
+            Fsgnjxs(op) => fr(f, "fsgnjx.s", op),
+            Fmins(op) => fr(f, "fmin.s", op),
+            Fmaxs(op) => fr(f, "fmax.s", op),
+            Fcvtws(op) => frs_to_x_rm(f, "fcvt.w.s", op),
+            Fcvtwus(op) => frs_to_x_rm(f, "fcvt.wu.s", op),
+            Fmvxw(op) => frs_to_x(f, "fmv.x.w", op),
+            Feqs(op) => frx(f, "feq.s", op),
+            Flts(op) => frx(f, "flt.s", op),
+            Fles(op) => frx(f, "fle.s", op),
+            Fclasss(op) => frs_to_x(f, "fclass.s", op),
+            Fcvtsw(op) => frs_to_f_rm(f, "fcvt.s.w", op),
+            Fcvtswu(op) => frs_to_f_rm(f, "fcvt.s.wu", op),
+            Fmvwx(op) => frs_to_f(f, "fmv.w.x", op),
+            Fcvtls(op) => frs_to_x_rm(f, "fcvt.l.s", op),
+            Fcvtlus(op) => frs_to_x_rm(f, "fcvt.lu.s", op),
+            Fcvtsl(op) => frs_to_f_rm(f, "fcvt.s.l", op),
+            Fcvtslu(op) => frs_to_f_rm(f, "fcvt.s.lu", op),
+            Fld(op) => load(f, "fld", F(op.rd), op),
+            Fsd(op) => store(f, "fsd", F(op.rs2), op),
+            Fmaddd(op) => r4(f, "fmadd.d", op),
+            Fmsubd(op) => r4(f, "fmsub.d", op),
+            Fnmsubd(op) => r4(f, "fnmsub.d", op),
+            Fnmaddd(op) => r4(f, "fnmadd.d", op),
+            Faddd(op) => fr_rm(f, "fadd.d", op),
+            Fsubd(op) => fr_rm(f, "fsub.d", op),
+            Fmuld(op) => fr_rm(f, "fmul.d", op),
+            Fdivd(op) => fr_rm(f, "fdiv.d", op),
+            Fsqrtd(op) => frs_rm(f, "fsqrt.d", op),
+            Fsgnjd(op) => fr(f, "fsgnj.d", op),
+            Fsgnjnd(op) => fr(f, "fsgnjn.d", op),
+            Fsgnjxd(op) => fr(f, "fsgnjx.d", op),
+            Fmind(op) => fr(f, "fmin.d", op),
+            Fmaxd(op) => fr(f, "fmax.d", op),
+            Fcvtsd(op) => frs_rm(f, "fcvt.s.d", op),
+            Fcvtds(op) => frs_exact(f, "fcvt.d.s", op),
+            Feqd(op) => frx(f, "feq.d", op),
+            Fltd(op) => frx(f, "flt.d", op),
+            Fled(op) => frx(f, "fle.d", op),
+            Fclassd(op) => frs_to_x(f, "fclass.d", op),
+            Fcvtwd(op) => frs_to_x_rm(f, "fcvt.w.d", op),
+            Fcvtwud(op) => frs_to_x_rm(f, "fcvt.wu.d", op),
+            Fcvtdw(op) => frs_to_f_exact(f, "fcvt.d.w", op),
+            Fcvtdwu(op) => frs_to_f_exact(f, "fcvt.d.wu", op),
+            Fcvtld(op) => frs_to_x_rm(f, "fcvt.l.d", op),
+            Fcvtlud(op) => frs_to_x_rm(f, "fcvt.lu.d", op),
+            Fmvxd(op) => frs_to_x(f, "fmv.x.d", op),
+            Fcvtdl(op) => frs_to_f_rm(f, "fcvt.d.l", op),
+            Fcvtdlu(op) => frs_to_f_rm(f, "fcvt.d.lu", op),
+            Fmvdx(op) => frs_to_f(f, "fmv.d.x", op),
+            Flh(op) => load(f, "flh", F(op.rd), op),
+            Fsh(op) => store(f, "fsh", F(op.rs2), op),
+            Fmaddh(op) => r4(f, "fmadd.h", op),
+            Fmsubh(op) => r4(f, "fmsub.h", op),
+            Fnmsubh(op) => r4(f, "fnmsub.h", op),
+            Fnmaddh(op) => r4(f, "fnmadd.h", op),
+            Faddh(op) => fr_rm(f, "fadd.h", op),
+            Fsubh(op) => fr_rm(f, "fsub.h", op),
+            Fmulh(op) => fr_rm(f, "fmul.h", op),
+            Fdivh(op) => fr_rm(f, "fdiv.h", op),
+            Fsqrth(op) => frs_rm(f, "fsqrt.h", op),
+            Fsgnjh(op) => fr(f, "fsgnj.h", op),
+            Fsgnjnh(op) => fr(f, "fsgnjn.h", op),
+            Fsgnjxh(op) => fr(f, "fsgnjx.h", op),
+            Fminh(op) => fr(f, "fmin.h", op),
+            Fmaxh(op) => fr(f, "fmax.h", op),
+            Fcvtsh(op) => frs_exact(f, "fcvt.s.h", op),
+            Fcvths(op) => frs_rm(f, "fcvt.h.s", op),
+            Fcvtdh(op) => frs_exact(f, "fcvt.d.h", op),
+            Fcvthd(op) => frs_rm(f, "fcvt.h.d", op),
+            Fcvtwh(op) => frs_to_x_rm(f, "fcvt.w.h", op),
+            Fcvtwuh(op) => frs_to_x_rm(f, "fcvt.wu.h", op),
+            Fmvxh(op) => frs_to_x(f, "fmv.x.h", op),
+            Feqh(op) => frx(f, "feq.h", op),
+            Flth(op) => frx(f, "flt.h", op),
+            Fleh(op) => frx(f, "fle.h", op),
+            Fclassh(op) => frs_to_x(f, "fclass.h", op),
+            Fcvthw(op) => frs_to_f_rm(f, "fcvt.h.w", op),
+            Fcvthwu(op) => frs_to_f_rm(f, "fcvt.h.wu", op),
+            Fmvhx(op) => frs_to_f(f, "fmv.h.x", op),
+            Fcvtlh(op) => frs_to_x_rm(f, "fcvt.l.h", op),
+            Fcvtluh(op) => frs_to_x_rm(f, "fcvt.lu.h", op),
+            Fcvthl(op) => frs_to_f_rm(f, "fcvt.h.l", op),
+            Fcvthlu(op) => frs_to_f_rm(f, "fcvt.h.lu", op),
+            Jal(op) => jal(f, op, pc),
+            Jalr(op) => load(f, "jalr", X(op.rd), op),
+            Ecall => write!(f, "ecall"),
+            Ebreak => write!(f, "ebreak"),
+            Fence(op) => fence(f, op),
+            FenceI(_) => write!(f, "fence.i"),
+            Vsetvli(op) => vsetvli(f, op),
+            Vsetivli(op) => vsetivli(f, op),
+            Vsetvl(op) => vsetvl(f, op),
+            Vlmv(op) => vl(f, "vlm.v", op),
+            Vsmv(op) => vs(f, "vsm.v", op),
+            Vaddvv(op) => opivv(f, "vadd.vv", op),
+            Vaddvx(op) => opivx(f, "vadd.vx", op),
+            Vaddvi(op) => opivi(f, "vadd.vi", op),
+            Vsubvv(op) => opivv(f, "vsub.vv", op),
+            Vsubvx(op) => opivx(f, "vsub.vx", op),
+            Vrsubvx(op) => opivx(f, "vrsub.vx", op),
+            Vrsubvi(op) => opivi(f, "vrsub.vi", op),
+            Vminuvv(op) => opivv(f, "vminu.vv", op),
+            Vminuvx(op) => opivx(f, "vminu.vx", op),
+            Vminvv(op) => opivv(f, "vmin.vv", op),
+            Vminvx(op) => opivx(f, "vmin.vx", op),
+            Vmaxuvv(op) => opivv(f, "vmaxu.vv", op),
+            Vmaxuvx(op) => opivx(f, "vmaxu.vx", op),
+            Vmaxvv(op) => opivv(f, "vmax.vv", op),
+            Vmaxvx(op) => opivx(f, "vmax.vx", op),
+            Vandvv(op) => opivv(f, "vand.vv", op),
+            Vandvx(op) => opivx(f, "vand.vx", op),
+            Vandvi(op) => opivi(f, "vand.vi", op),
+            Vorvv(op) => opivv(f, "vor.vv", op),
+            Vorvx(op) => opivx(f, "vor.vx", op),
+            Vorvi(op) => opivi(f, "vor.vi", op),
+            Vxorvv(op) => opivv(f, "vxor.vv", op),
+            Vxorvx(op) => opivx(f, "vxor.vx", op),
+            Vxorvi(op) => opivi(f, "vxor.vi", op),
+            Vrgathervv(op) => opivv(f, "vrgather.vv", op),
+            Vrgathervx(op) => opivx(f, "vrgather.vx", op),
+            Vrgathervi(op) => opivi(f, "vrgather.vi", op),
+            Vrgatherei16vv(op) => opivv(f, "vrgatherei16.vv", op),
+            Vslideupvx(op) => opivx(f, "vslideup.vx", op),
+            Vslideupvi(op) => opivi(f, "vslideup.vi", op),
+            Vslidedownvx(op) => opivx(f, "vslidedown.vx", op),
+            Vslidedownvi(op) => opivi(f, "vslidedown.vi", op),
+            Vadcvvm(op) => opivv_v0(f, "vadc.vvm", op),
+            Vadcvxm(op) => opivx_v0(f, "vadc.vxm", op),
+            Vadcvim(op) => opivi_v0(f, "vadc.vim", op),
+            Vmadcvvm(op) => opivv_v0(f, "vmadc.vvm", op),
+            Vmadcvxm(op) => opivx_v0(f, "vmadc.vxm", op),
+            Vmadcvim(op) => opivi_v0(f, "vmadc.vim", op),
+            Vmadcvv(op) => opivv_maskless(f, "vmadc.vv", op),
+            Vmadcvx(op) => opivx_maskless(f, "vmadc.vx", op),
+            Vmadcvi(op) => opivi_maskless(f, "vmadc.vi", op),
+            Vsbcvvm(op) => opivv_v0(f, "vsbc.vvm", op),
+            Vsbcvxm(op) => opivx_v0(f, "vsbc.vxm", op),
+            Vmsbcvvm(op) => opivv_v0(f, "vmsbc.vvm", op),
+            Vmsbcvxm(op) => opivx_v0(f, "vmsbc.vxm", op),
+            Vmsbcvv(op) => opivv_maskless(f, "vmsbc.vv", op),
+            Vmsbcvx(op) => opivx_maskless(f, "vmsbc.vx", op),
+            Vmergevvm(op) => opivv_v0(f, "vmerge.vvm", op),
+            Vmergevxm(op) => opivx_v0(f, "vmerge.vxm", op),
+            Vmergevim(op) => opivi_v0(f, "vmerge.vim", op),
+            Vmvvv(op) => opivv_vmv(f, "vmv.v.v", op),
+            Vmvvx(op) => opivx_vmv(f, "vmv.v.x", op),
+            Vmvvi(op) => opivi_vmv(f, "vmv.v.i", op),
+            Vmseqvv(op) => opivv(f, "vmseq.vv", op),
+            Vmseqvx(op) => opivx(f, "vmseq.vx", op),
+            Vmseqvi(op) => opivi(f, "vmseq.vi", op),
+            Vmsnevv(op) => opivv(f, "vmsne.vv", op),
+            Vmsnevx(op) => opivx(f, "vmsne.vx", op),
+            Vmsnevi(op) => opivi(f, "vmsne.vi", op),
+            Vmsltuvv(op) => opivv(f, "vmsltu.vv", op),
+            Vmsltuvx(op) => opivx(f, "vmsltu.vx", op),
+            Vmsltvv(op) => opivv(f, "vmslt.vv", op),
+            Vmsltvx(op) => opivx(f, "vmslt.vx", op),
+            Vmsleuvv(op) => opivv(f, "vmsleu.vv", op),
+            Vmsleuvx(op) => opivx(f, "vmsleu.vx", op),
+            Vmsleuvi(op) => opivi(f, "vmsleu.vi", op),
+            Vmslevv(op) => opivv(f, "vmsle.vv", op),
+            Vmslevx(op) => opivx(f, "vmsle.vx", op),
+            Vmslevi(op) => opivi(f, "vmsle.vi", op),
+            Vmsgtuvx(op) => opivx(f, "vmsgtu.vx", op),
+            Vmsgtuvi(op) => opivi(f, "vmsgtu.vi", op),
+            Vmsgtvx(op) => opivx(f, "vmsgt.vx", op),
+            Vmsgtvi(op) => opivi(f, "vmsgt.vi", op),
+            Vsadduvv(op) => opivv(f, "vsaddu.vv", op),
+            Vsadduvx(op) => opivx(f, "vsaddu.vx", op),
+            Vsadduvi(op) => opivi(f, "vsaddu.vi", op),
+            Vsaddvv(op) => opivv(f, "vsadd.vv", op),
+            Vsaddvx(op) => opivx(f, "vsadd.vx", op),
+            Vsaddvi(op) => opivi(f, "vsadd.vi", op),
+            Vssubuvv(op) => opivv(f, "vssubu.vv", op),
+            Vssubuvx(op) => opivx(f, "vssubu.vx", op),
+            Vssubvv(op) => opivv(f, "vssub.vv", op),
+            Vssubvx(op) => opivx(f, "vssub.vx", op),
+            Vsllvv(op) => opivv(f, "vsll.vv", op),
+            Vsllvx(op) => opivx(f, "vsll.vx", op),
+            Vsllvi(op) => opivi(f, "vsll.vi", op),
+            Vsmulvv(op) => opivv(f, "vsmul.vv", op),
+            Vsmulvx(op) => opivx(f, "vsmul.vx", op),
+            Vmv1rv(op) => vmvnr(f, "vmv1r.v", op),
+            Vmv2rv(op) => vmvnr(f, "vmv2r.v", op),
+            Vmv4rv(op) => vmvnr(f, "vmv4r.v", op),
+            Vmv8rv(op) => vmvnr(f, "vmv8r.v", op),
+            Vsrlvv(op) => opivv(f, "vsrl.vv", op),
+            Vsrlvx(op) => opivx(f, "vsrl.vx", op),
+            Vsrlvi(op) => opivi(f, "vsrl.vi", op),
+            Vsravv(op) => opivv(f, "vsra.vv", op),
+            Vsravx(op) => opivx(f, "vsra.vx", op),
+            Vsravi(op) => opivi(f, "vsra.vi", op),
+            Vssrlvv(op) => opivv(f, "vssrl.vv", op),
+            Vssrlvx(op) => opivx(f, "vssrl.vx", op),
+            Vssrlvi(op) => opivi(f, "vssrl.vi", op),
+            Vssravv(op) => opivv(f, "vssra.vv", op),
+            Vssravx(op) => opivx(f, "vssra.vx", op),
+            Vssravi(op) => opivi(f, "vssra.vi", op),
+            Vnsrlwv(op) => opivv(f, "vnsrl.wv", op),
+            Vnsrlwx(op) => opivx(f, "vnsrl.wx", op),
+            Vnsrlwi(op) => opivi(f, "vnsrl.wi", op),
+            Vnsrawv(op) => opivv(f, "vnsra.wv", op),
+            Vnsrawx(op) => opivx(f, "vnsra.wx", op),
+            Vnsrawi(op) => opivi(f, "vnsra.wi", op),
+            Vnclipuwv(op) => opivv(f, "vnclipu.wv", op),
+            Vnclipuwx(op) => opivx(f, "vnclipu.wx", op),
+            Vnclipuwi(op) => opivi(f, "vnclipu.wi", op),
+            Vnclipwv(op) => opivv(f, "vnclip.wv", op),
+            Vnclipwx(op) => opivx(f, "vnclip.wx", op),
+            Vnclipwi(op) => opivi(f, "vnclip.wi", op),
+            Vwredsumuvs(op) => opivv(f, "vwredsumu.vs", op),
+            Vwredsumvs(op) => opivv(f, "vwredsum.vs", op),
+            Vredsumvs(op) => opmvv(f, "vredsum.vs", op),
+            Vredandvs(op) => opmvv(f, "vredand.vs", op),
+            Vredorvs(op) => opmvv(f, "vredor.vs", op),
+            Vredxorvs(op) => opmvv(f, "vredxor.vs", op),
+            Vredminuvs(op) => opmvv(f, "vredminu.vs", op),
+            Vredminvs(op) => opmvv(f, "vredmin.vs", op),
+            Vredmaxuvs(op) => opmvv(f, "vredmaxu.vs", op),
+            Vredmaxvs(op) => opmvv(f, "vredmax.vs", op),
+            Vaadduvv(op) => opmvv(f, "vaaddu.vv", op),
+            Vaadduvx(op) => opmvx(f, "vaaddu.vx", op),
+            Vaaddvv(op) => opmvv(f, "vaadd.vv", op),
+            Vaaddvx(op) => opmvx(f, "vaadd.vx", op),
+            Vasubuvv(op) => opmvv(f, "vasubu.vv", op),
+            Vasubuvx(op) => opmvx(f, "vasubu.vx", op),
+            Vasubvv(op) => opmvv(f, "vasub.vv", op),
+            Vasubvx(op) => opmvx(f, "vasub.vx", op),
+            Vslide1upvx(op) => opmvx(f, "vslide1up.vx", op),
+            Vslide1downvx(op) => opmvx(f, "vslide1down.vx", op),
+            Vmvxs(op) => vmvxs(f, "vmv.x.s", op),
+            Vcpopm(op) => vwxunary0(f, "vcpop.m", op),
+            Vfirstm(op) => vwxunary0(f, "vfirst.m", op),
+            Vmvsx(op) => vrxunary0(f, "vmv.s.x", op),
+            Vsextvf2(op) => vxunary0(f, "vsext.vf2", op),
+            Vsextvf4(op) => vxunary0(f, "vsext.vf4", op),
+            Vsextvf8(op) => vxunary0(f, "vsext.vf8", op),
+            Vzextvf2(op) => vxunary0(f, "vzext.vf2", op),
+            Vzextvf4(op) => vxunary0(f, "vzext.vf4", op),
+            Vzextvf8(op) => vxunary0(f, "vzext.vf8", op),
+            Vmsbfm(op) => vmunary0(f, "vmsbf.m", op),
+            Vmsofm(op) => vmunary0(f, "vmsof.m", op),
+            Vmsifm(op) => vmunary0(f, "vmsif.m", op),
+            Viotam(op) => vmunary0(f, "viota.m", op),
+            Vidv(op) => vidv(f, "vid.v", op),
+            Vcompressvm(op) => opmvv_maskless(f, "vcompress.vm", op),
+            Vmandnmm(op) => opmvv_maskless(f, "vmandn.mm", op),
+            Vmandmm(op) => opmvv_maskless(f, "vmand.mm", op),
+            Vmormm(op) => opmvv_maskless(f, "vmor.mm", op),
+            Vmxormm(op) => opmvv_maskless(f, "vmxor.mm", op),
+            Vmornmm(op) => opmvv_maskless(f, "vmorn.mm", op),
+            Vmnandmm(op) => opmvv_maskless(f, "vmnand.mm", op),
+            Vmnormm(op) => opmvv_maskless(f, "vmnor.mm", op),
+            Vmxnormm(op) => opmvv_maskless(f, "vmxnor.mm", op),
+            Vdivuvv(op) => opmvv(f, "vdivu.vv", op),
+            Vdivuvx(op) => opmvx(f, "vdivu.vx", op),
+            Vdivvv(op) => opmvv(f, "vdiv.vv", op),
+            Vdivvx(op) => opmvx(f, "vdiv.vx", op),
+            Vremuvv(op) => opmvv(f, "vremu.vv", op),
+            Vremuvx(op) => opmvx(f, "vremu.vx", op),
+            Vremvv(op) => opmvv(f, "vrem.vv", op),
+            Vremvx(op) => opmvx(f, "vrem.vx", op),
+            Vmulhuvv(op) => opmvv(f, "vmulhu.vv", op),
+            Vmulhuvx(op) => opmvx(f, "vmulhu.vx", op),
+            Vmulvv(op) => opmvv(f, "vmul.vv", op),
+            Vmulvx(op) => opmvx(f, "vmul.vx", op),
+            Vmulhsuvv(op) => opmvv(f, "vmulhsu.vv", op),
+            Vmulhsuvx(op) => opmvx(f, "vmulhsu.vx", op),
+            Vmulhvv(op) => opmvv(f, "vmulh.vv", op),
+            Vmulhvx(op) => opmvx(f, "vmulh.vx", op),
+            Vmaddvv(op) => opmvv_fma(f, "vmadd.vv", op),
+            Vmaddvx(op) => opmvx_fma(f, "vmadd.vx", op),
+            Vnmsubvv(op) => opmvv_fma(f, "vnmsub.vv", op),
+            Vnmsubvx(op) => opmvx_fma(f, "vnmsub.vx", op),
+            Vmaccvv(op) => opmvv_fma(f, "vmacc.vv", op),
+            Vmaccvx(op) => opmvx_fma(f, "vmacc.vx", op),
+            Vnmsacvv(op) => opmvv_fma(f, "vnmsac.vv", op),
+            Vnmsacvx(op) => opmvx_fma(f, "vnmsac.vx", op),
+            Vwadduvv(op) => opmvv(f, "vwaddu.vv", op),
+            Vwadduvx(op) => opmvx(f, "vwaddu.vx", op),
+            Vwaddvv(op) => opmvv(f, "vwadd.vv", op),
+            Vwaddvx(op) => opmvx(f, "vwadd.vx", op),
+            Vwsubuvv(op) => opmvv(f, "vwsubu.vv", op),
+            Vwsubuvx(op) => opmvx(f, "vwsubu.vx", op),
+            Vwsubvv(op) => opmvv(f, "vwsub.vv", op),
+            Vwsubvx(op) => opmvx(f, "vwsub.vx", op),
+            Vwadduwv(op) => opmvv(f, "vwaddu.wv", op),
+            Vwadduwx(op) => opmvx(f, "vwaddu.wx", op),
+            Vwaddwv(op) => opmvv(f, "vwadd.wv", op),
+            Vwaddwx(op) => opmvx(f, "vwadd.wx", op),
+            Vwsubuwv(op) => opmvv(f, "vwsubu.wv", op),
+            Vwsubuwx(op) => opmvx(f, "vwsubu.wx", op),
+            Vwsubwv(op) => opmvv(f, "vwsub.wv", op),
+            Vwsubwx(op) => opmvx(f, "vwsub.wx", op),
+            Vwmuluvv(op) => opmvv(f, "vwmulu.vv", op),
+            Vwmuluvx(op) => opmvx(f, "vwmulu.vx", op),
+            Vwmulsuvv(op) => opmvv(f, "vwmulsu.vv", op),
+            Vwmulsuvx(op) => opmvx(f, "vwmulsu.vx", op),
+            Vwmulvv(op) => opmvv(f, "vwmul.vv", op),
+            Vwmulvx(op) => opmvx(f, "vwmul.vx", op),
+            Vwmaccuvv(op) => opmvv_fma(f, "vwmaccu.vv", op),
+            Vwmaccuvx(op) => opmvx_fma(f, "vwmaccu.vx", op),
+            Vwmaccvv(op) => opmvv_fma(f, "vwmacc.vv", op),
+            Vwmaccvx(op) => opmvx_fma(f, "vwmacc.vx", op),
+            Vwmaccusvx(op) => opmvx_fma(f, "vwmaccus.vx", op),
+            Vwmaccsuvv(op) => opmvv_fma(f, "vwmaccsu.vv", op),
+            Vwmaccsuvx(op) => opmvx_fma(f, "vwmaccsu.vx", op),
+            Vfaddvv(op) => opfvv(f, "vfadd.vv", op),
+            Vfaddvf(op) => opfvf(f, "vfadd.vf", op),
+            Vfredusumvs(op) => opfvv(f, "vfredusum.vs", op),
+            Vfsubvv(op) => opfvv(f, "vfsub.vv", op),
+            Vfsubvf(op) => opfvf(f, "vfsub.vf", op),
+            Vfredosumvs(op) => opfvv(f, "vfredosum.vs", op),
+            Vfminvv(op) => opfvv(f, "vfmin.vv", op),
+            Vfminvf(op) => opfvf(f, "vfmin.vf", op),
+            Vfredminvs(op) => opfvv(f, "vfredmin.vs", op),
+            Vfmaxvv(op) => opfvv(f, "vfmax.vv", op),
+            Vfmaxvf(op) => opfvf(f, "vfmax.vf", op),
+            Vfredmaxvs(op) => opfvv(f, "vfredmax.vs", op),
+            Vfsgnjvv(op) => opfvv(f, "vfsgnj.vv", op),
+            Vfsgnjvf(op) => opfvf(f, "vfsgnj.vf", op),
+            Vfsgnjnvv(op) => opfvv(f, "vfsgnjn.vv", op),
+            Vfsgnjnvf(op) => opfvf(f, "vfsgnjn.vf", op),
+            Vfsgnjxvv(op) => opfvv(f, "vfsgnjx.vv", op),
+            Vfsgnjxvf(op) => opfvf(f, "vfsgnjx.vf", op),
+            Vfslide1upvf(op) => opfvf(f, "vfslide1up.vf", op),
+            Vfslide1downvf(op) => opfvf(f, "vfslide1down.vf", op),
+            Vfmvfs(op) => vwfunary0(f, "vfmv.f.s", op),
+            Vfmvsf(op) => vrfunary0(f, "vfmv.s.f", op),
+            Vfcvtxufv(op) => vfunary0(f, "vfcvt.xu.f.v", op),
+            Vfcvtxfv(op) => vfunary0(f, "vfcvt.x.f.v", op),
+            Vfcvtfxuv(op) => vfunary0(f, "vfcvt.f.xu.v", op),
+            Vfcvtfxv(op) => vfunary0(f, "vfcvt.f.x.v", op),
+            VfcvtRtzxufv(op) => vfunary0(f, "vfcvt.rtz.xu.f.v", op),
+            VfcvtRtzxfv(op) => vfunary0(f, "vfcvt.rtz.x.f.v", op),
+            Vfwcvtxufv(op) => vfunary0(f, "vfwcvt.xu.f.v", op),
+            Vfwcvtxfv(op) => vfunary0(f, "vfwcvt.x.f.v", op),
+            Vfwcvtfxuv(op) => vfunary0(f, "vfwcvt.f.xu.v", op),
+            Vfwcvtfxv(op) => vfunary0(f, "vfwcvt.f.x.v", op),
+            Vfwcvtffv(op) => vfunary0(f, "vfwcvt.f.f.v", op),
+            VfwcvtRtzxufv(op) => vfunary0(f, "vfwcvt.rtz.xu.f.v", op),
+            VfwcvtRtzxfv(op) => vfunary0(f, "vfwcvt.rtz.x.f.v", op),
+            Vfncvtxufw(op) => vfunary0(f, "vfncvt.xu.f.w", op),
+            Vfncvtxfw(op) => vfunary0(f, "vfncvt.x.f.w", op),
+            Vfncvtfxuw(op) => vfunary0(f, "vfncvt.f.xu.w", op),
+            Vfncvtfxw(op) => vfunary0(f, "vfncvt.f.x.w", op),
+            Vfncvtffw(op) => vfunary0(f, "vfncvt.f.f.w", op),
+            VfncvtRodffw(op) => vfunary0(f, "vfncvt.rod.f.f.w", op),
+            VfncvtRtzxufw(op) => vfunary0(f, "vfncvt.rtz.xu.f.w", op),
+            VfncvtRtzxfw(op) => vfunary0(f, "vfncvt.rtz.x.f.w", op),
+            Vfsqrtv(op) => vfunary1(f, "vfsqrt.v", op),
+            Vfrsqrt7v(op) => vfunary1(f, "vfrsqrt7.v", op),
+            Vfrec7v(op) => vfunary1(f, "vfrec7.v", op),
+            Vfclassv(op) => vfunary1(f, "vfclass.v", op),
+            Vfmergevfm(op) => opfvf_v0(f, "vfmerge.vfm", op),
+            Vfmvvf(op) => opfvf_vmv(f, "vfmv.v.f", op),
+            Vmfeqvv(op) => opfvv(f, "vmfeq.vv", op),
+            Vmfeqvf(op) => opfvf(f, "vmfeq.vf", op),
+            Vmflevv(op) => opfvv(f, "vmfle.vv", op),
+            Vmflevf(op) => opfvf(f, "vmfle.vf", op),
+            Vmfltvv(op) => opfvv(f, "vmflt.vv", op),
+            Vmfltvf(op) => opfvf(f, "vmflt.vf", op),
+            Vmfnevv(op) => opfvv(f, "vmfne.vv", op),
+            Vmfnevf(op) => opfvf(f, "vmfne.vf", op),
+            Vmfgtvf(op) => opfvf(f, "vmfgt.vf", op),
+            Vmfgevf(op) => opfvf(f, "vmfge.vf", op),
+            Vfdivvv(op) => opfvv(f, "vfdiv.vv", op),
+            Vfdivvf(op) => opfvf(f, "vfdiv.vf", op),
+            Vfrdivvf(op) => opfvf(f, "vfrdiv.vf", op),
+            Vfmulvv(op) => opfvv(f, "vfmul.vv", op),
+            Vfmulvf(op) => opfvf(f, "vfmul.vf", op),
+            Vfrsubvf(op) => opfvf(f, "vfrsub.vf", op),
+            Vfmaddvv(op) => opfvv_fma(f, "vfmadd.vv", op),
+            Vfmaddvf(op) => opfvf_fma(f, "vfmadd.vf", op),
+            Vfnmaddvv(op) => opfvv_fma(f, "vfnmadd.vv", op),
+            Vfnmaddvf(op) => opfvf_fma(f, "vfnmadd.vf", op),
+            Vfmsubvv(op) => opfvv_fma(f, "vfmsub.vv", op),
+            Vfmsubvf(op) => opfvf_fma(f, "vfmsub.vf", op),
+            Vfnmsubvv(op) => opfvv_fma(f, "vfnmsub.vv", op),
+            Vfnmsubvf(op) => opfvf_fma(f, "vfnmsub.vf", op),
+            Vfmaccvv(op) => opfvv_fma(f, "vfmacc.vv", op),
+            Vfmaccvf(op) => opfvf_fma(f, "vfmacc.vf", op),
+            Vfnmaccvv(op) => opfvv_fma(f, "vfnmacc.vv", op),
+            Vfnmaccvf(op) => opfvf_fma(f, "vfnmacc.vf", op),
+            Vfmsacvv(op) => opfvv_fma(f, "vfmsac.vv", op),
+            Vfmsacvf(op) => opfvf_fma(f, "vfmsac.vf", op),
+            Vfnmsacvv(op) => opfvv_fma(f, "vfnmsac.vv", op),
+            Vfnmsacvf(op) => opfvf_fma(f, "vfnmsac.vf", op),
+            Vfwaddvv(op) => opfvv(f, "vfwadd.vv", op),
+            Vfwaddvf(op) => opfvf(f, "vfwadd.vf", op),
+            Vfwredusumvs(op) => opfvv(f, "vfwredusum.vs", op),
+            Vfwsubvv(op) => opfvv(f, "vfwsub.vv", op),
+            Vfwsubvf(op) => opfvf(f, "vfwsub.vf", op),
+            Vfwredosumvs(op) => opfvv(f, "vfwredosum.vs", op),
+            Vfwaddwv(op) => opfvv(f, "vfwadd.wv", op),
+            Vfwaddwf(op) => opfvf(f, "vfwadd.wf", op),
+            Vfwsubwv(op) => opfvv(f, "vfwsub.wv", op),
+            Vfwsubwf(op) => opfvf(f, "vfwsub.wf", op),
+            Vfwmulvv(op) => opfvv(f, "vfwmul.vv", op),
+            Vfwmulvf(op) => opfvf(f, "vfwmul.vf", op),
+            Vfwmaccvv(op) => opfvv_fma(f, "vfwmacc.vv", op),
+            Vfwmaccvf(op) => opfvf_fma(f, "vfwmacc.vf", op),
+            Vfwnmaccvv(op) => opfvv_fma(f, "vfwnmacc.vv", op),
+            Vfwnmaccvf(op) => opfvf_fma(f, "vfwnmacc.vf", op),
+            Vfwmsacvv(op) => opfvv_fma(f, "vfwmsac.vv", op),
+            Vfwmsacvf(op) => opfvf_fma(f, "vfwmsac.vf", op),
+            Vfwnmsacvv(op) => opfvv_fma(f, "vfwnmsac.vv", op),
+            Vfwnmsacvf(op) => opfvf_fma(f, "vfwnmsac.vf", op),
+            Vlv { data, eew } => vl(f, &format!("vle{}.v", Eew(eew)), data),
+            Vsv { data, eew } => vs(f, &format!("vse{}.v", Eew(eew)), data),
+            Vlsv { data, eew } => vls(f, &format!("vlse{}.v", Eew(eew)), data),
+            Vssv { data, eew } => vss(f, &format!("vsse{}.v", Eew(eew)), data),
+            Vluxv { data, eew } => vlx(f, &format!("vluxei{}.v", Eew(eew)), data),
+            Vloxv { data, eew } => vlx(f, &format!("vloxei{}.v", Eew(eew)), data),
+            Vsuxv { data, eew } => vsx(f, &format!("vsuxei{}.v", Eew(eew)), data),
+            Vsoxv { data, eew } => vsx(f, &format!("vsoxei{}.v", Eew(eew)), data),
+            Vlffv { data, eew } => vl(f, &format!("vle{}ff.v", Eew(eew)), data),
+            Vlsegv { data, eew, nf } => vl(f, &format!("vlseg{}e{}.v", nf, Eew(eew)), data),
+            Vlsegffv { data, eew, nf } => vl(f, &format!("vlseg{}e{}ff.v", nf, Eew(eew)), data),
+            Vssegv { data, eew, nf } => vs(f, &format!("vsseg{}e{}.v", nf, Eew(eew)), data),
+            Vlssegv { data, eew, nf } => vls(f, &format!("vlsseg{}e{}.v", nf, Eew(eew)), data),
+            Vsssegv { data, eew, nf } => vss(f, &format!("vssseg{}e{}.v", nf, Eew(eew)), data),
+            Vluxsegv { data, eew, nf } => vlx(f, &format!("vluxseg{}ei{}.v", nf, Eew(eew)), data),
+            Vloxsegv { data, eew, nf } => vlx(f, &format!("vloxseg{}ei{}.v", nf, Eew(eew)), data),
+            Vsuxsegv { data, eew, nf } => vsx(f, &format!("vsuxseg{}ei{}.v", nf, Eew(eew)), data),
+            Vsoxsegv { data, eew, nf } => vsx(f, &format!("vsoxseg{}ei{}.v", nf, Eew(eew)), data),
+            Vlrv { data, eew, nf } => {
+                write!(
+                    f,
+                    "vl{}re{}.v {}, ({})",
+                    nf,
+                    Eew(eew),
+                    V(data.vd),
+                    X(data.rs1)
+                )
+            }
+            Vsrv { data, nf } => write!(f, "vs{}r.v {}, ({})", nf, V(data.vs3), X(data.rs1)),
+
+            Compressed(instruction, half) => compressed(f, instruction, *half, pc),
+            Fusion(instructions) => fusion(f, instructions, pc),
+        }
+    }
+}
+
+const X_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const F_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+/// Integer register by ABI name
+struct X(usize);
+
+impl Display for X {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match X_NAMES.get(self.0) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "x{}", self.0),
+        }
+    }
+}
+
+/// Floating-point register by ABI name
+struct F(usize);
+
+impl Display for F {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match F_NAMES.get(self.0) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "f{}", self.0),
+        }
+    }
+}
+
+struct V(usize);
+
+impl Display for V {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// Trailing `, v0.t` operand of masked vector instructions
+struct Mask(bool);
+
+impl Display for Mask {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.0 {
+            write!(f, ", v0.t")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Trailing rounding mode operand, omitted for the dynamic one
+struct Rm(u32);
+
+impl Display for Rm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match RoundingMode::from_bits(self.0 as u64) {
+            Some(RoundingMode::Rne) => write!(f, ", rne"),
+            Some(RoundingMode::Rtz) => write!(f, ", rtz"),
+            Some(RoundingMode::Rdn) => write!(f, ", rdn"),
+            Some(RoundingMode::Rup) => write!(f, ", rup"),
+            Some(RoundingMode::Rmm) => write!(f, ", rmm"),
+            None if self.0 == RoundingMode::DYN => Ok(()),
+            None => write!(f, ", {}", self.0),
+        }
+    }
+}
+
+/// Rounding mode of conversions that are always exact, omitted for rne which assemblers encode by default
+struct ExactRm(u32);
+
+impl Display for ExactRm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.0 {
+            0 => Ok(()),
+            RoundingMode::DYN => write!(f, ", dyn"),
+            rm => Rm(rm).fmt(f),
+        }
+    }
+}
+
+struct Eew<'a>(&'a BaseSew);
+
+impl Display for Eew<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.0.bit_length())
+    }
+}
+
+/// CSR name as assemblers spell it, unnamed CSRs are printed as their address
+pub(crate) struct Csr(pub usize);
+
+impl Display for Csr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let name = match self.0 {
+            csr::INSTRET => "instret",
+            csr::CYCLE => "cycle",
+            csr::TIME => "time",
+            csr::MARCHID => "marchid",
+            csr::FCSR => "fcsr",
+            csr::FFLAGS => "fflags",
+            csr::FRM => "frm",
+            csr::SSTATUS => "sstatus",
+            csr::SIE => "sie",
+            csr::STVEC => "stvec",
+            csr::SSCRATCH => "sscratch",
+            csr::SEPC => "sepc",
+            csr::SCAUSE => "scause",
+            csr::STVAL => "stval",
+            csr::SIP => "sip",
+            csr::SATP => "satp",
+            csr::MSTATUS => "mstatus",
+            csr::MISA => "misa",
+            csr::MEDELEG => "medeleg",
+            csr::MIDELEG => "mideleg",
+            csr::MIE => "mie",
+            csr::MTVEC => "mtvec",
+            csr::MSCRATCH => "mscratch",
+            csr::MEPC => "mepc",
+            csr::MCAUSE => "mcause",
+            csr::MTVAL => "mtval",
+            csr::MIP => "mip",
+            csr::MVENDORID => "mvendorid",
+            csr::MIMPID => "mimpid",
+            csr::MHARTID => "mhartid",
+            csr::VSSTATUS => "vsstatus",
+            csr::VTYPE => "vtype",
+            csr::VL => "vl",
+            csr::VLENB => "vlenb",
+            csr::VSTART => "vstart",
+            csr::VXRM => "vxrm",
+            csr::VXSAT => "vxsat",
+            csr::VCSR => "vcsr",
+            other => return write!(f, "{:#x}", other),
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Absolute address of a pc-relative offset, as assemblers read numeric targets
+struct Target(u64, i32);
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{:#x}", self.0.wrapping_add(self.1 as i64 as u64))
+    }
+}
+
+/// vtype immediate as `e32, m1, ta, ma`, reserved encodings are printed raw
+struct Vtype(u32);
+
+impl Display for Vtype {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let vlmul = self.0 & 0b111;
+        let vsew = (self.0 >> 3) & 0b111;
+        let vta = (self.0 >> 6) & 0b1;
+        let vma = (self.0 >> 7) & 0b1;
+
+        let lmul = match vlmul {
+            0b000 => "m1",
+            0b001 => "m2",
+            0b010 => "m4",
+            0b011 => "m8",
+            0b101 => "mf8",
+            0b110 => "mf4",
+            0b111 => "mf2",
+            _ => return write!(f, "{:#x}", self.0),
+        };
+
+        if vsew > 0b011 || self.0 >> 8 != 0 {
+            return write!(f, "{:#x}", self.0);
+        }
+
+        write!(
+            f,
+            "e{}, {}, {}, {}",
+            8 << vsew,
+            lmul,
+            if vta == 1 { "ta" } else { "tu" },
+            if vma == 1 { "ma" } else { "mu" }
+        )
+    }
+}
+
+// Operand layouts, named after the assembler formats accepted by eeric-interpreter
+
+fn r(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}, {}", mnemonic, X(r.rd), X(r.rs1), X(r.rs2))
+}
+
+fn i(f: &mut Formatter<'_>, mnemonic: &str, i: &I) -> Result {
+    write!(f, "{} {}, {}, {}", mnemonic, X(i.rd), X(i.rs1), i.imm12)
+}
+
+fn u(f: &mut Formatter<'_>, mnemonic: &str, u: &U) -> Result {
+    write!(
+        f,
+        "{} {}, {:#x}",
+        mnemonic,
+        X(u.rd),
+        u.imm20 as u32 & 0xfffff
+    )
+}
+
+fn unary(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}", mnemonic, X(r.rd), X(r.rs1))
+}
+
+fn load(f: &mut Formatter<'_>, mnemonic: &str, rd: impl Display, i: &I) -> Result {
+    write!(f, "{} {}, {}({})", mnemonic, rd, i.imm12, X(i.rs1))
+}
+
+fn store(f: &mut Formatter<'_>, mnemonic: &str, rs2: impl Display, s: &S) -> Result {
+    write!(f, "{} {}, {}({})", mnemonic, rs2, s.imm12, X(s.rs1))
+}
+
+fn branch(f: &mut Formatter<'_>, mnemonic: &str, s: &S, pc: u64) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        X(s.rs1),
+        X(s.rs2),
+        Target(pc, s.imm12)
+    )
+}
+
+fn jal(f: &mut Formatter<'_>, u: &U, pc: u64) -> Result {
+    write!(f, "jal {}, {}", X(u.rd), Target(pc, u.imm20))
+}
+
+fn csrr(f: &mut Formatter<'_>, mnemonic: &str, csrr: &Csrr) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        X(csrr.rd),
+        Csr(csrr.csr),
+        X(csrr.rs1)
+    )
+}
+
+fn csri(f: &mut Formatter<'_>, mnemonic: &str, csri: &Csri) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        X(csri.rd),
+        Csr(csri.csr),
+        csri.uimm
+    )
+}
+
+fn sfence_vma(f: &mut Formatter<'_>, r: &R) -> Result {
+    match (r.rs1, r.rs2) {
+        (0, 0) => write!(f, "sfence.vma"),
+        (rs1, 0) => write!(f, "sfence.vma {}", X(rs1)),
+        (rs1, rs2) => write!(f, "sfence.vma {}, {}", X(rs1), X(rs2)),
+    }
+}
+
+fn lr(f: &mut Formatter<'_>, mnemonic: &str, a: &Atomic) -> Result {
+    write!(f, "{}{} {}, ({})", mnemonic, ordering(a), X(a.rd), X(a.rs1))
+}
+
+fn amo(f: &mut Formatter<'_>, mnemonic: &str, a: &Atomic) -> Result {
+    write!(
+        f,
+        "{}{} {}, {}, ({})",
+        mnemonic,
+        ordering(a),
+        X(a.rd),
+        X(a.rs2),
+        X(a.rs1)
+    )
+}
+
+fn ordering(a: &Atomic) -> &'static str {
+    match (a.aq, a.rl) {
+        (false, false) => "",
+        (true, false) => ".aq",
+        (false, true) => ".rl",
+        (true, true) => ".aqrl",
+    }
+}
+
+fn fence(f: &mut Formatter<'_>, fence: &format::Fence) -> Result {
+    // Empty sets are printed as 0, like GNU objdump does
+    let set = |bits: u32| match "iorw"
+        .chars()
+        .enumerate()
+        .filter(|(index, _)| bits & (0b1000 >> index) != 0)
+        .map(|(_, letter)| letter)
+        .collect::<String>()
+    {
+        letters if letters.is_empty() => "0".to_owned(),
+        letters => letters,
+    };
+
+    match (fence.fm, fence.pred, fence.succ) {
+        (0b1000, 0b0011, 0b0011) => write!(f, "fence.tso"),
+        (_, 0b1111, 0b1111) => write!(f, "fence"),
+        (_, pred, succ) => write!(f, "fence {}, {}", set(pred), set(succ)),
+    }
+}
+
+fn r4(f: &mut Formatter<'_>, mnemonic: &str, r4: &R4) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}, {}{}",
+        mnemonic,
+        F(r4.rd),
+        F(r4.rs1),
+        F(r4.rs2),
+        F(r4.rs3),
+        Rm(r4.rm)
+    )
+}
+
+fn fr(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}, {}", mnemonic, F(r.rd), F(r.rs1), F(r.rs2))
+}
+
+fn frs_to_x(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}", mnemonic, X(r.rd), F(r.rs1))
+}
+
+fn frs_to_f(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}", mnemonic, F(r.rd), X(r.rs1))
+}
+
+fn frx(f: &mut Formatter<'_>, mnemonic: &str, r: &R) -> Result {
+    write!(f, "{} {}, {}, {}", mnemonic, X(r.rd), F(r.rs1), F(r.rs2))
+}
+
+fn fr_rm(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        F(r.rd),
+        F(r.rs1),
+        F(r.rs2),
+        Rm(r.rm)
+    )
+}
+
+fn frs_rm(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(f, "{} {}, {}{}", mnemonic, F(r.rd), F(r.rs1), Rm(r.rm))
+}
+
+fn frs_to_x_rm(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(f, "{} {}, {}{}", mnemonic, X(r.rd), F(r.rs1), Rm(r.rm))
+}
+
+fn frs_to_f_rm(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(f, "{} {}, {}{}", mnemonic, F(r.rd), X(r.rs1), Rm(r.rm))
+}
+
+fn frs_exact(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(f, "{} {}, {}{}", mnemonic, F(r.rd), F(r.rs1), ExactRm(r.rm))
+}
+
+fn frs_to_f_exact(f: &mut Formatter<'_>, mnemonic: &str, r: &Rfp) -> Result {
+    write!(f, "{} {}, {}{}", mnemonic, F(r.rd), X(r.rs1), ExactRm(r.rm))
+}
+
+fn vsetvli(f: &mut Formatter<'_>, vsetvli: &format::Vsetvli) -> Result {
+    write!(
+        f,
+        "vsetvli {}, {}, {}",
+        X(vsetvli.rd),
+        X(vsetvli.rs1),
+        Vtype(vsetvli.vtypei)
+    )
+}
+
+fn vsetivli(f: &mut Formatter<'_>, vsetivli: &format::Vsetivli) -> Result {
+    write!(
+        f,
+        "vsetivli {}, {}, {}",
+        X(vsetivli.rd),
+        vsetivli.uimm,
+        Vtype(vsetivli.vtypei)
+    )
+}
+
+fn vsetvl(f: &mut Formatter<'_>, vsetvl: &format::Vsetvl) -> Result {
+    write!(
+        f,
+        "vsetvl {}, {}, {}",
+        X(vsetvl.rd),
+        X(vsetvl.rs1),
+        X(vsetvl.rs2)
+    )
+}
+
+fn vl(f: &mut Formatter<'_>, mnemonic: &str, vl: &Vl) -> Result {
+    write!(
+        f,
+        "{} {}, ({}){}",
+        mnemonic,
+        V(vl.vd),
+        X(vl.rs1),
+        Mask(vl.vm)
+    )
+}
+
+fn vls(f: &mut Formatter<'_>, mnemonic: &str, vls: &Vls) -> Result {
+    write!(
+        f,
+        "{} {}, ({}), {}{}",
+        mnemonic,
+        V(vls.vd),
+        X(vls.rs1),
+        X(vls.rs2),
+        Mask(vls.vm)
+    )
+}
+
+fn vlx(f: &mut Formatter<'_>, mnemonic: &str, vlx: &Vlx) -> Result {
+    write!(
+        f,
+        "{} {}, ({}), {}{}",
+        mnemonic,
+        V(vlx.vd),
+        X(vlx.rs1),
+        V(vlx.vs2),
+        Mask(vlx.vm)
+    )
+}
+
+fn vs(f: &mut Formatter<'_>, mnemonic: &str, vs: &Vs) -> Result {
+    write!(
+        f,
+        "{} {}, ({}){}",
+        mnemonic,
+        V(vs.vs3),
+        X(vs.rs1),
+        Mask(vs.vm)
+    )
+}
+
+fn vss(f: &mut Formatter<'_>, mnemonic: &str, vss: &Vss) -> Result {
+    write!(
+        f,
+        "{} {}, ({}), {}{}",
+        mnemonic,
+        V(vss.vs3),
+        X(vss.rs1),
+        X(vss.rs2),
+        Mask(vss.vm)
+    )
+}
+
+fn vsx(f: &mut Formatter<'_>, mnemonic: &str, vsx: &Vsx) -> Result {
+    write!(
+        f,
+        "{} {}, ({}), {}{}",
+        mnemonic,
+        V(vsx.vs3),
+        X(vsx.rs1),
+        V(vsx.vs2),
+        Mask(vsx.vm)
+    )
+}
+
+fn opivv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1),
+        Mask(op.vm)
+    )
+}
+
+fn opivv_v0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}, v0",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1)
+    )
+}
+
+fn opivv_maskless(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1)
+    )
+}
+
+fn opivv_vmv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivv) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.dest), V(op.vs1))
+}
+
+fn opivx(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivx) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        X(op.rs1),
+        Mask(op.vm)
+    )
+}
+
+fn opivx_v0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivx) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}, v0",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        X(op.rs1)
+    )
+}
+
+fn opivx_maskless(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivx) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        X(op.rs1)
+    )
+}
+
+fn opivx_vmv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivx) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.dest), X(op.rs1))
+}
+
+fn opivi(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivi) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        op.imm5,
+        Mask(op.vm)
+    )
+}
+
+fn opivi_v0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivi) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}, v0",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        op.imm5
+    )
+}
+
+fn opivi_maskless(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivi) -> Result {
+    write!(f, "{} {}, {}, {}", mnemonic, V(op.dest), V(op.vs2), op.imm5)
+}
+
+fn opivi_vmv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivi) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.dest), op.imm5)
+}
+
+fn vmvnr(f: &mut Formatter<'_>, mnemonic: &str, op: &Opivi) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.dest), V(op.vs2))
+}
+
+fn opmvv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1),
+        Mask(op.vm)
+    )
+}
+
+fn opmvv_maskless(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1)
+    )
+}
+
+fn opmvv_fma(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs1),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn opmvx(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvx) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        X(op.rs1),
+        Mask(op.vm)
+    )
+}
+
+fn opmvx_fma(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvx) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        X(op.rs1),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vmvxs(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(f, "{} {}, {}", mnemonic, X(op.dest), V(op.vs2))
+}
+
+fn vwxunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}{}",
+        mnemonic,
+        X(op.dest),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vrxunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvx) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.dest), X(op.rs1))
+}
+
+fn vxunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vmunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vidv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opmvv) -> Result {
+    write!(f, "{} {}{}", mnemonic, V(op.dest), Mask(op.vm))
+}
+
+fn opfvv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        V(op.vs1),
+        Mask(op.vm)
+    )
+}
+
+fn opfvv_fma(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs1),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn opfvf(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvf) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.vd),
+        V(op.vs2),
+        F(op.rs1),
+        Mask(op.vm)
+    )
+}
+
+fn opfvf_v0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvf) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}, v0",
+        mnemonic,
+        V(op.vd),
+        V(op.vs2),
+        F(op.rs1)
+    )
+}
+
+fn opfvf_vmv(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvf) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.vd), F(op.rs1))
+}
+
+fn opfvf_fma(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvf) -> Result {
+    write!(
+        f,
+        "{} {}, {}, {}{}",
+        mnemonic,
+        V(op.vd),
+        F(op.rs1),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vwfunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvv) -> Result {
+    write!(f, "{} {}, {}", mnemonic, F(op.dest), V(op.vs2))
+}
+
+fn vrfunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvf) -> Result {
+    write!(f, "{} {}, {}", mnemonic, V(op.vd), F(op.rs1))
+}
+
+fn vfunary0(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+fn vfunary1(f: &mut Formatter<'_>, mnemonic: &str, op: &Opfvv) -> Result {
+    write!(
+        f,
+        "{} {}, {}{}",
+        mnemonic,
+        V(op.dest),
+        V(op.vs2),
+        Mask(op.vm)
+    )
+}
+
+/// Recognizes sequences emitted for pseudo-instructions, anything else is printed one by one
+/// Operands of compressed instructions are printed in their shortened form, e.g. `c.add a0, a1`
+fn compressed(f: &mut Formatter<'_>, instruction: &Instruction, half: u16, pc: u64) -> Result {
+    let mnemonic = compressed_mnemonic(half);
+
+    match instruction {
+        Addi(_) if half == 0b01 => write!(f, "c.nop"),
+        Addi(op) if mnemonic == "c.addi4spn" => i(f, mnemonic, op),
+        Addi(op) | Addiw(op) | Slli(op) | Srli(op) | Srai(op) | Andi(op) => {
+            write!(f, "{} {}, {}", mnemonic, X(op.rd), op.imm12)
+        }
+        Lui(op) => u(f, mnemonic, op),
+        Lw(op) | Ld(op) => load(f, mnemonic, X(op.rd), op),
+        Fld(op) => load(f, mnemonic, F(op.rd), op),
+        Sw(op) | Sd(op) => store(f, mnemonic, X(op.rs2), op),
+        Fsd(op) => store(f, mnemonic, F(op.rs2), op),
+        Add(op) | Sub(op) | Xor(op) | Or(op) | And(op) | Subw(op) | Addw(op) => {
+            write!(f, "{} {}, {}", mnemonic, X(op.rd), X(op.rs2))
+        }
+        Jal(op) => write!(f, "{} {}", mnemonic, Target(pc, op.imm20)),
+        Jalr(op) => write!(f, "{} {}", mnemonic, X(op.rs1)),
+        Beq(op) | Bne(op) => {
+            write!(f, "{} {}, {}", mnemonic, X(op.rs1), Target(pc, op.imm12))
+        }
+        _ => write!(f, "{}", mnemonic),
+    }
+}
+
+/// Mnemonic of a valid compressed encoding
+fn compressed_mnemonic(half: u16) -> &'static str {
+    let bits = |high: u32, low: u32| (half as u32 >> low) & ((1 << (high - low + 1)) - 1);
+
+    match (bits(1, 0), bits(15, 13)) {
+        (0b00, 0b000) => "c.addi4spn",
+        (0b00, 0b001) => "c.fld",
+        (0b00, 0b010) => "c.lw",
+        (0b00, 0b011) => "c.ld",
+        (0b00, 0b101) => "c.fsd",
+        (0b00, 0b110) => "c.sw",
+        (0b00, _) => "c.sd",
+        (0b01, 0b000) => "c.addi",
+        (0b01, 0b001) => "c.addiw",
+        (0b01, 0b010) => "c.li",
+        (0b01, 0b011) if bits(11, 7) == 2 => "c.addi16sp",
+        (0b01, 0b011) => "c.lui",
+        (0b01, 0b100) => match (bits(11, 10), bits(12, 12), bits(6, 5)) {
+            (0b00, ..) => "c.srli",
+            (0b01, ..) => "c.srai",
+            (0b10, ..) => "c.andi",
+            (_, 0, 0b00) => "c.sub",
+            (_, 0, 0b01) => "c.xor",
+            (_, 0, 0b10) => "c.or",
+            (_, 0, _) => "c.and",
+            (_, _, 0b00) => "c.subw",
+            _ => "c.addw",
+        },
+        (0b01, 0b101) => "c.j",
+        (0b01, 0b110) => "c.beqz",
+        (0b01, _) => "c.bnez",
+        (_, 0b000) => "c.slli",
+        (_, 0b001) => "c.fldsp",
+        (_, 0b010) => "c.lwsp",
+        (_, 0b011) => "c.ldsp",
+        (_, 0b100) => match (bits(12, 12), bits(11, 7), bits(6, 2)) {
+            (0, _, 0) => "c.jr",
+            (0, ..) => "c.mv",
+            (_, 0, 0) => "c.ebreak",
+            (_, _, 0) => "c.jalr",
+            _ => "c.add",
+        },
+        (_, 0b101) => "c.fsdsp",
+        (_, 0b110) => "c.swsp",
+        _ => "c.sdsp",
+    }
+}
+
+fn fusion(f: &mut Formatter<'_>, instructions: &[Instruction], pc: u64) -> Result {
+    match instructions {
+        [Lui(U { rd, imm20 }), Addiw(I {
+            rd: addi_rd,
+            rs1,
+            imm12,
+        })] if addi_rd == rd && rs1 == rd => {
+            write!(f, "li {}, {}", X(*rd), (imm20 << 12).wrapping_add(*imm12))
+        }
+        [Auipc(U { rd: 1, imm20 }), Jalr(I {
+            rd: 1,
+            rs1: 1,
+            imm12,
+        })] => write!(f, "call {}", Target(pc, (imm20 << 12).wrapping_add(*imm12))),
+        [Auipc(U { rd: 6, imm20 }), Jalr(I {
+            rd: 0,
+            rs1: 6,
+            imm12,
+        })] => write!(f, "tail {}", Target(pc, (imm20 << 12).wrapping_add(*imm12))),
+        [Slli(first), second @ (Srai(_) | Srli(_))] => {
+            let (Srai(I { rd, imm12, .. }) | Srli(I { rd, imm12, .. })) = second else {
+                unreachable!()
+            };
+
+            let pseudo = match (second, imm12) {
+                (Srai(_), 56) => Some("sext.b"),
+                (Srai(_), 48) => Some("sext.h"),
+                (Srli(_), 48) => Some("zext.h"),
+                (Srli(_), 32) => Some("zext.w"),
+                _ => None,
+            };
+
+            match pseudo {
+                Some(pseudo) if first.rd == *rd && first.imm12 == *imm12 => {
+                    write!(f, "{} {}, {}", pseudo, X(first.rd), X(first.rs1))
+                }
+                _ => sequence(f, instructions, pc),
+            }
+        }
+        _ => sequence(f, instructions, pc),
+    }
+}
+
+fn sequence(f: &mut Formatter<'_>, instructions: &[Instruction], mut pc: u64) -> Result {
+    for (index, instruction) in instructions.iter().enumerate() {
+        if index > 0 {
+            write!(f, "; ")?;
+        }
+
+        write!(f, "{}", instruction.disassemble(pc))?;
+        pc = pc.wrapping_add(instruction.size());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fuse, rv_core::instruction::decoder::decode};
+
+    #[test]
+    fn disassembling_works() {
+        // Expected text taken from llvm-mc
+        let cases = [
+            (0x00150513, "addi a0, a0, 1"),
+            (0xff4580e7, "jalr ra, -12(a1)"),
+            (0x00853023, "sd s0, 0(a0)"),
+            (0x0005b407, "fld fs0, 0(a1)"),
+            (0x12b57553, "fmul.d fa0, fa0, fa1"),
+            (0x0d07f2d7, "vsetvli t0, a5, e32, m1, ta, ma"),
+            (0xc073f2d7, "vsetivli t0, 7, e8, mf2, tu, mu"),
+            (0x00055087, "vle16.v v1, (a0), v0.t"),
+            (0x0ab56087, "vlse32.v v1, (a0), a1"),
+            (0x4005e207, "vlseg3e32.v v4, (a1), v0.t"),
+            (0x00208057, "vadd.vv v0, v2, v1, v0.t"),
+            (0x5e0030d7, "vmv.v.i v1, 0"),
+            (0x482090d7, "vfcvt.x.f.v v1, v2, v0.t"),
+            (0xb6b5e057, "vmacc.vx v0, a1, v11"),
+            (0x421025d7, "vmv.x.s a1, v1"),
+            (0x305512f3, "csrrw t0, mtvec, a0"),
+            (0x30200073, "mret"),
+            (0x12b50073, "sfence.vma a0, a1"),
+            (0x12000073, "sfence.vma"),
+            (0x0310000f, "fence rw, w"),
+            (0x0ff0000f, "fence"),
+            (0x0000100f, "fence.i"),
+            (0x00100073, "ebreak"),
+            (0x1605b52f, "lr.d.aqrl a0, (a1)"),
+            (0x04c5a52f, "amoadd.w.aq a0, a2, (a1)"),
+            (0xffe59507, "flh fa0, -2(a1)"),
+            (0x6cc5f543, "fmadd.h fa0, fa1, fa2, fa3"),
+            (0x40258553, "fcvt.s.h fa0, fa1"),
+            (0x4415f553, "fcvt.h.d fa0, fa1"),
+            (0xc435f553, "fcvt.lu.h a0, fa1"),
+            (0xf4058553, "fmv.h.x fa0, a1"),
+            (0x00c59553, "fadd.s fa0, fa1, fa2, rtz"),
+            (0x6ac5c543, "fmadd.d fa0, fa1, fa2, fa3, rmm"),
+            (0xc0051553, "fcvt.w.s a0, fa0, rtz"),
+            (0x0805853b, "add.uw a0, a1, zero"),
+            (0x20c5e53b, "sh3add.uw a0, a1, a2"),
+            (0x0bf5951b, "slli.uw a0, a1, 63"),
+            (0x6015951b, "ctzw a0, a1"),
+            (0x0805c53b, "zext.h a0, a1"),
+            (0x61f5d51b, "roriw a0, a1, 31"),
+            (0x2875d513, "orc.b a0, a1"),
+            (0x4bf5d513, "bexti a0, a1, 63"),
+            (0x0808, "c.addi4spn a0, sp, 16"),
+            (0x41c8, "c.lw a0, 4(a1)"),
+            (0xe588, "c.sd a0, 8(a1)"),
+            (0x7139, "c.addi16sp sp, -64"),
+            (0x957d, "c.srai a0, 63"),
+            (0x9d0d, "c.subw a0, a1"),
+            (0x852e, "c.mv a0, a1"),
+            (0x9502, "c.jalr a0"),
+            (0x2522, "c.fldsp fa0, 8(sp)"),
+            (0xa42a, "c.fsdsp fa0, 8(sp)"),
+            (0x0001, "c.nop"),
+            (0x9002, "c.ebreak"),
+        ];
+
+        for (word, text) in cases {
+            assert_eq!(decode(word).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn pseudo_instructions_are_recovered() {
+        assert_eq!(
+            fuse![
+                Lui(U { rd: 10, imm20: 74 }),
+                Addiw(I {
+                    rd: 10,
+                    rs1: 10,
+                    imm12: 1383
+                })
+            ]
+            .to_string(),
+            "li a0, 304487"
+        );
+        assert_eq!(
+            fuse![
+                Slli(I {
+                    rd: 11,
+                    rs1: 12,
+                    imm12: 32
+                }),
+                Srli(I {
+                    rd: 11,
+                    rs1: 11,
+                    imm12: 32
+                })
+            ]
+            .to_string(),
+            "zext.w a1, a2"
+        );
+        assert_eq!(
+            fuse![
+                Addi(I {
+                    rd: 5,
+                    rs1: 5,
+                    imm12: 1
+                }),
+                Sub(R {
+                    rd: 5,
+                    rs1: 0,
+                    rs2: 5
+                })
+            ]
+            .to_string(),
+            "addi t0, t0, 1; sub t0, zero, t0"
+        );
+    }
+
+    #[test]
+    fn targets_are_printed_as_addresses() {
+        let cases = [
+            (0x00b50863, "beq a0, a1, 0x1010"),
+            (0xff5ff0ef, "jal ra, 0xff4"),
+            (0xbfc5, "c.j 0xff0"),
+            (0xfd65, "c.bnez a0, 0xff8"),
+        ];
+
+        for (word, text) in cases {
+            assert_eq!(decode(word).unwrap().disassemble(0x1000).to_string(), text);
+        }
+
+        let call = fuse![
+            Auipc(U { rd: 1, imm20: 0 }),
+            Jalr(I {
+                rd: 1,
+                rs1: 1,
+                imm12: -64
+            })
+        ];
+
+        assert_eq!(call.disassemble(0x1000).to_string(), "call 0xfc0");
+        assert_eq!(call.to_string(), "call 0xffffffffffffffc0");
+    }
+
+    #[test]
+    fn compressed_forms_are_printed_as_decoded() {
+        assert_eq!(decode(0x0141).unwrap().to_string(), "c.addi sp, 16");
+        assert_eq!(decode(0x6141).unwrap().to_string(), "c.addi16sp sp, 16");
+    }
+}
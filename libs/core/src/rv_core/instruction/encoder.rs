@@ -0,0 +1,1783 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use super::{format::*, Instruction, Instruction::*};
+use crate::rv_core::vector_engine::sew::BaseSew;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EncodeError {
+    /// Fused pseudo-instructions span multiple words, see [`encode_fused`]
+    Fusion,
+    /// Instruction has no 16-bit form, or its operands don't fit in one
+    Incompressible,
+    /// Operand doesn't fit in its instruction field
+    OutOfRange { field: &'static str, value: i64 },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fusion => write!(f, "Fused instruction can't be encoded as a single word"),
+            Self::Incompressible => write!(f, "Instruction has no compressed form"),
+            Self::OutOfRange { field, value } => {
+                write!(f, "Value {} doesn't fit in the {} field", value, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl TryFrom<&Instruction> for u32 {
+    type Error = EncodeError;
+
+    fn try_from(instruction: &Instruction) -> Result<Self, Self::Error> {
+        encode(instruction)
+    }
+}
+
+/// Encodes instruction into words, fusions are flattened in execution order
+pub fn encode_fused(instruction: &Instruction) -> Result<Vec<u32>, EncodeError> {
+    match instruction {
+        Fusion(instructions) => instructions.iter().map(encode_fused).flatten_ok().collect(),
+        other => Ok(vec![encode(other)?]),
+    }
+}
+
+/// Encodes instruction into the bytes it takes in memory, compressed instructions take a halfword
+pub fn encode_bytes(instruction: &Instruction) -> Result<Vec<u8>, EncodeError> {
+    match instruction {
+        Fusion(instructions) => instructions.iter().map(encode_bytes).flatten_ok().collect(),
+        other => Ok(encode(other)?.to_le_bytes()[..other.size() as usize].to_vec()),
+    }
+}
+
+/// Wraps instruction in [`Compressed`] when it has a 16-bit form, fusion parts are compressed one by one
+pub fn compress(instruction: &Instruction) -> Instruction {
+    match instruction {
+        Fusion(instructions) => Fusion(instructions.iter().map(compress).collect()),
+        Compressed(..) => instruction.clone(),
+        // mv is an addi, but its compressed form expands to an add
+        Addi(I { rd, rs1, imm12: 0 }) if *rd != 0 && *rs1 != 0 => compress(&Add(R {
+            rd: *rd,
+            rs1: 0,
+            rs2: *rs1,
+        })),
+        other => match encode_compressed(other) {
+            Ok(half) => Compressed(Box::new(other.clone()), half),
+            Err(_) => other.clone(),
+        },
+    }
+}
+
+/// Encodes instruction into its 16-bit form, HINT encodings are never produced.
+/// Where several forms fit, the one picked by assemblers is used, e.g. `c.addi sp, 16` over `c.addi16sp`.
+pub fn encode_compressed(instruction: &Instruction) -> Result<u16, EncodeError> {
+    let half = match *instruction {
+        Addi(I {
+            rd: 0,
+            rs1: 0,
+            imm12: 0,
+        }) => 0b01,
+        Addi(I { rd, rs1: SP, imm12 }) if is_creg(rd) && scaled(imm12, 4, 1020) && imm12 != 0 => {
+            field(imm12, 5, 4) << 11
+                | field(imm12, 9, 6) << 7
+                | field(imm12, 2, 2) << 6
+                | field(imm12, 3, 3) << 5
+                | creg(rd) << 2
+        }
+        Addi(I { rd, rs1: 0, imm12 }) if rd != 0 && fits(imm12, 6) => ci(0b010, rd, imm12, 0b01),
+        Addi(I { rd, rs1, imm12 }) if rd == rs1 && rd != 0 && imm12 != 0 && fits(imm12, 6) => {
+            ci(0b000, rd, imm12, 0b01)
+        }
+        Addi(I {
+            rd: SP,
+            rs1: SP,
+            imm12,
+        }) if is_addi16sp(imm12) => addi16sp(imm12),
+        Addiw(I { rd, rs1, imm12 }) if rd == rs1 && rd != 0 && fits(imm12, 6) => {
+            ci(0b001, rd, imm12, 0b01)
+        }
+        Lui(U { rd, imm20 }) if rd != 0 && rd != SP && imm20 != 0 && fits(imm20, 6) => {
+            ci(0b011, rd, imm20, 0b01)
+        }
+        Slli(I { rd, rs1, imm12 }) if rd == rs1 && rd != 0 && (1..64).contains(&imm12) => {
+            ci(0b000, rd, imm12, 0b10)
+        }
+        Srli(I { rd, rs1, imm12 }) if rd == rs1 && is_creg(rd) && (1..64).contains(&imm12) => {
+            cb_alu(0b00, rd, imm12)
+        }
+        Srai(I { rd, rs1, imm12 }) if rd == rs1 && is_creg(rd) && (1..64).contains(&imm12) => {
+            cb_alu(0b01, rd, imm12)
+        }
+        Andi(I { rd, rs1, imm12 }) if rd == rs1 && is_creg(rd) && fits(imm12, 6) => {
+            cb_alu(0b10, rd, imm12)
+        }
+        Sub(ref r) if is_ca(r) => ca(0b100011, 0b00, r),
+        Xor(ref r) if is_ca(r) => ca(0b100011, 0b01, r),
+        Or(ref r) if is_ca(r) => ca(0b100011, 0b10, r),
+        And(ref r) if is_ca(r) => ca(0b100011, 0b11, r),
+        Subw(ref r) if is_ca(r) => ca(0b100111, 0b00, r),
+        Addw(ref r) if is_ca(r) => ca(0b100111, 0b01, r),
+        Add(R { rd, rs1: 0, rs2 }) if rd != 0 && rs2 != 0 => cr(0b1000, rd, rs2),
+        Add(R { rd, rs1, rs2 }) if rd == rs1 && rd != 0 && rs2 != 0 => cr(0b1001, rd, rs2),
+        Jal(U { rd: 0, imm20 }) if imm20 % 2 == 0 && fits(imm20, 12) => {
+            0b101 << 13
+                | field(imm20, 11, 11) << 12
+                | field(imm20, 4, 4) << 11
+                | field(imm20, 9, 8) << 9
+                | field(imm20, 10, 10) << 8
+                | field(imm20, 6, 6) << 7
+                | field(imm20, 7, 7) << 6
+                | field(imm20, 3, 1) << 3
+                | field(imm20, 5, 5) << 2
+                | 0b01
+        }
+        Jalr(I {
+            rd: 0,
+            rs1,
+            imm12: 0,
+        }) if rs1 != 0 => cr(0b1000, rs1, 0),
+        Jalr(I {
+            rd: RA,
+            rs1,
+            imm12: 0,
+        }) if rs1 != 0 => cr(0b1001, rs1, 0),
+        Beq(S { rs1, rs2: 0, imm12 }) if is_cb_branch(rs1, imm12) => cb_branch(0b110, rs1, imm12),
+        Bne(S { rs1, rs2: 0, imm12 }) if is_cb_branch(rs1, imm12) => cb_branch(0b111, rs1, imm12),
+        Ebreak => cr(0b1001, 0, 0),
+        Lw(I { rd, rs1: SP, imm12 }) if rd != 0 && scaled(imm12, 4, 252) => {
+            ci_sp_w(0b010, rd, imm12)
+        }
+        Ld(I { rd, rs1: SP, imm12 }) if rd != 0 && scaled(imm12, 8, 504) => {
+            ci_sp_d(0b011, rd, imm12)
+        }
+        Fld(I { rd, rs1: SP, imm12 }) if scaled(imm12, 8, 504) => ci_sp_d(0b001, rd, imm12),
+        Lw(I { rd, rs1, imm12 }) if is_creg(rd) && is_creg(rs1) && scaled(imm12, 4, 124) => {
+            cl_cs(0b010, rd, rs1, cl_w_offset(imm12), 0b00)
+        }
+        Ld(I { rd, rs1, imm12 }) if is_creg(rd) && is_creg(rs1) && scaled(imm12, 8, 248) => {
+            cl_cs(0b011, rd, rs1, cl_d_offset(imm12), 0b00)
+        }
+        Fld(I { rd, rs1, imm12 }) if is_creg(rd) && is_creg(rs1) && scaled(imm12, 8, 248) => {
+            cl_cs(0b001, rd, rs1, cl_d_offset(imm12), 0b00)
+        }
+        Sw(S {
+            rs1: SP,
+            rs2,
+            imm12,
+        }) if scaled(imm12, 4, 252) => css_w(0b110, rs2, imm12),
+        Sd(S {
+            rs1: SP,
+            rs2,
+            imm12,
+        }) if scaled(imm12, 8, 504) => css_d(0b111, rs2, imm12),
+        Fsd(S {
+            rs1: SP,
+            rs2,
+            imm12,
+        }) if scaled(imm12, 8, 504) => css_d(0b101, rs2, imm12),
+        Sw(S { rs1, rs2, imm12 }) if is_creg(rs1) && is_creg(rs2) && scaled(imm12, 4, 124) => {
+            cl_cs(0b110, rs2, rs1, cl_w_offset(imm12), 0b00)
+        }
+        Sd(S { rs1, rs2, imm12 }) if is_creg(rs1) && is_creg(rs2) && scaled(imm12, 8, 248) => {
+            cl_cs(0b111, rs2, rs1, cl_d_offset(imm12), 0b00)
+        }
+        Fsd(S { rs1, rs2, imm12 }) if is_creg(rs1) && is_creg(rs2) && scaled(imm12, 8, 248) => {
+            cl_cs(0b101, rs2, rs1, cl_d_offset(imm12), 0b00)
+        }
+        _ => return Err(EncodeError::Incompressible),
+    };
+
+    Ok(half as u16)
+}
+
+/// Like [`encode_compressed`], but the form is the one named by `mnemonic`,
+/// so `c.addi16sp` and HINTs such as `c.li zero, 1` can be produced
+pub fn encode_compressed_as(instruction: &Instruction, mnemonic: &str) -> Result<u16, EncodeError> {
+    let half = match (mnemonic, instruction) {
+        (
+            "c.addi16sp",
+            &Addi(I {
+                rd: SP,
+                rs1: SP,
+                imm12,
+            }),
+        ) if is_addi16sp(imm12) => addi16sp(imm12),
+        ("c.addi", &Addi(I { rd, rs1, imm12 })) if rd == rs1 && fits(imm12, 6) => {
+            ci(0b000, rd, imm12, 0b01)
+        }
+        ("c.li", &Addi(I { rd, rs1: 0, imm12 })) if fits(imm12, 6) => ci(0b010, rd, imm12, 0b01),
+        ("c.lui", &Lui(U { rd: 0, imm20 })) if imm20 != 0 && fits(imm20, 6) => {
+            ci(0b011, 0, imm20, 0b01)
+        }
+        ("c.slli", &Slli(I { rd, rs1, imm12 })) if rd == rs1 && (0..64).contains(&imm12) => {
+            ci(0b000, rd, imm12, 0b10)
+        }
+        ("c.srli", &Srli(I { rd, rs1, imm12 })) if rd == rs1 && is_creg(rd) && imm12 == 0 => {
+            cb_alu(0b00, rd, imm12)
+        }
+        ("c.srai", &Srai(I { rd, rs1, imm12 })) if rd == rs1 && is_creg(rd) && imm12 == 0 => {
+            cb_alu(0b01, rd, imm12)
+        }
+        ("c.mv", &Add(R { rd: 0, rs1: 0, rs2 })) if rs2 != 0 => cr(0b1000, 0, rs2),
+        ("c.add", &Add(R { rd: 0, rs1: 0, rs2 })) if rs2 != 0 => cr(0b1001, 0, rs2),
+        _ => return encode_compressed(instruction),
+    };
+
+    Ok(half as u16)
+}
+
+pub fn encode(instruction: &Instruction) -> Result<u32, EncodeError> {
+    match instruction {
+        Add(r) => op(r, 0b000, 0b0000000),
+        Addw(r) => op_32(r, 0b000, 0b0000000),
+        Sub(r) => op(r, 0b000, 0b0100000),
+        Subw(r) => op_32(r, 0b000, 0b0100000),
+        Addi(i) => op_imm(i, 0b000),
+        Addiw(i) => i_type(i, OP_IMM_32, 0b000),
+        Slt(r) => op(r, 0b010, 0b0000000),
+        Slti(i) => op_imm(i, 0b010),
+        Sltu(r) => op(r, 0b011, 0b0000000),
+        Sltiu(i) => op_imm(i, 0b011),
+        Lui(u) => u_type(u, 0b0110111),
+        Auipc(u) => u_type(u, 0b0010111),
+
+        And(r) => op(r, 0b111, 0b0000000),
+        Or(r) => op(r, 0b110, 0b0000000),
+        Xor(r) => op(r, 0b100, 0b0000000),
+        Andi(i) => op_imm(i, 0b111),
+        Ori(i) => op_imm(i, 0b110),
+        Xori(i) => op_imm(i, 0b100),
+        Sll(r) => op(r, 0b001, 0b0000000),
+        Sllw(r) => op_32(r, 0b001, 0b0000000),
+        Srl(r) => op(r, 0b101, 0b0000000),
+        Srlw(r) => op_32(r, 0b101, 0b0000000),
+        Sra(r) => op(r, 0b101, 0b0100000),
+        Sraw(r) => op_32(r, 0b101, 0b0100000),
+        Slli(i) => shift(i, OP_IMM, 0b001, 0b0000000, 6),
+        Slliw(i) => shift(i, OP_IMM_32, 0b001, 0b0000000, 5),
+        Srli(i) => shift(i, OP_IMM, 0b101, 0b0000000, 6),
+        Srliw(i) => shift(i, OP_IMM_32, 0b101, 0b0000000, 5),
+        Srai(i) => shift(i, OP_IMM, 0b101, 0b0100000, 6),
+        Sraiw(i) => shift(i, OP_IMM_32, 0b101, 0b0100000, 5),
+
+        Ld(i) => load(i, 0b011),
+        Lw(i) => load(i, 0b010),
+        Lh(i) => load(i, 0b001),
+        Lb(i) => load(i, 0b000),
+        Lwu(i) => load(i, 0b110),
+        Lhu(i) => load(i, 0b101),
+        Lbu(i) => load(i, 0b100),
+        Sd(s) => store(s, 0b011),
+        Sw(s) => store(s, 0b010),
+        Sh(s) => store(s, 0b001),
+        Sb(s) => store(s, 0b000),
+
+        Beq(s) => branch(s, 0b000),
+        Bne(s) => branch(s, 0b001),
+        Bge(s) => branch(s, 0b101),
+        Bgeu(s) => branch(s, 0b111),
+        Blt(s) => branch(s, 0b100),
+        Bltu(s) => branch(s, 0b110),
+        Jal(u) => jal(u),
+        Jalr(i) => i_type(i, 0b1100111, 0b000),
+
+        Ecall => Ok(0x00000073),
+        Ebreak => Ok(0x00100073),
+        Fence(fence) => Ok(unsigned(fence.fm, 4, "fm")? << 28
+            | unsigned(fence.pred, 4, "pred")? << 24
+            | unsigned(fence.succ, 4, "succ")? << 20
+            | reg(fence.rs1)? << 15
+            | reg(fence.rd)? << 7
+            | MISC_MEM),
+        FenceI(i) => i_type(i, MISC_MEM, 0b001),
+
+        Csrrw(csrr) => csr(csrr.rd, csrr.rs1, csrr.csr, 0b001),
+        Csrrs(csrr) => csr(csrr.rd, csrr.rs1, csrr.csr, 0b010),
+        Csrrc(csrr) => csr(csrr.rd, csrr.rs1, csrr.csr, 0b011),
+        Csrrwi(csri) => csr(csri.rd, csri.uimm, csri.csr, 0b101),
+        Csrrsi(csri) => csr(csri.rd, csri.uimm, csri.csr, 0b110),
+        Csrrci(csri) => csr(csri.rd, csri.uimm, csri.csr, 0b111),
+
+        Mret => Ok(0x30200073),
+        Wfi => Ok(0x10500073),
+        Sret => Ok(0x10200073),
+        SfenceVma(r) => r_type(r, SYSTEM, 0b000, 0b0001001),
+
+        Mul(r) => op(r, 0b000, 0b0000001),
+        Mulh(r) => op(r, 0b001, 0b0000001),
+        Mulhu(r) => op(r, 0b011, 0b0000001),
+        Mulhsu(r) => op(r, 0b010, 0b0000001),
+        Mulw(r) => op_32(r, 0b000, 0b0000001),
+        Div(r) => op(r, 0b100, 0b0000001),
+        Divu(r) => op(r, 0b101, 0b0000001),
+        Divw(r) => op_32(r, 0b100, 0b0000001),
+        Divuw(r) => op_32(r, 0b101, 0b0000001),
+        Rem(r) => op(r, 0b110, 0b0000001),
+        Remu(r) => op(r, 0b111, 0b0000001),
+        Remw(r) => op_32(r, 0b110, 0b0000001),
+        Remuw(r) => op_32(r, 0b111, 0b0000001),
+
+        Adduw(r) => op_32(r, 0b000, 0b0000100),
+        Sh1add(r) => op(r, 0b010, 0b0010000),
+        Sh2add(r) => op(r, 0b100, 0b0010000),
+        Sh3add(r) => op(r, 0b110, 0b0010000),
+        Sh1adduw(r) => op_32(r, 0b010, 0b0010000),
+        Sh2adduw(r) => op_32(r, 0b100, 0b0010000),
+        Sh3adduw(r) => op_32(r, 0b110, 0b0010000),
+        Slliuw(i) => shift(i, OP_IMM_32, 0b001, 0b0000100, 6),
+
+        Andn(r) => op(r, 0b111, 0b0100000),
+        Orn(r) => op(r, 0b110, 0b0100000),
+        Xnor(r) => op(r, 0b100, 0b0100000),
+        Clz(r) => unary(r, OP_IMM, 0b001, 0b011000000000),
+        Clzw(r) => unary(r, OP_IMM_32, 0b001, 0b011000000000),
+        Ctz(r) => unary(r, OP_IMM, 0b001, 0b011000000001),
+        Ctzw(r) => unary(r, OP_IMM_32, 0b001, 0b011000000001),
+        Cpop(r) => unary(r, OP_IMM, 0b001, 0b011000000010),
+        Cpopw(r) => unary(r, OP_IMM_32, 0b001, 0b011000000010),
+        Max(r) => op(r, 0b110, 0b0000101),
+        Maxu(r) => op(r, 0b111, 0b0000101),
+        Min(r) => op(r, 0b100, 0b0000101),
+        Minu(r) => op(r, 0b101, 0b0000101),
+        Sextb(r) => unary(r, OP_IMM, 0b001, 0b011000000100),
+        Sexth(r) => unary(r, OP_IMM, 0b001, 0b011000000101),
+        Zexth(r) => unary(r, OP_32, 0b100, 0b000010000000),
+        Rol(r) => op(r, 0b001, 0b0110000),
+        Rolw(r) => op_32(r, 0b001, 0b0110000),
+        Ror(r) => op(r, 0b101, 0b0110000),
+        Rori(i) => shift(i, OP_IMM, 0b101, 0b0110000, 6),
+        Roriw(i) => shift(i, OP_IMM_32, 0b101, 0b0110000, 5),
+        Rorw(r) => op_32(r, 0b101, 0b0110000),
+        Orcb(r) => unary(r, OP_IMM, 0b101, 0b001010000111),
+        Rev8(r) => unary(r, OP_IMM, 0b101, 0b011010111000),
+
+        Bclr(r) => op(r, 0b001, 0b0100100),
+        Bclri(i) => shift(i, OP_IMM, 0b001, 0b0100100, 6),
+        Bext(r) => op(r, 0b101, 0b0100100),
+        Bexti(i) => shift(i, OP_IMM, 0b101, 0b0100100, 6),
+        Binv(r) => op(r, 0b001, 0b0110100),
+        Binvi(i) => shift(i, OP_IMM, 0b001, 0b0110100, 6),
+        Bset(r) => op(r, 0b001, 0b0010100),
+        Bseti(i) => shift(i, OP_IMM, 0b001, 0b0010100, 6),
+
+        Lrw(a) => amo(a, 0b00010, 0b010),
+        Scw(a) => amo(a, 0b00011, 0b010),
+        Amoswapw(a) => amo(a, 0b00001, 0b010),
+        Amoaddw(a) => amo(a, 0b00000, 0b010),
+        Amoxorw(a) => amo(a, 0b00100, 0b010),
+        Amoandw(a) => amo(a, 0b01100, 0b010),
+        Amoorw(a) => amo(a, 0b01000, 0b010),
+        Amominw(a) => amo(a, 0b10000, 0b010),
+        Amomaxw(a) => amo(a, 0b10100, 0b010),
+        Amominuw(a) => amo(a, 0b11000, 0b010),
+        Amomaxuw(a) => amo(a, 0b11100, 0b010),
+
+        Lrd(a) => amo(a, 0b00010, 0b011),
+        Scd(a) => amo(a, 0b00011, 0b011),
+        Amoswapd(a) => amo(a, 0b00001, 0b011),
+        Amoaddd(a) => amo(a, 0b00000, 0b011),
+        Amoxord(a) => amo(a, 0b00100, 0b011),
+        Amoandd(a) => amo(a, 0b01100, 0b011),
+        Amoord(a) => amo(a, 0b01000, 0b011),
+        Amomind(a) => amo(a, 0b10000, 0b011),
+        Amomaxd(a) => amo(a, 0b10100, 0b011),
+        Amominud(a) => amo(a, 0b11000, 0b011),
+        Amomaxud(a) => amo(a, 0b11100, 0b011),
+
+        Flw(i) => i_type(i, LOAD_FP, 0b010),
+        Fsw(s) => s_type(s, STORE_FP, 0b010),
+        Fmadds(r4) => r4_type(r4, 0b1000011, SINGLE),
+        Fmsubs(r4) => r4_type(r4, 0b1000111, SINGLE),
+        Fnmsubs(r4) => r4_type(r4, 0b1001011, SINGLE),
+        Fnmadds(r4) => r4_type(r4, 0b1001111, SINGLE),
+        Fadds(r) => op_fp_rounding(r, 0b00000, SINGLE),
+        Fsubs(r) => op_fp_rounding(r, 0b00001, SINGLE),
+        Fmuls(r) => op_fp_rounding(r, 0b00010, SINGLE),
+        Fdivs(r) => op_fp_rounding(r, 0b00011, SINGLE),
+        Fsqrts(r) => op_fp_rounding_unary(r, 0b01011, SINGLE, 0b00000),
+        Fsgnjs(r) => op_fp(r, 0b00100, SINGLE, 0b000),
+        Fsgnjns(r) => op_fp(r, 0b00100, SINGLE, 0b001),
+        Fsgnjxs(r) => op_fp(r, 0b00100, SINGLE, 0b010),
+        Fmins(r) => op_fp(r, 0b00101, SINGLE, 0b000),
+        Fmaxs(r) => op_fp(r, 0b00101, SINGLE, 0b001),
+        Fcvtws(r) => op_fp_rounding_unary(r, 0b11000, SINGLE, 0b00000),
+        Fcvtwus(r) => op_fp_rounding_unary(r, 0b11000, SINGLE, 0b00001),
+        Fcvtls(r) => op_fp_rounding_unary(r, 0b11000, SINGLE, 0b00010),
+        Fcvtlus(r) => op_fp_rounding_unary(r, 0b11000, SINGLE, 0b00011),
+        Fmvxw(r) => op_fp_unary(r, 0b11100, SINGLE, 0b00000, 0b000),
+        Feqs(r) => op_fp(r, 0b10100, SINGLE, 0b010),
+        Flts(r) => op_fp(r, 0b10100, SINGLE, 0b001),
+        Fles(r) => op_fp(r, 0b10100, SINGLE, 0b000),
+        Fclasss(r) => op_fp_unary(r, 0b11100, SINGLE, 0b00000, 0b001),
+        Fcvtsw(r) => op_fp_rounding_unary(r, 0b11010, SINGLE, 0b00000),
+        Fcvtswu(r) => op_fp_rounding_unary(r, 0b11010, SINGLE, 0b00001),
+        Fcvtsl(r) => op_fp_rounding_unary(r, 0b11010, SINGLE, 0b00010),
+        Fcvtslu(r) => op_fp_rounding_unary(r, 0b11010, SINGLE, 0b00011),
+        Fmvwx(r) => op_fp_unary(r, 0b11110, SINGLE, 0b00000, 0b000),
+
+        Fld(i) => i_type(i, LOAD_FP, 0b011),
+        Fsd(s) => s_type(s, STORE_FP, 0b011),
+        Fmaddd(r4) => r4_type(r4, 0b1000011, DOUBLE),
+        Fmsubd(r4) => r4_type(r4, 0b1000111, DOUBLE),
+        Fnmsubd(r4) => r4_type(r4, 0b1001011, DOUBLE),
+        Fnmaddd(r4) => r4_type(r4, 0b1001111, DOUBLE),
+        Faddd(r) => op_fp_rounding(r, 0b00000, DOUBLE),
+        Fsubd(r) => op_fp_rounding(r, 0b00001, DOUBLE),
+        Fmuld(r) => op_fp_rounding(r, 0b00010, DOUBLE),
+        Fdivd(r) => op_fp_rounding(r, 0b00011, DOUBLE),
+        Fsqrtd(r) => op_fp_rounding_unary(r, 0b01011, DOUBLE, 0b00000),
+        Fsgnjd(r) => op_fp(r, 0b00100, DOUBLE, 0b000),
+        Fsgnjnd(r) => op_fp(r, 0b00100, DOUBLE, 0b001),
+        Fsgnjxd(r) => op_fp(r, 0b00100, DOUBLE, 0b010),
+        Fmind(r) => op_fp(r, 0b00101, DOUBLE, 0b000),
+        Fmaxd(r) => op_fp(r, 0b00101, DOUBLE, 0b001),
+        Fcvtsd(r) => op_fp_rounding_unary(r, 0b01000, SINGLE, 0b00001),
+        // Widening conversions are exact, assemblers emit them with RNE
+        Fcvtds(r) => op_fp_rounding_unary(r, 0b01000, DOUBLE, 0b00000),
+        Feqd(r) => op_fp(r, 0b10100, DOUBLE, 0b010),
+        Fltd(r) => op_fp(r, 0b10100, DOUBLE, 0b001),
+        Fled(r) => op_fp(r, 0b10100, DOUBLE, 0b000),
+        Fclassd(r) => op_fp_unary(r, 0b11100, DOUBLE, 0b00000, 0b001),
+        Fcvtwd(r) => op_fp_rounding_unary(r, 0b11000, DOUBLE, 0b00000),
+        Fcvtwud(r) => op_fp_rounding_unary(r, 0b11000, DOUBLE, 0b00001),
+        Fcvtld(r) => op_fp_rounding_unary(r, 0b11000, DOUBLE, 0b00010),
+        Fcvtlud(r) => op_fp_rounding_unary(r, 0b11000, DOUBLE, 0b00011),
+        Fmvxd(r) => op_fp_unary(r, 0b11100, DOUBLE, 0b00000, 0b000),
+        Fcvtdw(r) => op_fp_rounding_unary(r, 0b11010, DOUBLE, 0b00000),
+        Fcvtdwu(r) => op_fp_rounding_unary(r, 0b11010, DOUBLE, 0b00001),
+        Fcvtdl(r) => op_fp_rounding_unary(r, 0b11010, DOUBLE, 0b00010),
+        Fcvtdlu(r) => op_fp_rounding_unary(r, 0b11010, DOUBLE, 0b00011),
+        Fmvdx(r) => op_fp_unary(r, 0b11110, DOUBLE, 0b00000, 0b000),
+
+        Flh(i) => i_type(i, LOAD_FP, 0b001),
+        Fsh(s) => s_type(s, STORE_FP, 0b001),
+        Fmaddh(r4) => r4_type(r4, 0b1000011, HALF),
+        Fmsubh(r4) => r4_type(r4, 0b1000111, HALF),
+        Fnmsubh(r4) => r4_type(r4, 0b1001011, HALF),
+        Fnmaddh(r4) => r4_type(r4, 0b1001111, HALF),
+        Faddh(r) => op_fp_rounding(r, 0b00000, HALF),
+        Fsubh(r) => op_fp_rounding(r, 0b00001, HALF),
+        Fmulh(r) => op_fp_rounding(r, 0b00010, HALF),
+        Fdivh(r) => op_fp_rounding(r, 0b00011, HALF),
+        Fsqrth(r) => op_fp_rounding_unary(r, 0b01011, HALF, 0b00000),
+        Fsgnjh(r) => op_fp(r, 0b00100, HALF, 0b000),
+        Fsgnjnh(r) => op_fp(r, 0b00100, HALF, 0b001),
+        Fsgnjxh(r) => op_fp(r, 0b00100, HALF, 0b010),
+        Fminh(r) => op_fp(r, 0b00101, HALF, 0b000),
+        Fmaxh(r) => op_fp(r, 0b00101, HALF, 0b001),
+        Fcvtsh(r) => op_fp_rounding_unary(r, 0b01000, SINGLE, 0b00010),
+        Fcvths(r) => op_fp_rounding_unary(r, 0b01000, HALF, 0b00000),
+        Fcvtdh(r) => op_fp_rounding_unary(r, 0b01000, DOUBLE, 0b00010),
+        Fcvthd(r) => op_fp_rounding_unary(r, 0b01000, HALF, 0b00001),
+        Fcvtwh(r) => op_fp_rounding_unary(r, 0b11000, HALF, 0b00000),
+        Fcvtwuh(r) => op_fp_rounding_unary(r, 0b11000, HALF, 0b00001),
+        Fcvtlh(r) => op_fp_rounding_unary(r, 0b11000, HALF, 0b00010),
+        Fcvtluh(r) => op_fp_rounding_unary(r, 0b11000, HALF, 0b00011),
+        Fmvxh(r) => op_fp_unary(r, 0b11100, HALF, 0b00000, 0b000),
+        Feqh(r) => op_fp(r, 0b10100, HALF, 0b010),
+        Flth(r) => op_fp(r, 0b10100, HALF, 0b001),
+        Fleh(r) => op_fp(r, 0b10100, HALF, 0b000),
+        Fclassh(r) => op_fp_unary(r, 0b11100, HALF, 0b00000, 0b001),
+        Fcvthw(r) => op_fp_rounding_unary(r, 0b11010, HALF, 0b00000),
+        Fcvthwu(r) => op_fp_rounding_unary(r, 0b11010, HALF, 0b00001),
+        Fcvthl(r) => op_fp_rounding_unary(r, 0b11010, HALF, 0b00010),
+        Fcvthlu(r) => op_fp_rounding_unary(r, 0b11010, HALF, 0b00011),
+        Fmvhx(r) => op_fp_unary(r, 0b11110, HALF, 0b00000, 0b000),
+
+        Vsetvli(vsetvli) => Ok(unsigned(vsetvli.vtypei, 11, "vtypei")? << 20
+            | reg(vsetvli.rs1)? << 15
+            | OPCFG << 12
+            | reg(vsetvli.rd)? << 7
+            | OP_V),
+        Vsetivli(vsetivli) => Ok(0b11 << 30
+            | unsigned(vsetivli.vtypei, 10, "vtypei")? << 20
+            | unsigned(vsetivli.uimm, 5, "uimm")? << 15
+            | OPCFG << 12
+            | reg(vsetivli.rd)? << 7
+            | OP_V),
+        Vsetvl(vsetvl) => Ok(0b1000000 << 25
+            | reg(vsetvl.rs2)? << 20
+            | reg(vsetvl.rs1)? << 15
+            | OPCFG << 12
+            | reg(vsetvl.rd)? << 7
+            | OP_V),
+
+        Vlv { data, eew } => {
+            vector_memory(LOAD_FP, 1, UNIT, data.vm, 0b00000, data.rs1, *eew, data.vd)
+        }
+        Vsv { data, eew } => vector_memory(
+            STORE_FP, 1, UNIT, data.vm, 0b00000, data.rs1, *eew, data.vs3,
+        ),
+
+        Vlmv(data) => vector_memory(
+            LOAD_FP,
+            1,
+            UNIT,
+            false,
+            0b01011,
+            data.rs1,
+            BaseSew::E8,
+            data.vd,
+        ),
+        Vsmv(data) => vector_memory(
+            STORE_FP,
+            1,
+            UNIT,
+            false,
+            0b01011,
+            data.rs1,
+            BaseSew::E8,
+            data.vs3,
+        ),
+
+        Vlsv { data, eew } => vector_memory(
+            LOAD_FP, 1, STRIDED, data.vm, data.rs2, data.rs1, *eew, data.vd,
+        ),
+        Vssv { data, eew } => vector_memory(
+            STORE_FP, 1, STRIDED, data.vm, data.rs2, data.rs1, *eew, data.vs3,
+        ),
+
+        Vluxv { data, eew } => vector_memory(
+            LOAD_FP,
+            1,
+            INDEXED_UNORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vd,
+        ),
+        Vloxv { data, eew } => vector_memory(
+            LOAD_FP,
+            1,
+            INDEXED_ORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vd,
+        ),
+        Vsuxv { data, eew } => vector_memory(
+            STORE_FP,
+            1,
+            INDEXED_UNORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vs3,
+        ),
+        Vsoxv { data, eew } => vector_memory(
+            STORE_FP,
+            1,
+            INDEXED_ORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vs3,
+        ),
+
+        Vlffv { data, eew } => {
+            vector_memory(LOAD_FP, 1, UNIT, data.vm, 0b10000, data.rs1, *eew, data.vd)
+        }
+
+        Vlsegv { data, eew, nf } => vector_memory(
+            LOAD_FP, *nf, UNIT, data.vm, 0b00000, data.rs1, *eew, data.vd,
+        ),
+        Vlsegffv { data, eew, nf } => vector_memory(
+            LOAD_FP, *nf, UNIT, data.vm, 0b10000, data.rs1, *eew, data.vd,
+        ),
+        Vssegv { data, eew, nf } => vector_memory(
+            STORE_FP, *nf, UNIT, data.vm, 0b00000, data.rs1, *eew, data.vs3,
+        ),
+
+        Vlssegv { data, eew, nf } => vector_memory(
+            LOAD_FP, *nf, STRIDED, data.vm, data.rs2, data.rs1, *eew, data.vd,
+        ),
+        Vsssegv { data, eew, nf } => vector_memory(
+            STORE_FP, *nf, STRIDED, data.vm, data.rs2, data.rs1, *eew, data.vs3,
+        ),
+
+        Vluxsegv { data, eew, nf } => vector_memory(
+            LOAD_FP,
+            *nf,
+            INDEXED_UNORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vd,
+        ),
+        Vloxsegv { data, eew, nf } => vector_memory(
+            LOAD_FP,
+            *nf,
+            INDEXED_ORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vd,
+        ),
+        Vsuxsegv { data, eew, nf } => vector_memory(
+            STORE_FP,
+            *nf,
+            INDEXED_UNORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vs3,
+        ),
+        Vsoxsegv { data, eew, nf } => vector_memory(
+            STORE_FP,
+            *nf,
+            INDEXED_ORDERED,
+            data.vm,
+            data.vs2,
+            data.rs1,
+            *eew,
+            data.vs3,
+        ),
+
+        Vlrv { data, eew, nf } => vector_whole_register(LOAD_FP, *nf, data.rs1, *eew, data.vd),
+        Vsrv { data, nf } => vector_whole_register(STORE_FP, *nf, data.rs1, BaseSew::E8, data.vs3),
+
+        Vaddvv(op) => opivv(0b000000, op),
+        Vaddvx(op) => opivx(0b000000, op),
+        Vaddvi(op) => opivi(0b000000, op),
+
+        Vsubvv(op) => opivv(0b000010, op),
+        Vsubvx(op) => opivx(0b000010, op),
+
+        Vrsubvx(op) => opivx(0b000011, op),
+        Vrsubvi(op) => opivi(0b000011, op),
+
+        Vwaddvv(op) => opmvv(0b110001, op),
+        Vwaddvx(op) => opmvx(0b110001, op),
+        Vwadduvv(op) => opmvv(0b110000, op),
+        Vwadduvx(op) => opmvx(0b110000, op),
+        Vwsubvv(op) => opmvv(0b110011, op),
+        Vwsubvx(op) => opmvx(0b110011, op),
+        Vwsubuvv(op) => opmvv(0b110010, op),
+        Vwsubuvx(op) => opmvx(0b110010, op),
+        Vwaddwv(op) => opmvv(0b110101, op),
+        Vwaddwx(op) => opmvx(0b110101, op),
+        Vwadduwv(op) => opmvv(0b110100, op),
+        Vwadduwx(op) => opmvx(0b110100, op),
+        Vwsubwv(op) => opmvv(0b110111, op),
+        Vwsubwx(op) => opmvx(0b110111, op),
+        Vwsubuwv(op) => opmvv(0b110110, op),
+        Vwsubuwx(op) => opmvx(0b110110, op),
+
+        Vzextvf2(op) => opmvv_unary(0b010010, 0b00110, op),
+        Vsextvf2(op) => opmvv_unary(0b010010, 0b00111, op),
+        Vzextvf4(op) => opmvv_unary(0b010010, 0b00100, op),
+        Vsextvf4(op) => opmvv_unary(0b010010, 0b00101, op),
+        Vzextvf8(op) => opmvv_unary(0b010010, 0b00010, op),
+        Vsextvf8(op) => opmvv_unary(0b010010, 0b00011, op),
+
+        Vadcvvm(op) => opivv_fixed(0b010000, op, false),
+        Vadcvxm(op) => opivx_fixed(0b010000, op, false),
+        Vadcvim(op) => opivi_fixed(0b010000, op, false),
+        Vmadcvvm(op) => opivv_fixed(0b010001, op, false),
+        Vmadcvxm(op) => opivx_fixed(0b010001, op, false),
+        Vmadcvim(op) => opivi_fixed(0b010001, op, false),
+        Vmadcvv(op) => opivv_fixed(0b010001, op, true),
+        Vmadcvx(op) => opivx_fixed(0b010001, op, true),
+        Vmadcvi(op) => opivi_fixed(0b010001, op, true),
+        Vsbcvvm(op) => opivv_fixed(0b010010, op, false),
+        Vsbcvxm(op) => opivx_fixed(0b010010, op, false),
+        Vmsbcvvm(op) => opivv_fixed(0b010011, op, false),
+        Vmsbcvxm(op) => opivx_fixed(0b010011, op, false),
+        Vmsbcvv(op) => opivv_fixed(0b010011, op, true),
+        Vmsbcvx(op) => opivx_fixed(0b010011, op, true),
+
+        Vandvv(op) => opivv(0b001001, op),
+        Vandvx(op) => opivx(0b001001, op),
+        Vandvi(op) => opivi(0b001001, op),
+        Vorvv(op) => opivv(0b001010, op),
+        Vorvx(op) => opivx(0b001010, op),
+        Vorvi(op) => opivi(0b001010, op),
+        Vxorvv(op) => opivv(0b001011, op),
+        Vxorvx(op) => opivx(0b001011, op),
+        Vxorvi(op) => opivi(0b001011, op),
+
+        Vsllvv(op) => opivv(0b100101, op),
+        Vsllvx(op) => opivx(0b100101, op),
+        Vsllvi(op) => opivi_unsigned(0b100101, op),
+        Vsrlvv(op) => opivv(0b101000, op),
+        Vsrlvx(op) => opivx(0b101000, op),
+        Vsrlvi(op) => opivi_unsigned(0b101000, op),
+        Vsravv(op) => opivv(0b101001, op),
+        Vsravx(op) => opivx(0b101001, op),
+        Vsravi(op) => opivi_unsigned(0b101001, op),
+
+        Vnsrlwv(op) => opivv(0b101100, op),
+        Vnsrlwx(op) => opivx(0b101100, op),
+        Vnsrlwi(op) => opivi_unsigned(0b101100, op),
+        Vnsrawv(op) => opivv(0b101101, op),
+        Vnsrawx(op) => opivx(0b101101, op),
+        Vnsrawi(op) => opivi_unsigned(0b101101, op),
+
+        Vmseqvv(op) => opivv(0b011000, op),
+        Vmseqvx(op) => opivx(0b011000, op),
+        Vmseqvi(op) => opivi(0b011000, op),
+        Vmsnevv(op) => opivv(0b011001, op),
+        Vmsnevx(op) => opivx(0b011001, op),
+        Vmsnevi(op) => opivi(0b011001, op),
+        Vmsltuvv(op) => opivv(0b011010, op),
+        Vmsltuvx(op) => opivx(0b011010, op),
+        Vmsltvv(op) => opivv(0b011011, op),
+        Vmsltvx(op) => opivx(0b011011, op),
+        Vmsleuvv(op) => opivv(0b011100, op),
+        Vmsleuvx(op) => opivx(0b011100, op),
+        Vmsleuvi(op) => opivi(0b011100, op),
+        Vmslevv(op) => opivv(0b011101, op),
+        Vmslevx(op) => opivx(0b011101, op),
+        Vmslevi(op) => opivi(0b011101, op),
+        Vmsgtuvx(op) => opivx(0b011110, op),
+        Vmsgtuvi(op) => opivi(0b011110, op),
+        Vmsgtvx(op) => opivx(0b011111, op),
+        Vmsgtvi(op) => opivi(0b011111, op),
+
+        Vminuvv(op) => opivv(0b000100, op),
+        Vminuvx(op) => opivx(0b000100, op),
+        Vminvv(op) => opivv(0b000101, op),
+        Vminvx(op) => opivx(0b000101, op),
+        Vmaxuvv(op) => opivv(0b000110, op),
+        Vmaxuvx(op) => opivx(0b000110, op),
+        Vmaxvv(op) => opivv(0b000111, op),
+        Vmaxvx(op) => opivx(0b000111, op),
+
+        Vmulvv(op) => opmvv(0b100101, op),
+        Vmulvx(op) => opmvx(0b100101, op),
+        Vmulhvv(op) => opmvv(0b100111, op),
+        Vmulhvx(op) => opmvx(0b100111, op),
+        Vmulhuvv(op) => opmvv(0b100100, op),
+        Vmulhuvx(op) => opmvx(0b100100, op),
+        Vmulhsuvv(op) => opmvv(0b100110, op),
+        Vmulhsuvx(op) => opmvx(0b100110, op),
+
+        Vdivuvv(op) => opmvv(0b100000, op),
+        Vdivuvx(op) => opmvx(0b100000, op),
+        Vdivvv(op) => opmvv(0b100001, op),
+        Vdivvx(op) => opmvx(0b100001, op),
+        Vremuvv(op) => opmvv(0b100010, op),
+        Vremuvx(op) => opmvx(0b100010, op),
+        Vremvv(op) => opmvv(0b100011, op),
+        Vremvx(op) => opmvx(0b100011, op),
+
+        Vwmulvv(op) => opmvv(0b111011, op),
+        Vwmulvx(op) => opmvx(0b111011, op),
+        Vwmuluvv(op) => opmvv(0b111000, op),
+        Vwmuluvx(op) => opmvx(0b111000, op),
+        Vwmulsuvv(op) => opmvv(0b111010, op),
+        Vwmulsuvx(op) => opmvx(0b111010, op),
+
+        Vmaccvv(op) => opmvv(0b101101, op),
+        Vmaccvx(op) => opmvx(0b101101, op),
+        Vnmsacvv(op) => opmvv(0b101111, op),
+        Vnmsacvx(op) => opmvx(0b101111, op),
+        Vmaddvv(op) => opmvv(0b101001, op),
+        Vmaddvx(op) => opmvx(0b101001, op),
+        Vnmsubvv(op) => opmvv(0b101011, op),
+        Vnmsubvx(op) => opmvx(0b101011, op),
+
+        Vwmaccuvv(op) => opmvv(0b111100, op),
+        Vwmaccuvx(op) => opmvx(0b111100, op),
+        Vwmaccvv(op) => opmvv(0b111101, op),
+        Vwmaccvx(op) => opmvx(0b111101, op),
+        Vwmaccsuvv(op) => opmvv(0b111111, op),
+        Vwmaccsuvx(op) => opmvx(0b111111, op),
+        Vwmaccusvx(op) => opmvx(0b111110, op),
+
+        Vmergevvm(op) => opivv_fixed(0b010111, op, false),
+        Vmergevxm(op) => opivx_fixed(0b010111, op, false),
+        Vmergevim(op) => opivi_fixed(0b010111, op, false),
+
+        Vmvvv(op) => opivv_fixed(
+            0b010111,
+            &Opivv {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+        Vmvvx(op) => opivx_fixed(
+            0b010111,
+            &Opivx {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+        Vmvvi(op) => opivi_fixed(
+            0b010111,
+            &Opivi {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+
+        Vsadduvv(op) => opivv(0b100000, op),
+        Vsadduvx(op) => opivx(0b100000, op),
+        Vsadduvi(op) => opivi(0b100000, op),
+        Vsaddvv(op) => opivv(0b100001, op),
+        Vsaddvx(op) => opivx(0b100001, op),
+        Vsaddvi(op) => opivi(0b100001, op),
+        Vssubuvv(op) => opivv(0b100010, op),
+        Vssubuvx(op) => opivx(0b100010, op),
+        Vssubvv(op) => opivv(0b100011, op),
+        Vssubvx(op) => opivx(0b100011, op),
+
+        Vaadduvv(op) => opmvv(0b001000, op),
+        Vaadduvx(op) => opmvx(0b001000, op),
+        Vaaddvv(op) => opmvv(0b001001, op),
+        Vaaddvx(op) => opmvx(0b001001, op),
+        Vasubuvv(op) => opmvv(0b001010, op),
+        Vasubuvx(op) => opmvx(0b001010, op),
+        Vasubvv(op) => opmvv(0b001011, op),
+        Vasubvx(op) => opmvx(0b001011, op),
+
+        Vsmulvv(op) => opivv(0b100111, op),
+        Vsmulvx(op) => opivx(0b100111, op),
+
+        Vssrlvv(op) => opivv(0b101010, op),
+        Vssrlvx(op) => opivx(0b101010, op),
+        Vssrlvi(op) => opivi_unsigned(0b101010, op),
+        Vssravv(op) => opivv(0b101011, op),
+        Vssravx(op) => opivx(0b101011, op),
+        Vssravi(op) => opivi_unsigned(0b101011, op),
+
+        Vnclipuwv(op) => opivv(0b101110, op),
+        Vnclipuwx(op) => opivx(0b101110, op),
+        Vnclipuwi(op) => opivi_unsigned(0b101110, op),
+        Vnclipwv(op) => opivv(0b101111, op),
+        Vnclipwx(op) => opivx(0b101111, op),
+        Vnclipwi(op) => opivi_unsigned(0b101111, op),
+
+        Vfaddvv(op) => opfvv(0b000000, op),
+        Vfaddvf(op) => opfvf(0b000000, op),
+        Vfsubvv(op) => opfvv(0b000010, op),
+        Vfsubvf(op) => opfvf(0b000010, op),
+        Vfrsubvf(op) => opfvf(0b100111, op),
+
+        Vfwaddvv(op) => opfvv(0b110000, op),
+        Vfwaddvf(op) => opfvf(0b110000, op),
+        Vfwsubvv(op) => opfvv(0b110010, op),
+        Vfwsubvf(op) => opfvf(0b110010, op),
+        Vfwaddwv(op) => opfvv(0b110100, op),
+        Vfwaddwf(op) => opfvf(0b110100, op),
+        Vfwsubwv(op) => opfvv(0b110110, op),
+        Vfwsubwf(op) => opfvf(0b110110, op),
+
+        Vfmulvv(op) => opfvv(0b100100, op),
+        Vfmulvf(op) => opfvf(0b100100, op),
+        Vfdivvv(op) => opfvv(0b100000, op),
+        Vfdivvf(op) => opfvf(0b100000, op),
+        Vfrdivvf(op) => opfvf(0b100001, op),
+
+        Vfwmulvv(op) => opfvv(0b111000, op),
+        Vfwmulvf(op) => opfvf(0b111000, op),
+
+        Vfmaccvv(op) => opfvv(0b101100, op),
+        Vfmaccvf(op) => opfvf(0b101100, op),
+        Vfnmaccvv(op) => opfvv(0b101101, op),
+        Vfnmaccvf(op) => opfvf(0b101101, op),
+        Vfmsacvv(op) => opfvv(0b101110, op),
+        Vfmsacvf(op) => opfvf(0b101110, op),
+        Vfnmsacvv(op) => opfvv(0b101111, op),
+        Vfnmsacvf(op) => opfvf(0b101111, op),
+        Vfmaddvv(op) => opfvv(0b101000, op),
+        Vfmaddvf(op) => opfvf(0b101000, op),
+        Vfnmaddvv(op) => opfvv(0b101001, op),
+        Vfnmaddvf(op) => opfvf(0b101001, op),
+        Vfmsubvv(op) => opfvv(0b101010, op),
+        Vfmsubvf(op) => opfvf(0b101010, op),
+        Vfnmsubvv(op) => opfvv(0b101011, op),
+        Vfnmsubvf(op) => opfvf(0b101011, op),
+
+        Vfwmaccvv(op) => opfvv(0b111100, op),
+        Vfwmaccvf(op) => opfvf(0b111100, op),
+        Vfwnmaccvv(op) => opfvv(0b111101, op),
+        Vfwnmaccvf(op) => opfvf(0b111101, op),
+        Vfwmsacvv(op) => opfvv(0b111110, op),
+        Vfwmsacvf(op) => opfvf(0b111110, op),
+        Vfwnmsacvv(op) => opfvv(0b111111, op),
+        Vfwnmsacvf(op) => opfvf(0b111111, op),
+
+        Vfsqrtv(op) => opfvv_unary(0b010011, 0b00000, op),
+        Vfrsqrt7v(op) => opfvv_unary(0b010011, 0b00100, op),
+        Vfrec7v(op) => opfvv_unary(0b010011, 0b00101, op),
+
+        Vfminvv(op) => opfvv(0b000100, op),
+        Vfminvf(op) => opfvf(0b000100, op),
+        Vfmaxvv(op) => opfvv(0b000110, op),
+        Vfmaxvf(op) => opfvf(0b000110, op),
+
+        Vfsgnjvv(op) => opfvv(0b001000, op),
+        Vfsgnjvf(op) => opfvf(0b001000, op),
+        Vfsgnjnvv(op) => opfvv(0b001001, op),
+        Vfsgnjnvf(op) => opfvf(0b001001, op),
+        Vfsgnjxvv(op) => opfvv(0b001010, op),
+        Vfsgnjxvf(op) => opfvf(0b001010, op),
+
+        Vmfeqvv(op) => opfvv(0b011000, op),
+        Vmfeqvf(op) => opfvf(0b011000, op),
+        Vmfnevv(op) => opfvv(0b011100, op),
+        Vmfnevf(op) => opfvf(0b011100, op),
+        Vmfltvv(op) => opfvv(0b011011, op),
+        Vmfltvf(op) => opfvf(0b011011, op),
+        Vmflevv(op) => opfvv(0b011001, op),
+        Vmflevf(op) => opfvf(0b011001, op),
+        Vmfgtvf(op) => opfvf(0b011101, op),
+        Vmfgevf(op) => opfvf(0b011111, op),
+
+        Vfclassv(op) => opfvv_unary(0b010011, 0b10000, op),
+
+        Vfmergevfm(op) => opfvf_fixed(0b010111, op, false),
+        Vfmvvf(op) => opfvf_fixed(
+            0b010111,
+            &Opfvf {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+
+        Vfcvtxufv(op) => opfvv_unary(0b010010, 0b00000, op),
+        Vfcvtxfv(op) => opfvv_unary(0b010010, 0b00001, op),
+        Vfcvtfxuv(op) => opfvv_unary(0b010010, 0b00010, op),
+        Vfcvtfxv(op) => opfvv_unary(0b010010, 0b00011, op),
+        VfcvtRtzxufv(op) => opfvv_unary(0b010010, 0b00110, op),
+        VfcvtRtzxfv(op) => opfvv_unary(0b010010, 0b00111, op),
+
+        Vfwcvtxufv(op) => opfvv_unary(0b010010, 0b01000, op),
+        Vfwcvtxfv(op) => opfvv_unary(0b010010, 0b01001, op),
+        Vfwcvtfxuv(op) => opfvv_unary(0b010010, 0b01010, op),
+        Vfwcvtfxv(op) => opfvv_unary(0b010010, 0b01011, op),
+        Vfwcvtffv(op) => opfvv_unary(0b010010, 0b01100, op),
+        VfwcvtRtzxufv(op) => opfvv_unary(0b010010, 0b01110, op),
+        VfwcvtRtzxfv(op) => opfvv_unary(0b010010, 0b01111, op),
+
+        Vfncvtxufw(op) => opfvv_unary(0b010010, 0b10000, op),
+        Vfncvtxfw(op) => opfvv_unary(0b010010, 0b10001, op),
+        Vfncvtfxuw(op) => opfvv_unary(0b010010, 0b10010, op),
+        Vfncvtfxw(op) => opfvv_unary(0b010010, 0b10011, op),
+        Vfncvtffw(op) => opfvv_unary(0b010010, 0b10100, op),
+        VfncvtRodffw(op) => opfvv_unary(0b010010, 0b10101, op),
+        VfncvtRtzxufw(op) => opfvv_unary(0b010010, 0b10110, op),
+        VfncvtRtzxfw(op) => opfvv_unary(0b010010, 0b10111, op),
+
+        Vredsumvs(op) => opmvv(0b000000, op),
+        Vredandvs(op) => opmvv(0b000001, op),
+        Vredorvs(op) => opmvv(0b000010, op),
+        Vredxorvs(op) => opmvv(0b000011, op),
+        Vredminuvs(op) => opmvv(0b000100, op),
+        Vredminvs(op) => opmvv(0b000101, op),
+        Vredmaxuvs(op) => opmvv(0b000110, op),
+        Vredmaxvs(op) => opmvv(0b000111, op),
+
+        Vwredsumuvs(op) => opivv(0b110000, op),
+        Vwredsumvs(op) => opivv(0b110001, op),
+
+        Vfredosumvs(op) => opfvv(0b000011, op),
+        Vfredusumvs(op) => opfvv(0b000001, op),
+        Vfredmaxvs(op) => opfvv(0b000111, op),
+        Vfredminvs(op) => opfvv(0b000101, op),
+
+        Vfwredosumvs(op) => opfvv(0b110011, op),
+        Vfwredusumvs(op) => opfvv(0b110001, op),
+
+        Vmandnmm(op) => opmvv_fixed(0b011000, op, true),
+        Vmandmm(op) => opmvv_fixed(0b011001, op, true),
+        Vmormm(op) => opmvv_fixed(0b011010, op, true),
+        Vmxormm(op) => opmvv_fixed(0b011011, op, true),
+        Vmornmm(op) => opmvv_fixed(0b011100, op, true),
+        Vmnandmm(op) => opmvv_fixed(0b011101, op, true),
+        Vmnormm(op) => opmvv_fixed(0b011110, op, true),
+        Vmxnormm(op) => opmvv_fixed(0b011111, op, true),
+
+        Vcpopm(op) => opmvv_unary(0b010000, 0b10000, op),
+        Vfirstm(op) => opmvv_unary(0b010000, 0b10001, op),
+        Vmsbfm(op) => opmvv_unary(0b010100, 0b00001, op),
+        Vmsifm(op) => opmvv_unary(0b010100, 0b00011, op),
+        Vmsofm(op) => opmvv_unary(0b010100, 0b00010, op),
+        Viotam(op) => opmvv_unary(0b010100, 0b10000, op),
+        Vidv(op) => opmvv_unary(
+            0b010100,
+            0b10001,
+            &Opmvv {
+                vs2: 0,
+                ..op.clone()
+            },
+        ),
+
+        Vmvxs(op) => opmvv_fixed(
+            0b010000,
+            &Opmvv {
+                vs1: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+        Vmvsx(op) => opmvx_fixed(
+            0b010000,
+            &Opmvx {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+
+        Vfmvfs(op) => opfvv_fixed(
+            0b010000,
+            &Opfvv {
+                vs1: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+        Vfmvsf(op) => opfvf_fixed(
+            0b010000,
+            &Opfvf {
+                vs2: 0,
+                ..op.clone()
+            },
+            true,
+        ),
+
+        Vslideupvx(op) => opivx(0b001110, op),
+        Vslideupvi(op) => opivi_unsigned(0b001110, op),
+        Vslidedownvx(op) => opivx(0b001111, op),
+        Vslidedownvi(op) => opivi_unsigned(0b001111, op),
+        Vslide1upvx(op) => opmvx(0b001110, op),
+        Vfslide1upvf(op) => opfvf(0b001110, op),
+        Vslide1downvx(op) => opmvx(0b001111, op),
+        Vfslide1downvf(op) => opfvf(0b001111, op),
+
+        Vrgathervv(op) => opivv(0b001100, op),
+        Vrgathervx(op) => opivx(0b001100, op),
+        Vrgathervi(op) => opivi_unsigned(0b001100, op),
+        Vrgatherei16vv(op) => opivv(0b001110, op),
+
+        Vcompressvm(op) => opmvv_fixed(0b010111, op, true),
+
+        Vmv1rv(op) => vmv_whole_register(1, op),
+        Vmv2rv(op) => vmv_whole_register(2, op),
+        Vmv4rv(op) => vmv_whole_register(4, op),
+        Vmv8rv(op) => vmv_whole_register(8, op),
+
+        Compressed(_, half) => Ok(*half as u32),
+        Fusion(_) => Err(EncodeError::Fusion),
+    }
+}
+
+const LOAD: u32 = 0b0000011;
+const LOAD_FP: u32 = 0b0000111;
+const MISC_MEM: u32 = 0b0001111;
+const AMO: u32 = 0b0101111;
+const OP_IMM: u32 = 0b0010011;
+const OP_IMM_32: u32 = 0b0011011;
+const STORE: u32 = 0b0100011;
+const STORE_FP: u32 = 0b0100111;
+const OP: u32 = 0b0110011;
+const OP_32: u32 = 0b0111011;
+const OP_FP: u32 = 0b1010011;
+const OP_V: u32 = 0b1010111;
+const BRANCH: u32 = 0b1100011;
+const SYSTEM: u32 = 0b1110011;
+
+// Floating-point fmt field
+const SINGLE: u32 = 0b00;
+const DOUBLE: u32 = 0b01;
+const HALF: u32 = 0b10;
+
+// OP-V funct3
+const OPIVV: u32 = 0b000;
+const OPFVV: u32 = 0b001;
+const OPMVV: u32 = 0b010;
+const OPIVI: u32 = 0b011;
+const OPIVX: u32 = 0b100;
+const OPFVF: u32 = 0b101;
+const OPMVX: u32 = 0b110;
+const OPCFG: u32 = 0b111;
+
+// Vector memory addressing modes
+const UNIT: u32 = 0b00;
+const INDEXED_UNORDERED: u32 = 0b01;
+const STRIDED: u32 = 0b10;
+const INDEXED_ORDERED: u32 = 0b11;
+
+// Registers with fixed roles in compressed instructions
+const RA: usize = 1;
+const SP: usize = 2;
+
+fn out_of_range(field: &'static str, value: impl Into<i64>) -> EncodeError {
+    EncodeError::OutOfRange {
+        field,
+        value: value.into(),
+    }
+}
+
+fn reg(register: usize) -> Result<u32, EncodeError> {
+    if register < 32 {
+        Ok(register as u32)
+    } else {
+        Err(out_of_range("register", register as i64))
+    }
+}
+
+fn unsigned(value: u32, bits: u32, field: &'static str) -> Result<u32, EncodeError> {
+    if value < 1 << bits {
+        Ok(value)
+    } else {
+        Err(out_of_range(field, value))
+    }
+}
+
+fn signed(value: i32, bits: u32, field: &'static str) -> Result<u32, EncodeError> {
+    let limit = 1 << (bits - 1);
+
+    if (-limit..limit).contains(&value) {
+        Ok(value as u32 & ((1 << bits) - 1))
+    } else {
+        Err(out_of_range(field, value))
+    }
+}
+
+fn r_type(r: &R, opcode: u32, funct3: u32, funct7: u32) -> Result<u32, EncodeError> {
+    Ok(funct7 << 25
+        | reg(r.rs2)? << 20
+        | reg(r.rs1)? << 15
+        | funct3 << 12
+        | reg(r.rd)? << 7
+        | opcode)
+}
+
+fn amo(atomic: &Atomic, funct5: u32, funct3: u32) -> Result<u32, EncodeError> {
+    Ok(funct5 << 27
+        | (atomic.aq as u32) << 26
+        | (atomic.rl as u32) << 25
+        | reg(atomic.rs2)? << 20
+        | reg(atomic.rs1)? << 15
+        | funct3 << 12
+        | reg(atomic.rd)? << 7
+        | AMO)
+}
+
+fn op(r: &R, funct3: u32, funct7: u32) -> Result<u32, EncodeError> {
+    r_type(r, OP, funct3, funct7)
+}
+
+fn op_32(r: &R, funct3: u32, funct7: u32) -> Result<u32, EncodeError> {
+    r_type(r, OP_32, funct3, funct7)
+}
+
+fn op_fp(r: &R, funct5: u32, fmt: u32, funct3: u32) -> Result<u32, EncodeError> {
+    r_type(r, OP_FP, funct3, funct5 << 2 | fmt)
+}
+
+fn op_fp_rounding(r: &Rfp, funct5: u32, fmt: u32) -> Result<u32, EncodeError> {
+    Ok(funct5 << 27
+        | fmt << 25
+        | reg(r.rs2)? << 20
+        | reg(r.rs1)? << 15
+        | unsigned(r.rm, 3, "rm")? << 12
+        | reg(r.rd)? << 7
+        | OP_FP)
+}
+
+/// Rounding OP-FP with function code in rs2 field
+fn op_fp_rounding_unary(r: &Rfp, funct5: u32, fmt: u32, rs2: u32) -> Result<u32, EncodeError> {
+    Ok(funct5 << 27
+        | fmt << 25
+        | rs2 << 20
+        | reg(r.rs1)? << 15
+        | unsigned(r.rm, 3, "rm")? << 12
+        | reg(r.rd)? << 7
+        | OP_FP)
+}
+
+/// OP-FP with function code in rs2 field
+fn op_fp_unary(r: &R, funct5: u32, fmt: u32, rs2: u32, funct3: u32) -> Result<u32, EncodeError> {
+    Ok(funct5 << 27
+        | fmt << 25
+        | rs2 << 20
+        | reg(r.rs1)? << 15
+        | funct3 << 12
+        | reg(r.rd)? << 7
+        | OP_FP)
+}
+
+fn r4_type(r4: &R4, opcode: u32, fmt: u32) -> Result<u32, EncodeError> {
+    Ok(reg(r4.rs3)? << 27
+        | fmt << 25
+        | reg(r4.rs2)? << 20
+        | reg(r4.rs1)? << 15
+        | unsigned(r4.rm, 3, "rm")? << 12
+        | reg(r4.rd)? << 7
+        | opcode)
+}
+
+fn i_type(i: &I, opcode: u32, funct3: u32) -> Result<u32, EncodeError> {
+    Ok(signed(i.imm12, 12, "imm12")? << 20
+        | reg(i.rs1)? << 15
+        | funct3 << 12
+        | reg(i.rd)? << 7
+        | opcode)
+}
+
+fn op_imm(i: &I, funct3: u32) -> Result<u32, EncodeError> {
+    i_type(i, OP_IMM, funct3)
+}
+
+fn load(i: &I, funct3: u32) -> Result<u32, EncodeError> {
+    i_type(i, LOAD, funct3)
+}
+
+fn shift(
+    i: &I,
+    opcode: u32,
+    funct3: u32,
+    funct7: u32,
+    shamt_bits: u32,
+) -> Result<u32, EncodeError> {
+    let shamt = u32::try_from(i.imm12).map_err(|_| out_of_range("shamt", i.imm12))?;
+
+    Ok(funct7 << 25
+        | unsigned(shamt, shamt_bits, "shamt")? << 20
+        | reg(i.rs1)? << 15
+        | funct3 << 12
+        | reg(i.rd)? << 7
+        | opcode)
+}
+
+/// Single-source instructions with the operation selected by the immediate field
+fn unary(r: &R, opcode: u32, funct3: u32, funct12: u32) -> Result<u32, EncodeError> {
+    Ok(funct12 << 20 | reg(r.rs1)? << 15 | funct3 << 12 | reg(r.rd)? << 7 | opcode)
+}
+
+fn s_type(s: &S, opcode: u32, funct3: u32) -> Result<u32, EncodeError> {
+    let imm = signed(s.imm12, 12, "imm12")?;
+
+    Ok((imm >> 5) << 25
+        | reg(s.rs2)? << 20
+        | reg(s.rs1)? << 15
+        | funct3 << 12
+        | (imm & 0b11111) << 7
+        | opcode)
+}
+
+fn store(s: &S, funct3: u32) -> Result<u32, EncodeError> {
+    s_type(s, STORE, funct3)
+}
+
+fn branch(s: &S, funct3: u32) -> Result<u32, EncodeError> {
+    if s.imm12 % 2 != 0 {
+        return Err(out_of_range("branch offset", s.imm12));
+    }
+
+    let imm = signed(s.imm12, 13, "branch offset")?;
+
+    Ok((imm >> 12) << 31
+        | ((imm >> 5) & 0b111111) << 25
+        | reg(s.rs2)? << 20
+        | reg(s.rs1)? << 15
+        | funct3 << 12
+        | ((imm >> 1) & 0b1111) << 8
+        | ((imm >> 11) & 0b1) << 7
+        | BRANCH)
+}
+
+fn u_type(u: &U, opcode: u32) -> Result<u32, EncodeError> {
+    // Upper immediates are accepted both sign-extended and as raw 20-bit values
+    if !(-(1 << 19)..1 << 20).contains(&u.imm20) {
+        return Err(out_of_range("imm20", u.imm20));
+    }
+
+    Ok((u.imm20 as u32 & 0xfffff) << 12 | reg(u.rd)? << 7 | opcode)
+}
+
+fn jal(u: &U) -> Result<u32, EncodeError> {
+    if u.imm20 % 2 != 0 {
+        return Err(out_of_range("jump offset", u.imm20));
+    }
+
+    let imm = signed(u.imm20, 21, "jump offset")?;
+
+    Ok((imm >> 20) << 31
+        | ((imm >> 1) & 0x3ff) << 21
+        | ((imm >> 11) & 0b1) << 20
+        | ((imm >> 12) & 0xff) << 12
+        | reg(u.rd)? << 7
+        | 0b1101111)
+}
+
+/// Register and immediate variants differ only in funct3, rs1 holds uimm in the latter
+fn csr(rd: usize, rs1: usize, csr: usize, funct3: u32) -> Result<u32, EncodeError> {
+    let csr = u32::try_from(csr).map_err(|_| out_of_range("csr", csr as i64))?;
+
+    Ok(unsigned(csr, 12, "csr")? << 20 | reg(rs1)? << 15 | funct3 << 12 | reg(rd)? << 7 | SYSTEM)
+}
+
+fn vector_width(eew: BaseSew) -> u32 {
+    match eew {
+        BaseSew::E8 => 0b000,
+        BaseSew::E16 => 0b101,
+        BaseSew::E32 => 0b110,
+        BaseSew::E64 => 0b111,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn vector_memory(
+    opcode: u32,
+    nf: usize,
+    mop: u32,
+    vm: bool,
+    field: usize,
+    rs1: usize,
+    eew: BaseSew,
+    vd: usize,
+) -> Result<u32, EncodeError> {
+    if !(1..=8).contains(&nf) {
+        return Err(out_of_range("nf", nf as i64));
+    }
+
+    Ok(((nf - 1) as u32) << 29
+        | mop << 26
+        | (!vm as u32) << 25
+        | reg(field)? << 20
+        | reg(rs1)? << 15
+        | vector_width(eew) << 12
+        | reg(vd)? << 7
+        | opcode)
+}
+
+fn vector_whole_register(
+    opcode: u32,
+    nf: usize,
+    rs1: usize,
+    eew: BaseSew,
+    vd: usize,
+) -> Result<u32, EncodeError> {
+    if !matches!(nf, 1 | 2 | 4 | 8) {
+        return Err(out_of_range("nf", nf as i64));
+    }
+
+    vector_memory(opcode, nf, UNIT, false, 0b01000, rs1, eew, vd)
+}
+
+/// `vm_bit` is the raw encoded bit, 0 means masked by v0
+fn op_v(
+    funct6: u32,
+    funct3: u32,
+    vm_bit: bool,
+    vs2: usize,
+    field: u32,
+    vd: usize,
+) -> Result<u32, EncodeError> {
+    Ok(funct6 << 26
+        | (vm_bit as u32) << 25
+        | reg(vs2)? << 20
+        | field << 15
+        | funct3 << 12
+        | reg(vd)? << 7
+        | OP_V)
+}
+
+fn opivv(funct6: u32, op: &Opivv) -> Result<u32, EncodeError> {
+    opivv_fixed(funct6, op, !op.vm)
+}
+
+fn opivv_fixed(funct6: u32, op: &Opivv, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPIVV, vm_bit, op.vs2, reg(op.vs1)?, op.dest)
+}
+
+fn opivx(funct6: u32, op: &Opivx) -> Result<u32, EncodeError> {
+    opivx_fixed(funct6, op, !op.vm)
+}
+
+fn opivx_fixed(funct6: u32, op: &Opivx, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPIVX, vm_bit, op.vs2, reg(op.rs1)?, op.dest)
+}
+
+fn opivi(funct6: u32, op: &Opivi) -> Result<u32, EncodeError> {
+    opivi_fixed(funct6, op, !op.vm)
+}
+
+fn opivi_fixed(funct6: u32, op: &Opivi, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(
+        funct6,
+        OPIVI,
+        vm_bit,
+        op.vs2,
+        signed(op.imm5, 5, "simm5")?,
+        op.dest,
+    )
+}
+
+fn opivi_unsigned(funct6: u32, op: &Opivi) -> Result<u32, EncodeError> {
+    let imm = u32::try_from(op.imm5).map_err(|_| out_of_range("uimm5", op.imm5))?;
+
+    op_v(
+        funct6,
+        OPIVI,
+        !op.vm,
+        op.vs2,
+        unsigned(imm, 5, "uimm5")?,
+        op.dest,
+    )
+}
+
+/// Register count is implied by the variant, simm5 holds nr - 1
+fn vmv_whole_register(nr: u32, op: &Opivi) -> Result<u32, EncodeError> {
+    op_v(0b100111, OPIVI, true, op.vs2, nr - 1, op.dest)
+}
+
+fn opmvv(funct6: u32, op: &Opmvv) -> Result<u32, EncodeError> {
+    opmvv_fixed(funct6, op, !op.vm)
+}
+
+fn opmvv_fixed(funct6: u32, op: &Opmvv, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPMVV, vm_bit, op.vs2, reg(op.vs1)?, op.dest)
+}
+
+/// OPMVV with function code in vs1 field
+fn opmvv_unary(funct6: u32, code: u32, op: &Opmvv) -> Result<u32, EncodeError> {
+    op_v(funct6, OPMVV, !op.vm, op.vs2, code, op.dest)
+}
+
+fn opmvx(funct6: u32, op: &Opmvx) -> Result<u32, EncodeError> {
+    opmvx_fixed(funct6, op, !op.vm)
+}
+
+fn opmvx_fixed(funct6: u32, op: &Opmvx, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPMVX, vm_bit, op.vs2, reg(op.rs1)?, op.dest)
+}
+
+fn opfvv(funct6: u32, op: &Opfvv) -> Result<u32, EncodeError> {
+    opfvv_fixed(funct6, op, !op.vm)
+}
+
+fn opfvv_fixed(funct6: u32, op: &Opfvv, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPFVV, vm_bit, op.vs2, reg(op.vs1)?, op.dest)
+}
+
+/// OPFVV with function code in vs1 field
+fn opfvv_unary(funct6: u32, code: u32, op: &Opfvv) -> Result<u32, EncodeError> {
+    op_v(funct6, OPFVV, !op.vm, op.vs2, code, op.dest)
+}
+
+fn opfvf(funct6: u32, op: &Opfvf) -> Result<u32, EncodeError> {
+    opfvf_fixed(funct6, op, !op.vm)
+}
+
+fn opfvf_fixed(funct6: u32, op: &Opfvf, vm_bit: bool) -> Result<u32, EncodeError> {
+    op_v(funct6, OPFVF, vm_bit, op.vs2, reg(op.rs1)?, op.vd)
+}
+
+/// Compressed register fields only reach x8-x15
+fn is_creg(register: usize) -> bool {
+    (8..16).contains(&register)
+}
+
+fn creg(register: usize) -> u32 {
+    register as u32 - 8
+}
+
+fn fits(value: i32, bits: u32) -> bool {
+    (-(1 << (bits - 1))..1 << (bits - 1)).contains(&value)
+}
+
+/// Unsigned offset that's a multiple of `scale`
+fn scaled(value: i32, scale: i32, max: i32) -> bool {
+    (0..=max).contains(&value) && value % scale == 0
+}
+
+/// Bits `high..=low` of an immediate, shifted down to bit 0
+fn field(value: i32, high: u32, low: u32) -> u32 {
+    (value as u32 >> low) & ((1 << (high - low + 1)) - 1)
+}
+
+fn is_addi16sp(imm12: i32) -> bool {
+    imm12 != 0 && imm12 % 16 == 0 && fits(imm12, 10)
+}
+
+fn addi16sp(imm12: i32) -> u32 {
+    0b011 << 13
+        | field(imm12, 9, 9) << 12
+        | (SP as u32) << 7
+        | field(imm12, 4, 4) << 6
+        | field(imm12, 6, 6) << 5
+        | field(imm12, 8, 7) << 3
+        | field(imm12, 5, 5) << 2
+        | 0b01
+}
+
+fn ci(funct3: u32, rd: usize, imm: i32, quadrant: u32) -> u32 {
+    funct3 << 13 | field(imm, 5, 5) << 12 | (rd as u32) << 7 | field(imm, 4, 0) << 2 | quadrant
+}
+
+fn ci_sp_w(funct3: u32, rd: usize, imm: i32) -> u32 {
+    funct3 << 13
+        | field(imm, 5, 5) << 12
+        | (rd as u32) << 7
+        | field(imm, 4, 2) << 4
+        | field(imm, 7, 6) << 2
+        | 0b10
+}
+
+fn ci_sp_d(funct3: u32, rd: usize, imm: i32) -> u32 {
+    funct3 << 13
+        | field(imm, 5, 5) << 12
+        | (rd as u32) << 7
+        | field(imm, 4, 3) << 5
+        | field(imm, 8, 6) << 2
+        | 0b10
+}
+
+fn css_w(funct3: u32, rs2: usize, imm: i32) -> u32 {
+    funct3 << 13 | field(imm, 5, 2) << 9 | field(imm, 7, 6) << 7 | (rs2 as u32) << 2 | 0b10
+}
+
+fn css_d(funct3: u32, rs2: usize, imm: i32) -> u32 {
+    funct3 << 13 | field(imm, 5, 3) << 10 | field(imm, 8, 6) << 7 | (rs2 as u32) << 2 | 0b10
+}
+
+fn cl_w_offset(imm: i32) -> u32 {
+    field(imm, 5, 3) << 10 | field(imm, 2, 2) << 6 | field(imm, 6, 6) << 5
+}
+
+fn cl_d_offset(imm: i32) -> u32 {
+    field(imm, 5, 3) << 10 | field(imm, 7, 6) << 5
+}
+
+/// Loads and stores on x8-x15, `rd` is the stored register for the latter
+fn cl_cs(funct3: u32, rd: usize, rs1: usize, offset: u32, quadrant: u32) -> u32 {
+    funct3 << 13 | offset | creg(rs1) << 7 | creg(rd) << 2 | quadrant
+}
+
+fn cb_alu(funct2: u32, rd: usize, imm: i32) -> u32 {
+    0b100 << 13
+        | field(imm, 5, 5) << 12
+        | funct2 << 10
+        | creg(rd) << 7
+        | field(imm, 4, 0) << 2
+        | 0b01
+}
+
+fn is_cb_branch(rs1: usize, imm: i32) -> bool {
+    is_creg(rs1) && imm % 2 == 0 && fits(imm, 9)
+}
+
+fn cb_branch(funct3: u32, rs1: usize, imm: i32) -> u32 {
+    funct3 << 13
+        | field(imm, 8, 8) << 12
+        | field(imm, 4, 3) << 10
+        | creg(rs1) << 7
+        | field(imm, 7, 6) << 5
+        | field(imm, 2, 1) << 3
+        | field(imm, 5, 5) << 2
+        | 0b01
+}
+
+fn is_ca(r: &R) -> bool {
+    r.rd == r.rs1 && is_creg(r.rd) && is_creg(r.rs2)
+}
+
+fn ca(funct6: u32, funct2: u32, r: &R) -> u32 {
+    funct6 << 10 | creg(r.rd) << 7 | funct2 << 5 | creg(r.rs2) << 2 | 0b01
+}
+
+fn cr(funct4: u32, rd: usize, rs2: usize) -> u32 {
+    funct4 << 12 | (rd as u32) << 7 | (rs2 as u32) << 2 | 0b10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fuse, rv_core::instruction::decoder::decode};
+
+    #[test]
+    fn encoding_works() {
+        assert_eq!(
+            encode(&Addi(I {
+                rd: 10,
+                rs1: 10,
+                imm12: 1
+            })),
+            Ok(0x00150513)
+        );
+        assert_eq!(
+            encode(&Bne(S {
+                rs1: 10,
+                rs2: 0,
+                imm12: -16
+            })),
+            Ok(0xfe0518e3)
+        );
+        assert_eq!(
+            encode(&Vlsegv {
+                data: Vl {
+                    vd: 4,
+                    rs1: 11,
+                    vm: true
+                },
+                eew: BaseSew::E32,
+                nf: 3
+            }),
+            Ok(0x4005e207)
+        );
+        assert_eq!(
+            encode(&Vmv4rv(Opivi {
+                dest: 4,
+                imm5: 0,
+                vs2: 8,
+                vm: false
+            })),
+            Ok(0x9e81b257)
+        );
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        let words = [
+            0x00150513, 0xfe113c23, 0xfe0518e3, 0x001000ef, 0xfffff7b7, 0x43f55513, 0xc2202573,
+            0x80b57063, 0x80000517, 0x3053f573, 0x0d0572d7, 0xc073f2d7, 0x80b572d7, 0x02050407,
+            0x4005e207, 0x03050087, 0xe2857407, 0x2e450127, 0x02b500a7, 0x22850127, 0x442180d7,
+            0x462180d7, 0x5c2eb0d7, 0x5e0830d7, 0x962fb0d7, 0x9e81b257, 0xba22b0d7, 0x40282557,
+            0x4b012457, 0x5208a0d7, 0x420560d7, 0x42201557, 0x4a2a90d7, 0x5c2550d7, 0x5e0550d7,
+            0x6ac5f543, 0x4015f553, 0x42058553, 0x30200073, 0x10500073, 0x10200073, 0x12b50073,
+            0x00000073, 0x00100073, 0x0310000f, 0x0ff0000f, 0x0000100f, 0x08c5853b, 0x20c5c533,
+            0x20c5e53b, 0x0bf5951b, 0x40c5f533, 0x40c5c533, 0x60059513, 0x6015951b, 0x60259513,
+            0x0ac5e533, 0x0ac5d533, 0x60459513, 0x60559513, 0x0805c53b, 0x60c5953b, 0x60c5d533,
+            0x63f5d513, 0x61f5d51b, 0x2875d513, 0x6b85d513, 0x48c59533, 0x4bf5d513, 0x68c59533,
+            0x2bf59513, 0xffe59507, 0x00a11327, 0x6cc5f543, 0x04c5f553, 0x5c05f553, 0x24c5a553,
+            0x40258553, 0x4415f553, 0x42258553, 0xc435f553, 0xe4058553, 0xe4059553, 0xa4c59553,
+            0xd435f553, 0xf4058553, 0x00c59553, 0x6ac5c543, 0xc0051553,
+        ];
+
+        for word in words {
+            assert_eq!(encode(&decode(word).unwrap()), Ok(word), "{:#010x}", word);
+        }
+    }
+
+    #[test]
+    fn compressed_encoding_round_trips() {
+        let halves = [
+            0x0808, 0x1fe0, 0x2588, 0x41c8, 0x6588, 0xa588, 0xc1c8, 0xe588, 0x0001, 0x1575, 0x2505,
+            0x4515, 0x7139, 0x617d, 0x6505, 0x757d, 0x810d, 0x957d, 0x997d, 0x8d0d, 0x8d2d, 0x8d4d,
+            0x8d6d, 0x9d0d, 0x9d2d, 0xbfc5, 0xc501, 0xfd65, 0x050e, 0x2522, 0x4512, 0x6522, 0x8502,
+            0x852e, 0x9002, 0x9502, 0x952e, 0xa42a, 0xc22a, 0xe42a, 0x0141,
+        ];
+
+        for half in halves {
+            assert_eq!(encode(&decode(half).unwrap()), Ok(half), "{:#06x}", half);
+        }
+    }
+
+    #[test]
+    fn compression_picks_short_forms() {
+        // addi sp, sp, -32 fits c.addi, which assemblers prefer over c.addi16sp
+        assert_eq!(
+            encode_compressed(&Addi(I {
+                rd: 2,
+                rs1: 2,
+                imm12: -32
+            })),
+            Ok(0x1101)
+        );
+        assert_eq!(
+            compress(&Ld(I {
+                rd: 10,
+                rs1: 2,
+                imm12: 512
+            })),
+            Ld(I {
+                rd: 10,
+                rs1: 2,
+                imm12: 512
+            })
+        );
+        assert_eq!(
+            encode_bytes(&compress(&fuse![
+                Lui(U { rd: 5, imm20: 1 }),
+                Addi(I {
+                    rd: 5,
+                    rs1: 5,
+                    imm12: 4
+                })
+            ])),
+            Ok(vec![0x85, 0x62, 0x91, 0x02])
+        );
+        // HINTs are left uncompressed
+        assert_eq!(
+            compress(&Addi(I {
+                rd: 0,
+                rs1: 0,
+                imm12: 5
+            })),
+            Addi(I {
+                rd: 0,
+                rs1: 0,
+                imm12: 5
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_operands_are_rejected() {
+        assert_eq!(
+            encode(&Addi(I {
+                rd: 1,
+                rs1: 0,
+                imm12: 2048
+            })),
+            Err(EncodeError::OutOfRange {
+                field: "imm12",
+                value: 2048
+            })
+        );
+        assert_eq!(
+            encode(&Vaddvi(Opivi {
+                dest: 1,
+                imm5: 16,
+                vs2: 2,
+                vm: false
+            })),
+            Err(EncodeError::OutOfRange {
+                field: "simm5",
+                value: 16
+            })
+        );
+        assert_eq!(
+            encode(&fuse![
+                Lui(U { rd: 5, imm20: 1 }),
+                Addi(I {
+                    rd: 5,
+                    rs1: 5,
+                    imm12: 4
+                })
+            ]),
+            Err(EncodeError::Fusion)
+        );
+        assert_eq!(
+            encode_fused(&fuse![
+                Lui(U { rd: 5, imm20: 1 }),
+                Addi(I {
+                    rd: 5,
+                    rs1: 5,
+                    imm12: 4
+                })
+            ]),
+            Ok(vec![0x000012b7, 0x00428293])
+        );
+    }
+}
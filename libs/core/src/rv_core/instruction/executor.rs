@@ -1,20 +1,30 @@
 use prelude::*;
 
+mod a;
 mod base;
+mod c;
 mod d;
 mod f;
 mod m;
+mod privileged;
 mod v;
+mod zba;
+mod zbb;
+mod zbs;
+mod zfh;
 mod zicsr;
 
 mod prelude;
 mod vector_context;
 
+use std::cell::Cell;
+
 use super::Instruction;
+use crate::rv_core::trap::Trap;
 pub use vector_context::VectorContext;
 
 pub struct Executor<'core> {
-    memory: &'core mut Memory,
+    mmu: Mmu<'core>,
     registers: &'core mut Registers,
     vec_engine: &'core mut VectorEngine,
 }
@@ -26,15 +36,29 @@ impl<'c> Executor<'c> {
         vec_engine: &'c mut VectorEngine,
     ) -> Self {
         Self {
+            mmu: Mmu::new(memory, &registers.c, registers.mode),
             registers,
-            memory,
             vec_engine,
         }
     }
 
-    pub fn execute(&mut self, input: Instruction) -> Result<(), String> {
-        use Instruction::*;
+    pub fn execute(&mut self, input: Instruction) -> Result<(), Trap> {
+        if let Instruction::Fusion(instructions) = input {
+            // Parts are laid out one after another in memory, so each of them advances pc on its own
+            for instruction in instructions.iter() {
+                self.execute(instruction.clone())?;
+            }
 
+            return Ok(());
+        }
+
+        let pc = self.registers.pc;
+
+        self.execute_instruction(input)
+            .map_err(|exception| exception.at(pc))
+    }
+
+    fn execute_instruction(&mut self, input: Instruction) -> Result<(), Exception> {
         let current_cycle = self.registers.c[CYCLE].read();
         unsafe {
             self.registers.c[CYCLE].set(current_cycle + 1);
@@ -42,6 +66,22 @@ impl<'c> Executor<'c> {
 
         unsafe { self.registers.c[TIME].set(current_cycle) }
 
+        let size = input.size();
+        self.execute_operation(input)?;
+
+        let current_instret = self.registers.c[INSTRET].read();
+        unsafe {
+            self.registers.c[INSTRET].set(current_instret + 1);
+        }
+        self.registers.pc = self.registers.pc.wrapping_add(size);
+
+        Ok(())
+    }
+
+    /// Runs instruction without advancing pc, control transfers leave it one instruction before their target
+    fn execute_operation(&mut self, input: Instruction) -> Result<(), Exception> {
+        use Instruction::*;
+
         match input {
             Add(args) => base::add(args, &mut self.registers.x),
             Addw(args) => base::addw(args, &mut self.registers.x),
@@ -73,17 +113,17 @@ impl<'c> Executor<'c> {
             Srliw(args) => base::srliw(args, &mut self.registers.x),
             Srai(args) => base::srai(args, &mut self.registers.x),
             Sraiw(args) => base::sraiw(args, &mut self.registers.x),
-            Ld(args) => base::ld(args, &mut self.registers.x, self.memory),
-            Lw(args) => base::lw(args, &mut self.registers.x, self.memory),
-            Lwu(args) => base::lwu(args, &mut self.registers.x, self.memory),
-            Lh(args) => base::lh(args, &mut self.registers.x, self.memory),
-            Lhu(args) => base::lhu(args, &mut self.registers.x, self.memory),
-            Lb(args) => base::lb(args, &mut self.registers.x, self.memory),
-            Lbu(args) => base::lbu(args, &mut self.registers.x, self.memory),
-            Sd(args) => base::sd(args, &self.registers.x, self.memory),
-            Sw(args) => base::sw(args, &self.registers.x, self.memory),
-            Sh(args) => base::sh(args, &self.registers.x, self.memory),
-            Sb(args) => base::sb(args, &self.registers.x, self.memory),
+            Ld(args) => base::ld(args, &mut self.registers.x, &self.mmu)?,
+            Lw(args) => base::lw(args, &mut self.registers.x, &self.mmu)?,
+            Lwu(args) => base::lwu(args, &mut self.registers.x, &self.mmu)?,
+            Lh(args) => base::lh(args, &mut self.registers.x, &self.mmu)?,
+            Lhu(args) => base::lhu(args, &mut self.registers.x, &self.mmu)?,
+            Lb(args) => base::lb(args, &mut self.registers.x, &self.mmu)?,
+            Lbu(args) => base::lbu(args, &mut self.registers.x, &self.mmu)?,
+            Sd(args) => base::sd(args, &self.registers.x, &mut self.mmu)?,
+            Sw(args) => base::sw(args, &self.registers.x, &mut self.mmu)?,
+            Sh(args) => base::sh(args, &self.registers.x, &mut self.mmu)?,
+            Sb(args) => base::sb(args, &self.registers.x, &mut self.mmu)?,
             Beq(args) => base::beq(args, &self.registers.x, &mut self.registers.pc),
             Bne(args) => base::bne(args, &self.registers.x, &mut self.registers.pc),
             Bge(args) => base::bge(args, &self.registers.x, &mut self.registers.pc),
@@ -93,12 +133,62 @@ impl<'c> Executor<'c> {
             Jal(args) => base::jal(args, &mut self.registers.x, &mut self.registers.pc),
             Jalr(args) => base::jalr(args, &mut self.registers.x, &mut self.registers.pc),
 
-            Csrrw(args) => zicsr::csrrw(args, &mut self.registers.x, &mut self.registers.c)?,
-            Csrrs(args) => zicsr::csrrs(args, &mut self.registers.x, &mut self.registers.c)?,
-            Csrrc(args) => zicsr::csrrc(args, &mut self.registers.x, &mut self.registers.c)?,
-            Csrrwi(args) => zicsr::csrrwi(args, &mut self.registers.x, &mut self.registers.c)?,
-            Csrrsi(args) => zicsr::csrrsi(args, &mut self.registers.x, &mut self.registers.c)?,
-            Csrrci(args) => zicsr::csrrci(args, &mut self.registers.x, &mut self.registers.c)?,
+            Ecall => base::ecall(self.registers.mode)?,
+            Ebreak => base::ebreak()?,
+            Fence(args) => base::fence(args),
+            FenceI(_) => base::fence_i(),
+
+            Csrrw(args) => zicsr::csrrw(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+            Csrrs(args) => zicsr::csrrs(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+            Csrrc(args) => zicsr::csrrc(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+            Csrrwi(args) => zicsr::csrrwi(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+            Csrrsi(args) => zicsr::csrrsi(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+            Csrrci(args) => zicsr::csrrci(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.c,
+                self.registers.mode,
+            )?,
+
+            Mret => privileged::mret(
+                &mut self.registers.c,
+                &mut self.registers.mode,
+                &mut self.registers.pc,
+            )?,
+            Sret => privileged::sret(
+                &mut self.registers.c,
+                &mut self.registers.mode,
+                &mut self.registers.pc,
+            )?,
+            Wfi => privileged::wfi(&self.registers.c, self.registers.mode)?,
+            SfenceVma(args) => {
+                privileged::sfence_vma(args, &self.registers.c, self.registers.mode)?
+            }
 
             Mul(args) => m::mul(args, &mut self.registers.x),
             Mulh(args) => m::mulh(args, &mut self.registers.x),
@@ -114,102 +204,419 @@ impl<'c> Executor<'c> {
             Remw(args) => m::remw(args, &mut self.registers.x),
             Remuw(args) => m::remuw(args, &mut self.registers.x),
 
-            Flw(args) => f::flw(args, &self.registers.x, &mut self.registers.f, self.memory),
-            Fsw(args) => f::fsw(args, &self.registers.x, &self.registers.f, self.memory),
-            Fmadds(args) => f::fmadd::s(args, &mut self.registers.f),
-            Fmsubs(args) => f::fmsub::s(args, &mut self.registers.f),
-            Fnmsubs(args) => f::fnmsub::s(args, &mut self.registers.f),
-            Fnmadds(args) => f::fnmadd::s(args, &mut self.registers.f),
-            Fadds(args) => f::fadd::s(args, &mut self.registers.f),
-            Fsubs(args) => f::fsub::s(args, &mut self.registers.f),
-            Fmuls(args) => f::fmul::s(args, &mut self.registers.f),
-            Fdivs(args) => f::fdiv::s(args, &mut self.registers.f),
-            Fsqrts(args) => f::fsqrt::s(args, &mut self.registers.f),
+            Lrw(args) => a::lr::w(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.reservation,
+                &self.mmu,
+            )?,
+            Scw(args) => a::sc::w(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.reservation,
+                &mut self.mmu,
+            )?,
+            Amoswapw(args) => a::amoswap::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoaddw(args) => a::amoadd::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoxorw(args) => a::amoxor::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoandw(args) => a::amoand::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoorw(args) => a::amoor::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amominw(args) => a::amomin::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amomaxw(args) => a::amomax::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amominuw(args) => a::amominu::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Amomaxuw(args) => a::amomaxu::w(args, &mut self.registers.x, &mut self.mmu)?,
+            Lrd(args) => a::lr::d(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.reservation,
+                &self.mmu,
+            )?,
+            Scd(args) => a::sc::d(
+                args,
+                &mut self.registers.x,
+                &mut self.registers.reservation,
+                &mut self.mmu,
+            )?,
+            Amoswapd(args) => a::amoswap::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoaddd(args) => a::amoadd::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoxord(args) => a::amoxor::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoandd(args) => a::amoand::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amoord(args) => a::amoor::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amomind(args) => a::amomin::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amomaxd(args) => a::amomax::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amominud(args) => a::amominu::d(args, &mut self.registers.x, &mut self.mmu)?,
+            Amomaxud(args) => a::amomaxu::d(args, &mut self.registers.x, &mut self.mmu)?,
+
+            Flw(args) => f::flw(args, &self.registers.x, &mut self.registers.f, &self.mmu)?,
+            Fsw(args) => f::fsw(args, &self.registers.x, &self.registers.f, &mut self.mmu)?,
+            Fmadds(args) => f::fmadd::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmsubs(args) => f::fmsub::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmsubs(args) => f::fnmsub::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmadds(args) => f::fnmadd::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fadds(args) => f::fadd::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsubs(args) => f::fsub::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmuls(args) => f::fmul::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fdivs(args) => f::fdiv::s(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsqrts(args) => f::fsqrt::s(args, &mut self.registers.f, &mut self.registers.c)?,
             Fsgnjs(args) => f::fsgnj::s(args, &mut self.registers.f),
             Fsgnjns(args) => f::fsgnjn::s(args, &mut self.registers.f),
             Fsgnjxs(args) => f::fsgnjx::s(args, &mut self.registers.f),
-            Fmins(args) => f::fmin::s(args, &mut self.registers.f),
-            Fmaxs(args) => f::fmax::s(args, &mut self.registers.f),
-            Fcvtws(args) => f::fcvt::ws(args, &mut self.registers.x, &self.registers.f),
-            Fcvtwus(args) => f::fcvt::wus(args, &mut self.registers.x, &self.registers.f),
+            Fmins(args) => f::fmin::s(args, &mut self.registers.f, &mut self.registers.c),
+            Fmaxs(args) => f::fmax::s(args, &mut self.registers.f, &mut self.registers.c),
+            Fcvtws(args) => f::fcvt::ws(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtwus(args) => f::fcvt::wus(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
             Fmvxw(args) => f::fmv::xw(args, &mut self.registers.x, &self.registers.f),
-            Feqs(args) => f::feq::s(args, &mut self.registers.x, &self.registers.f),
-            Flts(args) => f::flt::s(args, &mut self.registers.x, &self.registers.f),
-            Fles(args) => f::fle::s(args, &mut self.registers.x, &self.registers.f),
+            Feqs(args) => f::feq::s(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Flts(args) => f::flt::s(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Fles(args) => f::fle::s(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
             Fclasss(args) => f::fclass::s(args, &mut self.registers.x, &self.registers.f),
-            Fcvtsw(args) => f::fcvt::sw(args, &self.registers.x, &mut self.registers.f),
-            Fcvtswu(args) => f::fcvt::swu(args, &self.registers.x, &mut self.registers.f),
+            Fcvtsw(args) => f::fcvt::sw(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtswu(args) => f::fcvt::swu(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
             Fmvwx(args) => f::fmv::wx(args, &self.registers.x, &mut self.registers.f),
-            Fcvtls(args) => f::fcvt::ls(args, &mut self.registers.x, &self.registers.f),
-            Fcvtlus(args) => f::fcvt::lus(args, &mut self.registers.x, &self.registers.f),
-            Fcvtsl(args) => f::fcvt::sl(args, &self.registers.x, &mut self.registers.f),
-            Fcvtslu(args) => f::fcvt::slu(args, &self.registers.x, &mut self.registers.f),
-
-            Fld(args) => d::fld(args, &self.registers.x, &mut self.registers.f, self.memory),
-            Fsd(args) => d::fsd(args, &self.registers.x, &self.registers.f, self.memory),
-            Fmaddd(args) => d::fmadd::d(args, &mut self.registers.f),
-            Fmsubd(args) => d::fmsub::d(args, &mut self.registers.f),
-            Fnmsubd(args) => d::fnmsub::d(args, &mut self.registers.f),
-            Fnmaddd(args) => d::fnmadd::d(args, &mut self.registers.f),
-            Faddd(args) => d::fadd::d(args, &mut self.registers.f),
-            Fsubd(args) => d::fsub::d(args, &mut self.registers.f),
-            Fmuld(args) => d::fmul::d(args, &mut self.registers.f),
-            Fdivd(args) => d::fdiv::d(args, &mut self.registers.f),
-            Fsqrtd(args) => d::fsqrt::d(args, &mut self.registers.f),
+            Fcvtls(args) => f::fcvt::ls(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtlus(args) => f::fcvt::lus(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtsl(args) => f::fcvt::sl(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtslu(args) => f::fcvt::slu(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+
+            Fld(args) => d::fld(args, &self.registers.x, &mut self.registers.f, &self.mmu)?,
+            Fsd(args) => d::fsd(args, &self.registers.x, &self.registers.f, &mut self.mmu)?,
+            Fmaddd(args) => d::fmadd::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmsubd(args) => d::fmsub::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmsubd(args) => d::fnmsub::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmaddd(args) => d::fnmadd::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Faddd(args) => d::fadd::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsubd(args) => d::fsub::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmuld(args) => d::fmul::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fdivd(args) => d::fdiv::d(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsqrtd(args) => d::fsqrt::d(args, &mut self.registers.f, &mut self.registers.c)?,
             Fsgnjd(args) => d::fsgnj::d(args, &mut self.registers.f),
             Fsgnjnd(args) => d::fsgnjn::d(args, &mut self.registers.f),
             Fsgnjxd(args) => d::fsgnjx::d(args, &mut self.registers.f),
-            Fmind(args) => d::fmin::d(args, &mut self.registers.f),
-            Fmaxd(args) => d::fmax::d(args, &mut self.registers.f),
-            Fcvtsd(args) => d::fcvt::sd(args, &mut self.registers.f),
-            Fcvtds(args) => d::fcvt::ds(args, &mut self.registers.f),
-            Feqd(args) => d::feq::d(args, &mut self.registers.x, &self.registers.f),
-            Fltd(args) => d::flt::d(args, &mut self.registers.x, &self.registers.f),
-            Fled(args) => d::fle::d(args, &mut self.registers.x, &self.registers.f),
+            Fmind(args) => d::fmin::d(args, &mut self.registers.f, &mut self.registers.c),
+            Fmaxd(args) => d::fmax::d(args, &mut self.registers.f, &mut self.registers.c),
+            Fcvtsd(args) => d::fcvt::sd(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fcvtds(args) => d::fcvt::ds(args, &mut self.registers.f, &mut self.registers.c),
+            Feqd(args) => d::feq::d(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Fltd(args) => d::flt::d(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Fled(args) => d::fle::d(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
             Fclassd(args) => d::fclass::d(args, &mut self.registers.x, &self.registers.f),
-            Fcvtwd(args) => d::fcvt::wd(args, &mut self.registers.x, &self.registers.f),
-            Fcvtwud(args) => d::fcvt::wud(args, &mut self.registers.x, &self.registers.f),
+            Fcvtwd(args) => d::fcvt::wd(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtwud(args) => d::fcvt::wud(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
             Fcvtdw(args) => d::fcvt::dw(args, &self.registers.x, &mut self.registers.f),
             Fcvtdwu(args) => d::fcvt::dwu(args, &self.registers.x, &mut self.registers.f),
-            Fcvtld(args) => d::fcvt::ld(args, &mut self.registers.x, &self.registers.f),
-            Fcvtlud(args) => d::fcvt::lud(args, &mut self.registers.x, &self.registers.f),
+            Fcvtld(args) => d::fcvt::ld(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtlud(args) => d::fcvt::lud(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
             Fmvxd(args) => d::fmv::xd(args, &mut self.registers.x, &self.registers.f),
-            Fcvtdl(args) => d::fcvt::dl(args, &self.registers.x, &mut self.registers.f),
-            Fcvtdlu(args) => d::fcvt::dlu(args, &self.registers.x, &mut self.registers.f),
+            Fcvtdl(args) => d::fcvt::dl(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtdlu(args) => d::fcvt::dlu(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
             Fmvdx(args) => d::fmv::dx(args, &self.registers.x, &mut self.registers.f),
 
-            Fusion(instructions) => {
-                // FIXME
-                instructions
-                    .iter()
-                    .map(|instruction| self.execute(instruction.clone()))
-                    .collect::<Result<Vec<()>, _>>()?;
-
-                self.registers.pc = self
-                    .registers
-                    .pc
-                    .wrapping_sub(instructions.len() as u64 * 4);
-            }
+            Flh(args) => zfh::flh(args, &self.registers.x, &mut self.registers.f, &self.mmu)?,
+            Fsh(args) => zfh::fsh(args, &self.registers.x, &self.registers.f, &mut self.mmu)?,
+            Fmaddh(args) => zfh::fmadd::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmsubh(args) => zfh::fmsub::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmsubh(args) => zfh::fnmsub::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fnmaddh(args) => zfh::fnmadd::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Faddh(args) => zfh::fadd::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsubh(args) => zfh::fsub::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fmulh(args) => zfh::fmul::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fdivh(args) => zfh::fdiv::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsqrth(args) => zfh::fsqrt::h(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fsgnjh(args) => zfh::fsgnj::h(args, &mut self.registers.f),
+            Fsgnjnh(args) => zfh::fsgnjn::h(args, &mut self.registers.f),
+            Fsgnjxh(args) => zfh::fsgnjx::h(args, &mut self.registers.f),
+            Fminh(args) => zfh::fmin::h(args, &mut self.registers.f, &mut self.registers.c),
+            Fmaxh(args) => zfh::fmax::h(args, &mut self.registers.f, &mut self.registers.c),
+            Fcvtsh(args) => zfh::fcvt::sh(args, &mut self.registers.f, &mut self.registers.c),
+            Fcvths(args) => zfh::fcvt::hs(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fcvtdh(args) => zfh::fcvt::dh(args, &mut self.registers.f, &mut self.registers.c),
+            Fcvthd(args) => zfh::fcvt::hd(args, &mut self.registers.f, &mut self.registers.c)?,
+            Fcvtwh(args) => zfh::fcvt::wh(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtwuh(args) => zfh::fcvt::wuh(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fmvxh(args) => zfh::fmv::xh(args, &mut self.registers.x, &self.registers.f),
+            Feqh(args) => zfh::feq::h(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Flth(args) => zfh::flt::h(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Fleh(args) => zfh::fle::h(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            ),
+            Fclassh(args) => zfh::fclass::h(args, &mut self.registers.x, &self.registers.f),
+            Fcvthw(args) => zfh::fcvt::hw(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvthwu(args) => zfh::fcvt::hwu(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fmvhx(args) => zfh::fmv::hx(args, &self.registers.x, &mut self.registers.f),
+            Fcvtlh(args) => zfh::fcvt::lh(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvtluh(args) => zfh::fcvt::luh(
+                args,
+                &mut self.registers.x,
+                &self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvthl(args) => zfh::fcvt::hl(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+            Fcvthlu(args) => zfh::fcvt::hlu(
+                args,
+                &self.registers.x,
+                &mut self.registers.f,
+                &mut self.registers.c,
+            )?,
+
+            Adduw(args) => zba::adduw(args, &mut self.registers.x),
+            Sh1add(args) => zba::sh1add(args, &mut self.registers.x),
+            Sh2add(args) => zba::sh2add(args, &mut self.registers.x),
+            Sh3add(args) => zba::sh3add(args, &mut self.registers.x),
+            Sh1adduw(args) => zba::sh1adduw(args, &mut self.registers.x),
+            Sh2adduw(args) => zba::sh2adduw(args, &mut self.registers.x),
+            Sh3adduw(args) => zba::sh3adduw(args, &mut self.registers.x),
+            Slliuw(args) => zba::slliuw(args, &mut self.registers.x),
+
+            Andn(args) => zbb::andn(args, &mut self.registers.x),
+            Orn(args) => zbb::orn(args, &mut self.registers.x),
+            Xnor(args) => zbb::xnor(args, &mut self.registers.x),
+            Clz(args) => zbb::clz(args, &mut self.registers.x),
+            Clzw(args) => zbb::clzw(args, &mut self.registers.x),
+            Ctz(args) => zbb::ctz(args, &mut self.registers.x),
+            Ctzw(args) => zbb::ctzw(args, &mut self.registers.x),
+            Cpop(args) => zbb::cpop(args, &mut self.registers.x),
+            Cpopw(args) => zbb::cpopw(args, &mut self.registers.x),
+            Max(args) => zbb::max(args, &mut self.registers.x),
+            Maxu(args) => zbb::maxu(args, &mut self.registers.x),
+            Min(args) => zbb::min(args, &mut self.registers.x),
+            Minu(args) => zbb::minu(args, &mut self.registers.x),
+            Sextb(args) => zbb::sextb(args, &mut self.registers.x),
+            Sexth(args) => zbb::sexth(args, &mut self.registers.x),
+            Zexth(args) => zbb::zexth(args, &mut self.registers.x),
+            Rol(args) => zbb::rol(args, &mut self.registers.x),
+            Rolw(args) => zbb::rolw(args, &mut self.registers.x),
+            Ror(args) => zbb::ror(args, &mut self.registers.x),
+            Rori(args) => zbb::rori(args, &mut self.registers.x),
+            Roriw(args) => zbb::roriw(args, &mut self.registers.x),
+            Rorw(args) => zbb::rorw(args, &mut self.registers.x),
+            Orcb(args) => zbb::orcb(args, &mut self.registers.x),
+            Rev8(args) => zbb::rev8(args, &mut self.registers.x),
+
+            Bclr(args) => zbs::bclr(args, &mut self.registers.x),
+            Bclri(args) => zbs::bclri(args, &mut self.registers.x),
+            Bext(args) => zbs::bext(args, &mut self.registers.x),
+            Bexti(args) => zbs::bexti(args, &mut self.registers.x),
+            Binv(args) => zbs::binv(args, &mut self.registers.x),
+            Binvi(args) => zbs::binvi(args, &mut self.registers.x),
+            Bset(args) => zbs::bset(args, &mut self.registers.x),
+            Bseti(args) => zbs::bseti(args, &mut self.registers.x),
+
+            // Only control transfers depend on the instruction size, the rest run in their expanded form
+            Compressed(instruction, _) => match *instruction {
+                Jal(args) => c::jal(args, &mut self.registers.x, &mut self.registers.pc),
+                Jalr(args) => c::jalr(args, &mut self.registers.x, &mut self.registers.pc),
+                Beq(args) => c::beq(args, &self.registers.x, &mut self.registers.pc),
+                Bne(args) => c::bne(args, &self.registers.x, &mut self.registers.pc),
+                instruction => self.execute_operation(instruction)?,
+            },
 
             _ => self.vector_execute(input)?,
         };
 
-        let current_instret = self.registers.c[INSTRET].read();
-        unsafe {
-            self.registers.c[INSTRET].set(current_instret + 1);
+        Ok(())
+    }
+
+    /// Vector instructions are illegal while vill is set, or when they use elements wider than the extension supports.
+    /// Element widths are taken from the instruction: EEW of loads and stores, SEW or 2 * SEW of arithmetic.
+    fn check_vector_support(&self, input: &Instruction) -> Result<(), Exception> {
+        use Instruction::*;
+
+        // Whole register loads, stores and moves don't depend on vtype
+        let whole_register = matches!(
+            input,
+            Vlrv { .. } | Vsrv { .. } | Vmv1rv(_) | Vmv2rv(_) | Vmv4rv(_) | Vmv8rv(_)
+        );
+
+        if !whole_register && self.registers.c[VTYPE].read() & VILL != 0 {
+            return Err(Exception::IllegalInstruction(0));
         }
-        self.registers.pc = self.registers.pc.wrapping_add(4);
 
-        Ok(())
+        let sew = self.vec_engine.sew.bit_length();
+
+        match input {
+            Vlv { eew, .. }
+            | Vsv { eew, .. }
+            | Vlsv { eew, .. }
+            | Vssv { eew, .. }
+            | Vluxv { eew, .. }
+            | Vloxv { eew, .. }
+            | Vsuxv { eew, .. }
+            | Vsoxv { eew, .. }
+            | Vlffv { eew, .. }
+            | Vlsegv { eew, .. }
+            | Vlsegffv { eew, .. }
+            | Vssegv { eew, .. }
+            | Vlssegv { eew, .. }
+            | Vsssegv { eew, .. }
+            | Vluxsegv { eew, .. }
+            | Vloxsegv { eew, .. }
+            | Vsuxsegv { eew, .. }
+            | Vsoxsegv { eew, .. }
+            | Vlrv { eew, .. } => self.vec_engine.check_eew(eew.bit_length(), false),
+            Vlmv(_) | Vsmv(_) | Vsrv { .. } => self.vec_engine.check_eew(8, false),
+            _ => {
+                let eew = if is_widening(input) { 2 * sew } else { sew };
+
+                self.vec_engine.check_eew(eew, is_floating_point(input))
+            }
+        }
     }
 
-    fn vector_execute(&mut self, input: Instruction) -> Result<(), String> {
+    fn vector_execute(&mut self, input: Instruction) -> Result<(), Exception> {
         use Instruction::*;
 
+        if !matches!(input, Vsetvli(_) | Vsetivli(_) | Vsetvl(_)) {
+            self.check_vector_support(&input)?;
+        }
+
         let mut vctx = VectorContext {
             v: &mut self.registers.v,
             csr: &mut self.registers.c,
             vec_engine: self.vec_engine,
+            masked: Cell::new(false),
         };
 
         match input {
@@ -218,84 +625,95 @@ impl<'c> Executor<'c> {
             Vsetvl(args) => v::vsetvl(args, &mut self.registers.x, &mut vctx),
 
             Vlv { data: args, eew } => {
-                v::vl::v(args, eew, &self.registers.x, &mut vctx, self.memory)?
+                v::vl::v(args, eew, &self.registers.x, &mut vctx, &self.mmu)?
+            }
+            Vsv { data: args, eew } => {
+                v::vs::v(args, eew, &self.registers.x, &mut vctx, &mut self.mmu)?
             }
-            Vsv { data: args, eew } => v::vs::v(args, eew, &self.registers.x, &vctx, self.memory),
 
-            Vlmv(args) => v::vlm::v(args, &mut vctx, &self.registers.x, self.memory),
-            Vsmv(args) => v::vsm::v(args, &vctx, &self.registers.x, self.memory),
+            Vlmv(args) => v::vlm::v(args, &mut vctx, &self.registers.x, &self.mmu)?,
+            Vsmv(args) => v::vsm::v(args, &mut vctx, &self.registers.x, &mut self.mmu)?,
 
             Vlsv { data: args, eew } => {
-                v::vls::v(args, eew, &self.registers.x, &mut vctx, self.memory)
+                v::vls::v(args, eew, &self.registers.x, &mut vctx, &self.mmu)?
+            }
+            Vssv { data: args, eew } => {
+                v::vss::v(args, eew, &mut vctx, &self.registers.x, &mut self.mmu)?
             }
-            Vssv { data: args, eew } => v::vss::v(args, eew, &vctx, &self.registers.x, self.memory),
 
             Vluxv { data: args, eew } => {
-                v::vlux::v(args, eew, &mut vctx, &self.registers.x, self.memory)
+                v::vlux::v(args, eew, &mut vctx, &self.registers.x, &self.mmu)?
             }
             Vloxv { data: args, eew } => {
-                v::vlox::v(args, eew, &mut vctx, &self.registers.x, self.memory)
+                v::vlox::v(args, eew, &mut vctx, &self.registers.x, &self.mmu)?
             }
             Vsuxv { data: args, eew } => {
-                v::vsux::v(args, eew, &vctx, &self.registers.x, self.memory)
+                v::vsux::v(args, eew, &mut vctx, &self.registers.x, &mut self.mmu)?
             }
             Vsoxv { data: args, eew } => {
-                v::vsox::v(args, eew, &vctx, &self.registers.x, self.memory)
+                v::vsox::v(args, eew, &mut vctx, &self.registers.x, &mut self.mmu)?
             }
 
             Vlffv { data: args, eew } => {
-                v::vlff::v(args, eew, &mut vctx, &self.registers.x, self.memory)?
+                v::vlff::v(args, eew, &mut vctx, &self.registers.x, &self.mmu)?
             }
 
             Vlsegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vlseg::v(args, eew, nf, &mut vctx, &self.registers.x, self.memory),
+            } => v::vlseg::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
+            Vlsegffv {
+                data: args,
+                eew,
+                nf,
+            } => v::vlsegff::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
             Vssegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vsseg::v(args, eew, nf, &vctx, &self.registers.x, self.memory),
+            } => v::vsseg::v(args, eew, nf, &mut vctx, &self.registers.x, &mut self.mmu)?,
 
             Vlssegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vlsseg::v(args, eew, nf, &mut vctx, &self.registers.x, self.memory),
+            } => v::vlsseg::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
             Vsssegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vssseg::v(args, eew, nf, &vctx, &self.registers.x, self.memory),
+            } => v::vssseg::v(args, eew, nf, &mut vctx, &self.registers.x, &mut self.mmu)?,
 
             Vluxsegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vluxseg::v(args, eew, nf, &mut vctx, &self.registers.x, self.memory),
+            } => v::vluxseg::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
             Vloxsegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vloxseg::v(args, eew, nf, &mut vctx, &self.registers.x, self.memory),
+            } => v::vloxseg::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
             Vsuxsegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vsuxseg::v(args, eew, nf, &vctx, &self.registers.x, self.memory),
+            } => v::vsuxseg::v(args, eew, nf, &mut vctx, &self.registers.x, &mut self.mmu)?,
             Vsoxsegv {
                 data: args,
                 eew,
                 nf,
-            } => v::vsoxseg::v(args, eew, nf, &vctx, &self.registers.x, self.memory),
+            } => v::vsoxseg::v(args, eew, nf, &mut vctx, &self.registers.x, &mut self.mmu)?,
 
             Vlrv {
                 data: args,
-                eew: _,
+                eew,
                 nf,
-            } => v::vlr::v(args, nf, &mut vctx, &self.registers.x, self.memory),
-            Vsrv { data: args, nf } => v::vsr::v(args, nf, &vctx, &self.registers.x, self.memory),
+            } => v::vlr::v(args, eew, nf, &mut vctx, &self.registers.x, &self.mmu)?,
+            Vsrv { data: args, nf } => {
+                v::vsr::v(args, nf, &mut vctx, &self.registers.x, &mut self.mmu)?
+            }
 
             Vaddvv(args) => v::vadd::vv(args, &mut vctx),
             Vaddvx(args) => v::vadd::vx(args, &mut vctx, &self.registers.x),
@@ -450,13 +868,13 @@ impl<'c> Executor<'c> {
             Vnsrawx(args) => v::vnsra::wx(args, &mut vctx, &self.registers.x)?,
             Vnsrawi(args) => v::vnsra::wi(args, &mut vctx)?,
 
-            Vnclipuwv(args) => v::vnclipu::wv(args, &mut vctx),
-            Vnclipuwx(args) => v::vnclipu::wx(args, &mut vctx, &self.registers.x),
-            Vnclipuwi(args) => v::vnclipu::wi(args, &mut vctx),
+            Vnclipuwv(args) => v::vnclipu::wv(args, &mut vctx)?,
+            Vnclipuwx(args) => v::vnclipu::wx(args, &mut vctx, &self.registers.x)?,
+            Vnclipuwi(args) => v::vnclipu::wi(args, &mut vctx)?,
 
-            Vnclipwv(args) => v::vnclip::wv(args, &mut vctx),
-            Vnclipwx(args) => v::vnclip::wx(args, &mut vctx, &self.registers.x),
-            Vnclipwi(args) => v::vnclip::wi(args, &mut vctx),
+            Vnclipwv(args) => v::vnclip::wv(args, &mut vctx)?,
+            Vnclipwx(args) => v::vnclip::wx(args, &mut vctx, &self.registers.x)?,
+            Vnclipwi(args) => v::vnclip::wi(args, &mut vctx)?,
 
             Vwredsumuvs(args) => v::vwredsumu::vs(args, &mut vctx)?,
             Vwredsumvs(args) => v::vwredsum::vs(args, &mut vctx)?,
@@ -749,11 +1167,197 @@ impl<'c> Executor<'c> {
             _ => unreachable!(),
         };
 
+        // A trapping instruction leaves vstart at the element it stopped on
+        unsafe { self.registers.c[VSTART].set(0) };
+
         Ok(())
     }
 }
 
+/// Widening ops, and narrowing shifts and clips, read or write 2 * SEW elements
+fn is_widening(input: &Instruction) -> bool {
+    use Instruction::*;
+
+    matches!(
+        input,
+        Vnsrlwv(_)
+            | Vnsrlwx(_)
+            | Vnsrlwi(_)
+            | Vnsrawv(_)
+            | Vnsrawx(_)
+            | Vnsrawi(_)
+            | Vnclipuwv(_)
+            | Vnclipuwx(_)
+            | Vnclipuwi(_)
+            | Vnclipwv(_)
+            | Vnclipwx(_)
+            | Vnclipwi(_)
+            | Vwredsumuvs(_)
+            | Vwredsumvs(_)
+            | Vwadduvv(_)
+            | Vwadduvx(_)
+            | Vwaddvv(_)
+            | Vwaddvx(_)
+            | Vwsubuvv(_)
+            | Vwsubuvx(_)
+            | Vwsubvv(_)
+            | Vwsubvx(_)
+            | Vwadduwv(_)
+            | Vwadduwx(_)
+            | Vwaddwv(_)
+            | Vwaddwx(_)
+            | Vwsubuwv(_)
+            | Vwsubuwx(_)
+            | Vwsubwv(_)
+            | Vwsubwx(_)
+            | Vwmuluvv(_)
+            | Vwmuluvx(_)
+            | Vwmulsuvv(_)
+            | Vwmulsuvx(_)
+            | Vwmulvv(_)
+            | Vwmulvx(_)
+            | Vwmaccuvv(_)
+            | Vwmaccuvx(_)
+            | Vwmaccvv(_)
+            | Vwmaccvx(_)
+            | Vwmaccusvx(_)
+            | Vwmaccsuvv(_)
+            | Vwmaccsuvx(_)
+            | Vfwaddvv(_)
+            | Vfwaddvf(_)
+            | Vfwredusumvs(_)
+            | Vfwsubvv(_)
+            | Vfwsubvf(_)
+            | Vfwredosumvs(_)
+            | Vfwaddwv(_)
+            | Vfwaddwf(_)
+            | Vfwsubwv(_)
+            | Vfwsubwf(_)
+            | Vfwmulvv(_)
+            | Vfwmulvf(_)
+            | Vfwmaccvv(_)
+            | Vfwmaccvf(_)
+            | Vfwnmaccvv(_)
+            | Vfwnmaccvf(_)
+            | Vfwmsacvv(_)
+            | Vfwmsacvf(_)
+            | Vfwnmsacvv(_)
+            | Vfwnmsacvf(_)
+    )
+}
+
+/// OPFVV and OPFVF instructions, their elements are checked against the floating point ELEN
+fn is_floating_point(input: &Instruction) -> bool {
+    use Instruction::*;
+
+    matches!(
+        input,
+        Vfaddvv(_)
+            | Vfaddvf(_)
+            | Vfredusumvs(_)
+            | Vfsubvv(_)
+            | Vfsubvf(_)
+            | Vfredosumvs(_)
+            | Vfminvv(_)
+            | Vfminvf(_)
+            | Vfredminvs(_)
+            | Vfmaxvv(_)
+            | Vfmaxvf(_)
+            | Vfredmaxvs(_)
+            | Vfsgnjvv(_)
+            | Vfsgnjvf(_)
+            | Vfsgnjnvv(_)
+            | Vfsgnjnvf(_)
+            | Vfsgnjxvv(_)
+            | Vfsgnjxvf(_)
+            | Vfslide1upvf(_)
+            | Vfslide1downvf(_)
+            | Vfmvfs(_)
+            | Vfmvsf(_)
+            | Vfcvtxufv(_)
+            | Vfcvtxfv(_)
+            | Vfcvtfxuv(_)
+            | Vfcvtfxv(_)
+            | VfcvtRtzxufv(_)
+            | VfcvtRtzxfv(_)
+            | Vfwcvtxufv(_)
+            | Vfwcvtxfv(_)
+            | Vfwcvtfxuv(_)
+            | Vfwcvtfxv(_)
+            | Vfwcvtffv(_)
+            | VfwcvtRtzxufv(_)
+            | VfwcvtRtzxfv(_)
+            | Vfncvtxufw(_)
+            | Vfncvtxfw(_)
+            | Vfncvtfxuw(_)
+            | Vfncvtfxw(_)
+            | Vfncvtffw(_)
+            | VfncvtRodffw(_)
+            | VfncvtRtzxufw(_)
+            | VfncvtRtzxfw(_)
+            | Vfsqrtv(_)
+            | Vfrsqrt7v(_)
+            | Vfrec7v(_)
+            | Vfclassv(_)
+            | Vfmergevfm(_)
+            | Vfmvvf(_)
+            | Vmfeqvv(_)
+            | Vmfeqvf(_)
+            | Vmflevv(_)
+            | Vmflevf(_)
+            | Vmfltvv(_)
+            | Vmfltvf(_)
+            | Vmfnevv(_)
+            | Vmfnevf(_)
+            | Vmfgtvf(_)
+            | Vmfgevf(_)
+            | Vfdivvv(_)
+            | Vfdivvf(_)
+            | Vfrdivvf(_)
+            | Vfmulvv(_)
+            | Vfmulvf(_)
+            | Vfrsubvf(_)
+            | Vfmaddvv(_)
+            | Vfmaddvf(_)
+            | Vfnmaddvv(_)
+            | Vfnmaddvf(_)
+            | Vfmsubvv(_)
+            | Vfmsubvf(_)
+            | Vfnmsubvv(_)
+            | Vfnmsubvf(_)
+            | Vfmaccvv(_)
+            | Vfmaccvf(_)
+            | Vfnmaccvv(_)
+            | Vfnmaccvf(_)
+            | Vfmsacvv(_)
+            | Vfmsacvf(_)
+            | Vfnmsacvv(_)
+            | Vfnmsacvf(_)
+            | Vfwaddvv(_)
+            | Vfwaddvf(_)
+            | Vfwredusumvs(_)
+            | Vfwsubvv(_)
+            | Vfwsubvf(_)
+            | Vfwredosumvs(_)
+            | Vfwaddwv(_)
+            | Vfwaddwf(_)
+            | Vfwsubwv(_)
+            | Vfwsubwf(_)
+            | Vfwmulvv(_)
+            | Vfwmulvf(_)
+            | Vfwmaccvv(_)
+            | Vfwmaccvf(_)
+            | Vfwnmaccvv(_)
+            | Vfwnmaccvf(_)
+            | Vfwmsacvv(_)
+            | Vfwmsacvf(_)
+            | Vfwnmsacvv(_)
+            | Vfwnmsacvf(_)
+    )
+}
+
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use crate::rv_core::{instruction::format, RvCoreBuilder};
 
@@ -791,7 +1395,8 @@ mod tests {
             }),
         ];
 
-        let mut core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         for _ in core.run() {}
     }
@@ -837,13 +1442,13 @@ mod tests {
                 eew: BaseSew::E8,
             },
             Vmseqvi(Opivi {
-                vd: 0,
+                dest: 0,
                 imm5: 0,
                 vs2: 8,
                 vm: false,
             }),
             Vmsnevv(Opivv {
-                vd: 1,
+                dest: 1,
                 vs1: 16,
                 vs2: 8,
                 vm: false,
@@ -902,7 +1507,8 @@ mod tests {
             }),
         ];
 
-        let _core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         // for _ in core.run() {}
     }
@@ -979,7 +1585,8 @@ mod tests {
             }),
         ];
 
-        let _core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         // for _ in core.run() {}
     }
@@ -1005,7 +1612,8 @@ mod tests {
             }),
         ];
 
-        let _core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         // for _ in core.run() {}
     }
@@ -1041,7 +1649,8 @@ mod tests {
             }),
         ];
 
-        let mut core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         println!("{:?}", core.step());
         println!("{:?}", core.step());
@@ -1127,7 +1736,8 @@ mod tests {
             }),
         ];
 
-        let mut core = RvCoreBuilder::default().instructions(instructions).build();
+        let mut core = RvCoreBuilder::default().build();
+        core.load_program(&instructions).unwrap();
 
         core.step();
         core.step();
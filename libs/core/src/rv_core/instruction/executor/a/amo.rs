@@ -0,0 +1,53 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+/// Stores `op(loaded, rs2)` and writes the loaded value to rd.
+/// Faults are reported as store faults, since the memory has to be writable for an AMO.
+pub fn w(
+    Atomic { rd, rs1, rs2, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    mem: &mut Mmu,
+    op: impl Fn(u32, u32) -> u32,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(4) {
+        return Err(Exception::StoreAddressMisaligned(addr));
+    }
+
+    mem.translate(addr, Access::Store)?;
+
+    let loaded = u32::from_le_bytes(
+        mem.get(addr as usize)
+            .map_err(|_| Exception::StoreAccessFault(addr))?,
+    );
+    mem.set(addr as usize, op(loaded, x[rs2] as u32).to_le_bytes())?;
+
+    x[rd] = loaded as i32 as u64;
+
+    Ok(())
+}
+
+pub fn d(
+    Atomic { rd, rs1, rs2, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    mem: &mut Mmu,
+    op: impl Fn(u64, u64) -> u64,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(8) {
+        return Err(Exception::StoreAddressMisaligned(addr));
+    }
+
+    mem.translate(addr, Access::Store)?;
+
+    let loaded = u64::from_le_bytes(
+        mem.get(addr as usize)
+            .map_err(|_| Exception::StoreAccessFault(addr))?,
+    );
+    mem.set(addr as usize, op(loaded, x[rs2]).to_le_bytes())?;
+
+    x[rd] = loaded;
+
+    Ok(())
+}
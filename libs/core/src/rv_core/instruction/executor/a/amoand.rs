@@ -0,0 +1,10 @@
+use super::amo;
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn w(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::w(args, x, mem, |loaded, operand| loaded & operand)
+}
+
+pub fn d(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::d(args, x, mem, |loaded, operand| loaded & operand)
+}
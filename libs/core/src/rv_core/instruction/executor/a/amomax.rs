@@ -0,0 +1,14 @@
+use super::amo;
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn w(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::w(args, x, mem, |loaded, operand| {
+        (loaded as i32).max(operand as i32) as u32
+    })
+}
+
+pub fn d(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::d(args, x, mem, |loaded, operand| {
+        (loaded as i64).max(operand as i64) as u64
+    })
+}
@@ -0,0 +1,48 @@
+use super::amo;
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn w(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::w(args, x, mem, |loaded, operand| {
+        (loaded as i32).min(operand as i32) as u32
+    })
+}
+
+pub fn d(args: Atomic, x: &mut IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
+    amo::d(args, x, mem, |loaded, operand| {
+        (loaded as i64).min(operand as i64) as u64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amomin_is_signed() {
+        let mut memory = Memory::default();
+        let mut mem = Mmu::bare(&mut memory);
+        let mut x = IntegerRegisters::new(&Memory::default());
+
+        let args = Atomic {
+            rd: A0,
+            rs1: A1,
+            rs2: A2,
+            aq: true,
+            rl: true,
+        };
+
+        mem.set(0x20, 5u32.to_le_bytes()).unwrap();
+        x[A1] = 0x20;
+        x[A2] = -3i64 as u64;
+
+        w(args.clone(), &mut x, &mut mem).unwrap();
+        assert_eq!(x[A0], 5);
+        assert_eq!(mem.get(0x20).unwrap(), (-3i32).to_le_bytes());
+
+        x[A2] = 7;
+
+        w(args, &mut x, &mut mem).unwrap();
+        assert_eq!(x[A0], -3i64 as u64);
+        assert_eq!(mem.get(0x20).unwrap(), (-3i32).to_le_bytes());
+    }
+}
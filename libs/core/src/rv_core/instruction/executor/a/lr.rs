@@ -0,0 +1,37 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn w(
+    Atomic { rd, rs1, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    reservation: &mut Option<u64>,
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(4) {
+        return Err(Exception::LoadAddressMisaligned(addr));
+    }
+
+    x[rd] = i32::from_le_bytes(mem.get(addr as usize)?) as u64;
+    *reservation = Some(addr);
+
+    Ok(())
+}
+
+pub fn d(
+    Atomic { rd, rs1, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    reservation: &mut Option<u64>,
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(8) {
+        return Err(Exception::LoadAddressMisaligned(addr));
+    }
+
+    x[rd] = u64::from_le_bytes(mem.get(addr as usize)?);
+    *reservation = Some(addr);
+
+    Ok(())
+}
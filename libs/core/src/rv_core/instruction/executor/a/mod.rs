@@ -0,0 +1,12 @@
+mod amo;
+pub mod amoadd;
+pub mod amoand;
+pub mod amomax;
+pub mod amomaxu;
+pub mod amomin;
+pub mod amominu;
+pub mod amoor;
+pub mod amoswap;
+pub mod amoxor;
+pub mod lr;
+pub mod sc;
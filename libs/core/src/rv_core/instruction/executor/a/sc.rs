@@ -0,0 +1,96 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+/// Writes 0 to rd on success and 1 when the address isn't reserved, in which case memory is left untouched
+pub fn w(
+    Atomic { rd, rs1, rs2, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    reservation: &mut Option<u64>,
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(4) {
+        return Err(Exception::StoreAddressMisaligned(addr));
+    }
+
+    let reserved = reservation.take() == Some(addr);
+
+    if reserved {
+        mem.set(addr as usize, (x[rs2] as u32).to_le_bytes())?;
+    }
+
+    x[rd] = !reserved as u64;
+
+    Ok(())
+}
+
+pub fn d(
+    Atomic { rd, rs1, rs2, .. }: Atomic,
+    x: &mut IntegerRegisters,
+    reservation: &mut Option<u64>,
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1];
+
+    if !addr.is_multiple_of(8) {
+        return Err(Exception::StoreAddressMisaligned(addr));
+    }
+
+    let reserved = reservation.take() == Some(addr);
+
+    if reserved {
+        mem.set(addr as usize, x[rs2].to_le_bytes())?;
+    }
+
+    x[rd] = !reserved as u64;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::instruction::executor::a::lr;
+
+    #[test]
+    fn reservation_works() {
+        let mut memory = Memory::default();
+        let mut mem = Mmu::bare(&mut memory);
+        let mut x = IntegerRegisters::new(&Memory::default());
+        let mut reservation = None;
+
+        let args = Atomic {
+            rd: A0,
+            rs1: A1,
+            rs2: A2,
+            aq: false,
+            rl: false,
+        };
+
+        x[A1] = 0x10;
+        x[A2] = 0xdead_beef;
+
+        // No reservation yet
+        w(args.clone(), &mut x, &mut reservation, &mut mem).unwrap();
+        assert_eq!(x[A0], 1);
+        assert_eq!(mem.get::<4>(0x10).unwrap(), [0; 4]);
+
+        lr::w(args.clone(), &mut x, &mut reservation, &mem).unwrap();
+        assert_eq!(reservation, Some(0x10));
+
+        x[A0] = 0x1234;
+        w(args.clone(), &mut x, &mut reservation, &mut mem).unwrap();
+        assert_eq!(x[A0], 0);
+        assert_eq!(mem.get(0x10).unwrap(), 0xdead_beef_u32.to_le_bytes());
+
+        // Reservation is consumed by the first store conditional
+        w(args.clone(), &mut x, &mut reservation, &mut mem).unwrap();
+        assert_eq!(x[A0], 1);
+
+        x[A1] = 0x12;
+        assert_eq!(
+            w(args, &mut x, &mut reservation, &mut mem),
+            Err(Exception::StoreAddressMisaligned(0x12))
+        );
+    }
+}
@@ -0,0 +1,5 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn ebreak() -> Result<(), Exception> {
+    Err(Exception::Breakpoint)
+}
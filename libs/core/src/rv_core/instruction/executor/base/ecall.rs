@@ -0,0 +1,6 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+/// Always traps, the call is serviced by the environment handler or the trap handler
+pub fn ecall(mode: PrivilegeMode) -> Result<(), Exception> {
+    Err(Exception::EnvironmentCall(mode))
+}
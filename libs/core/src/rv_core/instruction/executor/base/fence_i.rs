@@ -0,0 +1,2 @@
+/// Decode cache compares fetched words with the ones it decoded, so there's nothing to flush
+pub fn fence_i() {}
@@ -1,7 +1,7 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn jal(U { rd, imm20 }: U, x: &mut IntegerRegisters, pc: &mut u64) {
-    x[rd] = *pc;
+    x[rd] = pc.wrapping_add(4);
     *pc = pc.wrapping_add(imm20 as u64).wrapping_sub(4);
 }
 
@@ -16,7 +16,7 @@ mod tests {
 
         jal(U { rd: 5, imm20: -12 }, &mut x, &mut pc);
 
-        assert_eq!(x[5], 8);
+        assert_eq!(x[5], 12);
         assert_eq!(pc, -8_i64 as u64);
     }
 }
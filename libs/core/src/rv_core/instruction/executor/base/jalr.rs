@@ -1,8 +1,10 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn jalr(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters, pc: &mut u64) {
-    x[rd] = *pc;
-    *pc = x[rs1].wrapping_add(imm12 as u64).wrapping_sub(4) & !1;
+    let target = x[rs1].wrapping_add(imm12 as u64) & !1;
+
+    x[rd] = pc.wrapping_add(4);
+    *pc = target.wrapping_sub(4);
 }
 
 #[cfg(test)]
@@ -25,7 +27,7 @@ mod tests {
             &mut pc,
         );
 
-        assert_eq!(x[5], 8);
+        assert_eq!(x[5], 12);
         assert_eq!(pc, 8);
     }
 }
@@ -1,8 +1,10 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn lh(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters, mem: &Memory) {
+pub fn lh(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters, mem: &Mmu) -> Result<(), Exception> {
     let addr = x[rs1].wrapping_add(imm12 as u64);
-    let int = i16::from_le_bytes(mem.get(addr as usize));
+    let int = i16::from_le_bytes(mem.get(addr as usize)?);
 
     x[rd] = int as u64;
+
+    Ok(())
 }
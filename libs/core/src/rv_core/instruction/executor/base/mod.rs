@@ -11,6 +11,10 @@ mod bgeu;
 mod blt;
 mod bltu;
 mod bne;
+mod ebreak;
+mod ecall;
+mod fence;
+mod fence_i;
 mod jal;
 mod jalr;
 mod lb;
@@ -61,6 +65,10 @@ pub use bgeu::bgeu;
 pub use blt::blt;
 pub use bltu::bltu;
 pub use bne::bne;
+pub use ebreak::ebreak;
+pub use ecall::ecall;
+pub use fence::fence;
+pub use fence_i::fence_i;
 pub use jal::jal;
 pub use jalr::jalr;
 pub use lb::lb;
@@ -1,8 +1,8 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn sd(S { rs1, rs2, imm12 }: S, x: &IntegerRegisters, mem: &mut Memory) {
+pub fn sd(S { rs1, rs2, imm12 }: S, x: &IntegerRegisters, mem: &mut Mmu) -> Result<(), Exception> {
     let addr = x[rs1].wrapping_add(imm12 as u64);
     let bytes = x[rs2].to_le_bytes();
 
-    mem.set(addr as usize, bytes);
+    mem.set(addr as usize, bytes)
 }
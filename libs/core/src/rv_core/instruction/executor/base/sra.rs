@@ -2,6 +2,6 @@ use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn sra(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
     let rs1 = x[rs1] as i64;
-    let shamt = x[rs2] & 0b11111;
+    let shamt = x[rs2] & 0b111111;
     x[rd] = (rs1 >> shamt) as u64;
 }
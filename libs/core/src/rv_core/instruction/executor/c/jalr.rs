@@ -0,0 +1,33 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn jalr(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters, pc: &mut u64) {
+    let target = x[rs1].wrapping_add(imm12 as u64) & !1;
+
+    x[rd] = pc.wrapping_add(2);
+    *pc = target.wrapping_sub(2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jalr_links_next_halfword() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        let mut pc = 6;
+        x[10] = 16;
+
+        jalr(
+            I {
+                rd: 1,
+                rs1: 10,
+                imm12: 0,
+            },
+            &mut x,
+            &mut pc,
+        );
+
+        assert_eq!(x[1], 8);
+        assert_eq!(pc, 14);
+    }
+}
@@ -0,0 +1,9 @@
+mod beq;
+mod bne;
+mod jal;
+mod jalr;
+
+pub use beq::beq;
+pub use bne::bne;
+pub use jal::jal;
+pub use jalr::jalr;
@@ -1,46 +1,170 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn sd(R { rd, rs1, rs2: _ }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
+pub fn sd(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::convert(f.double(rs1), c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, float);
 
-    f[rd] = fs1 as f64;
+    Ok(())
 }
 
-pub fn ds(R { rd, rs1, rs2: _ }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (_, rest) = decompose(f[rd]);
+/// Widening is exact, only NaNs are affected
+pub fn ds(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm: _,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let fs1 = f.single(rs1);
 
-    f[rd] = compose(fs1, rest);
+    let (float, flags) = softfloat::convert(fs1, softfloat::RoundingMode::Rne);
+    c.accrue(flags);
+    f.set_double(rd, float);
 }
 
-pub fn wd(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    x[rd] = unsafe { f[rs1].to_int_unchecked::<i32>() as u64 };
+pub fn wd(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (integer, flags) = softfloat::to_int(f.double(rs1), true, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn wud(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    x[rd] = unsafe { f[rs1].to_int_unchecked::<u32>() as i32 as u64 };
+pub fn wud(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (integer, flags) = softfloat::to_int(f.double(rs1), false, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn dw(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    f[rd] = x[rs1] as i32 as f64;
+pub fn dw(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm: _,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+) {
+    f.set_double(rd, x[rs1] as i32 as f64);
 }
 
-pub fn dwu(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    f[rd] = x[rs1] as u32 as f64;
+pub fn dwu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm: _,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+) {
+    f.set_double(rd, x[rs1] as u32 as f64);
 }
 
-pub fn ld(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    x[rd] = unsafe { f[rs1].to_int_unchecked::<i64>() as u64 };
+pub fn ld(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (integer, flags) = softfloat::to_int(f.double(rs1), true, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn lud(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    x[rd] = unsafe { f[rs1].to_int_unchecked() };
+pub fn lud(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (integer, flags) = softfloat::to_int(f.double(rs1), false, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn dl(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    f[rd] = x[rs1] as i64 as f64;
+pub fn dl(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_i64(x[rs1] as i64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_double(rd, float);
+
+    Ok(())
 }
 
-pub fn dlu(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    f[rd] = x[rs1] as f64;
+pub fn dlu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_u64(x[rs1], c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_double(rd, float);
+
+    Ok(())
 }
@@ -1,5 +1,13 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn d(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    f[rd] = f[rs1] / f[rs2];
+pub fn d(
+    Rfp { rd, rs1, rs2, rm }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (quotient, flags) = softfloat::div(f.double(rs1), f.double(rs2), c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_double(rd, quotient);
+
+    Ok(())
 }
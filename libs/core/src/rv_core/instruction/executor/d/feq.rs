@@ -1,5 +1,12 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn d(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    x[rd] = if f[rs1] == f[rs2] { 1 } else { 0 };
+pub fn d(
+    R { rd, rs1, rs2 }: R,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let (holds, flags) = softfloat::eq(f.double(rs1), f.double(rs2));
+    c.accrue(flags);
+    x[rd] = holds as u64;
 }
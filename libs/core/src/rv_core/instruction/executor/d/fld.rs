@@ -1,8 +1,15 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn fld(I { rd, rs1, imm12 }: I, x: &IntegerRegisters, f: &mut FloatRegisters, mem: &Memory) {
+pub fn fld(
+    I { rd, rs1, imm12 }: I,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    mem: &Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1].wrapping_add(imm12 as u64);
-    let fp = f64::from_le_bytes(mem.get(addr as usize));
+    let fp = f64::from_le_bytes(mem.get(addr as usize)?);
 
-    f[rd] = fp;
+    f.set_double(rd, fp);
+
+    Ok(())
 }
@@ -1,5 +1,7 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn d(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    f[rd] = if f[rs1] > f[rs2] { f[rs1] } else { f[rs2] };
+pub fn d(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters, c: &mut CsrRegisters) {
+    let (max, flags) = softfloat::max(f.double(rs1), f.double(rs2));
+    c.accrue(flags);
+    f.set_double(rd, max);
 }
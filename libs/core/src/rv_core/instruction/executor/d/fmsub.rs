@@ -1,5 +1,24 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn d(R4 { rd, rs1, rs2, rs3 }: R4, f: &mut FloatRegisters) {
-    f[rd] = f[rs1] * f[rs2] - f[rs3];
+pub fn d(
+    R4 {
+        rd,
+        rs1,
+        rs2,
+        rs3,
+        rm,
+    }: R4,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (result, flags) = softfloat::mul_add(
+        f.double(rs1),
+        f.double(rs2),
+        -f.double(rs3),
+        c.rounding_mode(rm)?,
+    );
+    c.accrue(flags);
+    f.set_double(rd, result);
+
+    Ok(())
 }
@@ -1,12 +1,14 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn d(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    let value = match (f[rs1].is_sign_positive(), f[rs2].is_sign_positive()) {
-        (true, true) => f[rs1],
-        (true, false) => -f[rs1],
-        (false, true) => -f[rs1],
-        (false, false) => f[rs1],
+    let (fs1, fs2) = (f.double(rs1), f.double(rs2));
+
+    let value = match (fs1.is_sign_positive(), fs2.is_sign_positive()) {
+        (true, true) => fs1,
+        (true, false) => -fs1,
+        (false, true) => -fs1,
+        (false, false) => fs1,
     };
 
-    f[rd] = value;
+    f.set_double(rd, value);
 }
@@ -1,5 +1,18 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn d(R { rd, rs1, rs2: _ }: R, f: &mut FloatRegisters) {
-    f[rd] = f[rs1].sqrt();
+pub fn d(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (root, flags) = softfloat::sqrt(f.double(rs1), c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_double(rd, root);
+
+    Ok(())
 }
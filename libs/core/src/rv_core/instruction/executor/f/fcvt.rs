@@ -1,49 +1,153 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn ws(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-
-    x[rd] = unsafe { fs1.to_int_unchecked::<i32>() as u64 };
+pub fn ws(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, true, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn wus(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-
-    x[rd] = unsafe { fs1.to_int_unchecked::<u32>() as u64 };
+pub fn wus(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, false, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn sw(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(x[rs1] as i32 as f32, rest);
+pub fn sw(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_i64(x[rs1] as i32 as i64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, float);
+
+    Ok(())
 }
 
-pub fn swu(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(x[rs1] as u32 as f32, rest);
+pub fn swu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_u64(x[rs1] as u32 as u64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, float);
+
+    Ok(())
 }
 
-pub fn ls(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-
-    x[rd] = unsafe { fs1.to_int_unchecked::<i64>() as u64 };
+pub fn ls(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, true, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn lus(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-
-    x[rd] = unsafe { fs1.to_int_unchecked() };
+pub fn lus(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, false, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
 }
 
-pub fn sl(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(x[rs1] as i64 as f32, rest);
+pub fn sl(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_i64(x[rs1] as i64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, float);
+
+    Ok(())
 }
 
-pub fn slu(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(x[rs1] as f32, rest);
+pub fn slu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_u64(x[rs1], c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, float);
+
+    Ok(())
 }
@@ -1,8 +1,15 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn s(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (fs2, _) = decompose(f[rs2]);
+pub fn s(
+    R { rd, rs1, rs2 }: R,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let fs1 = f.single(rs1);
+    let fs2 = f.single(rs2);
 
-    x[rd] = if fs1 == fs2 { 1 } else { 0 };
+    let (holds, flags) = softfloat::eq(fs1, fs2);
+    c.accrue(flags);
+    x[rd] = holds as u64;
 }
@@ -1,21 +1,27 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn flw(I { rd, rs1, imm12 }: I, x: &IntegerRegisters, f: &mut FloatRegisters, mem: &Memory) {
+pub fn flw(
+    I { rd, rs1, imm12 }: I,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    mem: &Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1].wrapping_add(imm12 as u64);
-    let fp = f32::from_le_bytes(mem.get(addr as usize));
+    let fp = f32::from_le_bytes(mem.get(addr as usize)?);
 
-    let (_, rest) = decompose(f[rd]);
+    f.set_single(rd, fp);
 
-    f[rd] = compose(fp, rest);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rv_core::registers::aliases::float::FT0;
 
     #[test]
     fn float_loads() {
-        let memory = Memory::new((0..123).into_iter());
+        let mut memory = Memory::new(0..123);
         let x = IntegerRegisters::new(&memory);
         let mut f = FloatRegisters::default();
 
@@ -27,18 +33,17 @@ mod tests {
             },
             &x,
             &mut f,
-            &memory,
-        );
+            &Mmu::bare(&mut memory),
+        )
+        .unwrap();
 
-        let (float, rest) = decompose(f[FT0]);
-
-        assert_eq!(rest, 0);
-        assert_eq!(float, f32::from_bits(0x13121110));
+        assert_eq!(f[FT0] >> 32, 0xffff_ffff);
+        assert_eq!(f.single(FT0), f32::from_bits(0x13121110));
     }
 
     #[test]
     fn float_loads_pi() {
-        let mut memory = Memory::new((0..123).into_iter());
+        let mut memory = Memory::new(0..123);
         let x = IntegerRegisters::new(&memory);
         let mut f = FloatRegisters::default();
 
@@ -52,14 +57,11 @@ mod tests {
             },
             &x,
             &mut f,
-            &memory,
-        );
-
-        let (float, rest) = decompose(f[FT0]);
-
-        print!("{}", float);
+            &Mmu::bare(&mut memory),
+        )
+        .unwrap();
 
-        assert_eq!(rest, 0);
-        assert_eq!(float, f32::from_bits(0x40490625));
+        assert_eq!(f[FT0] >> 32, 0xffff_ffff);
+        assert_eq!(f.single(FT0), f32::from_bits(0x40490625));
     }
 }
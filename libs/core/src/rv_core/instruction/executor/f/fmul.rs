@@ -1,9 +1,16 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn s(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (fs2, _) = decompose(f[rs2]);
-    let (_, rest) = decompose(f[rd]);
+pub fn s(
+    Rfp { rd, rs1, rs2, rm }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+    let fs2 = f.single(rs2);
 
-    f[rd] = compose(fs1 * fs2, rest);
+    let (product, flags) = softfloat::mul(fs1, fs2, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, product);
+
+    Ok(())
 }
@@ -1,10 +1,23 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn s(R4 { rd, rs1, rs2, rs3 }: R4, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (fs2, _) = decompose(f[rs2]);
-    let (fs3, _) = decompose(f[rs3]);
-    let (_, rest) = decompose(f[rd]);
+pub fn s(
+    R4 {
+        rd,
+        rs1,
+        rs2,
+        rs3,
+        rm,
+    }: R4,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+    let fs2 = f.single(rs2);
+    let fs3 = f.single(rs3);
 
-    f[rd] = compose(-fs1 * fs2 + fs3, rest);
+    let (result, flags) = softfloat::mul_add(-fs1, fs2, fs3, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, result);
+
+    Ok(())
 }
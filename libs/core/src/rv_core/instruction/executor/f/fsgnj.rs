@@ -1,10 +1,8 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn s(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (fs2, _) = decompose(f[rs2]);
+    let fs1 = f.single(rs1);
+    let fs2 = f.single(rs2);
 
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(fs1.copysign(fs2), rest);
+    f.set_single(rd, fs1.copysign(fs2));
 }
@@ -1,8 +1,8 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn s(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (fs2, _) = decompose(f[rs2]);
+    let fs1 = f.single(rs1);
+    let fs2 = f.single(rs2);
 
     let value = match (fs1.is_sign_positive(), fs2.is_sign_positive()) {
         (true, true) => fs1,   // +x, +y => +x
@@ -11,7 +11,5 @@ pub fn s(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
         (false, false) => fs1, // -x, -y => +x
     };
 
-    let (_, rest) = decompose(f[rd]);
-
-    f[rd] = compose(value, rest);
+    f.set_single(rd, value);
 }
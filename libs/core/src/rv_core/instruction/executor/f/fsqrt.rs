@@ -1,8 +1,20 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn s(R { rd, rs1, rs2: _ }: R, f: &mut FloatRegisters) {
-    let (fs1, _) = decompose(f[rs1]);
-    let (_, rest) = decompose(f[rd]);
+pub fn s(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
 
-    f[rd] = compose(fs1.sqrt(), rest);
+    let (root, flags) = softfloat::sqrt(fs1, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_single(rd, root);
+
+    Ok(())
 }
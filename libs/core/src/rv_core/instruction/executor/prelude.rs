@@ -5,17 +5,20 @@ pub use crate::extensions::{
 };
 
 pub use crate::rv_core::{
-    arbitrary_float::{compose, decompose, ArbitraryFloat, RoundingMode},
+    arbitrary_float::{ArbitraryFloat, RoundingMode},
     instruction::{executor::VectorContext, format::*},
     memory::Memory,
+    mmu::{Access, Mmu},
     registers::{
-        aliases::{csr::*, float::*, integer::*, vector::*},
-        vector::{Vreg, WideVreg},
+        aliases::{csr::*, integer::*, vtype::VILL},
+        vector::Vreg,
         *,
     },
+    softfloat,
+    trap::Exception,
     vector_engine::{
-        sew::{BaseSew, DoubleFpSew, DoubleSew, EighthSew, FourthSew, FpSew, HalfSew, Sew},
-        Lmul, MaskBehavior, VectorEngine, VectorEngineBuilder, Vlen,
+        sew::{BaseSew, EighthSew, FourthSew, FpSew, HalfSew, Sew},
+        Lmul, MaskBehavior, VectorEngine,
     },
 };
 
@@ -0,0 +1,9 @@
+mod mret;
+mod sfence_vma;
+mod sret;
+mod wfi;
+
+pub use mret::mret;
+pub use sfence_vma::sfence_vma;
+pub use sret::sret;
+pub use wfi::wfi;
@@ -0,0 +1,51 @@
+use crate::rv_core::{instruction::executor::prelude::*, registers::aliases::mstatus};
+
+pub fn mret(c: &mut CsrRegisters, mode: &mut PrivilegeMode, pc: &mut u64) -> Result<(), Exception> {
+    if *mode != PrivilegeMode::Machine {
+        return Err(Exception::IllegalInstruction(0));
+    }
+
+    let status = c[MSTATUS].read();
+    let previous_mode = PrivilegeMode::from_bits(status >> 11);
+
+    // MIE is restored from MPIE and MPP drops to U-mode
+    let mut restored = status & !(mstatus::MIE | mstatus::MPP) | mstatus::MPIE;
+    if status & mstatus::MPIE != 0 {
+        restored |= mstatus::MIE;
+    }
+    if previous_mode != PrivilegeMode::Machine {
+        restored &= !mstatus::MPRV;
+    }
+
+    unsafe { c[MSTATUS].set(restored) }
+
+    *mode = previous_mode;
+    *pc = c[MEPC].read().wrapping_sub(4);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mret_works() {
+        let mut c = CsrRegisters::default();
+        let mut mode = PrivilegeMode::Machine;
+        let mut pc = 0x200;
+
+        c[MEPC].write(0x104).unwrap();
+        c[MSTATUS]
+            .write(mstatus::MPIE | mstatus::MPRV | 1 << 11)
+            .unwrap();
+
+        mret(&mut c, &mut mode, &mut pc).unwrap();
+
+        assert_eq!(pc, 0x100);
+        assert_eq!(mode, PrivilegeMode::Supervisor);
+        assert_eq!(c[MSTATUS].read(), mstatus::MIE | mstatus::MPIE);
+
+        assert!(mret(&mut c, &mut mode, &mut pc).is_err());
+    }
+}
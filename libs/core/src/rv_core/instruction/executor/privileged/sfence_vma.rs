@@ -0,0 +1,10 @@
+use crate::rv_core::{instruction::executor::prelude::*, registers::aliases::mstatus};
+
+/// Page tables are walked on every access, so there are no cached translations to flush
+pub fn sfence_vma(_: R, c: &CsrRegisters, mode: PrivilegeMode) -> Result<(), Exception> {
+    if mode == PrivilegeMode::User || mode == PrivilegeMode::Supervisor && c.status(mstatus::TVM) {
+        return Err(Exception::IllegalInstruction(0));
+    }
+
+    Ok(())
+}
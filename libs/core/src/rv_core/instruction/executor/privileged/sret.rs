@@ -0,0 +1,56 @@
+use crate::rv_core::{instruction::executor::prelude::*, registers::aliases::mstatus};
+
+pub fn sret(c: &mut CsrRegisters, mode: &mut PrivilegeMode, pc: &mut u64) -> Result<(), Exception> {
+    if *mode == PrivilegeMode::User || *mode == PrivilegeMode::Supervisor && c.status(mstatus::TSR)
+    {
+        return Err(Exception::IllegalInstruction(0));
+    }
+
+    let status = c[MSTATUS].read();
+    let previous_mode = if status & mstatus::SPP != 0 {
+        PrivilegeMode::Supervisor
+    } else {
+        PrivilegeMode::User
+    };
+
+    // SIE is restored from SPIE and SPP drops to U-mode
+    let mut restored = status & !(mstatus::SIE | mstatus::SPP | mstatus::MPRV) | mstatus::SPIE;
+    if status & mstatus::SPIE != 0 {
+        restored |= mstatus::SIE;
+    }
+
+    unsafe { c[MSTATUS].set(restored) }
+
+    *mode = previous_mode;
+    *pc = c[SEPC].read().wrapping_sub(4);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sret_works() {
+        let mut c = CsrRegisters::default();
+        let mut mode = PrivilegeMode::Supervisor;
+        let mut pc = 0x200;
+
+        c[SEPC].write(0x104).unwrap();
+        c[MSTATUS].write(mstatus::SIE).unwrap();
+
+        sret(&mut c, &mut mode, &mut pc).unwrap();
+
+        assert_eq!(pc, 0x100);
+        assert_eq!(mode, PrivilegeMode::User);
+        assert_eq!(c[MSTATUS].read(), mstatus::SPIE);
+
+        assert!(sret(&mut c, &mut mode, &mut pc).is_err());
+
+        let mut mode = PrivilegeMode::Supervisor;
+        c[MSTATUS].write(mstatus::TSR).unwrap();
+
+        assert!(sret(&mut c, &mut mode, &mut pc).is_err());
+    }
+}
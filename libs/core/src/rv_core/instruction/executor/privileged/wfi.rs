@@ -0,0 +1,10 @@
+use crate::rv_core::{instruction::executor::prelude::*, registers::aliases::mstatus};
+
+/// Stalling is optional, so waiting resumes right away and pending interrupts are taken before the next instruction
+pub fn wfi(c: &CsrRegisters, mode: PrivilegeMode) -> Result<(), Exception> {
+    if mode == PrivilegeMode::User || mode == PrivilegeMode::Supervisor && c.status(mstatus::TW) {
+        return Err(Exception::IllegalInstruction(0));
+    }
+
+    Ok(())
+}
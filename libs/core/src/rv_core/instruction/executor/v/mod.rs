@@ -63,6 +63,7 @@ pub mod vloxseg;
 pub mod vlr;
 pub mod vls;
 pub mod vlseg;
+pub mod vlsegff;
 pub mod vlsseg;
 pub mod vlux;
 pub mod vluxseg;
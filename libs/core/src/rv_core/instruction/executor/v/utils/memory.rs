@@ -0,0 +1,26 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+/// Reads an `eew` wide element, zero-extended
+pub fn load_element(mem: &Mmu, address: usize, eew: BaseSew) -> Result<u64, Exception> {
+    Ok(match eew {
+        BaseSew::E8 => u8::from_le_bytes(mem.get(address)?) as u64,
+        BaseSew::E16 => u16::from_le_bytes(mem.get(address)?) as u64,
+        BaseSew::E32 => u32::from_le_bytes(mem.get(address)?) as u64,
+        BaseSew::E64 => u64::from_le_bytes(mem.get(address)?),
+    })
+}
+
+/// Writes the low `eew` bits of an element
+pub fn store_element(
+    mem: &mut Mmu,
+    address: usize,
+    eew: BaseSew,
+    value: u64,
+) -> Result<(), Exception> {
+    match eew {
+        BaseSew::E8 => mem.set(address, (value as u8).to_le_bytes()),
+        BaseSew::E16 => mem.set(address, (value as u16).to_le_bytes()),
+        BaseSew::E32 => mem.set(address, (value as u32).to_le_bytes()),
+        BaseSew::E64 => mem.set(address, value.to_le_bytes()),
+    }
+}
@@ -21,7 +21,7 @@ pub struct Roundoff {
 
 impl Roundoff {
     fn parse_csr(csr: &CsrRegisters) -> RoundingMode {
-        match csr[VXRM].read() {
+        match csr.read(VXRM) & 0b11 {
             0b00 => RoundingMode::Rnu,
             0b01 => RoundingMode::Rne,
             0b10 => RoundingMode::Rdn,
@@ -45,61 +45,104 @@ impl Roundoff {
     }
 }
 
-// NOTE: u8 is fine as long as max SEW = 256b, which is the case for now
+// Signed values are passed in two's complement, sign-extended to the full 128 bits
 impl FnOnce<(u128, u8)> for Roundoff {
-    type Output = u64;
+    type Output = u128;
 
     extern "rust-call" fn call_once(self, args: (u128, u8)) -> Self::Output {
-        if self.signed {
-            roundoff_signed_internal(args.0, args.1, &self.mode)
-        } else {
-            roundoff_unsigned_internal(args.0, args.1, &self.mode)
-        }
+        roundoff(args.0, args.1, &self.mode, self.signed)
     }
 }
 
 impl FnMut<(u128, u8)> for Roundoff {
     extern "rust-call" fn call_mut(&mut self, args: (u128, u8)) -> Self::Output {
-        if self.signed {
-            roundoff_signed_internal(args.0, args.1, &self.mode)
-        } else {
-            roundoff_unsigned_internal(args.0, args.1, &self.mode)
-        }
+        roundoff(args.0, args.1, &self.mode, self.signed)
     }
 }
 
 impl Fn<(u128, u8)> for Roundoff {
     extern "rust-call" fn call(&self, args: (u128, u8)) -> Self::Output {
-        if self.signed {
-            roundoff_signed_internal(args.0, args.1, &self.mode)
-        } else {
-            roundoff_unsigned_internal(args.0, args.1, &self.mode)
-        }
+        roundoff(args.0, args.1, &self.mode, self.signed)
     }
 }
 
-fn roundoff_unsigned_internal(v: u128, d: u8, mode: &RoundingMode) -> u64 {
+/// Sign-extends the low `width` bits of a value to 128 bits
+pub fn sign_extend(value: u128, width: usize) -> u128 {
+    let unused = 128 - width;
+
+    ((value << unused) as i128 >> unused) as u128
+}
+
+/// Shifts `v` right by `d` bits and rounds the result according to vxrm
+fn roundoff(v: u128, d: u8, mode: &RoundingMode, signed: bool) -> u128 {
     use RoundingMode::*;
 
-    let r = match mode {
-        Rnu => v >> (d - 1) & 1,
-        Rne => (v >> (d - 1) & 1) & (if (v >> (d - 2)) != 0 { 1 } else { 0 } | (v >> d & 1)),
-        Rdn => 0,
-        Rod => !(v >> d & 1) & if (v >> (d - 1)) != 0 { 1 } else { 0 },
+    let bit = |n: u8| v >> n & 1;
+    let any_below = |n: u8| (v & ((1 << n) - 1) != 0) as u128;
+
+    let r = match (mode, d) {
+        (_, 0) => 0,
+        (Rnu, _) => bit(d - 1),
+        (Rne, _) => bit(d - 1) & (any_below(d - 1) | bit(d)),
+        (Rdn, _) => 0,
+        (Rod, _) => (1 - bit(d)) & any_below(d),
     };
 
-    ((v >> d) + r) as u64
+    let shifted = match signed {
+        true => (v as i128 >> d) as u128,
+        false => v >> d,
+    };
+
+    shifted.wrapping_add(r)
 }
 
-fn roundoff_signed_internal(v: u128, d: u8, mode: &RoundingMode) -> u64 {
-    use RoundingMode::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_vxrm_mode_is_honoured() {
+        use RoundingMode::*;
+
+        // 0b1011 >> 2 = 0b10.11, 0b1010 >> 2 = 0b10.10, 0b1001 >> 2 = 0b10.01
+        let cases = [
+            (Rnu, [3, 3, 2]),
+            (Rne, [3, 2, 2]),
+            (Rdn, [2, 2, 2]),
+            (Rod, [3, 3, 3]),
+        ];
+
+        for (mode, expected) in cases {
+            let results = [0b1011, 0b1010, 0b1001].map(|v| roundoff(v, 2, &mode, false));
+            assert_eq!(results, expected);
+        }
 
-    let r = match mode {
-        Rnu => v >> (d - 1) & 1,
-        Rne => (v >> (d - 1) & 1) & (if (v >> (d - 2)) != 0 { 1 } else { 0 } | (v >> d & 1)),
-        Rdn => 0,
-        Rod => !(v >> d & 1) & if (v >> (d - 1)) != 0 { 1 } else { 0 },
-    };
+        // Odd results are already exact under rod
+        assert_eq!(roundoff(0b1101, 2, &Rod, false), 3);
+        assert_eq!(roundoff(0b1100, 2, &Rod, false), 3);
+        assert_eq!(roundoff(0b1011, 0, &Rnu, false), 0b1011);
+    }
 
-    ((v as i128 >> d) + r as i128) as u64
+    #[test]
+    fn signed_values_round_towards_the_right_neighbour() {
+        let minus_five = sign_extend(0xfb, 8);
+
+        // -5 / 2 = -2.5
+        assert_eq!(
+            roundoff(minus_five, 1, &RoundingMode::Rnu, true) as i128,
+            -2
+        );
+        assert_eq!(
+            roundoff(minus_five, 1, &RoundingMode::Rne, true) as i128,
+            -2
+        );
+        assert_eq!(
+            roundoff(minus_five, 1, &RoundingMode::Rdn, true) as i128,
+            -3
+        );
+        assert_eq!(
+            roundoff(minus_five, 1, &RoundingMode::Rod, true) as i128,
+            -3
+        );
+    }
 }
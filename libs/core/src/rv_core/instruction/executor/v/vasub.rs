@@ -1,6 +1,6 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-use super::utils::rounding::Roundoff;
+use super::utils::rounding::{sign_extend, Roundoff};
 
 pub fn vv(
     Opmvv {
@@ -12,10 +12,13 @@ pub fn vv(
     v: &mut VectorContext<'_>,
 ) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
+    let sew = v.vec_engine.sew.bit_length();
 
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |(vs2, vs1)| {
-            roundoff_signed((vs2 as u128).wrapping_sub(vs1 as u128), 1)
+            let (vs2, vs1) = (sign_extend(vs2 as u128, sew), sign_extend(vs1 as u128, sew));
+
+            roundoff_signed(vs2.wrapping_sub(vs1), 1) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
@@ -33,12 +36,18 @@ pub fn vx(
     x: &IntegerRegisters,
 ) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
+    let sew = v.vec_engine.sew.bit_length();
 
     let vreg = v
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |vs2| {
-            roundoff_signed((vs2 as u128).wrapping_sub(x[rs1] as u128), 1)
+            let (vs2, rs1) = (
+                sign_extend(vs2 as u128, sew),
+                sign_extend(x[rs1] as u128, sew),
+            );
+
+            roundoff_signed(vs2.wrapping_sub(rs1), 1) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
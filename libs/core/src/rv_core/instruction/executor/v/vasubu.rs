@@ -15,7 +15,7 @@ pub fn vv(
 
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |(vs2, vs1)| {
-            roundoff_unsigned((vs2 as u128).wrapping_sub(vs1 as u128), 1)
+            roundoff_unsigned((vs2 as u128).wrapping_sub(vs1 as u128), 1) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
@@ -33,12 +33,13 @@ pub fn vx(
     x: &IntegerRegisters,
 ) {
     let roundoff_unsigned = Roundoff::new_unsigned(v.csr);
+    let rs1 = x[rs1] & (u64::MAX >> (64 - v.vec_engine.sew.bit_length()));
 
     let vreg = v
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |vs2| {
-            roundoff_unsigned((vs2 as u128).wrapping_sub(x[rs1] as u128), 1)
+            roundoff_unsigned((vs2 as u128).wrapping_sub(rs1 as u128), 1) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
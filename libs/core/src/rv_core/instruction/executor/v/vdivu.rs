@@ -13,13 +13,7 @@ pub fn vv(
         .masked_map(
             v.default_mask(vm),
             v.get(vd).iter_eew(),
-            |(dividend, divisor)| {
-                if divisor == 0 {
-                    u64::MAX
-                } else {
-                    dividend / divisor
-                }
-            },
+            |(dividend, divisor)| dividend.checked_div(divisor).unwrap_or(u64::MAX),
         )
         .collect_with_eew(v.vec_engine.sew);
 
@@ -42,11 +36,7 @@ pub fn vx(
         .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |dividend| {
             let divisor = x[rs1];
 
-            if divisor == 0 {
-                u64::MAX
-            } else {
-                dividend / divisor
-            }
+            dividend.checked_div(divisor).unwrap_or(u64::MAX)
         })
         .collect_with_eew(v.vec_engine.sew);
 
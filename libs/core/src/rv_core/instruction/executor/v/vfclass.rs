@@ -9,7 +9,7 @@ pub fn v(
         dest: vd, vs2, vm, ..
     }: Vfunary1,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -7,7 +7,7 @@ pub fn xufv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -26,7 +26,7 @@ pub fn xfv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -45,7 +45,7 @@ pub fn fxuv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fp_sew = v.vec_engine.sew.fp()?;
 
     let vreg = v
@@ -55,6 +55,7 @@ pub fn fxuv(
             v.default_mask(vm),
             v.get(vd).iter_fp()?,
             |vs2| match fp_sew {
+                FpSew::E16 => ArbitraryFloat::F16(vs2 as f16),
                 FpSew::E32 => ArbitraryFloat::F32(vs2 as f32),
                 FpSew::E64 => ArbitraryFloat::F64(vs2 as f64),
             },
@@ -71,7 +72,7 @@ pub fn fxv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fp_sew = v.vec_engine.sew.fp()?;
 
     let vreg = v
@@ -81,7 +82,8 @@ pub fn fxv(
             v.default_mask(vm),
             v.get(vd).iter_fp()?,
             |vs2| match fp_sew {
-                FpSew::E32 => ArbitraryFloat::F32(vs2 as i64 as f32),
+                FpSew::E16 => ArbitraryFloat::F16(vs2 as i16 as f16),
+                FpSew::E32 => ArbitraryFloat::F32(vs2 as i32 as f32),
                 FpSew::E64 => ArbitraryFloat::F64(vs2 as i64 as f64),
             },
         )
@@ -97,7 +99,7 @@ pub fn rtzxufv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -116,7 +118,7 @@ pub fn rtzxfv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
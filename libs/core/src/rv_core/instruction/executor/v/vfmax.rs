@@ -8,7 +8,7 @@ pub fn vv(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_fp()?, v.get(vs1).iter_fp()?)
         .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |(vs2, vs1)| {
             if vs2 > vs1 {
@@ -27,7 +27,7 @@ pub fn vf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -9,7 +9,7 @@ pub fn vfm(
     }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.default_mask(true), v.get(vs2).iter_fp()?)
         .map(|(mask, vs2)| {
             if mask == 1 {
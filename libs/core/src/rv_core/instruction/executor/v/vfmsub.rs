@@ -1,29 +1,15 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn vv(
-    Opfvv {
-        dest,
-        vs1,
-        vs2,
-        vm,
-    }: Opfvv,
-    v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+pub fn vv(Opfvv { dest, vs1, vs2, vm }: Opfvv, v: &mut VectorContext<'_>) -> Result<(), Exception> {
     let vs2 = v.get(vs2);
     let vs1 = v.get(vs1);
-    let vd  = v.get(dest);
+    let vd = v.get(dest);
 
-    let vreg = izip!(
-        vs2.iter_fp()?,
-        vs1.iter_fp()?,
-        vd.iter_fp()?
-    )
-    .masked_map(
-        v.default_mask(vm),
-        vd.iter_fp()?,
-        |(vs2, vs1, vd)| (vd * vs1) - vs2,
-    )
-    .collect_fp();
+    let vreg = izip!(vs2.iter_fp()?, vs1.iter_fp()?, vd.iter_fp()?)
+        .masked_map(v.default_mask(vm), vd.iter_fp()?, |(vs2, vs1, vd)| {
+            (vd * vs1) - vs2
+        })
+        .collect_fp();
 
     v.apply(dest, vreg);
 
@@ -34,7 +20,7 @@ pub fn vf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_fp()?, v.get(vd).iter_fp()?)
         .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |(vs2, vd)| {
             (vd * ArbitraryFloat::copy_type(&vs2, f[rs1])) - vs2
@@ -9,7 +9,7 @@ pub fn vf(
     }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vd)
         .iter_fp()?
@@ -22,36 +22,27 @@ pub fn vf(
 }
 
 pub fn fs(
-    Vwfunary0 {
-        dest: rd,
-        vs2,
-        vm: _,
-        ..
-    }: Vwfunary0,
+    Vwfunary0 { dest: rd, vs2, .. }: Vwfunary0,
     v: &VectorContext<'_>,
     f: &mut FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let first_value = v.get(vs2).iter_fp()?.next().unwrap();
 
-    let value = match first_value {
-        ArbitraryFloat::F32(fp) => {
-            let (_, rest) = decompose(f[rd]);
-            compose(fp, rest)
-        }
-        ArbitraryFloat::F64(fp) => fp,
-    };
-
-    f[rd] = value;
+    match first_value {
+        ArbitraryFloat::F16(fp) => f.set_half(rd, fp),
+        ArbitraryFloat::F32(fp) => f.set_single(rd, fp),
+        ArbitraryFloat::F64(fp) => f.set_double(rd, fp),
+    }
 
     Ok(())
 }
 
 pub fn sf(
-    Vrfunary0 { vd, rs1, vm: _, .. }: Vrfunary0,
+    Vrfunary0 { vd, rs1, .. }: Vrfunary0,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
-    let first_value = f64::to_le_bytes(f[rs1]);
+) -> Result<(), Exception> {
+    let first_value = unbox(f[rs1], v.vec_engine.sew.fp()?.bit_length()).to_le_bytes();
 
     let vreg = v.get(vd);
     let mut vreg_data = vreg.iter_byte().collect_vec();
@@ -59,7 +50,7 @@ pub fn sf(
     vreg_data[..v.vec_engine.sew.fp()?.byte_length()]
         .copy_from_slice(&first_value[..v.vec_engine.sew.byte_length()]);
 
-    v.apply(vd, vreg_data.into_iter().collect());
+    v.apply_scalar(vd, vreg_data.into_iter().collect());
 
     Ok(())
 }
@@ -7,7 +7,7 @@ pub fn xufw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_fp()?
@@ -26,7 +26,7 @@ pub fn xfw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_fp()?
@@ -45,7 +45,7 @@ pub fn fxuw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fp_sew = v.vec_engine.sew.fp()?;
 
     let vreg = v
@@ -55,6 +55,7 @@ pub fn fxuw(
             v.default_mask(vm),
             v.get(vd).iter_fp()?,
             |vs2| match fp_sew {
+                FpSew::E16 => ArbitraryFloat::F16(vs2 as u32 as f16),
                 FpSew::E32 => ArbitraryFloat::F32(vs2 as u32 as f32),
                 FpSew::E64 => ArbitraryFloat::F64(vs2 as u64 as f64),
             },
@@ -71,7 +72,7 @@ pub fn fxw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fp_sew = v.vec_engine.sew.fp()?;
 
     let vreg = v
@@ -81,6 +82,7 @@ pub fn fxw(
             v.default_mask(vm),
             v.get(vd).iter_fp()?,
             |vs2| match fp_sew {
+                FpSew::E16 => ArbitraryFloat::F16(vs2 as i32 as f16),
                 FpSew::E32 => ArbitraryFloat::F32(vs2 as i32 as f32),
                 FpSew::E64 => ArbitraryFloat::F64(vs2 as i64 as f64),
             },
@@ -97,13 +99,15 @@ pub fn ffw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
-    let vreg = v
+) -> Result<(), Exception> {
+    let narrowed = v
         .get_wide(vs2)?
         .iter_fp()?
-        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
-            vs2.half_precision(RoundingMode::Nearest)
-        })
+        .map(|vs2| vs2.half_precision(RoundingMode::Nearest))
+        .collect::<Result<Vec<_>, _>>()?;
+    let vreg = narrowed
+        .into_iter()
+        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| vs2)
         .collect_fp();
 
     v.apply(vd, vreg);
@@ -116,13 +120,15 @@ pub fn rodffw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
-    let vreg = v
+) -> Result<(), Exception> {
+    let narrowed = v
         .get_wide(vs2)?
         .iter_fp()?
-        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
-            vs2.half_precision(RoundingMode::TowardsOdd)
-        })
+        .map(|vs2| vs2.half_precision(RoundingMode::TowardsOdd))
+        .collect::<Result<Vec<_>, _>>()?;
+    let vreg = narrowed
+        .into_iter()
+        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| vs2)
         .collect_fp();
 
     v.apply(vd, vreg);
@@ -135,7 +141,7 @@ pub fn rtzxufw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_fp()?
@@ -154,7 +160,7 @@ pub fn rtzxfw(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_fp()?
@@ -1,3 +1,5 @@
+use num_traits::Float;
+
 use crate::rv_core::instruction::executor::prelude::*;
 
 pub fn v(
@@ -5,13 +7,11 @@ pub fn v(
         dest: vd, vs2, vm, ..
     }: Vfunary1,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
-        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
-            ArbitraryFloat::copy_type(&vs2, 1.0) / vs2
-        })
+        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| vs2.recip())
         .collect_fp();
 
     v.apply(vd, vreg);
@@ -8,7 +8,7 @@ pub fn vs(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let initial_value = v.get(vs1).iter_fp()?.next().unwrap();
     let sum =
         izip!(v.get(vs2).iter_fp()?, v.default_mask(vm)).fold(initial_value, |acc, (vs2, mask)| {
@@ -24,7 +24,7 @@ pub fn vs(
 
     let vreg = vd_snapshot.into_iter().collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_scalar(vd, vreg);
 
     Ok(())
 }
@@ -7,12 +7,12 @@ pub fn v(
         dest: vd, vs2, vm, ..
     }: Vfunary1,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
         .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
-            ArbitraryFloat::copy_type(&vs2, 1.0) / vs2.sqrt()
+            vs2.sqrt().recip()
         })
         .collect_fp();
 
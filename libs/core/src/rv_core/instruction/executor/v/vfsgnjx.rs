@@ -10,7 +10,7 @@ pub fn vv(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_fp()?, v.get(vs1).iter_fp()?)
         .masked_map(
             v.default_mask(vm),
@@ -33,12 +33,15 @@ pub fn vf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
         .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
-            match (vs2.is_sign_positive(), f[rs1].is_sign_positive()) {
+            match (
+                vs2.is_sign_positive(),
+                ArbitraryFloat::copy_type(&vs2, f[rs1]).is_sign_positive(),
+            ) {
                 (true, true) => vs2,
                 (true, false) => -vs2,
                 (false, true) => -vs2,
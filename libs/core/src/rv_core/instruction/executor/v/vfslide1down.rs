@@ -4,8 +4,8 @@ pub fn vf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
-    let last_value = f64::to_le_bytes(f[rs1]);
+) -> Result<(), Exception> {
+    let last_value = unbox(f[rs1], v.vec_engine.sew.fp()?.bit_length()).to_le_bytes();
 
     let vreg_values: Vreg = v
         .get(vs2)
@@ -8,7 +8,7 @@ pub fn vv(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_fp()?, v.get(vs1).iter_fp()?)
         .masked_map(
             v.default_mask(vm),
@@ -17,7 +17,7 @@ pub fn vv(
         )
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -26,7 +26,7 @@ pub fn vf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -36,7 +36,7 @@ pub fn vf(
         })
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -49,7 +49,7 @@ pub fn wv(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get_wide(vs2)?.iter_fp()?, v.get(vs1).iter_fp()?)
         .masked_map(
             v.default_mask(vm),
@@ -58,7 +58,7 @@ pub fn wv(
         )
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -67,7 +67,7 @@ pub fn wf(
     Opfvf { vd, rs1, vs2, vm }: Opfvf,
     v: &mut VectorContext<'_>,
     f: &FloatRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -76,7 +76,7 @@ pub fn wf(
         })
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
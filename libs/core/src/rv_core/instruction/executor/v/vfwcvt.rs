@@ -7,7 +7,7 @@ pub fn xufv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -16,7 +16,7 @@ pub fn xufv(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -26,7 +26,7 @@ pub fn xfv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -35,7 +35,7 @@ pub fn xfv(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -45,7 +45,7 @@ pub fn fxuv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let sew = v.vec_engine.sew;
 
     let vreg = v
@@ -55,14 +55,15 @@ pub fn fxuv(
             v.default_mask(vm),
             v.get_wide(vd)?.iter_fp()?,
             |vs2| match sew {
+                BaseSew::E8 => ArbitraryFloat::F16(vs2 as u8 as f16),
                 BaseSew::E16 => ArbitraryFloat::F32(vs2 as u16 as f32),
                 BaseSew::E32 => ArbitraryFloat::F64(vs2 as u32 as f64),
-                _ => unreachable!(),
+                BaseSew::E64 => unreachable!(),
             },
         )
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -72,7 +73,7 @@ pub fn fxv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let sew = v.vec_engine.sew;
 
     let vreg = v
@@ -82,14 +83,15 @@ pub fn fxv(
             v.default_mask(vm),
             v.get_wide(vd)?.iter_fp()?,
             |vs2| match sew {
+                BaseSew::E8 => ArbitraryFloat::F16(vs2 as i8 as f16),
                 BaseSew::E16 => ArbitraryFloat::F32(vs2 as i16 as f32),
                 BaseSew::E32 => ArbitraryFloat::F64(vs2 as i32 as f64),
-                _ => unreachable!(),
+                BaseSew::E64 => unreachable!(),
             },
         )
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -99,16 +101,16 @@ pub fn ffv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
-        .get_wide(vs2)?
+        .get(vs2)
         .iter_fp()?
-        .masked_map(v.default_mask(vm), v.get(vd).iter_fp()?, |vs2| {
+        .masked_map(v.default_mask(vm), v.get_wide(vd)?.iter_fp()?, |vs2| {
             vs2.double_precision()
         })
         .collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -118,7 +120,7 @@ pub fn rtzxufv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -127,7 +129,7 @@ pub fn rtzxufv(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -137,7 +139,7 @@ pub fn rtzxfv(
         dest: vd, vs2, vm, ..
     }: Vfunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_fp()?
@@ -146,7 +148,7 @@ pub fn rtzxfv(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -8,7 +8,7 @@ pub fn vs(
         vm,
     }: Opfvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let initial_value = v.get_wide(vs1)?.iter_fp()?.next().unwrap();
     let sum =
         izip!(v.get(vs2).iter_fp()?, v.default_mask(vm)).fold(initial_value, |acc, (vs2, mask)| {
@@ -19,12 +19,12 @@ pub fn vs(
             }
         });
 
-    let mut vd_snapshot = v.get(vd).iter_fp()?.collect_vec();
+    let mut vd_snapshot = v.get_wide(vd)?.iter_fp()?.collect_vec();
     vd_snapshot[0] = sum;
 
     let vreg = vd_snapshot.into_iter().collect_fp();
 
-    v.apply(vd, vreg);
+    v.apply_scalar_wide(vd, vreg);
 
     Ok(())
 }
@@ -1,58 +1,36 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::load_element;
+
 pub fn v(
     Vl { vd, rs1, vm }: Vl,
     eew: BaseSew,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) -> Result<(), String> {
+    mem: &Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
+    let vstart = v.csr[VSTART].read();
 
-    let element_amount = v.vlmax();
-
-    let mut store = Vec::<u64>::with_capacity(element_amount);
+    let result = v.load(vd, vm, 1, eew, |index, _| {
+        let address = addr.wrapping_add(index * eew.byte_length());
 
-    for offset in 0..element_amount {
-        let address = addr.wrapping_add(offset.wrapping_mul(eew.byte_length()));
+        load_element(mem, address, eew)
+    });
 
-        let result = match eew {
-            BaseSew::E8 => mem
-                .fallible_get(address)
-                .map(u8::from_le_bytes)
-                .map(Into::into),
-            BaseSew::E16 => mem
-                .fallible_get(address)
-                .map(u16::from_le_bytes)
-                .map(Into::into),
-            BaseSew::E32 => mem
-                .fallible_get(address)
-                .map(u32::from_le_bytes)
-                .map(Into::into),
-            BaseSew::E64 => mem.fallible_get(address).map(u64::from_le_bytes),
-        };
+    match result {
+        Err(exception) if v.csr[VSTART].read() == vstart => Err(exception),
+        // Faults past the first element only trim vl
+        Err(_) => {
+            let index = v.csr[VSTART].read();
 
-        match result {
-            Some(element) => store.push(element),
-            None => {
-                if offset == 0 {
-                    return Err(String::from("Fault-Only-First Load trap"));
-                } else {
-                    v.csr[VL].write(offset as u64)?;
-                }
+            unsafe {
+                v.csr[VL].set(index);
+                v.csr[VSTART].set(vstart);
             }
-        };
-    }
 
-    let vreg = v
-        .get(vd)
-        .iter_eew()
-        .enumerate()
-        .masked_map(v.default_mask(vm), v.get(vd).iter_eew(), |(index, _)| {
-            store[index]
-        })
-        .collect_with_eew(v.vec_engine.sew);
-
-    v.apply(vd, vreg);
-    Ok(())
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
 }
@@ -1,14 +1,18 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn v(Vl { vd, rs1, vm: _ }: Vl, v: &mut VectorContext<'_>, x: &IntegerRegisters, mem: &Memory) {
-    let addr = x[rs1] as usize;
-    let element_amount = v.vec_engine.vlen.byte_length();
+use super::utils::memory::load_element;
 
-    let vreg = (0..element_amount)
-        .map(|offset| addr.wrapping_add(offset))
-        .map(|address| mem.get(address))
-        .map(u8::from_le_bytes)
-        .collect();
+pub fn v(
+    Vl { vd, rs1, vm: _ }: Vl,
+    v: &mut VectorContext<'_>,
+    x: &IntegerRegisters,
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1] as usize;
+    // One mask bit per element up to vl
+    let evl = (v.csr[VL].read() as usize).div_ceil(8);
 
-    v.apply(vd, vreg);
+    v.load_whole(vd, evl, BaseSew::E8, |index| {
+        load_element(mem, addr.wrapping_add(index), BaseSew::E8)
+    })
 }
@@ -1,22 +1,19 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::load_element;
+
 pub fn v(
     Vlr { vd, rs1 }: Vlr,
+    eew: BaseSew,
     nf: usize,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) {
+    mem: &Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
-    let elements_amount = v.vec_engine.vlen.byte_length();
-
-    for segment in 0..nf {
-        let vreg = (0..elements_amount)
-            .map(|offset| addr.wrapping_add(offset.wrapping_mul(nf).wrapping_add(segment)))
-            .map(|address| mem.get(address))
-            .map(u8::from_le_bytes)
-            .collect();
+    let evl = nf * v.vec_engine.vlen.byte_length() / eew.byte_length();
 
-        v.apply(vd + segment, vreg);
-    }
+    v.load_whole(vd, evl, eew, |index| {
+        load_element(mem, addr.wrapping_add(index * eew.byte_length()), eew)
+    })
 }
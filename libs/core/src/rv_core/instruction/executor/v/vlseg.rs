@@ -1,46 +1,22 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::load_element;
+
 pub fn v(
     Vl { vd, rs1, vm }: Vl,
     eew: BaseSew,
     nf: usize,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) {
+    mem: &Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
-    let element_amount = v.vlmax();
-
-    for segment in 0..nf {
-        let mut vn = Vec::new();
-
-        for offset in 0..element_amount {
-            let address = addr
-                .wrapping_add(offset.wrapping_mul(nf).wrapping_add(segment))
-                .wrapping_mul(eew.byte_length());
-
-            let element: u64 = match eew {
-                BaseSew::E8 => u8::from_le_bytes(mem.get(address)) as u64,
-                BaseSew::E16 => u16::from_le_bytes(mem.get(address)) as u64,
-                BaseSew::E32 => u32::from_le_bytes(mem.get(address)) as u64,
-                BaseSew::E64 => u64::from_le_bytes(mem.get(address)),
-            };
-
-            vn.push(element);
-        }
-
-        let vreg = v
-            .get(vd + segment)
-            .iter_eew()
-            .enumerate()
-            .masked_map(
-                v.default_mask(vm),
-                v.get(vd + segment).iter_eew(),
-                |(index, _)| vn[index],
-            )
-            .collect_with_eew(v.vec_engine.sew);
-        v.apply(vd + segment, vreg)
-    }
+
+    v.load(vd, vm, nf, eew, |index, field| {
+        let address = addr.wrapping_add((index * nf + field) * eew.byte_length());
+
+        load_element(mem, address, eew)
+    })
 }
 
 // #[cfg(test)]
@@ -0,0 +1,37 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+use super::utils::memory::load_element;
+
+pub fn v(
+    Vl { vd, rs1, vm }: Vl,
+    eew: BaseSew,
+    nf: usize,
+    v: &mut VectorContext<'_>,
+    x: &IntegerRegisters,
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1] as usize;
+    let vstart = v.csr[VSTART].read();
+
+    let result = v.load(vd, vm, nf, eew, |index, field| {
+        let address = addr.wrapping_add((index * nf + field) * eew.byte_length());
+
+        load_element(mem, address, eew)
+    });
+
+    match result {
+        Err(exception) if v.csr[VSTART].read() == vstart => Err(exception),
+        // Faults past the first segment only trim vl
+        Err(_) => {
+            let index = v.csr[VSTART].read();
+
+            unsafe {
+                v.csr[VL].set(index);
+                v.csr[VSTART].set(vstart);
+            }
+
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
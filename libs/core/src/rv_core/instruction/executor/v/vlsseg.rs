@@ -1,48 +1,25 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::load_element;
+
 pub fn v(
     Vls { vd, rs1, rs2, vm }: Vls,
     eew: BaseSew,
     nf: usize,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) {
-    let base_addr = x[rs1] as usize;
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1] as usize;
     let stride = x[rs2] as usize;
-    let element_amount = v.vlmax();
-
-    for segment in 0..nf {
-        let mut vn = Vec::new();
-
-        for offset in 0..element_amount {
-            let element_addr = base_addr
-                .wrapping_add(offset.wrapping_mul(stride))
-                .wrapping_add(segment.wrapping_mul(eew.byte_length()));
-            let element: u64 = match eew.byte_length() {
-                1 => u8::from_le_bytes(mem.get(element_addr)) as u64,
-                2 => u16::from_le_bytes(mem.get(element_addr)) as u64,
-                4 => u32::from_le_bytes(mem.get(element_addr)) as u64,
-                8 => u64::from_le_bytes(mem.get(element_addr)),
-                _ => panic!("Unsupported EEW byte length."),
-            };
-
-            vn.push(element);
-        }
-
-        let vreg = v
-            .get(vd + segment)
-            .iter_eew()
-            .enumerate()
-            .masked_map(
-                v.default_mask(vm),
-                v.get(vd + segment).iter_eew(),
-                |(index, _)| vn[index],
-            )
-            .collect_with_eew(v.vec_engine.sew);
-
-        v.apply(vd + segment, vreg)
-    }
+
+    v.load(vd, vm, nf, eew, |index, field| {
+        let address = addr
+            .wrapping_add(index.wrapping_mul(stride))
+            .wrapping_add(field * eew.byte_length());
+
+        load_element(mem, address, eew)
+    })
 }
 
 // #[cfg(test)]
@@ -5,7 +5,7 @@ pub fn v(
     eew: BaseSew,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) {
+    mem: &Mmu,
+) -> Result<(), Exception> {
     super::vlox::v(Vlx { vd, rs1, vs2, vm }, eew, v, x, mem)
 }
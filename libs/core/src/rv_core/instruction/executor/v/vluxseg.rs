@@ -6,7 +6,7 @@ pub fn v(
     nf: usize,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &Memory,
-) {
+    mem: &Mmu,
+) -> Result<(), Exception> {
     super::vloxseg::v(vlx, eew, nf, v, x, mem)
 }
@@ -52,12 +52,7 @@ pub fn vi(
 }
 
 pub fn xs(
-    Vwxunary0 {
-        dest: rd,
-        vs2,
-        vm: _,
-        ..
-    }: Vwxunary0,
+    Vwxunary0 { dest: rd, vs2, .. }: Vwxunary0,
     v: &VectorContext<'_>,
     x: &mut IntegerRegisters,
 ) {
@@ -66,16 +61,7 @@ pub fn xs(
     x[rd] = first_value;
 }
 
-pub fn sx(
-    Vrxunary0 {
-        dest,
-        rs1,
-        vm: _,
-        ..
-    }: Vrxunary0,
-    v: &mut VectorContext<'_>,
-    x: &IntegerRegisters,
-) {
+pub fn sx(Vrxunary0 { dest, rs1, .. }: Vrxunary0, v: &mut VectorContext<'_>, x: &IntegerRegisters) {
     let first_value = u64::to_le_bytes(x[rs1]);
 
     let vreg = v.get(dest);
@@ -84,5 +70,5 @@ pub fn sx(
     vreg_data[..v.vec_engine.sew.byte_length()]
         .copy_from_slice(&first_value[..v.vec_engine.sew.byte_length()]);
 
-    v.apply(dest, vreg_data.into_iter().collect());
+    v.apply_scalar(dest, vreg_data.into_iter().collect());
 }
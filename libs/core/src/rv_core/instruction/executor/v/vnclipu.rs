@@ -1,88 +1,98 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-use super::utils::rounding::Roundoff;
+use super::utils::{rounding::Roundoff, shamt::narrow_shamt};
 
-pub fn wv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
-    let roundoff_signed = Roundoff::new_signed(v.csr);
+pub fn wv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Result<(), Exception> {
+    let roundoff_unsigned = Roundoff::new_unsigned(v.csr);
 
     let uint_max = u64::MAX >> (64 - v.vec_engine.sew.bit_length());
-    let sign_mask = u64::MIN << v.vec_engine.sew.bit_length();
 
     let vec_engine = *v.vec_engine;
 
-    let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
+    let vreg = izip!(v.get_wide(vs2)?.iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |(vs2, vs1)| {
-            let result = roundoff_signed(
-                vs2 as u128,
-                vs1 as u8 & (2 * v.vec_engine.sew.bit_length() as u8 - 1),
-            );
+            let result = roundoff_unsigned(vs2, narrow_shamt(vs1, vec_engine.sew.double()) as u8);
 
-            if sign_mask & result != 0 {
+            if result > uint_max as u128 {
                 let _ = v.csr[VXSAT].write(1);
                 uint_max
             } else {
-                result
+                result as u64
             }
         })
         .collect_with_eew(vec_engine.sew);
 
     v.apply(dest, vreg);
+
+    Ok(())
 }
 
-pub fn wx(Opivx { dest, rs1, vs2, vm }: Opivx, v: &mut VectorContext<'_>, x: &IntegerRegisters) {
-    let roundoff_signed = Roundoff::new_signed(v.csr);
+pub fn wx(
+    Opivx { dest, rs1, vs2, vm }: Opivx,
+    v: &mut VectorContext<'_>,
+    x: &IntegerRegisters,
+) -> Result<(), Exception> {
+    let roundoff_unsigned = Roundoff::new_unsigned(v.csr);
 
     let uint_max = u64::MAX >> (64 - v.vec_engine.sew.bit_length());
-    let sign_mask = u64::MIN << v.vec_engine.sew.bit_length();
 
     let vec_engine = *v.vec_engine;
 
     let vreg = v
-        .get(vs2)
+        .get_wide(vs2)?
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            let result = roundoff_signed(
-                vs2 as u128,
-                x[rs1] as u8 & (2 * v.vec_engine.sew.bit_length() as u8 - 1),
-            );
+            let result =
+                roundoff_unsigned(vs2, narrow_shamt(x[rs1], vec_engine.sew.double()) as u8);
 
-            if sign_mask & result != 0 {
+            if result > uint_max as u128 {
                 let _ = v.csr[VXSAT].write(1);
                 uint_max
             } else {
-                result
+                result as u64
             }
         })
         .collect_with_eew(vec_engine.sew);
 
     v.apply(dest, vreg);
+
+    Ok(())
 }
 
-pub fn wi(Opivi { dest, imm5, vs2, vm }: Opivi, v: &mut VectorContext<'_>) {
-    let roundoff_signed = Roundoff::new_signed(v.csr);
+pub fn wi(
+    Opivi {
+        dest,
+        imm5,
+        vs2,
+        vm,
+    }: Opivi,
+    v: &mut VectorContext<'_>,
+) -> Result<(), Exception> {
+    let roundoff_unsigned = Roundoff::new_unsigned(v.csr);
 
     let uint_max = u64::MAX >> (64 - v.vec_engine.sew.bit_length());
-    let sign_mask = u64::MIN << v.vec_engine.sew.bit_length();
 
     let vec_engine = *v.vec_engine;
 
     let vreg = v
-        .get(vs2)
+        .get_wide(vs2)?
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            let result = roundoff_signed(
-                vs2 as u128,
-                imm5 as u8 & (2 * v.vec_engine.sew.bit_length() as u8 - 1),
+            let result = roundoff_unsigned(
+                vs2,
+                narrow_shamt(imm5 as u64, vec_engine.sew.double()) as u8,
             );
 
-            if sign_mask & result != 0 {
+            if result > uint_max as u128 {
                 let _ = v.csr[VXSAT].write(1);
                 uint_max
             } else {
-                result
+                result as u64
             }
         })
         .collect_with_eew(vec_engine.sew);
 
     v.apply(dest, vreg);
+
+    Ok(())
 }
@@ -2,7 +2,7 @@ use crate::rv_core::instruction::executor::prelude::*;
 
 use super::utils::shamt::narrow_shamt;
 
-pub fn wv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Result<(), String> {
+pub fn wv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Result<(), Exception> {
     let vreg = izip!(v.get_wide(vs2)?.iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |(vs2, vs1)| {
             (vs2 >> narrow_shamt(vs1, v.vec_engine.sew.double())) as u64
@@ -18,7 +18,7 @@ pub fn wx(
     Opivx { dest, rs1, vs2, vm }: Opivx,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_eew()
@@ -32,7 +32,15 @@ pub fn wx(
     Ok(())
 }
 
-pub fn wi(Opivi { dest, imm5, vs2, vm }: Opivi, v: &mut VectorContext<'_>) -> Result<(), String> {
+pub fn wi(
+    Opivi {
+        dest,
+        imm5,
+        vs2,
+        vm,
+    }: Opivi,
+    v: &mut VectorContext<'_>,
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_eew()
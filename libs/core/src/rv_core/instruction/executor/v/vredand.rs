@@ -24,5 +24,5 @@ pub fn vs(
 
     let vreg = vd_data.into_iter().collect_with_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_scalar(vd, vreg);
 }
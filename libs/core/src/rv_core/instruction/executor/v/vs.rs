@@ -1,28 +1,24 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::store_element;
+
 pub fn v(
     Vs { vs3, rs1, vm }: Vs,
     eew: BaseSew,
     x: &IntegerRegisters,
-    v: &VectorContext<'_>,
-    mem: &mut Memory,
-) {
+    v: &mut VectorContext<'_>,
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
 
-    izip!(v.get(vs3).iter_custom_eew(eew), v.default_mask(vm))
-        .enumerate()
-        .for_each(|(index, (vs3, mask))| {
-            let address = addr.wrapping_add(index.wrapping_mul(eew.byte_length()));
-
-            if mask == 1 {
-                match eew {
-                    BaseSew::E8 => mem.set(address, (vs3 as u8).to_le_bytes()),
-                    BaseSew::E16 => mem.set(address, (vs3 as u16).to_le_bytes()),
-                    BaseSew::E32 => mem.set(address, (vs3 as u32).to_le_bytes()),
-                    BaseSew::E64 => mem.set(address, vs3.to_le_bytes()),
-                }
-            }
-        });
+    v.store(vs3, vm, 1, eew, |index, _, value| {
+        store_element(
+            mem,
+            addr.wrapping_add(index * eew.byte_length()),
+            eew,
+            value,
+        )
+    })
 }
 
 // #[cfg(test)]
@@ -5,7 +5,13 @@ pub fn vsetivli(
     x: &mut IntegerRegisters,
     v: &mut VectorContext<'_>,
 ) {
-    v.set_vtype(vtypei as u64).unwrap();
-    unsafe { v.csr[VL].set(uimm as u64) };
-    x[rd] = uimm as u64;
+    if !v.set_vtype(vtypei as u64) {
+        x[rd] = 0;
+        return;
+    }
+
+    let avl = (uimm as u64).min(v.vlmax() as u64);
+
+    unsafe { v.csr[VL].set(avl) };
+    x[rd] = avl;
 }
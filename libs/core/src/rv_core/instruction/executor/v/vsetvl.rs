@@ -5,7 +5,10 @@ pub fn vsetvl(
     x: &mut IntegerRegisters,
     v: &mut VectorContext<'_>,
 ) {
-    v.set_vtype(x[rs2]).unwrap();
+    if !v.set_vtype(x[rs2]) {
+        x[rd] = 0;
+        return;
+    }
 
     let avl = match (rd, rs1) {
         (ZERO, ZERO) => v.csr[VL].read(),
@@ -5,7 +5,10 @@ pub fn vsetvli(
     x: &mut IntegerRegisters,
     v: &mut VectorContext<'_>,
 ) {
-    v.set_vtype(vtypei as u64).unwrap();
+    if !v.set_vtype(vtypei as u64) {
+        x[rd] = 0;
+        return;
+    }
 
     let avl = match (rd, rs1) {
         (ZERO, ZERO) => v.csr[VL].read(),
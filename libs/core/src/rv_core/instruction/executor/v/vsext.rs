@@ -5,7 +5,7 @@ pub fn vf2(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let half_sew = v.vec_engine.sew.half()?;
 
     let vreg = v
@@ -33,7 +33,7 @@ pub fn vf4(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fourth_sew = v.vec_engine.sew.fourth()?;
 
     let vreg = v
@@ -60,7 +60,7 @@ pub fn vf8(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let eighth_sew = v.vec_engine.sew.eighth()?;
 
     let vreg = v
@@ -1,18 +1,18 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::store_element;
+
 pub fn v(
     Vs { vs3, rs1, vm: _ }: Vs,
-    v: &VectorContext<'_>,
+    v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &mut Memory,
-) {
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
+    // One mask bit per element up to vl
+    let evl = (v.csr[VL].read() as usize).div_ceil(8);
 
-    v.get_single(vs3)
-        .iter_byte()
-        .enumerate()
-        .for_each(|(index, vs3)| {
-            let address = addr.wrapping_add(index);
-            mem.set(address, vs3.to_le_bytes());
-        });
+    v.store_whole(vs3, evl, BaseSew::E8, |index, value| {
+        store_element(mem, addr.wrapping_add(index), BaseSew::E8, value)
+    })
 }
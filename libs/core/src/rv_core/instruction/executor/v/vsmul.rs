@@ -1,27 +1,25 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-use super::utils::rounding::Roundoff;
+use super::utils::rounding::{sign_extend, Roundoff};
 
 pub fn vv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
 
-    let int_max = i64::MAX >> (64 - v.vec_engine.sew.bit_length());
-    let int_min = i64::MIN >> (64 - v.vec_engine.sew.bit_length());
+    let sew = v.vec_engine.sew.bit_length();
+    let int_max = i64::MAX >> (64 - sew);
 
     let vec_engine = *v.vec_engine;
 
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |(vs2, vs1)| {
-            let is_overflow = vs2 == vs1 && vs1 == int_min as u64;
+            let product = sign_extend(vs2 as u128, sew).wrapping_mul(sign_extend(vs1 as u128, sew));
+            let result = roundoff_signed(product, sew as u8 - 1) as i128;
 
-            if is_overflow {
+            if result > int_max as i128 {
                 let _ = v.csr[VXSAT].write(1);
                 int_max as u64
             } else {
-                roundoff_signed(
-                    vs2 as u128 * vs1 as u128,
-                    v.vec_engine.sew.bit_length() as u8 - 1,
-                )
+                result as u64
             }
         })
         .collect_with_eew(vec_engine.sew);
@@ -32,8 +30,8 @@ pub fn vv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
 pub fn vx(Opivx { dest, rs1, vs2, vm }: Opivx, v: &mut VectorContext<'_>, x: &IntegerRegisters) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
 
-    let int_max = i64::MAX >> (64 - v.vec_engine.sew.bit_length());
-    let int_min = i64::MIN >> (64 - v.vec_engine.sew.bit_length());
+    let sew = v.vec_engine.sew.bit_length();
+    let int_max = i64::MAX >> (64 - sew);
 
     let vec_engine = *v.vec_engine;
 
@@ -41,16 +39,15 @@ pub fn vx(Opivx { dest, rs1, vs2, vm }: Opivx, v: &mut VectorContext<'_>, x: &In
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            let is_overflow = vs2 == x[rs1] && x[rs1] == int_min as u64;
+            let product =
+                sign_extend(vs2 as u128, sew).wrapping_mul(sign_extend(x[rs1] as u128, sew));
+            let result = roundoff_signed(product, sew as u8 - 1) as i128;
 
-            if is_overflow {
+            if result > int_max as i128 {
                 let _ = v.csr[VXSAT].write(1);
                 int_max as u64
             } else {
-                roundoff_signed(
-                    vs2 as u128 * x[rs1] as u128,
-                    v.vec_engine.sew.bit_length() as u8 - 1,
-                )
+                result as u64
             }
         })
         .collect_with_eew(vec_engine.sew);
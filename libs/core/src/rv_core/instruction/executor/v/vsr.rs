@@ -1,21 +1,18 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::store_element;
+
 pub fn v(
     Vsr { vs3, rs1 }: Vsr,
     nf: usize,
-    v: &VectorContext<'_>,
+    v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &mut Memory,
-) {
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
+    let evl = nf * v.vec_engine.vlen.byte_length();
 
-    for segment in 0..nf {
-        v.get_single(vs3 + segment)
-            .iter_byte()
-            .enumerate()
-            .for_each(|(offset, vs3)| {
-                let address = addr.wrapping_add(offset.wrapping_mul(nf).wrapping_add(segment));
-                mem.set(address, vs3.to_le_bytes());
-            });
-    }
+    v.store_whole(vs3, evl, BaseSew::E8, |index, value| {
+        store_element(mem, addr.wrapping_add(index), BaseSew::E8, value)
+    })
 }
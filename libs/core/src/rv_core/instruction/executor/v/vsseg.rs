@@ -1,38 +1,22 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
+use super::utils::memory::store_element;
+
 pub fn v(
     Vs { vs3, rs1, vm }: Vs,
     eew: BaseSew,
     nf: usize,
-    v: &VectorContext<'_>,
+    v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &mut Memory,
-) {
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     let addr = x[rs1] as usize;
 
-    for segment in 0..nf {
-        izip!(
-            v.get(vs3 + segment).iter_custom_eew(eew),
-            v.default_mask(vm)
-        )
-        .enumerate()
-        .for_each(|(index, (value, mask))| {
-            let segment_size = nf.wrapping_mul(eew.byte_length());
-            let segment_index = segment.wrapping_mul(eew.byte_length());
-
-            let offset = index.wrapping_mul(segment_size).wrapping_add(segment_index);
+    v.store(vs3, vm, nf, eew, |index, field, value| {
+        let address = addr.wrapping_add((index * nf + field) * eew.byte_length());
 
-            let address = addr.wrapping_add(offset);
-            if mask == 1 {
-                match eew {
-                    BaseSew::E8 => mem.set(address, (value as u8).to_le_bytes()),
-                    BaseSew::E16 => mem.set(address, (value as u16).to_le_bytes()),
-                    BaseSew::E32 => mem.set(address, (value as u32).to_le_bytes()),
-                    BaseSew::E64 => mem.set(address, value.to_le_bytes()),
-                };
-            }
-        });
-    }
+        store_element(mem, address, eew, value)
+    })
 }
 
 // #[cfg(test)]
@@ -1,13 +1,20 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-use super::utils::{rounding::Roundoff, shamt::shamt};
+use super::utils::{
+    rounding::{sign_extend, Roundoff},
+    shamt::shamt,
+};
 
 pub fn vv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
+    let sew = v.vec_engine.sew.bit_length();
 
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |(vs2, vs1)| {
-            roundoff_signed(vs2 as u128, shamt(vs1, v.vec_engine.sew) as u8)
+            roundoff_signed(
+                sign_extend(vs2 as u128, sew),
+                shamt(vs1, v.vec_engine.sew) as u8,
+            ) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
@@ -16,26 +23,42 @@ pub fn vv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
 
 pub fn vx(Opivx { dest, rs1, vs2, vm }: Opivx, v: &mut VectorContext<'_>, x: &IntegerRegisters) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
+    let sew = v.vec_engine.sew.bit_length();
 
     let vreg = v
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            roundoff_signed(vs2 as u128, shamt(x[rs1], v.vec_engine.sew) as u8)
+            roundoff_signed(
+                sign_extend(vs2 as u128, sew),
+                shamt(x[rs1], v.vec_engine.sew) as u8,
+            ) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
     v.apply(dest, vreg);
 }
 
-pub fn vi(Opivi { dest, imm5, vs2, vm }: Opivi, v: &mut VectorContext<'_>) {
+pub fn vi(
+    Opivi {
+        dest,
+        imm5,
+        vs2,
+        vm,
+    }: Opivi,
+    v: &mut VectorContext<'_>,
+) {
     let roundoff_signed = Roundoff::new_signed(v.csr);
+    let sew = v.vec_engine.sew.bit_length();
 
     let vreg = v
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            roundoff_signed(vs2 as u128, shamt(imm5 as u64, v.vec_engine.sew) as u8)
+            roundoff_signed(
+                sign_extend(vs2 as u128, sew),
+                shamt(imm5 as u64, v.vec_engine.sew) as u8,
+            ) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
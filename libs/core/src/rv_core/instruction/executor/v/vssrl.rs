@@ -7,7 +7,7 @@ pub fn vv(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) {
 
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |(vs2, vs1)| {
-            roundoff_unsigned(vs2 as u128, shamt(vs1, v.vec_engine.sew) as u8)
+            roundoff_unsigned(vs2 as u128, shamt(vs1, v.vec_engine.sew) as u8) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
@@ -21,21 +21,29 @@ pub fn vx(Opivx { dest, rs1, vs2, vm }: Opivx, v: &mut VectorContext<'_>, x: &In
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            roundoff_unsigned(vs2 as u128, shamt(x[rs1], v.vec_engine.sew) as u8)
+            roundoff_unsigned(vs2 as u128, shamt(x[rs1], v.vec_engine.sew) as u8) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
     v.apply(dest, vreg);
 }
 
-pub fn vi(Opivi { dest, imm5, vs2, vm }: Opivi, v: &mut VectorContext<'_>) {
+pub fn vi(
+    Opivi {
+        dest,
+        imm5,
+        vs2,
+        vm,
+    }: Opivi,
+    v: &mut VectorContext<'_>,
+) {
     let roundoff_unsigned = Roundoff::new_unsigned(v.csr);
 
     let vreg = v
         .get(vs2)
         .iter_eew()
         .masked_map(v.default_mask(vm), v.get(dest).iter_eew(), |vs2| {
-            roundoff_unsigned(vs2 as u128, shamt(imm5 as u64, v.vec_engine.sew) as u8)
+            roundoff_unsigned(vs2 as u128, shamt(imm5 as u64, v.vec_engine.sew) as u8) as u64
         })
         .collect_with_eew(v.vec_engine.sew);
 
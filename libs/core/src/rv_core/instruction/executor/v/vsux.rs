@@ -1,5 +1,11 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn v(vsx: Vsx, eew: BaseSew, v: &VectorContext<'_>, x: &IntegerRegisters, mem: &mut Memory) {
+pub fn v(
+    vsx: Vsx,
+    eew: BaseSew,
+    v: &mut VectorContext<'_>,
+    x: &IntegerRegisters,
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     super::vsox::v(vsx, eew, v, x, mem)
 }
@@ -4,9 +4,9 @@ pub fn v(
     vsx: Vsx,
     eew: BaseSew,
     nf: usize,
-    v: &VectorContext<'_>,
+    v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-    mem: &mut Memory,
-) {
+    mem: &mut Mmu,
+) -> Result<(), Exception> {
     super::vsoxseg::v(vsx, eew, nf, v, x, mem)
 }
@@ -9,7 +9,7 @@ pub fn vx(
     }: Opmvx,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_eew(), v.get_wide(vd)?.iter_eew())
         .masked_map(
             v.default_mask(vm),
@@ -22,7 +22,7 @@ pub fn vx(
         )
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
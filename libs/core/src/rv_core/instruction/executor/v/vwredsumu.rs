@@ -1,6 +1,6 @@
 use crate::rv_core::instruction::executor::prelude::*;
 
-pub fn vs(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Result<(), String> {
+pub fn vs(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Result<(), Exception> {
     let initial_value = v.get_wide(vs1)?.iter_eew().next().unwrap();
     let sum = izip!(v.get(vs2).iter_eew(), v.default_mask(vm))
         .fold(initial_value, |acc, (vs2, mask)| {
@@ -12,7 +12,7 @@ pub fn vs(Opivv { dest, vs1, vs2, vm }: Opivv, v: &mut VectorContext<'_>) -> Res
 
     let vreg = vd_data.into_iter().collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(dest, vreg);
+    v.apply_scalar_wide(dest, vreg);
 
     Ok(())
 }
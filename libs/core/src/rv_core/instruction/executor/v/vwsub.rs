@@ -8,7 +8,7 @@ pub fn vv(
         vm,
     }: Opmvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get(vs2).iter_eew(), v.get(vs1).iter_eew())
         .masked_map(
             v.default_mask(vm),
@@ -17,7 +17,7 @@ pub fn vv(
         )
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -31,7 +31,7 @@ pub fn vx(
     }: Opmvx,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get(vs2)
         .iter_eew()
@@ -40,7 +40,7 @@ pub fn vx(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -53,7 +53,7 @@ pub fn wv(
         vm,
     }: Opmvv,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = izip!(v.get_wide(vs2)?.iter_eew(), v.get(vs1).iter_eew())
         .masked_map(
             v.default_mask(vm),
@@ -62,7 +62,7 @@ pub fn wv(
         )
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
@@ -76,7 +76,7 @@ pub fn wx(
     }: Opmvx,
     v: &mut VectorContext<'_>,
     x: &IntegerRegisters,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let vreg = v
         .get_wide(vs2)?
         .iter_eew()
@@ -85,7 +85,7 @@ pub fn wx(
         })
         .collect_with_wide_eew(v.vec_engine.sew);
 
-    v.apply(vd, vreg);
+    v.apply_wide(vd, vreg)?;
 
     Ok(())
 }
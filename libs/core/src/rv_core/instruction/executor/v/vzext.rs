@@ -5,7 +5,7 @@ pub fn vf2(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let half_sew = v.vec_engine.sew.half()?;
 
     let vreg = v
@@ -25,7 +25,7 @@ pub fn vf4(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let fourth_sew = v.vec_engine.sew.fourth()?;
 
     let vreg = v
@@ -45,7 +45,7 @@ pub fn vf8(
         dest: vd, vs2, vm, ..
     }: Vxunary0,
     v: &mut VectorContext<'_>,
-) -> Result<(), String> {
+) -> Result<(), Exception> {
     let eighth = v.vec_engine.sew.eighth()?;
 
     let vreg = v
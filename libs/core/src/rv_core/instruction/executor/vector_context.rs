@@ -1,105 +1,394 @@
-use std::collections::VecDeque;
+use std::{cell::Cell, collections::VecDeque};
 
 use crate::rv_core::{
     registers::{CsrRegisters, VectorRegisters},
-    vector_engine::VectorEngine,
+    vector_engine::{MaskBehavior, VectorEngine},
 };
 
 use super::prelude::{
     aliases::csr::{VL, VSTART, VTYPE},
     vector::{Vreg, WideVreg},
-    BaseSew, Lmul, Sew,
+    BaseSew, Exception, IterEEWCollectorExt, Lmul, Sew, VILL,
 };
 
 pub struct VectorContext<'c> {
     pub v: &'c mut VectorRegisters,
     pub csr: &'c mut CsrRegisters,
     pub vec_engine: &'c mut VectorEngine,
+    /// Set once the instruction asks for its v0 mask, so write-back knows about inactive elements
+    pub(super) masked: Cell<bool>,
 }
 
 impl VectorContext<'_> {
-    fn start_ptr(&self, nth: usize) -> usize {
-        let vstart = self.csr[VSTART].read() as usize * self.vec_engine.sew.byte_length();
+    // Element width is given in bytes, widened operands use 2 * SEW
+    fn start_ptr(&self, nth: usize, eewb: usize) -> usize {
+        let vstart = self.csr[VSTART].read() as usize * eewb;
         let vlen = self.vec_engine.vlen.byte_length();
         nth * vlen + vstart
     }
 
-    fn end_ptr(&self, nth: usize, lmul: Lmul) -> usize {
+    fn end_ptr(&self, nth: usize, lmul: Lmul, eewb: usize) -> usize {
         let sewb = self.vec_engine.sew.byte_length();
 
-        let vstart = self.csr[VSTART].read() as usize * sewb;
+        let vstart = self.csr[VSTART].read() as usize * eewb;
         let vlbmax = self.vlmax_custom_emul(lmul) * sewb;
-        let vlb = self.csr[VL].read() as usize * sewb;
+        let vlb = self.csr[VL].read() as usize * eewb;
 
-        let start = self.start_ptr(nth);
+        let start = self.start_ptr(nth, eewb);
 
         start + usize::min(vlbmax, vlb) - vstart
     }
 
-    fn register_view_with_lmul(&self, nth: usize, lmul: Lmul) -> impl Iterator<Item = u8> + '_ {
-        let start = self.start_ptr(nth);
-        let end = self.end_ptr(nth, lmul);
-
-        self.v.0[start..end].iter().copied()
+    fn register_view_with_lmul(
+        &self,
+        nth: usize,
+        lmul: Lmul,
+        eewb: usize,
+    ) -> impl Iterator<Item = u8> + '_ {
+        let start = self.start_ptr(nth, eewb);
+        // Views are empty once vstart is past vl
+        let end = self.end_ptr(nth, lmul, eewb).max(start);
+
+        self.v
+            .bytes()
+            .get(start..end)
+            .unwrap_or_default()
+            .iter()
+            .copied()
     }
 
     fn register_view(&self, nth: usize) -> impl Iterator<Item = u8> + '_ {
-        self.register_view_with_lmul(nth, self.vec_engine.lmul)
+        self.register_view_with_lmul(nth, self.vec_engine.lmul, self.vec_engine.sew.byte_length())
     }
 
     pub fn get(&self, nth: usize) -> Vreg {
         Vreg::new(self.register_view(nth).collect(), self.vec_engine.sew)
     }
 
-    fn wide_register_view(&self, nth: usize) -> Result<impl Iterator<Item = u8> + '_, String> {
-        Ok(self.register_view_with_lmul(nth, self.vec_engine.lmul.double()?))
+    fn wide_register_view(&self, nth: usize) -> Result<impl Iterator<Item = u8> + '_, Exception> {
+        Ok(self.register_view_with_lmul(
+            nth,
+            self.vec_engine.lmul.double()?,
+            self.vec_engine.sew.double().byte_length(),
+        ))
     }
 
-    pub fn get_wide(&self, nth: usize) -> Result<WideVreg, String> {
+    pub fn get_wide(&self, nth: usize) -> Result<WideVreg, Exception> {
         Ok(WideVreg::new(
             self.wide_register_view(nth)?.collect(),
             self.vec_engine.sew.double(),
         ))
     }
 
-    fn single_register_view(&self, nth: usize) -> impl Iterator<Item = u8> + '_ {
-        self.register_view_with_lmul(nth, Lmul::M1)
+    /// Reads a register group holding `eew` wide elements, EMUL is scaled from LMUL by EEW / SEW
+    pub fn get_custom_eew(&self, nth: usize, eew: BaseSew) -> Result<Vreg, Exception> {
+        let view = self.register_view_with_lmul(nth, self.emul(eew)?, eew.byte_length());
+
+        Ok(Vreg::new(view.collect(), eew))
     }
 
-    pub fn get_single(&self, nth: usize) -> Vreg {
-        Vreg::new(
-            self.single_register_view(nth).collect(),
-            self.vec_engine.sew,
-        )
+    pub fn apply_custom_eew(
+        &mut self,
+        nth: usize,
+        vreg: Vreg,
+        eew: BaseSew,
+    ) -> Result<(), Exception> {
+        self.apply_with_eew(nth, vreg, self.emul(eew)?, eew.byte_length());
+
+        Ok(())
+    }
+
+    fn emul(&self, eew: BaseSew) -> Result<Lmul, Exception> {
+        let ratio = eew.bit_length() as f32 / self.vec_engine.sew.bit_length() as f32;
+
+        // EMUL has to be in range <1/8, 8>
+        Lmul::try_from(ratio * self.vec_engine.lmul.ratio())
+            .map_err(|_| Exception::IllegalInstruction(0))
+    }
+
+    /// Loads the active segments from vstart up to vl, field `n` goes to the register group at `vd + n * EMUL`.
+    /// `load` gets the element index and field. A fault stops the load and is recorded in vstart,
+    /// so executing the instruction again resumes from the faulting segment.
+    pub fn load(
+        &mut self,
+        vd: usize,
+        vm: bool,
+        nf: usize,
+        eew: BaseSew,
+        mut load: impl FnMut(usize, usize) -> Result<u64, Exception>,
+    ) -> Result<(), Exception> {
+        let vstart = self.csr[VSTART].read() as usize;
+        let vl = self.csr[VL].read() as usize;
+        let group = self.emul(eew)?.multiply(1).max(1);
+
+        let mut loaded = vec![Vec::new(); nf];
+        let mut fault = None;
+
+        'segments: for (index, mask) in (vstart..vl).zip(self.default_mask(vm)) {
+            for (field, values) in loaded.iter_mut().enumerate() {
+                if mask == 0 {
+                    values.push(None);
+                    continue;
+                }
+
+                match load(index, field) {
+                    Ok(value) => values.push(Some(value)),
+                    Err(exception) => {
+                        fault = Some((index, exception));
+                        break 'segments;
+                    }
+                }
+            }
+        }
+
+        for (field, values) in loaded.into_iter().enumerate() {
+            let nth = vd + field * group;
+            let vreg = self
+                .get_custom_eew(nth, eew)?
+                .iter_eew()
+                .zip(values.into_iter().chain(std::iter::repeat(None)))
+                .map(|(old, value)| value.unwrap_or(old))
+                .collect_with_eew(eew);
+
+            self.apply_custom_eew(nth, vreg, eew)?;
+        }
+
+        match fault {
+            Some((index, exception)) => {
+                unsafe { self.csr[VSTART].set(index as u64) };
+                Err(exception)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Stores the active segments from vstart up to vl, field `n` comes from the register group at `vs3 + n * EMUL`.
+    /// `store` gets the element index, field and value. A fault is recorded in vstart like in [`Self::load`].
+    pub fn store(
+        &mut self,
+        vs3: usize,
+        vm: bool,
+        nf: usize,
+        eew: BaseSew,
+        mut store: impl FnMut(usize, usize, u64) -> Result<(), Exception>,
+    ) -> Result<(), Exception> {
+        let vstart = self.csr[VSTART].read() as usize;
+        let vl = self.csr[VL].read() as usize;
+        let group = self.emul(eew)?.multiply(1).max(1);
+
+        let fields: Vec<Vec<u64>> = (0..nf)
+            .map(|field| {
+                Ok(self
+                    .get_custom_eew(vs3 + field * group, eew)?
+                    .iter_eew()
+                    .collect())
+            })
+            .collect::<Result<_, Exception>>()?;
+
+        for (index, mask) in (vstart..vl).zip(self.default_mask(vm)) {
+            if mask == 0 {
+                continue;
+            }
+
+            for (field, values) in fields.iter().enumerate() {
+                if let Err(exception) = store(index, field, values[index - vstart]) {
+                    unsafe { self.csr[VSTART].set(index as u64) };
+                    return Err(exception);
+                }
+            }
+        }
+
+        Ok(())
     }
 
+    /// Loads elements from vstart up to `evl` into the registers from `vd` on, ignoring vtype, vl and v0.
+    /// Whole register and mask loads use it, faults are recorded in vstart the same way as in `load`.
+    pub fn load_whole(
+        &mut self,
+        vd: usize,
+        evl: usize,
+        eew: BaseSew,
+        mut load: impl FnMut(usize) -> Result<u64, Exception>,
+    ) -> Result<(), Exception> {
+        let vstart = self.csr[VSTART].read() as usize;
+        let eewb = eew.byte_length();
+        let start = vd * self.vec_engine.vlen.byte_length();
+
+        for index in vstart..evl {
+            let value = match load(index) {
+                Ok(value) => value,
+                Err(exception) => {
+                    unsafe { self.csr[VSTART].set(index as u64) };
+                    return Err(exception);
+                }
+            };
+
+            let offset = start + index * eewb;
+            self.v
+                .bytes_mut(offset..offset + eewb)
+                .clone_from_slice(&value.to_le_bytes()[..eewb]);
+        }
+
+        Ok(())
+    }
+
+    /// Stores elements from vstart up to `evl` of the registers from `vs3` on, the counterpart of `load_whole`
+    pub fn store_whole(
+        &mut self,
+        vs3: usize,
+        evl: usize,
+        eew: BaseSew,
+        mut store: impl FnMut(usize, u64) -> Result<(), Exception>,
+    ) -> Result<(), Exception> {
+        let vstart = self.csr[VSTART].read() as usize;
+        let eewb = eew.byte_length();
+        let start = vs3 * self.vec_engine.vlen.byte_length();
+
+        for index in vstart..evl {
+            let offset = start + index * eewb;
+            let mut bytes = [0; 8];
+            bytes[..eewb].clone_from_slice(&self.v.bytes()[offset..offset + eewb]);
+
+            if let Err(exception) = store(index, u64::from_le_bytes(bytes)) {
+                unsafe { self.csr[VSTART].set(index as u64) };
+                return Err(exception);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mask bits of v0, starting with the element at vstart
     pub fn default_mask(&self, enabled: bool) -> MaskIterator {
         if enabled {
-            MaskIterator::Exact(self.get(0).iter_mask().collect())
+            self.masked.set(true);
+
+            let vstart = self.csr[VSTART].read() as usize;
+            let bits = self.vec_engine.vlen.bit_length();
+
+            MaskIterator::Exact(
+                (vstart..bits)
+                    .map(|index| (self.v.bytes()[index / 8] >> (index % 8) & 1) as u64)
+                    .collect(),
+            )
         } else {
             MaskIterator::Infinite(std::iter::repeat(1))
         }
     }
 
     pub fn apply(&mut self, nth: usize, vreg: Vreg) {
-        let sewb = self.vec_engine.sew.byte_length();
-        let engine_vlen = self.vlmax() * sewb;
-        let vstart = self.csr[VSTART].read() as usize * sewb;
+        self.apply_with_eew(
+            nth,
+            vreg,
+            self.vec_engine.lmul,
+            self.vec_engine.sew.byte_length(),
+        );
+    }
+
+    /// Writes back a register group holding 2 * SEW wide elements
+    pub fn apply_wide(&mut self, nth: usize, vreg: Vreg) -> Result<(), Exception> {
+        let lmul = self.vec_engine.lmul.double()?;
+        self.apply_with_eew(nth, vreg, lmul, self.vec_engine.sew.double().byte_length());
+
+        Ok(())
+    }
+
+    /// Writes back element 0 of a reduction or scalar move, the rest of the register is tail
+    pub fn apply_scalar(&mut self, nth: usize, vreg: Vreg) {
+        self.apply_scalar_with_eew(nth, vreg, self.vec_engine.sew.byte_length());
+    }
+
+    pub fn apply_scalar_wide(&mut self, nth: usize, vreg: Vreg) {
+        self.apply_scalar_with_eew(nth, vreg, self.vec_engine.sew.double().byte_length());
+    }
 
-        let start = self.start_ptr(nth);
+    fn apply_scalar_with_eew(&mut self, nth: usize, vreg: Vreg, eewb: usize) {
+        if self.csr[VSTART].read() >= self.csr[VL].read() {
+            return;
+        }
+
+        let start = nth * self.vec_engine.vlen.byte_length();
+        self.v
+            .bytes_mut(start..start + eewb)
+            .clone_from_slice(&vreg.raw[0..eewb]);
+
+        self.fill_agnostic(nth, Lmul::M1, eewb, 1, &[]);
+    }
+
+    fn apply_with_eew(&mut self, nth: usize, vreg: Vreg, lmul: Lmul, eewb: usize) {
+        if self.csr[VSTART].read() >= self.csr[VL].read() {
+            return;
+        }
 
-        if vreg.iter_byte().len() >= engine_vlen - vstart {
-            let end = self.end_ptr(nth, self.vec_engine.lmul);
+        // Read before write-back, a mask-producing instruction may overwrite v0
+        let inactive = self.inactive_elements();
+
+        let engine_vlen = self.vlmax_custom_emul(lmul) * self.vec_engine.sew.byte_length();
+        let vstart = self.csr[VSTART].read() as usize * eewb;
+
+        let start = self.start_ptr(nth, eewb);
+
+        if vreg.iter_byte().len() >= engine_vlen.saturating_sub(vstart) {
+            let end = self.end_ptr(nth, lmul, eewb);
             let vreg_length = end - start;
 
-            self.v.0[start..end].clone_from_slice(&vreg.raw[0..vreg_length])
+            self.v
+                .bytes_mut(start..end)
+                .clone_from_slice(&vreg.raw[0..vreg_length])
         } else {
             // Vreg has fractional EMUL or has less elements than VLEN / SEW
             let vreg_length = vreg.raw.len();
             let end = start + vreg_length;
 
-            self.v.0[start..end].clone_from_slice(&vreg.raw[0..vreg_length])
+            self.v
+                .bytes_mut(start..end)
+                .clone_from_slice(&vreg.raw[0..vreg_length])
         }
+
+        let vl = self.csr[VL].read() as usize;
+        self.fill_agnostic(nth, lmul, eewb, vl, &inactive);
+    }
+
+    fn inactive_elements(&self) -> Vec<usize> {
+        if !self.masked.get() || self.vec_engine.inactive_elements == MaskBehavior::Undisturbed {
+            return Vec::new();
+        }
+
+        let vstart = self.csr[VSTART].read() as usize;
+        let vl = self.csr[VL].read() as usize;
+
+        (vstart..vl)
+            .filter(|index| self.v.bytes()[index / 8] >> (index % 8) & 1 == 0)
+            .collect()
+    }
+
+    /// Overwrites agnostic tail and inactive elements, the tail starts after `body` elements
+    fn fill_agnostic(
+        &mut self,
+        nth: usize,
+        lmul: Lmul,
+        eewb: usize,
+        body: usize,
+        inactive: &[usize],
+    ) {
+        let vlen = self.vec_engine.vlen.byte_length();
+        let mut fill = self.vec_engine.agnostic_fill;
+
+        let group_start = nth * vlen;
+        // With fractional LMUL the rest of the register is tail too
+        let group_end = group_start + lmul.multiply(vlen).max(vlen);
+        let tail_start = usize::min(group_start + body * eewb, group_end);
+
+        if self.vec_engine.tail_elements == MaskBehavior::Agnostic {
+            fill.fill(self.v.bytes_mut(tail_start..group_end));
+        }
+
+        for index in inactive {
+            let start = group_start + index * eewb;
+            fill.fill(self.v.bytes_mut(start..start + eewb));
+        }
+
+        self.vec_engine.agnostic_fill = fill;
     }
 
     pub fn vlmax(&self) -> usize {
@@ -134,12 +423,24 @@ impl VectorContext<'_> {
         })
     }
 
-    pub fn set_vtype(&mut self, value: u64) -> Result<(), String> {
-        unsafe { self.csr[VTYPE].set(value) };
+    /// Sets vtype, reserved or unsupported configurations set vill and clear vl instead
+    pub fn set_vtype(&mut self, value: u64) -> bool {
+        let supported = value & VILL == 0 && self.configure(value).is_ok();
+
+        unsafe { self.csr[VTYPE].set(if supported { value } else { VILL }) };
+
+        if !supported {
+            unsafe { self.csr[VL].set(0) };
+        }
+
+        supported
+    }
 
+    /// Engine is only updated once the whole configuration is known to be valid
+    fn configure(&mut self, value: u64) -> Result<(), String> {
         let raw_vtype = Self::decompose_vtype(value)?;
 
-        self.vec_engine.lmul = match raw_vtype.vlmul {
+        let lmul = match raw_vtype.vlmul {
             0b100 => return Err(String::from("vlmul=100 is reserved")),
             0b101 => Lmul::MF8,
             0b110 => Lmul::MF4,
@@ -151,7 +452,7 @@ impl VectorContext<'_> {
             _ => unreachable!(),
         };
 
-        self.vec_engine.sew = match raw_vtype.vsew {
+        let sew = match raw_vtype.vsew {
             0b000 => BaseSew::E8,
             0b001 => BaseSew::E16,
             0b010 => BaseSew::E32,
@@ -160,6 +461,16 @@ impl VectorContext<'_> {
             _ => unreachable!(),
         };
 
+        if !self.vec_engine.supports_vtype(sew, lmul) {
+            return Err(format!(
+                "vsew={:03b} with vlmul={:03b} isn't supported",
+                raw_vtype.vsew, raw_vtype.vlmul
+            ));
+        }
+
+        self.vec_engine.lmul = lmul;
+        self.vec_engine.sew = sew;
+
         use super::prelude::MaskBehavior::*;
         self.vec_engine.tail_elements = if raw_vtype.vta { Agnostic } else { Undisturbed };
         self.vec_engine.inactive_elements = if raw_vtype.vma { Agnostic } else { Undisturbed };
@@ -168,12 +479,6 @@ impl VectorContext<'_> {
     }
 }
 
-impl Drop for VectorContext<'_> {
-    fn drop(&mut self) {
-        unsafe { self.csr[VSTART].set(0) };
-    }
-}
-
 #[derive(Debug, PartialEq)]
 struct RawVType {
     pub vlmul: u8,
@@ -200,7 +505,8 @@ impl Iterator for MaskIterator {
 
 #[cfg(test)]
 mod tests {
-    use super::{RawVType, VectorContext};
+    use super::*;
+    use crate::rv_core::vector_engine::VectorExtension;
 
     #[test]
     fn vtype_parsing() {
@@ -218,4 +524,32 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn rejected_vtypes_leave_engine_untouched() {
+        let mut vec_engine = VectorEngine {
+            extension: VectorExtension::Zve32x,
+            ..Default::default()
+        };
+        let mut v = VectorRegisters::default(&vec_engine);
+        let mut csr = CsrRegisters::new(&vec_engine);
+        let mut vctx = VectorContext {
+            v: &mut v,
+            csr: &mut csr,
+            vec_engine: &mut vec_engine,
+            masked: Cell::new(false),
+        };
+
+        // e64 isn't supported by Zve32x, the second one has a reserved vsew
+        for vtype in [0b011_001, 0b100_011] {
+            assert!(!vctx.set_vtype(vtype));
+            assert_eq!(vctx.vec_engine.sew, BaseSew::E8);
+            assert_eq!(vctx.vec_engine.lmul, Lmul::M1);
+            assert_eq!(vctx.csr[VTYPE].read(), VILL);
+        }
+
+        assert!(vctx.set_vtype(0b010_001));
+        assert_eq!(vctx.vec_engine.sew, BaseSew::E32);
+        assert_eq!(vctx.vec_engine.lmul, Lmul::M2);
+    }
 }
@@ -0,0 +1,17 @@
+mod adduw;
+mod sh1add;
+mod sh1adduw;
+mod sh2add;
+mod sh2adduw;
+mod sh3add;
+mod sh3adduw;
+mod slliuw;
+
+pub use adduw::adduw;
+pub use sh1add::sh1add;
+pub use sh1adduw::sh1adduw;
+pub use sh2add::sh2add;
+pub use sh2adduw::sh2adduw;
+pub use sh3add::sh3add;
+pub use sh3adduw::sh3adduw;
+pub use slliuw::slliuw;
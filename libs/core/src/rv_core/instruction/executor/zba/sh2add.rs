@@ -0,0 +1,5 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn sh2add(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    x[rd] = x[rs2].wrapping_add(x[rs1] << 2);
+}
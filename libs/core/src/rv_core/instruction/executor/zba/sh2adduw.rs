@@ -0,0 +1,28 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn sh2adduw(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    x[rd] = x[rs2].wrapping_add((x[rs1] as u32 as u64) << 2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sh2adduw_ignores_upper_index_bits() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        x[11] = 0xffff_ffff_0000_0003;
+        x[12] = 0x1000;
+
+        sh2adduw(
+            R {
+                rd: 10,
+                rs1: 11,
+                rs2: 12,
+            },
+            &mut x,
+        );
+
+        assert_eq!(x[10], 0x100c);
+    }
+}
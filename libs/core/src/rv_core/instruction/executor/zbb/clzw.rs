@@ -0,0 +1,5 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn clzw(R { rd, rs1, .. }: R, x: &mut IntegerRegisters) {
+    x[rd] = (x[rs1] as u32).leading_zeros() as u64;
+}
@@ -0,0 +1,5 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn cpop(R { rd, rs1, .. }: R, x: &mut IntegerRegisters) {
+    x[rd] = x[rs1].count_ones() as u64;
+}
@@ -0,0 +1,5 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn min(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    x[rd] = (x[rs1] as i64).min(x[rs2] as i64) as u64;
+}
@@ -0,0 +1,49 @@
+mod andn;
+mod clz;
+mod clzw;
+mod cpop;
+mod cpopw;
+mod ctz;
+mod ctzw;
+mod max;
+mod maxu;
+mod min;
+mod minu;
+mod orcb;
+mod orn;
+mod rev8;
+mod rol;
+mod rolw;
+mod ror;
+mod rori;
+mod roriw;
+mod rorw;
+mod sextb;
+mod sexth;
+mod xnor;
+mod zexth;
+
+pub use andn::andn;
+pub use clz::clz;
+pub use clzw::clzw;
+pub use cpop::cpop;
+pub use cpopw::cpopw;
+pub use ctz::ctz;
+pub use ctzw::ctzw;
+pub use max::max;
+pub use maxu::maxu;
+pub use min::min;
+pub use minu::minu;
+pub use orcb::orcb;
+pub use orn::orn;
+pub use rev8::rev8;
+pub use rol::rol;
+pub use rolw::rolw;
+pub use ror::ror;
+pub use rori::rori;
+pub use roriw::roriw;
+pub use rorw::rorw;
+pub use sextb::sextb;
+pub use sexth::sexth;
+pub use xnor::xnor;
+pub use zexth::zexth;
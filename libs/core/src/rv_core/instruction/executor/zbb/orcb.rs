@@ -0,0 +1,30 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn orcb(R { rd, rs1, .. }: R, x: &mut IntegerRegisters) {
+    let bytes = x[rs1]
+        .to_le_bytes()
+        .map(|byte| if byte == 0 { 0 } else { 0xff });
+    x[rd] = u64::from_le_bytes(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orcb_fills_nonzero_bytes() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        x[11] = 0x0080_0000_0100_0a00;
+
+        orcb(
+            R {
+                rd: 10,
+                rs1: 11,
+                rs2: 0,
+            },
+            &mut x,
+        );
+
+        assert_eq!(x[10], 0x00ff_0000_ff00_ff00);
+    }
+}
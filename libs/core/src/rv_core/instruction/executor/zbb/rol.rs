@@ -0,0 +1,6 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn rol(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    let shamt = x[rs2] & 0b111111;
+    x[rd] = x[rs1].rotate_left(shamt as u32);
+}
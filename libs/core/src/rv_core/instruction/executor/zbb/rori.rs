@@ -0,0 +1,6 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn rori(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters) {
+    let shamt = imm12 & 0b111111;
+    x[rd] = x[rs1].rotate_right(shamt as u32);
+}
@@ -0,0 +1,6 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn roriw(I { rd, rs1, imm12 }: I, x: &mut IntegerRegisters) {
+    let shamt = imm12 & 0b11111;
+    x[rd] = (x[rs1] as u32).rotate_right(shamt as u32) as i32 as u64;
+}
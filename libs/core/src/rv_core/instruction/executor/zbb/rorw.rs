@@ -0,0 +1,6 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn rorw(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    let shamt = x[rs2] & 0b11111;
+    x[rd] = (x[rs1] as u32).rotate_right(shamt as u32) as i32 as u64;
+}
@@ -0,0 +1,29 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn bext(R { rd, rs1, rs2 }: R, x: &mut IntegerRegisters) {
+    let index = x[rs2] & 0b111111;
+    x[rd] = (x[rs1] >> index) & 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bext_wraps_index() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        x[11] = 1 << 63;
+        x[12] = 127;
+
+        bext(
+            R {
+                rd: 10,
+                rs1: 11,
+                rs2: 12,
+            },
+            &mut x,
+        );
+
+        assert_eq!(x[10], 1);
+    }
+}
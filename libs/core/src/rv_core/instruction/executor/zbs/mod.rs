@@ -0,0 +1,17 @@
+mod bclr;
+mod bclri;
+mod bext;
+mod bexti;
+mod binv;
+mod binvi;
+mod bset;
+mod bseti;
+
+pub use bclr::bclr;
+pub use bclri::bclri;
+pub use bext::bext;
+pub use bexti::bexti;
+pub use binv::binv;
+pub use binvi::binvi;
+pub use bset::bset;
+pub use bseti::bseti;
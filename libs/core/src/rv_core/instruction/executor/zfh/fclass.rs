@@ -0,0 +1,7 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
+    let fs1 = f.half(rs1);
+
+    x[rd] = softfloat::classify(fs1);
+}
@@ -0,0 +1,268 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+/// Widening is exact, only NaNs are affected
+pub fn sh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm: _,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let fs1 = f.half(rs1);
+
+    let (float, flags) = softfloat::convert(fs1, softfloat::RoundingMode::Rne);
+    c.accrue(flags);
+    f.set_single(rd, float);
+}
+
+pub fn hs(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.single(rs1);
+
+    let (float, flags) = softfloat::convert(fs1, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+pub fn dh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm: _,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let fs1 = f.half(rs1);
+
+    let (float, flags) = softfloat::convert(fs1, softfloat::RoundingMode::Rne);
+    c.accrue(flags);
+    f.set_double(rd, float);
+}
+
+pub fn hd(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::convert(f.double(rs1), c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+pub fn wh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, true, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
+}
+
+pub fn wuh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, false, 32, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
+}
+
+pub fn hw(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_i64(x[rs1] as i32 as i64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+pub fn hwu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_u64(x[rs1] as u32 as u64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+pub fn lh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, true, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
+}
+
+pub fn luh(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+
+    let (integer, flags) = softfloat::to_int(fs1, false, 64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    x[rd] = integer;
+
+    Ok(())
+}
+
+pub fn hl(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_i64(x[rs1] as i64, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+pub fn hlu(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let (float, flags) = softfloat::from_u64(x[rs1], c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, float);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::registers::aliases::{
+        fflags::{NX, OF},
+        float::{FA0, FA1},
+    };
+
+    #[test]
+    fn half_conversions_follow_frm() {
+        let mut f = FloatRegisters::default();
+        let mut c = CsrRegisters::default();
+        let dynamic = |rd, rs1| Rfp {
+            rd,
+            rs1,
+            rs2: 0,
+            rm: softfloat::RoundingMode::DYN,
+        };
+
+        // 1 + 2^-11 lies halfway between two halves, ties go to the even one
+        f.set_double(FA0, 1.0 + 2f64.powi(-11));
+        hd(dynamic(FA1, FA0), &mut f, &mut c).unwrap();
+        assert_eq!(f.half(FA1).to_bits(), 0x3c00);
+
+        f.set_double(FA0, 1.0 + 3.0 * 2f64.powi(-11));
+        hd(dynamic(FA1, FA0), &mut f, &mut c).unwrap();
+        assert_eq!(f.half(FA1).to_bits(), 0x3c02);
+
+        dh(dynamic(FA0, FA1), &mut f, &mut c);
+        assert_eq!(f.double(FA0), 1.0 + 2f64.powi(-9));
+        assert_eq!(c.read(FFLAGS), NX);
+
+        f.set_double(FA0, 65520.0);
+        hd(dynamic(FA1, FA0), &mut f, &mut c).unwrap();
+        assert!(f.half(FA1).is_infinite());
+
+        // Rounding towards zero saturates at the largest finite half
+        c.write(FRM, 0b001).unwrap();
+        hd(dynamic(FA1, FA0), &mut f, &mut c).unwrap();
+        assert_eq!(f.half(FA1), f16::MAX);
+        assert_eq!(c.read(FFLAGS), OF | NX);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn flh(
+    I { rd, rs1, imm12 }: I,
+    x: &IntegerRegisters,
+    f: &mut FloatRegisters,
+    mem: &Mmu,
+) -> Result<(), Exception> {
+    let addr = x[rs1].wrapping_add(imm12 as u64);
+    let fp = f16::from_le_bytes(mem.get(addr as usize)?);
+
+    f.set_half(rd, fp);
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(
+    R { rd, rs1, rs2 }: R,
+    x: &mut IntegerRegisters,
+    f: &FloatRegisters,
+    c: &mut CsrRegisters,
+) {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+
+    let (holds, flags) = softfloat::lt(fs1, fs2);
+    c.accrue(flags);
+    x[rd] = holds as u64;
+}
@@ -0,0 +1,10 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters, c: &mut CsrRegisters) {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+
+    let (max, flags) = softfloat::max(fs1, fs2);
+    c.accrue(flags);
+    f.set_half(rd, max);
+}
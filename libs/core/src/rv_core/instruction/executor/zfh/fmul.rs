@@ -0,0 +1,16 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(
+    Rfp { rd, rs1, rs2, rm }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+
+    let (product, flags) = softfloat::mul(fs1, fs2, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, product);
+
+    Ok(())
+}
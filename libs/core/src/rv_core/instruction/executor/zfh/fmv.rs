@@ -0,0 +1,9 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn xh(R { rd, rs1, rs2: _ }: R, x: &mut IntegerRegisters, f: &FloatRegisters) {
+    x[rd] = f[rs1] as i16 as u64;
+}
+
+pub fn hx(R { rd, rs1, rs2: _ }: R, x: &IntegerRegisters, f: &mut FloatRegisters) {
+    f.set_half(rd, f16::from_bits(x[rs1] as u16));
+}
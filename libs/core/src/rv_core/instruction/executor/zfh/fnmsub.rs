@@ -0,0 +1,23 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(
+    R4 {
+        rd,
+        rs1,
+        rs2,
+        rs3,
+        rm,
+    }: R4,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+    let fs3 = f.half(rs3);
+
+    let (result, flags) = softfloat::mul_add(-fs1, fs2, fs3, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, result);
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+
+    f.set_half(rd, fs1.copysign(fs2));
+}
@@ -0,0 +1,10 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(R { rd, rs1, rs2 }: R, f: &mut FloatRegisters) {
+    let fs1 = f.half(rs1);
+    let fs2 = f.half(rs2);
+
+    let sign = (fs1.to_bits() ^ fs2.to_bits()) & 0x8000;
+
+    f.set_half(rd, f16::from_bits(fs1.to_bits() & 0x7fff | sign));
+}
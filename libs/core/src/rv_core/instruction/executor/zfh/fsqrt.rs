@@ -0,0 +1,20 @@
+use crate::rv_core::instruction::executor::prelude::*;
+
+pub fn h(
+    Rfp {
+        rd,
+        rs1,
+        rs2: _,
+        rm,
+    }: Rfp,
+    f: &mut FloatRegisters,
+    c: &mut CsrRegisters,
+) -> Result<(), Exception> {
+    let fs1 = f.half(rs1);
+
+    let (root, flags) = softfloat::sqrt(fs1, c.rounding_mode(rm)?);
+    c.accrue(flags);
+    f.set_half(rd, root);
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+pub mod fadd;
+pub mod fclass;
+pub mod fcvt;
+pub mod fdiv;
+pub mod feq;
+pub mod fle;
+mod flh;
+pub mod flt;
+pub mod fmadd;
+pub mod fmax;
+pub mod fmin;
+pub mod fmsub;
+pub mod fmul;
+pub mod fmv;
+pub mod fnmadd;
+pub mod fnmsub;
+pub mod fsgnj;
+pub mod fsgnjn;
+pub mod fsgnjx;
+mod fsh;
+pub mod fsqrt;
+pub mod fsub;
+
+pub use flh::flh;
+pub use fsh::fsh;
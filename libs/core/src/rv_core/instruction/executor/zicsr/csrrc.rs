@@ -4,14 +4,42 @@ pub fn csrrc(
     Csrr { rd, rs1, csr }: Csrr,
     x: &mut IntegerRegisters,
     c: &mut CsrRegisters,
-) -> Result<(), String> {
-    let csr_value = c[csr].read();
-    x[rd] = csr_value;
+    mode: PrivilegeMode,
+) -> Result<(), Exception> {
+    c.check_access(csr, mode)?;
+
+    let csr_value = c.read(csr);
 
+    // rs1 is read before rd is written, the two may be the same register
     if rs1 != ZERO {
         let clear_mask = x[rs1];
-        c[csr].write(csr_value & !clear_mask)?;
+        c.write(csr, csr_value & !clear_mask)?;
     }
 
+    x[rd] = csr_value;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rd_can_be_rs1() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        let mut c = CsrRegisters::default();
+        let mode = PrivilegeMode::Machine;
+
+        c[MSCRATCH].write(0xff).unwrap();
+        x[A0] = 0x0f;
+        let clear = Csrr {
+            rd: A0,
+            rs1: A0,
+            csr: MSCRATCH,
+        };
+        csrrc(clear, &mut x, &mut c, mode).unwrap();
+        assert_eq!(c[MSCRATCH].read(), 0xf0);
+        assert_eq!(x[A0], 0xff);
+    }
+}
@@ -4,12 +4,19 @@ pub fn csrrci(
     Csri { rd, uimm, csr }: Csri,
     x: &mut IntegerRegisters,
     c: &mut CsrRegisters,
-) -> Result<(), String> {
-    let csr_value = c[csr].read();
-    x[rd] = csr_value;
+    mode: PrivilegeMode,
+) -> Result<(), Exception> {
+    c.check_access(csr, mode)?;
+
+    let csr_value = c.read(csr);
 
-    let clear_mask = uimm as u64;
-    c[csr].write(csr_value & !clear_mask)?;
+    // Zero immediate doesn't write, so read-only CSRs can be read this way
+    if uimm != 0 {
+        let clear_mask = uimm as u64;
+        c.write(csr, csr_value & !clear_mask)?;
+    }
+
+    x[rd] = csr_value;
 
     Ok(())
 }
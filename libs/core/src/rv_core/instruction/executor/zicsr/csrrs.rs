@@ -4,14 +4,51 @@ pub fn csrrs(
     Csrr { rd, rs1, csr }: Csrr,
     x: &mut IntegerRegisters,
     c: &mut CsrRegisters,
-) -> Result<(), String> {
-    let csr_value = c[csr].read();
-    x[rd] = csr_value;
+    mode: PrivilegeMode,
+) -> Result<(), Exception> {
+    c.check_access(csr, mode)?;
+
+    let csr_value = c.read(csr);
 
+    // rs1 is read before rd is written, the two may be the same register
     if rs1 != ZERO {
-        let clear_mask = x[rs1];
-        c[csr].write(csr_value | clear_mask)?;
+        let set_mask = x[rs1];
+        c.write(csr, csr_value | set_mask)?;
     }
 
+    x[rd] = csr_value;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rd_can_be_rs1() {
+        let mut x = IntegerRegisters::new(&Memory::default());
+        let mut c = CsrRegisters::default();
+        let mode = PrivilegeMode::Machine;
+
+        x[A0] = 5;
+        let set = Csrr {
+            rd: A0,
+            rs1: A0,
+            csr: MSCRATCH,
+        };
+        csrrs(set, &mut x, &mut c, mode).unwrap();
+        assert_eq!(c[MSCRATCH].read(), 5);
+        assert_eq!(x[A0], 0);
+
+        // Failed write leaves rd untouched
+        x[A0] = 1;
+        let read_only = Csrr {
+            rd: A0,
+            rs1: A0,
+            csr: MVENDORID,
+        };
+        assert!(csrrs(read_only, &mut x, &mut c, mode).is_err());
+        assert_eq!(x[A0], 1);
+    }
+}
@@ -4,9 +4,12 @@ pub fn csrrw(
     Csrr { rd, rs1, csr }: Csrr,
     x: &mut IntegerRegisters,
     c: &mut CsrRegisters,
-) -> Result<(), String> {
-    let csr_value = c[csr].read();
-    c[csr].write(x[rs1])?;
+    mode: PrivilegeMode,
+) -> Result<(), Exception> {
+    c.check_access(csr, mode)?;
+
+    let csr_value = c.read(csr);
+    c.write(csr, x[rs1])?;
     x[rd] = csr_value;
 
     Ok(())
@@ -4,9 +4,12 @@ pub fn csrrwi(
     Csri { rd, uimm, csr }: Csri,
     x: &mut IntegerRegisters,
     c: &mut CsrRegisters,
-) -> Result<(), String> {
-    let csr_value = c[csr].read();
-    c[csr].write(uimm as u64)?;
+    mode: PrivilegeMode,
+) -> Result<(), Exception> {
+    c.check_access(csr, mode)?;
+
+    let csr_value = c.read(csr);
+    c.write(csr, uimm as u64)?;
     x[rd] = csr_value;
 
     Ok(())
@@ -1,8 +1,10 @@
+mod atomic;
 mod base;
 mod csr;
 mod float;
 mod vector;
 
+pub use atomic::*;
 pub use base::*;
 pub use csr::*;
 pub use float::*;
@@ -0,0 +1,10 @@
+/// Address is held in rs1, lr leaves rs2 as 0.
+/// Ordering bits are kept for encoding, a single hart observes its accesses in program order anyway.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Atomic {
+    pub rd: usize,
+    pub rs1: usize,
+    pub rs2: usize,
+    pub aq: bool,
+    pub rl: bool,
+}
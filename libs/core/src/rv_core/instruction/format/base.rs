@@ -24,3 +24,14 @@ pub struct U {
     pub rd: usize,
     pub imm20: i32,
 }
+
+/// Predecessor and successor sets, bits from the highest are I, O, R and W.
+/// Fence mode and the unused register fields are kept so the word encodes back.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fence {
+    pub fm: u32,
+    pub pred: u32,
+    pub succ: u32,
+    pub rs1: usize,
+    pub rd: usize,
+}
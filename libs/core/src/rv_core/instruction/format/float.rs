@@ -4,4 +4,14 @@ pub struct R4 {
     pub rs1: usize,
     pub rs2: usize,
     pub rs3: usize,
+    pub rm: u32,
+}
+
+/// R-type with a rounding mode in place of funct3
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rfp {
+    pub rd: usize,
+    pub rs1: usize,
+    pub rs2: usize,
+    pub rm: u32,
 }
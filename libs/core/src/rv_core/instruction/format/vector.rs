@@ -165,7 +165,7 @@ pub struct Opfvf {
     pub vm: bool,
 }
 
-/// Other encoding spaces
+// Other encoding spaces
 
 // VRXUNARY0 - OPMVX with vs2 as function opcode
 pub type Vrxunary0 = Opmvx;
@@ -1,63 +1,472 @@
-use std::collections::VecDeque;
+use std::{cell::RefCell, collections::BTreeMap};
 
-use super::snapshot::Snapshotable;
+use super::{
+    device::SharedDevice, history::Journal, snapshot::Snapshotable, trace::MemoryAccess,
+    trap::Exception,
+};
 
+const PAGE_SIZE: u64 = 0x1000;
+
+type Page = Box<[u8; PAGE_SIZE as usize]>;
+
+/// Accesses a region allows
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Permissions {
+    pub const RWX: Self = Self::new(true, true, true);
+    pub const RW: Self = Self::new(true, true, false);
+    pub const RX: Self = Self::new(true, false, true);
+    pub const R: Self = Self::new(true, false, false);
+
+    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
+        Self {
+            read,
+            write,
+            execute,
+        }
+    }
+}
+
+/// Named range of memory, such as text, rodata, data, heap or stack
+#[derive(Clone, PartialEq, Debug)]
+pub struct Region {
+    pub name: String,
+    pub start: u64,
+    pub size: u64,
+    pub permissions: Permissions,
+}
+
+impl Region {
+    pub fn new(name: impl Into<String>, start: u64, size: u64, permissions: Permissions) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            size,
+            permissions,
+        }
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address.wrapping_sub(self.start) < self.size
+    }
+
+    /// Address of the last byte, regions may reach the top of the address space
+    fn last(&self) -> u64 {
+        self.start + (self.size - 1)
+    }
+}
+
+/// Sparse physical memory spanning the whole 64-bit address space.
+/// Pages are allocated on first write and read as zeros until then.
+/// Only accesses that fall in a region with matching permissions succeed, anything else is an access fault.
+/// Regions with a device attached forward loads and stores to it instead of the pages.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Memory {
-    raw: VecDeque<u8>,
-    data_ptr: usize,
+    pages: BTreeMap<u64, Page>,
+    /// Sorted by start address and never overlapping
+    regions: Vec<Region>,
+    devices: Devices,
+    /// Bytes overwritten by stores
+    journal: Journal<(u64, Vec<u8>)>,
+    /// Loads and stores made through the MMU, loads only see `&self`
+    accesses: RefCell<Journal<MemoryAccess>>,
+    data_ptr: u64,
+}
+
+/// Devices by the start of their region
+#[derive(Clone, Default, Debug)]
+struct Devices(BTreeMap<u64, SharedDevice>);
+
+// Device state is owned by the embedder, it doesn't take part in comparing memory contents
+impl PartialEq for Devices {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
 
 impl Snapshotable for Memory {
-    type Snapshot = VecDeque<u8>;
+    type Snapshot = Self;
 
     fn snapshot(&self) -> Self::Snapshot {
-        self.raw.clone()
+        self.clone()
     }
 }
 
 impl Memory {
+    /// Flat memory starting at address 0, all of it readable, writable and executable
     pub fn new(raw: impl ExactSizeIterator<Item = u8>) -> Self {
+        let mut memory = Self::empty();
+        let raw: Vec<u8> = raw.collect();
+
+        memory.resize(raw.len() as u64);
+        memory.load(0, &raw);
+
+        memory
+    }
+
+    /// Memory without any regions, every access faults until regions are mapped
+    pub fn empty() -> Self {
         Self {
-            raw: VecDeque::from_iter(raw),
+            pages: BTreeMap::new(),
+            regions: Vec::new(),
+            devices: Devices::default(),
+            journal: Journal::default(),
+            accesses: RefCell::default(),
             data_ptr: 0,
         }
     }
 
-    pub fn get<const BYTES: usize>(&self, address: usize) -> [u8; BYTES] {
+    pub fn get<const BYTES: usize>(&self, address: u64) -> Result<[u8; BYTES], Exception> {
+        if !self.permits(address, BYTES, |permissions| permissions.read) {
+            return Err(Exception::LoadAccessFault(address));
+        }
+
         let mut bytes = [0; BYTES];
+        self.dispatch_read(address, &mut bytes);
+
+        Ok(bytes)
+    }
 
-        bytes[..BYTES].copy_from_slice(&self.raw.as_slices().0[address..(BYTES + address)]);
+    pub fn set<const BYTES: usize>(
+        &mut self,
+        address: u64,
+        value: [u8; BYTES],
+    ) -> Result<(), Exception> {
+        if !self.permits(address, BYTES, |permissions| permissions.write) {
+            return Err(Exception::StoreAccessFault(address));
+        }
 
-        bytes
+        if self.journal.is_recording() {
+            let old = self.bytes(address, BYTES);
+            self.journal.record((address, old));
+        }
+
+        self.dispatch_write(address, &value);
+
+        Ok(())
     }
 
-    pub fn fallible_get<const BYTES: usize>(&self, address: usize) -> Option<[u8; BYTES]> {
+    /// Reads instruction bytes, the region has to be executable
+    pub fn fetch<const BYTES: usize>(&self, address: u64) -> Result<[u8; BYTES], Exception> {
+        if !self.permits(address, BYTES, |permissions| permissions.execute) {
+            return Err(Exception::InstructionAccessFault(address));
+        }
+
         let mut bytes = [0; BYTES];
+        self.read(address, &mut bytes);
 
-        for (offset, byte_element) in bytes.iter_mut().enumerate().take(BYTES) {
-            let Some(byte) = self.raw.get(address + offset).cloned() else {
-                return None;
-            };
+        Ok(bytes)
+    }
 
-            *byte_element = byte;
+    /// Copies data in the way a loader would, ignoring regions, their permissions and devices
+    pub fn load(&mut self, address: u64, data: &[u8]) {
+        self.write(address, data);
+    }
+
+    /// Zeroes bytes the way a loader would, pages that were never written stay unallocated
+    pub fn zero(&mut self, address: u64, len: u64) {
+        if len > 0 {
+            self.clear(address, address.saturating_add(len - 1));
         }
+    }
+
+    /// Reads bytes the way a debugger would, ignoring regions, their permissions and devices
+    pub fn bytes(&self, address: u64, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        self.read(address, &mut bytes);
+
+        bytes
+    }
 
-        Some(bytes)
+    /// Moves the end of the highest region without a device, a flat region starting at 0 is mapped when there is none.
+    /// Bytes cut off by shrinking read as zeros once the region grows again.
+    pub fn resize(&mut self, len: u64) {
+        let Some(index) = self.highest_region() else {
+            if len > 0 {
+                self.regions
+                    .insert(0, Region::new("memory", 0, len, Permissions::RWX));
+            }
+            return;
+        };
+
+        let region = &mut self.regions[index];
+        let last = region.last();
+        region.size = len.saturating_sub(region.start);
+
+        if region.size == 0 {
+            self.regions.remove(index);
+        }
+
+        if len <= last {
+            self.clear(len, last);
+        }
     }
 
-    pub fn set<const BYTES: usize>(&mut self, address: usize, value: [u8; BYTES]) {
-        self.raw.as_mut_slices().0[address..(BYTES + address)].copy_from_slice(&value[..BYTES]);
+    /// End of the highest region without a device
+    pub fn len(&self) -> u64 {
+        self.highest_region()
+            .map_or(0, |index| self.regions[index].last().saturating_add(1))
     }
 
-    pub fn len(&self) -> usize {
-        self.raw.len()
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn assign(&mut self, data: &[u8]) {
-        self.raw.as_mut_slices().0[self.data_ptr..self.data_ptr + data.len()].copy_from_slice(data);
-        self.data_ptr += data.len();
+        self.load(self.data_ptr, data);
+        self.data_ptr += data.len() as u64;
+    }
+
+    /// Regions that don't fit in the address space, or overlap a mapped one, fault like a store to their first conflicting byte
+    pub fn map(&mut self, region: Region) -> Result<(), Exception> {
+        if region.size == 0 || region.start.checked_add(region.size - 1).is_none() {
+            return Err(Exception::StoreAccessFault(region.start));
+        }
+
+        if let Some(other) = self
+            .regions
+            .iter()
+            .find(|other| other.start <= region.last() && region.start <= other.last())
+        {
+            return Err(Exception::StoreAccessFault(region.start.max(other.start)));
+        }
+
+        let index = self
+            .regions
+            .partition_point(|other| other.start < region.start);
+        self.regions.insert(index, region);
+
+        Ok(())
+    }
+
+    /// Maps a region whose loads and stores are handled by `device`, it gets offsets from the region start
+    pub fn attach(&mut self, region: Region, device: SharedDevice) -> Result<(), Exception> {
+        let start = region.start;
+
+        self.map(region)?;
+        self.devices.0.insert(start, device);
+
+        Ok(())
+    }
+
+    /// Removes the first region called `name` along with its device, its contents are kept
+    pub fn unmap(&mut self, name: &str) -> Option<Region> {
+        let index = self.regions.iter().position(|region| region.name == name)?;
+        let region = self.regions.remove(index);
+        self.devices.0.remove(&region.start);
+
+        Some(region)
+    }
+
+    /// Device attached to the first region called `name`
+    pub fn device(&self, name: &str) -> Option<SharedDevice> {
+        self.devices.0.get(&self.region(name)?.start).cloned()
+    }
+
+    /// Advances every device by a step, returns the mip bits they raise or None when there are no devices
+    pub fn tick_devices(&mut self) -> Option<u64> {
+        if self.devices.0.is_empty() {
+            return None;
+        }
+
+        let raised = self.devices.0.values().fold(0, |raised, device| {
+            let mut device = device.borrow_mut();
+            device.tick();
+
+            raised | device.interrupts()
+        });
+
+        Some(raised)
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+
+    pub fn region_at(&self, address: u64) -> Option<&Region> {
+        let index = self
+            .regions
+            .partition_point(|region| region.start <= address)
+            .checked_sub(1)?;
+
+        self.regions
+            .get(index)
+            .filter(|region| region.contains(address))
+    }
+
+    /// Checks that every byte of the access is in a region that allows it, accesses may span adjacent regions
+    fn permits(&self, address: u64, len: usize, allowed: impl Fn(Permissions) -> bool) -> bool {
+        let Some(last) = address.checked_add(len as u64 - 1) else {
+            return false;
+        };
+
+        let mut cursor = address;
+
+        loop {
+            let Some(region) = self.region_at(cursor) else {
+                return false;
+            };
+
+            if !allowed(region.permissions) {
+                return false;
+            }
+
+            if region.last() >= last {
+                return true;
+            }
+
+            cursor = region.last() + 1;
+        }
+    }
+
+    pub(crate) fn journal(&mut self) -> &mut Journal<(u64, Vec<u8>)> {
+        &mut self.journal
+    }
+
+    pub(crate) fn accesses(&mut self) -> &mut Journal<MemoryAccess> {
+        self.accesses.get_mut()
+    }
+
+    pub(crate) fn record_access(&self, access: impl FnOnce() -> MemoryAccess) {
+        let mut accesses = self.accesses.borrow_mut();
+
+        if accesses.is_recording() {
+            accesses.record(access());
+        }
+    }
+
+    /// Puts journaled bytes back, devices behind them aren't written
+    pub(crate) fn undo(&mut self, entries: Vec<(u64, Vec<u8>)>) {
+        for (address, old) in entries.into_iter().rev() {
+            self.write(address, &old);
+        }
+    }
+
+    fn highest_region(&self) -> Option<usize> {
+        self.regions
+            .iter()
+            .rposition(|region| !self.devices.0.contains_key(&region.start))
+    }
+
+    /// Device and offset into its region backing an address
+    fn device_at(&self, address: u64) -> Option<(&SharedDevice, u64)> {
+        let region = self.region_at(address)?;
+        let device = self.devices.0.get(&region.start)?;
+
+        Some((device, address - region.start))
     }
+
+    fn dispatch_read(&self, address: u64, bytes: &mut [u8]) {
+        if self.devices.0.is_empty() {
+            return self.read(address, bytes);
+        }
+
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            let address = address.wrapping_add(index as u64);
+
+            match self.device_at(address) {
+                Some((device, offset)) => *byte = device.borrow_mut().read(offset),
+                None => self.read(address, std::slice::from_mut(byte)),
+            }
+        }
+    }
+
+    fn dispatch_write(&mut self, address: u64, data: &[u8]) {
+        if self.devices.0.is_empty() {
+            return self.write(address, data);
+        }
+
+        for (index, &value) in data.iter().enumerate() {
+            let address = address.wrapping_add(index as u64);
+
+            match self.device_at(address) {
+                Some((device, offset)) => device.borrow_mut().write(offset, value),
+                None => self.write(address, &[value]),
+            }
+        }
+    }
+
+    fn read(&self, address: u64, bytes: &mut [u8]) {
+        let len = bytes.len();
+
+        for (index, chunk) in chunks(address, len) {
+            let (page, offset) = (chunk / PAGE_SIZE, (chunk % PAGE_SIZE) as usize);
+            let destination = &mut bytes[index..][..chunk_len(chunk, len - index)];
+
+            match self.pages.get(&page) {
+                Some(page) => {
+                    destination.copy_from_slice(&page[offset..offset + destination.len()])
+                }
+                None => destination.fill(0),
+            }
+        }
+    }
+
+    fn write(&mut self, address: u64, data: &[u8]) {
+        for (index, chunk) in chunks(address, data.len()) {
+            let (page, offset) = (chunk / PAGE_SIZE, (chunk % PAGE_SIZE) as usize);
+            let source = &data[index..][..chunk_len(chunk, data.len() - index)];
+
+            let page = self
+                .pages
+                .entry(page)
+                .or_insert_with(|| Box::new([0; PAGE_SIZE as usize]));
+            page[offset..offset + source.len()].copy_from_slice(source);
+        }
+    }
+
+    /// Zeroes `start..=last`, dropping pages that end up fully cleared
+    fn clear(&mut self, start: u64, last: u64) {
+        let pages: Vec<u64> = self
+            .pages
+            .range(start / PAGE_SIZE..=last / PAGE_SIZE)
+            .map(|(&page, _)| page)
+            .collect();
+
+        for page in pages {
+            let page_start = page * PAGE_SIZE;
+            let from = (start.max(page_start) - page_start) as usize;
+            let to = (last.min(page_start + (PAGE_SIZE - 1)) - page_start) as usize;
+
+            match (from, to) {
+                (0, to) if to as u64 == PAGE_SIZE - 1 => {
+                    self.pages.remove(&page);
+                }
+                _ => self.pages.get_mut(&page).unwrap()[from..=to].fill(0),
+            }
+        }
+    }
+}
+
+/// Splits an access at page boundaries, yields offsets into the access and the address of each part
+fn chunks(address: u64, len: usize) -> impl Iterator<Item = (usize, u64)> {
+    let mut index = 0;
+
+    std::iter::from_fn(move || {
+        if index >= len {
+            return None;
+        }
+
+        let chunk = address.wrapping_add(index as u64);
+        let current = index;
+        index += chunk_len(chunk, len - index);
+
+        Some((current, chunk))
+    })
+}
+
+/// Bytes of an access starting at `address` that stay in its page
+fn chunk_len(address: u64, remaining: usize) -> usize {
+    remaining.min((PAGE_SIZE - address % PAGE_SIZE) as usize)
 }
 
 impl Default for Memory {
@@ -79,11 +488,134 @@ mod tests {
         mem.assign(&[1, 2]);
 
         assert_eq!(mem.data_ptr, 2);
-        assert_eq!(mem.raw, &[1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            mem.bytes(0, 0x10),
+            &[1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
 
         mem.assign(&[5, 10, 15]);
 
         assert_eq!(mem.data_ptr, 5);
-        assert_eq!(mem.raw, &[1, 2, 5, 10, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            mem.bytes(0, 0x10),
+            &[1, 2, 5, 10, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn out_of_range_accesses_fault() {
+        let mut mem = Memory::new(vec![0; 0x10].into_iter());
+
+        assert_eq!(mem.get::<4>(0xc), Ok([0; 4]));
+        assert_eq!(mem.get::<4>(0xd), Err(Exception::LoadAccessFault(0xd)));
+        assert_eq!(
+            mem.get::<1>(u64::MAX),
+            Err(Exception::LoadAccessFault(u64::MAX))
+        );
+        assert_eq!(mem.set(0xf, [1, 2]), Err(Exception::StoreAccessFault(0xf)));
+        assert_eq!(mem.bytes(0xf, 1), [0]);
+    }
+
+    #[test]
+    fn regions_enforce_permissions() {
+        let mut mem = Memory::empty();
+
+        mem.map(Region::new("text", 0x1000, 0x1000, Permissions::RX))
+            .unwrap();
+        mem.map(Region::new(
+            "stack",
+            0xffff_ffff_ffff_f000,
+            0x1000,
+            Permissions::RW,
+        ))
+        .unwrap();
+        mem.load(0x1ffe, &[1, 2]);
+
+        assert_eq!(mem.fetch(0x1ffe), Ok([1, 2]));
+        assert_eq!(mem.get(0x1ffe), Ok([1, 2]));
+        assert_eq!(
+            mem.set(0x1000, [0]),
+            Err(Exception::StoreAccessFault(0x1000))
+        );
+        assert_eq!(
+            mem.get::<4>(0x1ffe),
+            Err(Exception::LoadAccessFault(0x1ffe))
+        );
+
+        assert_eq!(mem.set(u64::MAX - 7, [7; 8]), Ok(()));
+        assert_eq!(mem.get(u64::MAX - 7), Ok([7; 8]));
+        assert_eq!(
+            mem.fetch::<2>(u64::MAX - 1),
+            Err(Exception::InstructionAccessFault(u64::MAX - 1))
+        );
+
+        assert_eq!(
+            mem.map(Region::new("data", 0x1800, 0x1000, Permissions::RW)),
+            Err(Exception::StoreAccessFault(0x1800))
+        );
+        assert_eq!(
+            mem.region_at(0x1234).map(|region| &*region.name),
+            Some("text")
+        );
+    }
+
+    #[test]
+    fn accesses_span_pages_and_adjacent_regions() {
+        let mut mem = Memory::empty();
+
+        mem.map(Region::new("rodata", 0, 0x1000, Permissions::R))
+            .unwrap();
+        mem.map(Region::new("data", 0x1000, 0x1000, Permissions::RW))
+            .unwrap();
+        mem.load(0xffc, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(mem.get(0xffc), Ok([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(
+            mem.set(0xffc, [0; 8]),
+            Err(Exception::StoreAccessFault(0xffc))
+        );
+        assert_eq!(mem.set(0x1000, [9; 4]), Ok(()));
+        assert_eq!(mem.bytes(0xffc, 8), [1, 2, 3, 4, 9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn devices_handle_their_region() {
+        use crate::rv_core::device::Uart16550;
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut mem = Memory::new(vec![0; 0x10].into_iter());
+        let uart = Rc::new(RefCell::new(Uart16550::default()));
+        uart.borrow_mut().input.push_back(b'a');
+
+        mem.attach(
+            Region::new("uart", 0x10, Uart16550::SIZE, Permissions::RW),
+            uart.clone(),
+        )
+        .unwrap();
+
+        // Spans memory and the transmitter
+        assert_eq!(mem.set(0xf, [7, b'h']), Ok(()));
+        assert_eq!(mem.get(0xf), Ok([7, b'a']));
+        assert_eq!(mem.get(Uart16550::LSR + 0x10), Ok([0x60]));
+        assert_eq!(mem.bytes(0x10, 1), [0]);
+        assert_eq!(uart.borrow().output_string(), "h");
+
+        assert!(mem.device("uart").is_some());
+        assert_eq!(mem.tick_devices(), Some(0));
+
+        mem.unmap("uart");
+        assert_eq!(mem.tick_devices(), None);
+    }
+
+    #[test]
+    fn shrinking_clears_memory() {
+        let mut mem = Memory::new(vec![0xff; 0x2010].into_iter());
+
+        mem.resize(0x8);
+        mem.resize(0x2010);
+
+        assert_eq!(mem.len(), 0x2010);
+        assert_eq!(mem.bytes(0x6, 4), [0xff, 0xff, 0, 0]);
+        assert_eq!(mem.bytes(0x200c, 4), [0; 4]);
     }
 }
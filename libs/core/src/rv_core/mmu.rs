@@ -0,0 +1,410 @@
+use super::{
+    memory::Memory,
+    registers::{
+        aliases::{
+            csr::{MSTATUS, SATP},
+            mstatus,
+        },
+        CsrRegisters, PrivilegeMode,
+    },
+    trace::MemoryAccess,
+    trap::Exception,
+};
+
+const PAGE_SIZE: u64 = 0x1000;
+const PPN_MASK: u64 = (1 << 44) - 1;
+
+mod pte {
+    pub const V: u64 = 1 << 0;
+    pub const R: u64 = 1 << 1;
+    pub const W: u64 = 1 << 2;
+    pub const X: u64 = 1 << 3;
+    pub const U: u64 = 1 << 4;
+    pub const A: u64 = 1 << 6;
+    pub const D: u64 = 1 << 7;
+    pub const RESERVED: u64 = 0x3ff << 54;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Access {
+    Fetch,
+    Load,
+    Store,
+}
+
+impl Access {
+    fn page_fault(self, address: u64) -> Exception {
+        match self {
+            Self::Fetch => Exception::InstructionPageFault(address),
+            Self::Load => Exception::LoadPageFault(address),
+            Self::Store => Exception::StorePageFault(address),
+        }
+    }
+
+    fn access_fault(self, address: u64) -> Exception {
+        match self {
+            Self::Fetch => Exception::InstructionAccessFault(address),
+            Self::Load => Exception::LoadAccessFault(address),
+            Self::Store => Exception::StoreAccessFault(address),
+        }
+    }
+}
+
+/// Memory as seen by the hart, addresses are translated through Sv39/Sv48 page tables when satp enables paging.
+/// There is no TLB, every access walks the tables, so sfence.vma has nothing to flush.
+/// Accessed and dirty bits aren't updated by hardware, accesses to pages without them raise page faults instead.
+pub struct Mmu<'m> {
+    memory: &'m mut Memory,
+    satp: u64,
+    fetch_mode: PrivilegeMode,
+    /// Differs from the fetch mode when mstatus.MPRV is set
+    data_mode: PrivilegeMode,
+    sum: bool,
+    mxr: bool,
+}
+
+impl<'m> Mmu<'m> {
+    pub fn new(memory: &'m mut Memory, c: &CsrRegisters, mode: PrivilegeMode) -> Self {
+        let status = c[MSTATUS].read();
+
+        let data_mode = if mode == PrivilegeMode::Machine && status & mstatus::MPRV != 0 {
+            PrivilegeMode::from_bits(status >> 11)
+        } else {
+            mode
+        };
+
+        Self {
+            memory,
+            satp: c[SATP].read(),
+            fetch_mode: mode,
+            data_mode,
+            sum: status & mstatus::SUM != 0,
+            mxr: status & mstatus::MXR != 0,
+        }
+    }
+
+    /// Physical memory accessed without translation
+    pub fn bare(memory: &'m mut Memory) -> Self {
+        Self {
+            memory,
+            satp: 0,
+            fetch_mode: PrivilegeMode::Machine,
+            data_mode: PrivilegeMode::Machine,
+            sum: false,
+            mxr: false,
+        }
+    }
+
+    pub fn get<const BYTES: usize>(&self, address: usize) -> Result<[u8; BYTES], Exception> {
+        let bytes = self.read(address)?;
+
+        self.memory.record_access(|| MemoryAccess::Load {
+            address: address as u64,
+            data: bytes.to_vec(),
+        });
+
+        Ok(bytes)
+    }
+
+    pub fn set<const BYTES: usize>(
+        &mut self,
+        address: usize,
+        value: [u8; BYTES],
+    ) -> Result<(), Exception> {
+        self.write(address, value)?;
+
+        self.memory.record_access(|| MemoryAccess::Store {
+            address: address as u64,
+            data: value.to_vec(),
+        });
+
+        Ok(())
+    }
+
+    fn read<const BYTES: usize>(&self, address: usize) -> Result<[u8; BYTES], Exception> {
+        let virtual_address = address as u64;
+
+        if !crosses_page(virtual_address, BYTES) {
+            let physical = self.translate(virtual_address, Access::Load)?;
+
+            return self
+                .memory
+                .get(physical)
+                .map_err(|_| Exception::LoadAccessFault(virtual_address));
+        }
+
+        let mut bytes = [0; BYTES];
+
+        for (offset, byte) in bytes.iter_mut().enumerate() {
+            [*byte] = self.read(address.wrapping_add(offset))?;
+        }
+
+        Ok(bytes)
+    }
+
+    fn write<const BYTES: usize>(
+        &mut self,
+        address: usize,
+        value: [u8; BYTES],
+    ) -> Result<(), Exception> {
+        let virtual_address = address as u64;
+
+        if !crosses_page(virtual_address, BYTES) {
+            let physical = self.translate(virtual_address, Access::Store)?;
+
+            return self
+                .memory
+                .set(physical, value)
+                .map_err(|_| Exception::StoreAccessFault(virtual_address));
+        }
+
+        // Both pages are translated upfront, so a fault on the second one doesn't leave the first one written
+        for offset in [0, BYTES - 1] {
+            self.translate(virtual_address.wrapping_add(offset as u64), Access::Store)?;
+        }
+
+        for (offset, byte) in value.into_iter().enumerate() {
+            self.write(address.wrapping_add(offset), [byte])?;
+        }
+
+        Ok(())
+    }
+
+    /// Instructions are fetched in halfwords, the upper one only when the lower doesn't hold a compressed instruction.
+    /// Each halfword is translated on its own, so an instruction may cross a page boundary.
+    pub fn fetch(&self, address: u64) -> Result<u32, Exception> {
+        let low = self.fetch_halfword(address)?;
+
+        if low & 0b11 != 0b11 {
+            return Ok(low as u32);
+        }
+
+        let high = self.fetch_halfword(address.wrapping_add(2))?;
+
+        Ok((high as u32) << 16 | low as u32)
+    }
+
+    fn fetch_halfword(&self, address: u64) -> Result<u16, Exception> {
+        let physical = self.translate(address, Access::Fetch)?;
+
+        self.memory
+            .fetch(physical)
+            .map(u16::from_le_bytes)
+            .map_err(|_| Exception::InstructionAccessFault(address))
+    }
+
+    pub fn translate(&self, address: u64, access: Access) -> Result<u64, Exception> {
+        let mode = match access {
+            Access::Fetch => self.fetch_mode,
+            Access::Load | Access::Store => self.data_mode,
+        };
+
+        let levels = match self.satp >> 60 {
+            8 => 3,
+            9 => 4,
+            _ => return Ok(address),
+        };
+
+        if mode == PrivilegeMode::Machine {
+            return Ok(address);
+        }
+
+        let fault = access.page_fault(address);
+
+        // Bits above the virtual address width have to be copies of its top bit
+        let unused_bits = 64 - (12 + 9 * levels);
+        if ((address << unused_bits) as i64 >> unused_bits) as u64 != address {
+            return Err(fault);
+        }
+
+        let mut table = (self.satp & PPN_MASK) * PAGE_SIZE;
+
+        for level in (0..levels).rev() {
+            let vpn = (address >> (12 + 9 * level)) & 0x1ff;
+            let entry_address = table + vpn * 8;
+
+            let entry = self
+                .memory
+                .get(entry_address)
+                .map(u64::from_le_bytes)
+                .map_err(|_| access.access_fault(address))?;
+
+            if entry & pte::V == 0
+                || entry & (pte::R | pte::W) == pte::W
+                || entry & pte::RESERVED != 0
+            {
+                return Err(fault);
+            }
+
+            let ppn = (entry >> 10) & PPN_MASK;
+
+            if entry & (pte::R | pte::X) == 0 {
+                table = ppn * PAGE_SIZE;
+                continue;
+            }
+
+            if !self.permits(entry, mode, access) {
+                return Err(fault);
+            }
+
+            let page_mask = (PAGE_SIZE << (9 * level)) - 1;
+
+            // Superpages have to be aligned to their size
+            if (ppn * PAGE_SIZE) & page_mask != 0 {
+                return Err(fault);
+            }
+
+            return Ok((ppn * PAGE_SIZE) | address & page_mask);
+        }
+
+        Err(fault)
+    }
+
+    fn permits(&self, entry: u64, mode: PrivilegeMode, access: Access) -> bool {
+        let user_page = entry & pte::U != 0;
+
+        let privilege = match mode {
+            PrivilegeMode::User => user_page,
+            PrivilegeMode::Supervisor => !user_page || (self.sum && access != Access::Fetch),
+            PrivilegeMode::Machine => true,
+        };
+
+        let permission = match access {
+            Access::Fetch => entry & pte::X != 0,
+            Access::Load => entry & pte::R != 0 || (self.mxr && entry & pte::X != 0),
+            Access::Store => entry & pte::W != 0,
+        };
+
+        let dirty = access != Access::Store || entry & pte::D != 0;
+
+        privilege && permission && entry & pte::A != 0 && dirty
+    }
+}
+
+fn crosses_page(address: u64, length: usize) -> bool {
+    address % PAGE_SIZE + length as u64 > PAGE_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::vector_engine::VectorEngine;
+
+    const ROOT: u64 = 0x1000;
+    const LEAF_TABLE: u64 = 0x2000;
+
+    fn entry(memory: &mut Memory, table: u64, vpn: u64, value: u64) {
+        memory.load(table + vpn * 8, &value.to_le_bytes());
+    }
+
+    /// Sv39 tables mapping gigapage 0 to itself and VA 0x4000_3000 to PA 0x5000 with the given flags
+    fn sv39_memory(flags: u64) -> (Memory, CsrRegisters) {
+        let mut memory = Memory::new(vec![0; 0x6000].into_iter());
+
+        entry(
+            &mut memory,
+            ROOT,
+            0,
+            pte::V | pte::R | pte::W | pte::X | pte::A | pte::D,
+        );
+        entry(&mut memory, ROOT, 1, (LEAF_TABLE >> 12) << 10 | pte::V);
+        let middle = 0x3000;
+        entry(&mut memory, LEAF_TABLE, 0, (middle >> 12) << 10 | pte::V);
+        entry(
+            &mut memory,
+            middle,
+            3,
+            (0x5000 >> 12) << 10 | pte::V | flags,
+        );
+
+        memory.load(0x5000, &[1, 2, 3, 4]);
+
+        let mut c = CsrRegisters::new(&VectorEngine::default());
+        c[SATP].write(8 << 60 | ROOT >> 12).unwrap();
+
+        (memory, c)
+    }
+
+    #[test]
+    fn sv39_translation_works() {
+        let (mut memory, c) = sv39_memory(pte::R | pte::W | pte::A | pte::D);
+        let mut mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Supervisor);
+
+        assert_eq!(mmu.translate(0x1234, Access::Load), Ok(0x1234));
+        assert_eq!(mmu.translate(0x4000_3008, Access::Load), Ok(0x5008));
+        assert_eq!(mmu.get(0x4000_3000), Ok([1, 2, 3, 4]));
+
+        mmu.set(0x4000_3004, [5, 6]).unwrap();
+        assert_eq!(memory.get(0x5004), Ok([5, 6]));
+
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Supervisor);
+        assert_eq!(
+            mmu.translate(0x4000_3000, Access::Fetch),
+            Err(Exception::InstructionPageFault(0x4000_3000))
+        );
+        assert_eq!(
+            mmu.translate(0x8000_0000, Access::Load),
+            Err(Exception::LoadPageFault(0x8000_0000))
+        );
+        // Not a sign-extended 39-bit address
+        assert_eq!(
+            mmu.translate(0x40_0000_0000, Access::Load),
+            Err(Exception::LoadPageFault(0x40_0000_0000))
+        );
+
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Machine);
+        assert_eq!(mmu.translate(0x4000_3000, Access::Load), Ok(0x4000_3000));
+    }
+
+    #[test]
+    fn sv39_permissions_are_checked() {
+        let (mut memory, mut c) = sv39_memory(pte::R | pte::U | pte::A);
+
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::User);
+        assert_eq!(mmu.translate(0x4000_3000, Access::Load), Ok(0x5000));
+        assert_eq!(
+            mmu.translate(0x4000_3000, Access::Store),
+            Err(Exception::StorePageFault(0x4000_3000))
+        );
+        // Kernel gigapage isn't accessible from U-mode
+        assert_eq!(
+            mmu.translate(0x1000, Access::Load),
+            Err(Exception::LoadPageFault(0x1000))
+        );
+
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Supervisor);
+        assert_eq!(
+            mmu.translate(0x4000_3000, Access::Load),
+            Err(Exception::LoadPageFault(0x4000_3000))
+        );
+
+        c[MSTATUS].write(mstatus::SUM).unwrap();
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Supervisor);
+        assert_eq!(mmu.translate(0x4000_3000, Access::Load), Ok(0x5000));
+
+        // M-mode loads and stores use MPP privilege with MPRV
+        c[MSTATUS].write(mstatus::MPRV).unwrap();
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Machine);
+        assert_eq!(mmu.translate(0x4000_3000, Access::Load), Ok(0x5000));
+        assert_eq!(mmu.translate(0x4000_3000, Access::Fetch), Ok(0x4000_3000));
+    }
+
+    #[test]
+    fn sv48_translation_works() {
+        let mut memory = Memory::new(vec![0; 0x4000].into_iter());
+
+        // Terapage 0 points at a table mapping gigapage 0 to itself
+        entry(&mut memory, ROOT, 0, (LEAF_TABLE >> 12) << 10 | pte::V);
+        entry(&mut memory, LEAF_TABLE, 0, pte::V | pte::R | pte::A);
+
+        let mut c = CsrRegisters::new(&VectorEngine::default());
+        c[SATP].write(9 << 60 | ROOT >> 12).unwrap();
+
+        let mmu = Mmu::new(&mut memory, &c, PrivilegeMode::Supervisor);
+        assert_eq!(mmu.translate(0x2345, Access::Load), Ok(0x2345));
+        assert_eq!(
+            mmu.translate(0x40_0000_0000, Access::Load),
+            Err(Exception::LoadPageFault(0x40_0000_0000))
+        );
+    }
+}
@@ -7,13 +7,36 @@ pub mod vector;
 use super::{memory::Memory, snapshot::Snapshotable, vector_engine::VectorEngine};
 
 pub use csr::{CsrPrivilege, CsrRegister, CsrRegisters};
-pub use float::FloatRegisters;
+pub use float::{unbox, FloatRegisters};
 pub use integer::IntegerRegisters;
 pub use vector::VectorRegisters;
 
+/// Privilege level the hart runs at, values match the mstatus.MPP encoding
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub enum PrivilegeMode {
+    User = 0,
+    Supervisor = 1,
+    #[default]
+    Machine = 3,
+}
+
+impl PrivilegeMode {
+    /// Decodes an MPP-like field, the reserved encoding is read as M-mode
+    pub fn from_bits(bits: u64) -> Self {
+        match bits & 0b11 {
+            0 => Self::User,
+            1 => Self::Supervisor,
+            _ => Self::Machine,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Registers {
     pub pc: u64,
+    pub mode: PrivilegeMode,
+    /// Address reserved by the last lr, sc only succeeds on it and consumes the reservation
+    pub reservation: Option<u64>,
     pub x: IntegerRegisters,
     pub c: CsrRegisters,
     pub f: FloatRegisters,
@@ -24,8 +47,10 @@ impl Registers {
     pub fn new(vec_engine: &VectorEngine, mem: &Memory) -> Self {
         Self {
             pc: 0,
+            mode: PrivilegeMode::Machine,
+            reservation: None,
             x: IntegerRegisters::new(mem),
-            c: CsrRegisters::new(&vec_engine.vlen),
+            c: CsrRegisters::new(vec_engine),
             f: FloatRegisters::default(),
             v: VectorRegisters::default(vec_engine),
         }
@@ -36,9 +61,10 @@ impl Registers {
 pub struct RegistersSnapshot {
     pub x: [u64; 32],
     pub c: [CsrRegister; 4096],
-    pub f: [f64; 32],
+    pub f: [u64; 32],
     pub v: Vec<u8>,
     pub pc: u64,
+    pub mode: PrivilegeMode,
 }
 
 impl Snapshotable for Registers {
@@ -51,6 +77,7 @@ impl Snapshotable for Registers {
             f: self.f.snapshot(),
             v: self.v.snapshot(),
             pc: self.pc,
+            mode: self.mode,
         }
     }
 }
@@ -60,9 +87,10 @@ impl Default for RegistersSnapshot {
         Self {
             x: [0; 32],
             c: CsrRegisters::default().snapshot(),
-            f: [0.0; 32],
+            f: [0; 32],
             v: Vec::new(),
             pc: 0,
+            mode: PrivilegeMode::Machine,
         }
     }
 }
@@ -41,7 +41,29 @@ pub mod csr {
     pub const FCSR: usize = 0x0003;
     pub const FFLAGS: usize = 0x0001;
     pub const FRM: usize = 0x0002;
+    pub const SSTATUS: usize = 0x0100;
+    pub const SIE: usize = 0x0104;
+    pub const STVEC: usize = 0x0105;
+    pub const SSCRATCH: usize = 0x0140;
+    pub const SEPC: usize = 0x0141;
+    pub const SCAUSE: usize = 0x0142;
+    pub const STVAL: usize = 0x0143;
+    pub const SIP: usize = 0x0144;
+    pub const SATP: usize = 0x0180;
     pub const MSTATUS: usize = 0x0300;
+    pub const MISA: usize = 0x0301;
+    pub const MEDELEG: usize = 0x0302;
+    pub const MIDELEG: usize = 0x0303;
+    pub const MIE: usize = 0x0304;
+    pub const MTVEC: usize = 0x0305;
+    pub const MSCRATCH: usize = 0x0340;
+    pub const MEPC: usize = 0x0341;
+    pub const MCAUSE: usize = 0x0342;
+    pub const MTVAL: usize = 0x0343;
+    pub const MIP: usize = 0x0344;
+    pub const MVENDORID: usize = 0x0f11;
+    pub const MIMPID: usize = 0x0f13;
+    pub const MHARTID: usize = 0x0f14;
     pub const VSSTATUS: usize = 0x0200;
     pub const VTYPE: usize = 0x0c21;
     pub const VL: usize = 0x0C20;
@@ -52,6 +74,50 @@ pub mod csr {
     pub const VCSR: usize = 0x000F;
 }
 
+/// mstatus fields
+pub mod mstatus {
+    pub const SIE: u64 = 1 << 1;
+    pub const MIE: u64 = 1 << 3;
+    pub const SPIE: u64 = 1 << 5;
+    pub const MPIE: u64 = 1 << 7;
+    pub const SPP: u64 = 1 << 8;
+    pub const VS: u64 = 0b11 << 9;
+    pub const MPP: u64 = 0b11 << 11;
+    pub const FS: u64 = 0b11 << 13;
+    pub const MPRV: u64 = 1 << 17;
+    pub const SUM: u64 = 1 << 18;
+    pub const MXR: u64 = 1 << 19;
+    pub const TVM: u64 = 1 << 20;
+    pub const TW: u64 = 1 << 21;
+    pub const TSR: u64 = 1 << 22;
+    pub const UXL: u64 = 0b11 << 32;
+    pub const SXL: u64 = 0b11 << 34;
+}
+
+/// mip and mie bits
+pub mod interrupt {
+    pub const SSI: u64 = 1 << 1;
+    pub const MSI: u64 = 1 << 3;
+    pub const STI: u64 = 1 << 5;
+    pub const MTI: u64 = 1 << 7;
+    pub const SEI: u64 = 1 << 9;
+    pub const MEI: u64 = 1 << 11;
+}
+
+/// fflags bits, also the low bits of fcsr
+pub mod fflags {
+    /// Inexact
+    pub const NX: u64 = 1 << 0;
+    /// Underflow
+    pub const UF: u64 = 1 << 1;
+    /// Overflow
+    pub const OF: u64 = 1 << 2;
+    /// Divide by zero
+    pub const DZ: u64 = 1 << 3;
+    /// Invalid operation
+    pub const NV: u64 = 1 << 4;
+}
+
 pub mod float {
     pub const FT0: usize = 0;
     pub const FT1: usize = 1;
@@ -87,4 +153,10 @@ pub mod float {
     pub const FT11: usize = 31;
 }
 
+/// vtype fields
+pub mod vtype {
+    /// Set when vsetvl* was given an unsupported configuration
+    pub const VILL: u64 = 1 << 63;
+}
+
 pub mod vector {}
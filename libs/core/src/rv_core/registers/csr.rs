@@ -1,6 +1,32 @@
-use crate::{prelude::Snapshotable, rv_core::vector_engine::Vlen};
+use crate::{
+    prelude::Snapshotable,
+    rv_core::{
+        history::Journal,
+        softfloat::RoundingMode,
+        trap::Exception,
+        vector_engine::{VectorEngine, VectorExtension},
+    },
+};
 
-use super::aliases::csr::VLENB;
+use super::{
+    aliases::{csr::*, fflags, interrupt, mstatus},
+    PrivilegeMode,
+};
+
+/// fflags field of fcsr
+const FFLAGS_FIELD: u64 = fflags::NX | fflags::UF | fflags::OF | fflags::DZ | fflags::NV;
+/// frm field of fcsr
+const FRM_FIELD: u64 = 0b111 << 5;
+
+/// mstatus fields visible through sstatus
+const SSTATUS_FIELDS: u64 = mstatus::SIE
+    | mstatus::SPIE
+    | mstatus::SPP
+    | mstatus::VS
+    | mstatus::FS
+    | mstatus::SUM
+    | mstatus::MXR
+    | mstatus::UXL;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum CsrPrivilege {
@@ -11,6 +37,8 @@ pub enum CsrPrivilege {
 #[derive(Clone, PartialEq, Debug)]
 pub struct CsrRegister {
     value: u64,
+    /// Bits that software writes can change, the rest are read-only fields
+    write_mask: u64,
     pub privilege: CsrPrivilege,
 }
 
@@ -19,13 +47,13 @@ impl CsrRegister {
         self.value
     }
 
-    pub fn write(&mut self, value: u64) -> Result<(), String> {
+    pub fn write(&mut self, value: u64) -> Result<(), Exception> {
         if self.privilege == CsrPrivilege::ReadOnly {
-            return Err("Cannot write to read-only register".to_owned());
+            return Err(Exception::IllegalInstruction(0));
         }
 
         unsafe {
-            self.set(value);
+            self.set((self.value & !self.write_mask) | (value & self.write_mask));
         }
 
         Ok(())
@@ -37,30 +65,204 @@ impl CsrRegister {
 }
 
 #[derive(Clone, PartialEq, Debug)]
-pub struct CsrRegisters([CsrRegister; 4096]);
+pub struct CsrRegisters {
+    registers: [CsrRegister; 4096],
+    /// Values overwritten through `IndexMut`, which every write goes through
+    journal: Journal<(usize, u64)>,
+}
 
 impl Snapshotable for CsrRegisters {
     type Snapshot = [CsrRegister; 4096];
 
     fn snapshot(&self) -> Self::Snapshot {
-        self.0.clone()
+        let mut snapshot = self.registers.clone();
+
+        // Views have no storage of their own
+        for csr in [FFLAGS, FRM, VCSR, SSTATUS, SIE, SIP] {
+            snapshot[csr].value = self.read(csr);
+        }
+
+        snapshot
     }
 }
 
 impl CsrRegisters {
-    pub fn new(vlen: &Vlen) -> Self {
+    pub fn new(vec_engine: &VectorEngine) -> Self {
         let mut regs = Self::default();
 
-        unsafe { regs[VLENB].set(vlen.byte_length() as u64) }
+        unsafe { regs[VLENB].set(vec_engine.vlen.byte_length() as u64) }
+
+        // Zve* subsets aren't reported in misa
+        let extensions: &[u8] = match vec_engine.extension {
+            VectorExtension::V => b"IMAFDCSUV",
+            _ => b"IMAFDCSU",
+        };
+        // MXL = 64
+        let misa = extensions
+            .iter()
+            .fold(2 << 62, |misa, extension| misa | 1 << (extension - b'A'));
+        let supervisor_interrupts = interrupt::SSI | interrupt::STI | interrupt::SEI;
+
+        // Both U-mode and S-mode are 64-bit
+        let xlen = 2 << 32 | 2 << 34;
+        regs.define(
+            MSTATUS,
+            xlen | mstatus::MPP,
+            SSTATUS_FIELDS & !mstatus::UXL
+                | mstatus::MIE
+                | mstatus::MPIE
+                | mstatus::MPP
+                | mstatus::MPRV
+                | mstatus::TVM
+                | mstatus::TW
+                | mstatus::TSR,
+        );
+        regs.define(MISA, misa, 0);
+        regs.define(VXSAT, 0, 0b1);
+        regs.define(VXRM, 0, 0b11);
+        // Largest element index is VLEN - 1, at SEW=8 and LMUL=8
+        regs.define(VSTART, 0, vec_engine.vlen.bit_length() as u64 - 1);
+        // Environment calls from M-mode can't be delegated
+        regs.define(MEDELEG, 0, 0xb3ff);
+        regs.define(MIDELEG, 0, supervisor_interrupts);
+        regs.define(
+            MIE,
+            0,
+            supervisor_interrupts | interrupt::MSI | interrupt::MTI | interrupt::MEI,
+        );
+        // Only direct and vectored modes are supported
+        regs.define(MTVEC, 0, !0b10);
+        regs.define(MSCRATCH, 0, u64::MAX);
+        regs.define(MEPC, 0, !1);
+        regs.define(MCAUSE, 0, u64::MAX);
+        regs.define(MTVAL, 0, u64::MAX);
+        // M-mode pending bits are raised by the hart and its devices, not by software
+        regs.define(MIP, 0, supervisor_interrupts);
+
+        regs.define(STVEC, 0, !0b10);
+        regs.define(SSCRATCH, 0, u64::MAX);
+        regs.define(SEPC, 0, !1);
+        regs.define(SCAUSE, 0, u64::MAX);
+        regs.define(STVAL, 0, u64::MAX);
+        // ASIDs aren't implemented
+        regs.define(SATP, 0, 0xf000_0fff_ffff_ffff);
 
         regs
     }
+
+    /// Checks whether an instruction running in `mode` may access the CSR, lowest privilege is encoded in bits 9:8
+    pub fn check_access(&self, csr: usize, mode: PrivilegeMode) -> Result<(), Exception> {
+        let trapped_satp =
+            csr == SATP && mode == PrivilegeMode::Supervisor && self.status(mstatus::TVM);
+
+        if (csr >> 8) & 0b11 > mode as usize || trapped_satp {
+            return Err(Exception::IllegalInstruction(0));
+        }
+
+        Ok(())
+    }
+
+    /// Reads a CSR the way instructions see it, views such as fflags, vcsr or sstatus are assembled from their backing registers
+    pub fn read(&self, csr: usize) -> u64 {
+        match csr {
+            FFLAGS => self[FCSR].read() & FFLAGS_FIELD,
+            FRM => (self[FCSR].read() & FRM_FIELD) >> 5,
+            VCSR => self[VXRM].read() << 1 | self[VXSAT].read(),
+            SSTATUS => self[MSTATUS].read() & SSTATUS_FIELDS,
+            SIE => self[MIE].read() & self[MIDELEG].read(),
+            SIP => self[MIP].read() & self[MIDELEG].read(),
+            _ => self[csr].read(),
+        }
+    }
+
+    /// Registers a CSR is stored in along with the fields of each it shows, views such as fflags or sstatus are part of another register
+    pub fn backing(&self, csr: usize) -> Vec<(usize, u64)> {
+        match csr {
+            FFLAGS => vec![(FCSR, FFLAGS_FIELD)],
+            FRM => vec![(FCSR, FRM_FIELD)],
+            VCSR => vec![(VXRM, u64::MAX), (VXSAT, u64::MAX)],
+            SSTATUS => vec![(MSTATUS, SSTATUS_FIELDS)],
+            SIE => vec![(MIE, self[MIDELEG].read())],
+            SIP => vec![(MIP, self[MIDELEG].read())],
+            _ => vec![(csr, u64::MAX)],
+        }
+    }
+
+    /// Writes a CSR the way instructions do, leaving read-only fields and unsupported WARL values untouched
+    pub fn write(&mut self, csr: usize, value: u64) -> Result<(), Exception> {
+        let value = match csr {
+            FFLAGS => return self.write_fields(FCSR, FFLAGS_FIELD, value),
+            FRM => return self.write_fields(FCSR, FRM_FIELD, value << 5),
+            VCSR => {
+                self[VXSAT].write(value)?;
+                return self[VXRM].write(value >> 1);
+            }
+            SSTATUS => return self.write_fields(MSTATUS, SSTATUS_FIELDS, value),
+            SIE => return self.write_fields(MIE, self[MIDELEG].read(), value),
+            SIP => return self.write_fields(MIP, self[MIDELEG].read() & interrupt::SSI, value),
+            // H-mode encoding of MPP is reserved
+            MSTATUS if value & mstatus::MPP == 2 << 11 => {
+                (value & !mstatus::MPP) | (self[MSTATUS].read() & mstatus::MPP)
+            }
+            // Writes selecting an unsupported translation mode have no effect
+            SATP if !matches!(value >> 60, 0 | 8 | 9) => return Ok(()),
+            _ => value,
+        };
+
+        self[csr].write(value)
+    }
+
+    /// Resolves the rm field of a floating point instruction, DYN takes the rounding mode from frm
+    pub fn rounding_mode(&self, rm: u32) -> Result<RoundingMode, Exception> {
+        let bits = match rm {
+            RoundingMode::DYN => self.read(FRM),
+            static_rm => static_rm as u64,
+        };
+
+        RoundingMode::from_bits(bits).ok_or(Exception::IllegalInstruction(0))
+    }
+
+    /// Accrues floating point exception flags, they stay set until software clears them.
+    /// Operations raising none leave fcsr unwritten.
+    pub fn accrue(&mut self, flags: u64) {
+        if flags & FFLAGS_FIELD == 0 {
+            return;
+        }
+
+        let fcsr = self[FCSR].read();
+        unsafe { self[FCSR].set(fcsr | (flags & FFLAGS_FIELD)) }
+    }
+
+    pub fn status(&self, field: u64) -> bool {
+        self[MSTATUS].read() & field != 0
+    }
+
+    fn write_fields(&mut self, csr: usize, fields: u64, value: u64) -> Result<(), Exception> {
+        let current = self[csr].read();
+        self[csr].write((current & !fields) | (value & fields))
+    }
+
+    fn define(&mut self, index: usize, value: u64, write_mask: u64) {
+        self.registers[index].value = value;
+        self.registers[index].write_mask = write_mask;
+    }
+
+    pub(crate) fn journal(&mut self) -> &mut Journal<(usize, u64)> {
+        &mut self.journal
+    }
+
+    /// Restores journaled values, latest write first
+    pub(crate) fn undo(&mut self, entries: Vec<(usize, u64)>) {
+        for (index, value) in entries.into_iter().rev() {
+            self.registers[index].value = value;
+        }
+    }
 }
 
 impl Default for CsrRegisters {
     fn default() -> Self {
         let mut index = 0;
-        Self([0; 4096].map(|_| {
+        let registers = [0; 4096].map(|_| {
             let privilege = if ((index >> 10) & 0b11) == 0b11 {
                 CsrPrivilege::ReadOnly
             } else {
@@ -68,13 +270,19 @@ impl Default for CsrRegisters {
             };
             let register = CsrRegister {
                 value: 0,
+                write_mask: u64::MAX,
                 privilege,
             };
 
             index += 1;
 
             register
-        }))
+        });
+
+        Self {
+            registers,
+            journal: Journal::default(),
+        }
     }
 }
 
@@ -82,12 +290,14 @@ impl std::ops::Index<usize> for CsrRegisters {
     type Output = CsrRegister;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+        &self.registers[index]
     }
 }
 
 impl std::ops::IndexMut<usize> for CsrRegisters {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.0[index]
+        self.journal.record((index, self.registers[index].value));
+
+        &mut self.registers[index]
     }
 }
@@ -1,26 +1,121 @@
-use crate::prelude::Snapshotable;
+use crate::{prelude::Snapshotable, rv_core::history::Journal};
 
+/// Raw 64-bit register contents, narrower values are NaN-boxed
 #[derive(Clone, Default, PartialEq, Debug)]
-pub struct FloatRegisters([f64; 32]);
+pub struct FloatRegisters {
+    regs: [u64; 32],
+    /// Values overwritten by the setters and `IndexMut`
+    journal: Journal<(usize, u64)>,
+}
 
 impl Snapshotable for FloatRegisters {
-    type Snapshot = [f64; 32];
+    type Snapshot = [u64; 32];
 
     fn snapshot(&self) -> Self::Snapshot {
-        self.0
+        self.regs
+    }
+}
+
+impl FloatRegisters {
+    pub fn double(&self, reg: usize) -> f64 {
+        f64::from_bits(self.regs[reg])
+    }
+
+    pub fn set_double(&mut self, reg: usize, value: f64) {
+        self[reg] = value.to_bits();
+    }
+
+    pub fn single(&self, reg: usize) -> f32 {
+        f32::from_bits(unbox(self.regs[reg], 32) as u32)
+    }
+
+    pub fn set_single(&mut self, reg: usize, value: f32) {
+        self[reg] = nan_box(value.to_bits() as u64, 32);
+    }
+
+    pub fn half(&self, reg: usize) -> f16 {
+        f16::from_bits(unbox(self.regs[reg], 16) as u16)
+    }
+
+    pub fn set_half(&mut self, reg: usize, value: f16) {
+        self[reg] = nan_box(value.to_bits() as u64, 16);
+    }
+
+    pub(crate) fn journal(&mut self) -> &mut Journal<(usize, u64)> {
+        &mut self.journal
+    }
+
+    pub(crate) fn undo(&mut self, entries: Vec<(usize, u64)>) {
+        for (index, value) in entries.into_iter().rev() {
+            self.regs[index] = value;
+        }
+    }
+}
+
+/// Fills the bits above a `width`-bit value with ones
+fn nan_box(bits: u64, width: usize) -> u64 {
+    u64::MAX << width | bits
+}
+
+/// Extracts a `width`-bit value, registers that aren't properly NaN-boxed read as the canonical NaN
+pub fn unbox(bits: u64, width: usize) -> u64 {
+    let canonical_nan = match width {
+        16 => 0x7e00,
+        32 => 0x7fc0_0000,
+        _ => return bits,
+    };
+
+    match bits >> width == u64::MAX >> width {
+        true => bits & !(u64::MAX << width),
+        false => canonical_nan,
     }
 }
 
 impl std::ops::Index<usize> for FloatRegisters {
-    type Output = f64;
+    type Output = u64;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+        &self.regs[index]
     }
 }
 
 impl std::ops::IndexMut<usize> for FloatRegisters {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.0[index]
+        self.journal.record((index, self.regs[index]));
+
+        &mut self.regs[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrow_values_are_nan_boxed() {
+        let mut f = FloatRegisters::default();
+
+        f.set_single(1, 1.5);
+        f.set_half(2, f16::from_bits(0x3c00));
+
+        assert_eq!(f[1], 0xffff_ffff_3fc0_0000);
+        assert_eq!(f[2], 0xffff_ffff_ffff_3c00);
+        assert_eq!(f.single(1), 1.5);
+        assert_eq!(f.half(2).to_bits(), 0x3c00);
+        // A boxed half isn't a boxed single
+        assert_eq!(f.single(2).to_bits(), 0xffff_3c00);
+    }
+
+    #[test]
+    fn unboxed_values_read_as_canonical_nan() {
+        let mut f = FloatRegisters::default();
+
+        f.set_double(1, 1.0);
+        f[2] = 0x0000_0001_3f80_0000;
+
+        assert_eq!(f.single(1).to_bits(), 0x7fc0_0000);
+        assert_eq!(f.single(2).to_bits(), 0x7fc0_0000);
+        assert_eq!(f.half(1).to_bits(), 0x7e00);
+        assert_eq!(f.single(0).to_bits(), 0x7fc0_0000);
     }
 }
@@ -1,17 +1,42 @@
-use crate::{prelude::Snapshotable, rv_core::memory::Memory};
+use crate::{
+    prelude::Snapshotable,
+    rv_core::{history::Journal, memory::Memory},
+};
 
 use super::aliases::integer::SP;
 
 #[derive(Clone, PartialEq, Debug)]
-pub struct IntegerRegisters([u64; 31]);
+pub struct IntegerRegisters {
+    // Slot 0 is never read, writes to x0 are discarded into it
+    regs: [u64; 32],
+    /// Values overwritten through `IndexMut`
+    journal: Journal<(usize, u64)>,
+}
 
 impl IntegerRegisters {
     pub fn new(mem: &Memory) -> Self {
-        let mut regs: [u64; 31] = Default::default();
+        let mut regs: [u64; 32] = Default::default();
 
-        regs[SP - 1] = mem.len() as u64 - 1;
+        // Stack grows down from the top of the stack region, or of memory when there's none
+        let top = mem
+            .region("stack")
+            .map_or(mem.len(), |stack| stack.start + stack.size);
+        regs[SP] = top - 1;
 
-        Self(regs)
+        Self {
+            regs,
+            journal: Journal::default(),
+        }
+    }
+
+    pub(crate) fn journal(&mut self) -> &mut Journal<(usize, u64)> {
+        &mut self.journal
+    }
+
+    pub(crate) fn undo(&mut self, entries: Vec<(usize, u64)>) {
+        for (index, value) in entries.into_iter().rev() {
+            self.regs[index] = value;
+        }
     }
 }
 
@@ -19,8 +44,8 @@ impl Snapshotable for IntegerRegisters {
     type Snapshot = [u64; 32];
 
     fn snapshot(&self) -> Self::Snapshot {
-        let mut regs = [0; 32];
-        regs[1..].copy_from_slice(&self.0);
+        let mut regs = self.regs;
+        regs[0] = 0;
         regs
     }
 }
@@ -31,20 +56,15 @@ impl std::ops::Index<usize> for IntegerRegisters {
     fn index(&self, index: usize) -> &Self::Output {
         match index {
             0 => &0,
-            _ => &self.0[index - 1],
+            _ => &self.regs[index],
         }
     }
 }
 
 impl std::ops::IndexMut<usize> for IntegerRegisters {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        // Rust prohibits returning &mut 0 because reference would outlive the value on the stack.
-        // Small static variables are basically free so IMO it's the best second choice
-        static mut DISCARD_VALUE: u64 = 0;
+        self.journal.record((index, self.regs[index]));
 
-        match index {
-            0 => unsafe { &mut DISCARD_VALUE },
-            _ => &mut self.0[index - 1],
-        }
+        &mut self.regs[index]
     }
 }
@@ -1,18 +1,25 @@
 mod vreg;
 mod wide_vreg;
 
-use crate::prelude::*;
+use std::ops::Range;
+
+use crate::{prelude::*, rv_core::history::Journal};
 pub use vreg::Vreg;
 pub use wide_vreg::WideVreg;
 
+/// All 32 registers laid out one after another
 #[derive(Clone, Default, PartialEq, Debug)]
-pub struct VectorRegisters(pub Vec<u8>);
+pub struct VectorRegisters {
+    bytes: Vec<u8>,
+    /// Whole registers, each kept once per step before its first write
+    journal: Journal<(usize, Vec<u8>)>,
+}
 
 impl Snapshotable for VectorRegisters {
     type Snapshot = Vec<u8>;
 
     fn snapshot(&self) -> Self::Snapshot {
-        self.0.clone()
+        self.bytes.clone()
     }
 }
 
@@ -20,6 +27,42 @@ impl VectorRegisters {
     pub fn default(vec_engine: &VectorEngine) -> Self {
         let vlenb = vec_engine.vlen.byte_length();
 
-        Self(vec![0x00; vlenb * 32])
+        Self {
+            bytes: vec![0x00; vlenb * 32],
+            journal: Journal::default(),
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn bytes_mut(&mut self, range: Range<usize>) -> &mut [u8] {
+        if self.journal.is_recording() && !range.is_empty() {
+            let vlenb = self.bytes.len() / 32;
+
+            for nth in range.start / vlenb..=(range.end - 1) / vlenb {
+                let journaled = self.journal.entries().iter().any(|(reg, _)| *reg == nth);
+
+                if !journaled {
+                    let old = self.bytes[nth * vlenb..][..vlenb].to_vec();
+                    self.journal.record((nth, old));
+                }
+            }
+        }
+
+        &mut self.bytes[range]
+    }
+
+    pub(crate) fn journal(&mut self) -> &mut Journal<(usize, Vec<u8>)> {
+        &mut self.journal
+    }
+
+    pub(crate) fn undo(&mut self, entries: Vec<(usize, Vec<u8>)>) {
+        let vlenb = self.bytes.len() / 32;
+
+        for (nth, old) in entries {
+            self.bytes[nth * vlenb..][..vlenb].copy_from_slice(&old);
+        }
     }
 }
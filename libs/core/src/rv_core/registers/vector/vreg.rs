@@ -1,5 +1,6 @@
 use crate::rv_core::{
     arbitrary_float::ArbitraryFloat,
+    trap::Exception,
     vector_engine::sew::{BaseSew, FpSew, Sew},
 };
 
@@ -40,7 +41,7 @@ impl Vreg {
         }
     }
 
-    pub fn iter_fp(&self) -> Result<VregFPIterator<'_>, String> {
+    pub fn iter_fp(&self) -> Result<VregFPIterator<'_>, Exception> {
         Ok(VregFPIterator {
             byte_iterator: self.iter_byte(),
             eew: self.eew.fp()?,
@@ -60,7 +61,7 @@ impl FromIterator<u8> for Vreg {
     }
 }
 
-/// Iterators
+// Iterators
 
 // byte-by-byte
 
@@ -146,6 +147,12 @@ impl<'a> Iterator for VregFPIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.eew {
+            FpSew::E16 => self
+                .byte_iterator
+                .next_chunk()
+                .map(f16::from_le_bytes)
+                .map(ArbitraryFloat::F16)
+                .ok(),
             FpSew::E32 => self
                 .byte_iterator
                 .next_chunk()
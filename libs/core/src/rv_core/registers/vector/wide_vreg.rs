@@ -1,5 +1,6 @@
 use crate::rv_core::{
     arbitrary_float::ArbitraryFloat,
+    trap::Exception,
     vector_engine::sew::{DoubleFpSew, DoubleSew, Sew},
 };
 
@@ -25,7 +26,7 @@ impl WideVreg {
         }
     }
 
-    pub fn iter_fp(&self) -> Result<WideVregFPIterator<'_>, String> {
+    pub fn iter_fp(&self) -> Result<WideVregFPIterator<'_>, Exception> {
         Ok(WideVregFPIterator {
             byte_iterator: self.iter_byte(),
             eew: self.eew.fp()?,
@@ -33,7 +34,7 @@ impl WideVreg {
     }
 }
 
-/// Iterators
+// Iterators
 
 // byte-by-byte
 
@@ -99,6 +100,18 @@ impl<'a> Iterator for WideVregFPIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.eew {
+            DoubleFpSew::E16 => self
+                .byte_iterator
+                .next_chunk()
+                .map(f16::from_le_bytes)
+                .map(ArbitraryFloat::F16)
+                .ok(),
+            DoubleFpSew::E32 => self
+                .byte_iterator
+                .next_chunk()
+                .map(f32::from_le_bytes)
+                .map(ArbitraryFloat::F32)
+                .ok(),
             DoubleFpSew::E64 => self
                 .byte_iterator
                 .next_chunk()
@@ -0,0 +1,903 @@
+//! IEEE 754 arithmetic in software, host floats can't round in other modes nor report exceptions.
+//! Values are unpacked into exact `sig * 2^exp` form, computed exactly or with the lost bits
+//! jammed into the lowest one, and rounded once.
+
+use std::cmp::Ordering;
+
+use super::registers::aliases::fflags::*;
+
+/// Rounding modes of the rm field and frm
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even
+    #[default]
+    Rne = 0b000,
+    /// Round towards zero
+    Rtz = 0b001,
+    /// Round down, towards negative infinity
+    Rdn = 0b010,
+    /// Round up, towards positive infinity
+    Rup = 0b011,
+    /// Round to nearest, ties to max magnitude
+    Rmm = 0b100,
+}
+
+impl RoundingMode {
+    /// rm value selecting the dynamic rounding mode held in frm
+    pub const DYN: u32 = 0b111;
+
+    /// Decodes rm and frm values, the reserved ones yield None
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        Some(match bits {
+            0b000 => Self::Rne,
+            0b001 => Self::Rtz,
+            0b010 => Self::Rdn,
+            0b011 => Self::Rup,
+            0b100 => Self::Rmm,
+            _ => return None,
+        })
+    }
+}
+
+/// Binary interchange formats of the F, D and Zfh extensions
+pub trait IeeeFloat: Copy {
+    const EXPONENT: u32;
+    const MANTISSA: u32;
+
+    fn to_raw(self) -> u64;
+    fn from_raw(bits: u64) -> Self;
+}
+
+macro_rules! ieee_float {
+    ($float: ty, $bits: ty, $exponent: literal, $mantissa: literal) => {
+        impl IeeeFloat for $float {
+            const EXPONENT: u32 = $exponent;
+            const MANTISSA: u32 = $mantissa;
+
+            fn to_raw(self) -> u64 {
+                self.to_bits() as u64
+            }
+
+            fn from_raw(bits: u64) -> Self {
+                <$float>::from_bits(bits as $bits)
+            }
+        }
+    };
+}
+
+ieee_float!(f16, u16, 5, 10);
+ieee_float!(f32, u32, 8, 23);
+ieee_float!(f64, u64, 11, 52);
+
+/// Exact value of `sig * 2^exp`, zero when sig is
+#[derive(Clone, Copy, Debug)]
+struct Finite {
+    sign: bool,
+    exp: i32,
+    sig: u128,
+}
+
+impl Finite {
+    fn is_zero(&self) -> bool {
+        self.sig == 0
+    }
+
+    /// Exponent of the leading bit
+    fn top(&self) -> i32 {
+        self.exp + 127 - self.sig.leading_zeros() as i32
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Value {
+    Nan { signalling: bool },
+    Infinite(bool),
+    Finite(Finite),
+}
+
+impl Value {
+    fn sign(&self) -> bool {
+        match self {
+            Self::Nan { .. } => false,
+            Self::Infinite(sign) => *sign,
+            Self::Finite(finite) => finite.sign,
+        }
+    }
+
+    fn is_nan(&self) -> bool {
+        matches!(self, Self::Nan { .. })
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Self::Finite(finite) if finite.is_zero())
+    }
+}
+
+/// Discarded bits compared to half of the last kept unit
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Remainder {
+    Exact,
+    Below,
+    Half,
+    Above,
+}
+
+fn bias<F: IeeeFloat>() -> i32 {
+    (1 << (F::EXPONENT - 1)) - 1
+}
+
+fn pack<F: IeeeFloat>(sign: bool, exponent: u64, fraction: u64) -> F {
+    F::from_raw((sign as u64) << (F::EXPONENT + F::MANTISSA) | exponent << F::MANTISSA | fraction)
+}
+
+fn unpack<F: IeeeFloat>(float: F) -> Value {
+    let bits = float.to_raw();
+    let sign = bits >> (F::EXPONENT + F::MANTISSA) & 1 == 1;
+    let exponent = (bits >> F::MANTISSA) & ((1 << F::EXPONENT) - 1);
+    let fraction = bits & ((1 << F::MANTISSA) - 1);
+    let mantissa = F::MANTISSA as i32;
+
+    match exponent {
+        0 => Value::Finite(Finite {
+            sign,
+            exp: 1 - bias::<F>() - mantissa,
+            sig: fraction as u128,
+        }),
+        _ if exponent == (1 << F::EXPONENT) - 1 => match fraction {
+            0 => Value::Infinite(sign),
+            _ => Value::Nan {
+                signalling: fraction >> (F::MANTISSA - 1) == 0,
+            },
+        },
+        _ => Value::Finite(Finite {
+            sign,
+            exp: exponent as i32 - bias::<F>() - mantissa,
+            sig: (fraction | 1 << F::MANTISSA) as u128,
+        }),
+    }
+}
+
+fn canonical_nan<F: IeeeFloat>() -> F {
+    pack(false, (1 << F::EXPONENT) - 1, 1 << (F::MANTISSA - 1))
+}
+
+fn infinity<F: IeeeFloat>(sign: bool) -> F {
+    pack(sign, (1 << F::EXPONENT) - 1, 0)
+}
+
+fn zero<F: IeeeFloat>(sign: bool) -> F {
+    pack(sign, 0, 0)
+}
+
+fn check_signalling(operands: &[Value], flags: &mut u64) {
+    if operands
+        .iter()
+        .any(|value| matches!(value, Value::Nan { signalling: true }))
+    {
+        *flags |= NV;
+    }
+}
+
+/// NaN operands produce the canonical NaN, signalling ones also raise NV
+fn nan<F: IeeeFloat>(operands: &[Value], flags: &mut u64) -> F {
+    check_signalling(operands, flags);
+    canonical_nan()
+}
+
+fn invalid<F: IeeeFloat>(flags: &mut u64) -> F {
+    *flags |= NV;
+    canonical_nan()
+}
+
+fn shift_right(sig: u128, shift: i32) -> (u128, Remainder) {
+    if shift <= 0 {
+        return (sig << -shift, Remainder::Exact);
+    }
+
+    let (kept, discarded, half) = match shift {
+        1..=127 => (sig >> shift, sig & ((1 << shift) - 1), 1 << (shift - 1)),
+        128 => (0, sig, 1 << 127),
+        _ => (0, sig, u128::MAX),
+    };
+
+    let remainder = match discarded.cmp(&half) {
+        _ if discarded == 0 => Remainder::Exact,
+        Ordering::Less => Remainder::Below,
+        Ordering::Equal => Remainder::Half,
+        Ordering::Greater => Remainder::Above,
+    };
+
+    (kept, remainder)
+}
+
+fn rounds_up(kept: u128, remainder: Remainder, sign: bool, rm: RoundingMode) -> bool {
+    match rm {
+        RoundingMode::Rne => {
+            remainder == Remainder::Above || remainder == Remainder::Half && kept & 1 == 1
+        }
+        RoundingMode::Rtz => false,
+        RoundingMode::Rdn => sign && remainder != Remainder::Exact,
+        RoundingMode::Rup => !sign && remainder != Remainder::Exact,
+        RoundingMode::Rmm => remainder == Remainder::Above || remainder == Remainder::Half,
+    }
+}
+
+/// Rounds `sig >> shift` to an integer
+fn round_shifted(sig: u128, shift: i32, sign: bool, rm: RoundingMode) -> (u128, bool) {
+    let (kept, remainder) = shift_right(sig, shift);
+
+    (
+        kept + rounds_up(kept, remainder, sign, rm) as u128,
+        remainder != Remainder::Exact,
+    )
+}
+
+/// Rounds a nonzero value to the format, tininess is detected after rounding
+fn round<F: IeeeFloat>(value: Finite, rm: RoundingMode, flags: &mut u64) -> F {
+    let Finite { sign, exp, sig } = value;
+    let mantissa = F::MANTISSA as i32;
+    let emin = 1 - bias::<F>();
+    let top = value.top();
+
+    // Underflow is judged by rounding to full precision as if the exponent range were unbounded
+    let tiny = top < emin
+        && (top < emin - 1 || {
+            let (rounded, _) = round_shifted(sig, top - mantissa - exp, sign, rm);
+            rounded >> (mantissa + 1) == 0
+        });
+
+    let mut quantum = top.max(emin) - mantissa;
+    let (mut kept, inexact) = round_shifted(sig, quantum - exp, sign, rm);
+
+    if kept >> (mantissa + 1) != 0 {
+        kept >>= 1;
+        quantum += 1;
+    }
+
+    if inexact {
+        *flags |= NX;
+
+        if tiny {
+            *flags |= UF;
+        }
+    }
+
+    if quantum + mantissa > bias::<F>() {
+        *flags |= OF | NX;
+
+        let to_infinity = match rm {
+            RoundingMode::Rne | RoundingMode::Rmm => true,
+            RoundingMode::Rtz => false,
+            RoundingMode::Rdn => sign,
+            RoundingMode::Rup => !sign,
+        };
+
+        return match to_infinity {
+            true => infinity(sign),
+            // Largest finite value
+            false => pack(sign, (1 << F::EXPONENT) - 2, (1 << F::MANTISSA) - 1),
+        };
+    }
+
+    let exponent = match kept >> mantissa {
+        0 => 0,
+        _ => (quantum + mantissa + bias::<F>()) as u64,
+    };
+
+    pack(sign, exponent, kept as u64 & ((1 << F::MANTISSA) - 1))
+}
+
+/// Rounds a value that isn't NaN
+fn finish<F: IeeeFloat>(value: Value, rm: RoundingMode, flags: &mut u64) -> F {
+    match value {
+        Value::Nan { .. } => canonical_nan(),
+        Value::Infinite(sign) => infinity(sign),
+        Value::Finite(finite) if finite.is_zero() => zero(finite.sign),
+        Value::Finite(finite) => round(finite, rm, flags),
+    }
+}
+
+/// Sign of an exact zero sum, it's only negative for two negative addends or when rounding down
+fn zero_sum_sign(a: bool, b: bool, rm: RoundingMode) -> bool {
+    match rm {
+        RoundingMode::Rdn => a || b,
+        _ => a && b,
+    }
+}
+
+/// Adds values below 2^126 exactly, bits under 2^-125 of the larger one are jammed into the lowest bit
+fn add_finite(a: Finite, b: Finite) -> Finite {
+    if a.is_zero() {
+        return b;
+    }
+
+    if b.is_zero() {
+        return a;
+    }
+
+    let (big, small) = match a.top() >= b.top() {
+        true => (a, b),
+        false => (b, a),
+    };
+
+    // Leading bit of the larger operand lands on bit 125, leaving room for a carry
+    let shift = big.sig.leading_zeros() as i32 - 2;
+    let exp = big.exp - shift;
+    let big_sig = big.sig << shift;
+    let small_sig = match small.exp - exp {
+        shift @ 0.. => small.sig << shift,
+        shift => {
+            let (kept, remainder) = shift_right(small.sig, -shift);
+            kept | (remainder != Remainder::Exact) as u128
+        }
+    };
+
+    let (sign, sig) = match (big.sign == small.sign, big_sig >= small_sig) {
+        (true, _) => (big.sign, big_sig + small_sig),
+        (false, true) => (big.sign, big_sig - small_sig),
+        (false, false) => (small.sign, small_sig - big_sig),
+    };
+
+    Finite { sign, exp, sig }
+}
+
+fn sum<F: IeeeFloat>(a: Value, b: Value, rm: RoundingMode, flags: &mut u64) -> F {
+    match (a, b) {
+        _ if a.is_nan() || b.is_nan() => nan(&[a, b], flags),
+        (Value::Infinite(x), Value::Infinite(y)) if x != y => invalid(flags),
+        (Value::Infinite(sign), _) | (_, Value::Infinite(sign)) => infinity(sign),
+        (Value::Finite(x), Value::Finite(y)) => {
+            let sum = add_finite(x, y);
+
+            match sum.is_zero() {
+                true if x.is_zero() && y.is_zero() => zero(zero_sum_sign(x.sign, y.sign, rm)),
+                true => zero(rm == RoundingMode::Rdn),
+                false => round(sum, rm, flags),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn negate(value: Value) -> Value {
+    match value {
+        Value::Nan { .. } => value,
+        Value::Infinite(sign) => Value::Infinite(!sign),
+        Value::Finite(finite) => Value::Finite(Finite {
+            sign: !finite.sign,
+            ..finite
+        }),
+    }
+}
+
+/// Exact product of values that aren't NaN, None for infinity times zero
+fn multiply(a: Value, b: Value) -> Option<Value> {
+    let sign = a.sign() != b.sign();
+
+    match (a, b) {
+        (Value::Infinite(_), zero) | (zero, Value::Infinite(_)) if zero.is_zero() => None,
+        (Value::Infinite(_), _) | (_, Value::Infinite(_)) => Some(Value::Infinite(sign)),
+        (Value::Finite(x), Value::Finite(y)) => Some(Value::Finite(Finite {
+            sign,
+            exp: x.exp + y.exp,
+            sig: x.sig * y.sig,
+        })),
+        _ => unreachable!(),
+    }
+}
+
+pub fn add<F: IeeeFloat>(a: F, b: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+    let result = sum(unpack(a), unpack(b), rm, &mut flags);
+
+    (result, flags)
+}
+
+pub fn sub<F: IeeeFloat>(a: F, b: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+    let result = sum(unpack(a), negate(unpack(b)), rm, &mut flags);
+
+    (result, flags)
+}
+
+pub fn mul<F: IeeeFloat>(a: F, b: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+    let (a, b) = (unpack(a), unpack(b));
+
+    let result = if a.is_nan() || b.is_nan() {
+        nan(&[a, b], &mut flags)
+    } else {
+        match multiply(a, b) {
+            None => invalid(&mut flags),
+            Some(product) => finish(product, rm, &mut flags),
+        }
+    };
+
+    (result, flags)
+}
+
+/// Computes `a * b + c` with a single rounding, negated variants negate the operands first
+pub fn mul_add<F: IeeeFloat>(a: F, b: F, c: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+    let (a, b, c) = (unpack(a), unpack(b), unpack(c));
+
+    let result = if a.is_nan() || b.is_nan() || c.is_nan() {
+        // Infinity times zero is invalid even when the addend is a quiet NaN
+        if !a.is_nan() && !b.is_nan() && multiply(a, b).is_none() {
+            flags |= NV;
+        }
+
+        nan(&[a, b, c], &mut flags)
+    } else {
+        match (multiply(a, b), c) {
+            (None, _) => invalid(&mut flags),
+            (Some(Value::Infinite(x)), Value::Infinite(y)) if x != y => invalid(&mut flags),
+            (Some(Value::Infinite(sign)), _) | (_, Value::Infinite(sign)) => infinity(sign),
+            (Some(Value::Finite(product)), Value::Finite(addend)) => {
+                let sum = add_finite(product, addend);
+
+                match sum.is_zero() {
+                    true if product.is_zero() && addend.is_zero() => {
+                        zero(zero_sum_sign(product.sign, addend.sign, rm))
+                    }
+                    true => zero(rm == RoundingMode::Rdn),
+                    false => round(sum, rm, &mut flags),
+                }
+            }
+            _ => unreachable!(),
+        }
+    };
+
+    (result, flags)
+}
+
+pub fn div<F: IeeeFloat>(a: F, b: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+    let (a, b) = (unpack(a), unpack(b));
+    let sign = a.sign() != b.sign();
+
+    let result = match (a, b) {
+        _ if a.is_nan() || b.is_nan() => nan(&[a, b], &mut flags),
+        (Value::Infinite(_), Value::Infinite(_)) => invalid(&mut flags),
+        _ if a.is_zero() && b.is_zero() => invalid(&mut flags),
+        (Value::Infinite(_), _) => infinity(sign),
+        (_, Value::Infinite(_)) => zero(sign),
+        _ if b.is_zero() => {
+            flags |= DZ;
+            infinity(sign)
+        }
+        _ if a.is_zero() => zero(sign),
+        (Value::Finite(x), Value::Finite(y)) => {
+            // Dividend is widened to 126 bits, leaving at least 72 significant bits in the quotient
+            let shift = x.sig.leading_zeros() as i32 - 2;
+            let dividend = x.sig << shift;
+            let quotient = dividend / y.sig;
+            let inexact = !dividend.is_multiple_of(y.sig);
+
+            round(
+                Finite {
+                    sign,
+                    exp: x.exp - shift - y.exp,
+                    sig: quotient | inexact as u128,
+                },
+                rm,
+                &mut flags,
+            )
+        }
+        _ => unreachable!(),
+    };
+
+    (result, flags)
+}
+
+pub fn sqrt<F: IeeeFloat>(a: F, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+
+    let result = match unpack(a) {
+        a @ Value::Nan { .. } => nan(&[a], &mut flags),
+        Value::Finite(x) if x.is_zero() => zero(x.sign),
+        Value::Infinite(false) => infinity(false),
+        Value::Infinite(true) => invalid(&mut flags),
+        Value::Finite(x) if x.sign => invalid(&mut flags),
+        Value::Finite(x) => {
+            // Radicand is widened to at least 125 bits with an even exponent, so its root has 62 or more
+            let mut shift = x.sig.leading_zeros() as i32 - 2;
+            if (x.exp - shift) % 2 != 0 {
+                shift -= 1;
+            }
+
+            let radicand = x.sig << shift;
+            let root = radicand.isqrt();
+            let inexact = root * root != radicand;
+
+            round(
+                Finite {
+                    sign: false,
+                    exp: (x.exp - shift) / 2,
+                    sig: root | inexact as u128,
+                },
+                rm,
+                &mut flags,
+            )
+        }
+    };
+
+    (result, flags)
+}
+
+/// Converts between formats, narrowing rounds with rm
+pub fn convert<F: IeeeFloat, T: IeeeFloat>(a: F, rm: RoundingMode) -> (T, u64) {
+    let mut flags = 0;
+
+    let result = match unpack(a) {
+        a @ Value::Nan { .. } => nan(&[a], &mut flags),
+        a => finish(a, rm, &mut flags),
+    };
+
+    (result, flags)
+}
+
+/// Converts to a `width` bit integer and sign-extends it to 64 bits.
+/// NaNs and out of range values saturate and raise NV, NaNs go to the largest value.
+pub fn to_int<F: IeeeFloat>(a: F, signed: bool, width: u32, rm: RoundingMode) -> (u64, u64) {
+    let mut flags = 0;
+    let (min, max): (i128, i128) = match signed {
+        true => (-(1 << (width - 1)), (1 << (width - 1)) - 1),
+        false => (0, (1 << width) - 1),
+    };
+
+    let integer = match unpack(a) {
+        Value::Nan { .. } => {
+            flags |= NV;
+            max
+        }
+        Value::Infinite(sign) => {
+            flags |= NV;
+            if sign {
+                min
+            } else {
+                max
+            }
+        }
+        Value::Finite(x) => {
+            // Anything past 2^65 is out of range, the bound keeps the shift within i128
+            let (magnitude, inexact) = match x.top() > 65 {
+                true => (1 << 66, false),
+                false => round_shifted(x.sig, -x.exp, x.sign, rm),
+            };
+            let integer = match x.sign {
+                true => -(magnitude as i128),
+                false => magnitude as i128,
+            };
+
+            if integer < min || integer > max {
+                flags |= NV;
+                integer.clamp(min, max)
+            } else {
+                if inexact {
+                    flags |= NX;
+                }
+                integer
+            }
+        }
+    };
+
+    let bits = match width {
+        32 => integer as u32 as i32 as u64,
+        _ => integer as u64,
+    };
+
+    (bits, flags)
+}
+
+fn from_integer<F: IeeeFloat>(sign: bool, magnitude: u64, rm: RoundingMode) -> (F, u64) {
+    let mut flags = 0;
+
+    let result = match magnitude {
+        0 => zero(false),
+        _ => round(
+            Finite {
+                sign,
+                exp: 0,
+                sig: magnitude as u128,
+            },
+            rm,
+            &mut flags,
+        ),
+    };
+
+    (result, flags)
+}
+
+pub fn from_i64<F: IeeeFloat>(value: i64, rm: RoundingMode) -> (F, u64) {
+    from_integer(value < 0, value.unsigned_abs(), rm)
+}
+
+pub fn from_u64<F: IeeeFloat>(value: u64, rm: RoundingMode) -> (F, u64) {
+    from_integer(false, value, rm)
+}
+
+/// Position on the number line, equal for both zeros
+fn ordinal<F: IeeeFloat>(float: F) -> i128 {
+    let bits = float.to_raw();
+    let magnitude = (bits & ((1 << (F::EXPONENT + F::MANTISSA)) - 1)) as i128;
+
+    match bits >> (F::EXPONENT + F::MANTISSA) {
+        0 => magnitude,
+        _ => -magnitude,
+    }
+}
+
+fn compare<F: IeeeFloat>(a: F, b: F, signalling: bool) -> (Option<Ordering>, u64) {
+    let (x, y) = (unpack(a), unpack(b));
+
+    if x.is_nan() || y.is_nan() {
+        let mut flags = 0;
+        match signalling {
+            true => flags |= NV,
+            false => check_signalling(&[x, y], &mut flags),
+        }
+
+        return (None, flags);
+    }
+
+    (Some(ordinal(a).cmp(&ordinal(b))), 0)
+}
+
+/// Quiet comparison, only signalling NaNs raise NV
+pub fn eq<F: IeeeFloat>(a: F, b: F) -> (bool, u64) {
+    let (ordering, flags) = compare(a, b, false);
+    (ordering == Some(Ordering::Equal), flags)
+}
+
+/// Signalling comparison, any NaN raises NV
+pub fn lt<F: IeeeFloat>(a: F, b: F) -> (bool, u64) {
+    let (ordering, flags) = compare(a, b, true);
+    (ordering == Some(Ordering::Less), flags)
+}
+
+/// Signalling comparison, any NaN raises NV
+pub fn le<F: IeeeFloat>(a: F, b: F) -> (bool, u64) {
+    let (ordering, flags) = compare(a, b, true);
+    (
+        matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        flags,
+    )
+}
+
+/// fmin and fmax pick the other operand over a NaN and order -0 below +0
+fn pick<F: IeeeFloat>(a: F, b: F, wanted: Ordering) -> (F, u64) {
+    let mut flags = 0;
+    let (x, y) = (unpack(a), unpack(b));
+
+    let result = match (x.is_nan(), y.is_nan()) {
+        (true, true) => nan(&[x, y], &mut flags),
+        (true, false) => {
+            check_signalling(&[x], &mut flags);
+            b
+        }
+        (false, true) => {
+            check_signalling(&[y], &mut flags);
+            a
+        }
+        (false, false) => {
+            let ordering = match ordinal(a).cmp(&ordinal(b)) {
+                Ordering::Equal if x.sign() != y.sign() => match x.sign() {
+                    true => Ordering::Less,
+                    false => Ordering::Greater,
+                },
+                ordering => ordering,
+            };
+
+            match ordering == wanted {
+                true => a,
+                false => b,
+            }
+        }
+    };
+
+    (result, flags)
+}
+
+pub fn min<F: IeeeFloat>(a: F, b: F) -> (F, u64) {
+    pick(a, b, Ordering::Less)
+}
+
+pub fn max<F: IeeeFloat>(a: F, b: F) -> (F, u64) {
+    pick(a, b, Ordering::Greater)
+}
+
+/// fclass mask, from bit 0: -inf, -normal, -subnormal, -0, +0, +subnormal, +normal, +inf, sNaN, qNaN
+pub fn classify<F: IeeeFloat>(a: F) -> u64 {
+    let exponent = (a.to_raw() >> F::MANTISSA) & ((1 << F::EXPONENT) - 1);
+
+    match unpack(a) {
+        Value::Nan { signalling: true } => 1 << 8,
+        Value::Nan { signalling: false } => 1 << 9,
+        Value::Infinite(true) => 1 << 0,
+        Value::Infinite(false) => 1 << 7,
+        Value::Finite(x) => match (x.sign, x.is_zero(), exponent) {
+            (true, true, _) => 1 << 3,
+            (false, true, _) => 1 << 4,
+            (true, false, 0) => 1 << 2,
+            (false, false, 0) => 1 << 5,
+            (true, false, _) => 1 << 1,
+            (false, false, _) => 1 << 6,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::Rne,
+        RoundingMode::Rtz,
+        RoundingMode::Rdn,
+        RoundingMode::Rup,
+        RoundingMode::Rmm,
+    ];
+
+    #[test]
+    fn nearest_even_matches_host() {
+        let values = [
+            0.1f64,
+            -3.75,
+            1e300,
+            -1e-310,
+            7.0,
+            f64::MIN_POSITIVE,
+            2.0f64.powi(-1074),
+            123456.789,
+            -0.0,
+        ];
+
+        // Host NaNs aren't canonical, zeros have to agree on the sign
+        let same = |x: f64, y: f64| x.to_bits() == y.to_bits() || x.is_nan() && y.is_nan();
+        let rne = RoundingMode::Rne;
+
+        for a in values {
+            for b in values {
+                assert!(same(add(a, b, rne).0, a + b));
+                assert!(same(sub(a, b, rne).0, a - b));
+                assert!(same(mul(a, b, rne).0, a * b));
+                assert!(same(mul_add(a, b, a, rne).0, a.mul_add(b, a)));
+                assert!(same(div(a, b, rne).0, a / b));
+
+                let (a, b) = (a as f32, b as f32);
+                assert!(same(add(a, b, rne).0 as f64, (a + b) as f64));
+                assert!(same(mul(a, b, rne).0 as f64, (a * b) as f64));
+                assert!(same(div(a, b, rne).0 as f64, (a / b) as f64));
+            }
+
+            assert!(same(sqrt(a.abs(), rne).0, a.abs().sqrt()));
+            assert!(same(convert::<f64, f32>(a, rne).0 as f64, a as f32 as f64));
+        }
+    }
+
+    #[test]
+    fn directed_modes_bracket_the_exact_result() {
+        let third = |rm| div(1.0f32, 3.0, rm);
+
+        assert_eq!(third(RoundingMode::Rne), (0.33333334, NX));
+        assert_eq!(third(RoundingMode::Rtz), (0.3333333, NX));
+        assert_eq!(third(RoundingMode::Rdn), (0.3333333, NX));
+        assert_eq!(third(RoundingMode::Rup), (0.33333334, NX));
+        assert_eq!(div(-1.0f32, 3.0, RoundingMode::Rdn).0, -0.33333334);
+
+        // 1 + 2^-24 is a tie between 1 and the next single
+        let tie = 1.0 + 2.0f64.powi(-24);
+        assert_eq!(convert::<f64, f32>(tie, RoundingMode::Rne).0, 1.0);
+        assert_eq!(
+            convert::<f64, f32>(tie, RoundingMode::Rmm).0,
+            1.0 + f32::EPSILON
+        );
+
+        for rm in MODES {
+            assert_eq!(add(0.5f64, 0.25, rm), (0.75, 0));
+        }
+    }
+
+    #[test]
+    fn exceptions_are_reported() {
+        assert_eq!(div(1.0f64, 0.0, RoundingMode::Rne), (f64::INFINITY, DZ));
+        assert_eq!(sqrt(-1.0f64, RoundingMode::Rne).1, NV);
+        assert_eq!(
+            mul(f32::MAX, 2.0, RoundingMode::Rne),
+            (f32::INFINITY, OF | NX)
+        );
+        assert_eq!(mul(f32::MAX, 2.0, RoundingMode::Rtz), (f32::MAX, OF | NX));
+        assert_eq!(
+            mul(f32::MIN_POSITIVE, 0.5, RoundingMode::Rne),
+            (f32::MIN_POSITIVE / 2.0, 0)
+        );
+        assert_eq!(mul(f32::MIN_POSITIVE, 0.3, RoundingMode::Rne).1, UF | NX);
+        // Rounds up to the smallest normal, so it isn't tiny after rounding
+        let almost_normal = f32::MIN_POSITIVE as f64 - 2.0f64.powi(-151);
+        assert_eq!(
+            convert::<f64, f32>(almost_normal, RoundingMode::Rup),
+            (f32::MIN_POSITIVE, NX)
+        );
+        assert_eq!(
+            mul_add(f64::INFINITY, 0.0, f64::NAN, RoundingMode::Rne).1,
+            NV
+        );
+    }
+
+    #[test]
+    fn nans_are_canonical() {
+        let signalling = f32::from_bits(0xff80_0001);
+
+        assert_eq!(
+            add(signalling, 1.0, RoundingMode::Rne).0.to_bits(),
+            0x7fc0_0000
+        );
+        assert_eq!(add(signalling, 1.0, RoundingMode::Rne).1, NV);
+        assert_eq!(
+            sub(f64::INFINITY, f64::INFINITY, RoundingMode::Rne)
+                .0
+                .to_bits(),
+            0x7ff8_0000_0000_0000
+        );
+        assert_eq!(
+            convert::<f32, f16>(f32::NAN, RoundingMode::Rne).0.to_bits(),
+            0x7e00
+        );
+        assert_eq!(min(f32::NAN, 2.0), (2.0, 0));
+        assert_eq!(min(signalling, 2.0), (2.0, NV));
+        assert_eq!(min(-0.0f32, 0.0).0.to_bits(), 0x8000_0000);
+        assert_eq!(max(-0.0f32, 0.0).0.to_bits(), 0);
+        assert_eq!(eq(f32::NAN, 1.0), (false, 0));
+        assert_eq!(lt(f32::NAN, 1.0), (false, NV));
+        assert_eq!(classify(signalling), 1 << 8);
+    }
+
+    #[test]
+    fn nan_products_are_canonical() {
+        let rne = RoundingMode::Rne;
+
+        // Only signalling NaNs raise the invalid flag
+        assert_eq!(mul(f64::NAN, 2.0, rne).0.to_bits(), 0x7ff8_0000_0000_0000);
+        assert_eq!(mul(f64::NAN, 2.0, rne).1, 0);
+        assert_eq!(mul(f32::INFINITY, f32::NAN, rne).0.to_bits(), 0x7fc0_0000);
+
+        let signalling = f32::from_bits(0x7f80_0001);
+        assert_eq!(mul(2.0, signalling, rne).0.to_bits(), 0x7fc0_0000);
+        assert_eq!(mul(2.0, signalling, rne).1, NV);
+
+        let signalling = f16::from_bits(0x7c01);
+        assert_eq!(mul(signalling, f16::from_bits(0), rne).0.to_bits(), 0x7e00);
+        assert_eq!(mul(signalling, f16::from_bits(0), rne).1, NV);
+    }
+
+    #[test]
+    fn integer_conversions_saturate() {
+        assert_eq!(to_int(2.5f64, true, 32, RoundingMode::Rne), (2, NX));
+        assert_eq!(to_int(2.5f64, true, 32, RoundingMode::Rmm), (3, NX));
+        assert_eq!(
+            to_int(-2.5f64, true, 32, RoundingMode::Rdn),
+            (-3i64 as u64, NX)
+        );
+        assert_eq!(to_int(-0.5f64, false, 32, RoundingMode::Rne), (0, NX));
+        assert_eq!(to_int(-1.0f64, false, 32, RoundingMode::Rne), (0, NV));
+        assert_eq!(
+            to_int(f64::NAN, true, 32, RoundingMode::Rne),
+            (0x7fff_ffff, NV)
+        );
+        assert_eq!(
+            to_int(1e10f64, false, 32, RoundingMode::Rne),
+            (u64::MAX, NV)
+        );
+        assert_eq!(to_int(-1e30f64, true, 64, RoundingMode::Rne), (1 << 63, NV));
+        assert_eq!(
+            to_int(-(2.0f64.powi(63)), true, 64, RoundingMode::Rne),
+            (1 << 63, 0)
+        );
+        assert_eq!(
+            from_u64::<f32>(u64::MAX, RoundingMode::Rtz),
+            (1.8446743e19, NX)
+        );
+        assert_eq!(from_i64::<f64>(-7, RoundingMode::Rne), (-7.0, 0));
+    }
+}
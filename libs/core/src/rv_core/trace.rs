@@ -0,0 +1,613 @@
+use std::fmt::Write;
+
+use super::{
+    history::Diff,
+    instruction::{decoder::decode, Csr, Instruction},
+    registers::{
+        aliases::csr::{CYCLE, FCSR, FFLAGS, FRM, INSTRET, TIME, VL},
+        PrivilegeMode,
+    },
+    vector_engine::{sew::Sew, Lmul},
+    RvCore,
+};
+
+/// Load or store made by an instruction, the address is virtual and the data little-endian
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MemoryAccess {
+    Load { address: u64, data: Vec<u8> },
+    Store { address: u64, data: Vec<u8> },
+}
+
+/// Vector configuration written vector registers were produced under
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VectorConfig {
+    pub sew: usize,
+    pub lmul: Lmul,
+    pub vl: u64,
+}
+
+/// Effects of a retired instruction, registers are listed with the value they were left with
+#[derive(Clone, PartialEq, Debug)]
+pub struct TraceEntry {
+    pub pc: u64,
+    /// Privilege mode the instruction ran in
+    pub mode: PrivilegeMode,
+    /// Encoding, the upper halfword is zero for compressed instructions
+    pub raw: u32,
+    pub instruction: Instruction,
+    pub x: Vec<(usize, u64)>,
+    pub f: Vec<(usize, u64)>,
+    pub v: Vec<(usize, Vec<u8>)>,
+    pub vector_config: VectorConfig,
+    /// Software visible CSR writes, the counters every instruction bumps are left out
+    pub c: Vec<(usize, u64)>,
+    pub memory: Vec<MemoryAccess>,
+}
+
+impl TraceEntry {
+    /// Line in the format of Spike's `--log-commits`, without the trailing newline
+    pub fn commit_log(&self) -> String {
+        let mut line = format!(
+            "core   0: {} 0x{:016x} (0x{})",
+            self.mode as u64,
+            self.pc,
+            self.raw_hex()
+        );
+
+        for &(index, value) in &self.x {
+            write!(line, " x{:<2} 0x{:016x}", index, value).unwrap();
+        }
+        for &(index, value) in &self.f {
+            write!(line, " f{:<2} 0x{:016x}", index, value).unwrap();
+        }
+        for (index, value) in &self.v {
+            let VectorConfig { sew, lmul, vl } = self.vector_config;
+
+            write!(
+                line,
+                " e{} {} l{} v{:<2} 0x{}",
+                sew,
+                lmul_name(lmul),
+                vl,
+                index,
+                hex(value)
+            )
+            .unwrap();
+        }
+        for &(csr, value) in &self.c {
+            write!(line, " c{}_{} 0x{:016x}", csr, Csr(csr), value).unwrap();
+        }
+
+        let loads = self.memory.iter().filter_map(|access| match access {
+            MemoryAccess::Load { address, .. } => Some(address),
+            MemoryAccess::Store { .. } => None,
+        });
+        for address in loads {
+            write!(line, " mem 0x{:016x}", address).unwrap();
+        }
+
+        let stores = self.memory.iter().filter_map(|access| match access {
+            MemoryAccess::Store { address, data } => Some((address, data)),
+            MemoryAccess::Load { .. } => None,
+        });
+        for (address, data) in stores {
+            write!(line, " mem 0x{:016x} 0x{}", address, hex(data)).unwrap();
+        }
+
+        line
+    }
+
+    /// Single line JSON object, values are hex strings so they survive 53-bit JSON numbers
+    pub fn json(&self) -> String {
+        let registers = |registers: &[(usize, u64)]| {
+            let entries: Vec<_> = registers
+                .iter()
+                .map(|(index, value)| format!("[{},\"0x{:x}\"]", index, value))
+                .collect();
+
+            format!("[{}]", entries.join(","))
+        };
+
+        let v: Vec<_> = self
+            .v
+            .iter()
+            .map(|(index, value)| format!("[{},\"0x{}\"]", index, hex(value)))
+            .collect();
+        let c: Vec<_> = self
+            .c
+            .iter()
+            .map(|(csr, value)| format!("[{},\"{}\",\"0x{:x}\"]", csr, Csr(*csr), value))
+            .collect();
+        let memory: Vec<_> = self
+            .memory
+            .iter()
+            .map(|access| {
+                let (kind, address, data) = match access {
+                    MemoryAccess::Load { address, data } => ("load", address, data),
+                    MemoryAccess::Store { address, data } => ("store", address, data),
+                };
+
+                format!(
+                    "{{\"op\":\"{}\",\"address\":\"0x{:x}\",\"data\":\"0x{}\"}}",
+                    kind,
+                    address,
+                    hex(data)
+                )
+            })
+            .collect();
+        let VectorConfig { sew, lmul, vl } = self.vector_config;
+
+        format!(
+            "{{\"pc\":\"0x{:x}\",\"mode\":{},\"raw\":\"0x{}\",\"disasm\":{},\"x\":{},\"f\":{},\"v\":[{}],\"vtype\":{{\"sew\":{},\"lmul\":\"{}\",\"vl\":{}}},\"csr\":[{}],\"mem\":[{}]}}",
+            self.pc,
+            self.mode as u64,
+            self.raw_hex(),
+            json_string(&self.instruction.disassemble(self.pc).to_string()),
+            registers(&self.x),
+            registers(&self.f),
+            v.join(","),
+            sew,
+            lmul_name(lmul),
+            vl,
+            c.join(","),
+            memory.join(",")
+        )
+    }
+
+    fn raw_hex(&self) -> String {
+        match self.instruction {
+            Instruction::Compressed(..) => format!("{:04x}", self.raw),
+            _ => format!("{:08x}", self.raw),
+        }
+    }
+}
+
+/// Retired instructions in program order, recorded while enabled.
+/// Entries accumulate until the embedder takes them.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Trace {
+    enabled: bool,
+    entries: Vec<TraceEntry>,
+}
+
+/// State ahead of a traced step
+pub(super) struct PendingEntry {
+    pc: u64,
+    mode: PrivilegeMode,
+    raw: Option<u32>,
+    instret: u64,
+}
+
+impl Trace {
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+
+    pub fn take(&mut self) -> Vec<TraceEntry> {
+        std::mem::take(&mut self.entries)
+    }
+
+    /// Spike commit log, one line per instruction
+    pub fn commit_log(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| entry.commit_log() + "\n")
+            .collect()
+    }
+
+    /// JSON lines, one object per instruction
+    pub fn json_lines(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| entry.json() + "\n")
+            .collect()
+    }
+
+    /// Fetches the instruction upfront since the step may change the mapping
+    pub(super) fn begin(core: &mut RvCore) -> Option<PendingEntry> {
+        if !core.trace.enabled {
+            return None;
+        }
+
+        let pc = core.registers.pc;
+
+        Some(PendingEntry {
+            pc,
+            mode: core.registers.mode,
+            raw: core.mmu().fetch(pc).ok(),
+            instret: core.registers.c[INSTRET].read(),
+        })
+    }
+
+    /// Adds an entry when the step retired an instruction, traps and interrupts don't
+    pub(super) fn finish(
+        core: &mut RvCore,
+        pending: PendingEntry,
+        diff: &Diff,
+        memory: Vec<MemoryAccess>,
+    ) {
+        let retired = core.registers.c[INSTRET].read() != pending.instret;
+        let Some((raw, instruction)) = pending
+            .raw
+            .filter(|_| retired)
+            .and_then(|raw| Some((raw, decode(raw).ok()?)))
+        else {
+            return;
+        };
+
+        let registers = &core.registers;
+        let vlenb = registers.v.bytes().len() / 32;
+        let counters = [CYCLE, TIME, INSTRET];
+        let c = written(&diff.c)
+            .filter(|csr| !counters.contains(csr))
+            .flat_map(|csr| logged_csrs(csr, &instruction))
+            .map(|csr| (csr, registers.c.read(csr)))
+            .collect();
+
+        let entry = TraceEntry {
+            pc: pending.pc,
+            mode: pending.mode,
+            raw: if raw & 0b11 == 0b11 {
+                raw
+            } else {
+                raw & 0xffff
+            },
+            instruction,
+            // Spike doesn't log writes to x0 either
+            x: written(&diff.x)
+                .filter(|&index| index != 0)
+                .map(|index| (index, registers.x[index]))
+                .collect(),
+            f: written(&diff.f)
+                .map(|index| (index, registers.f[index]))
+                .collect(),
+            v: written(&diff.v)
+                .map(|index| {
+                    (
+                        index,
+                        registers.v.bytes()[index * vlenb..][..vlenb].to_vec(),
+                    )
+                })
+                .collect(),
+            vector_config: VectorConfig {
+                sew: core.vec_engine.sew.bit_length(),
+                lmul: core.vec_engine.lmul,
+                vl: registers.c[VL].read(),
+            },
+            c,
+            memory,
+        };
+
+        core.trace.entries.push(entry);
+    }
+}
+
+/// Registers a journal holds, in the order of their first write
+fn written<T>(journal: &[(usize, T)]) -> impl Iterator<Item = usize> {
+    let mut registers: Vec<usize> = Vec::new();
+
+    for (index, _) in journal {
+        if !registers.contains(index) {
+            registers.push(*index);
+        }
+    }
+
+    registers.into_iter()
+}
+
+/// CSRs a write to a backing register is logged as.
+/// Spike keeps fflags and frm on their own and logs fcsr writes through them.
+fn logged_csrs(csr: usize, instruction: &Instruction) -> Vec<usize> {
+    use Instruction::*;
+
+    let accessed = match instruction {
+        Csrrw(csrr) | Csrrs(csrr) | Csrrc(csrr) => Some(csrr.csr),
+        Csrrwi(csri) | Csrrsi(csri) | Csrrci(csri) => Some(csri.csr),
+        _ => None,
+    };
+
+    match (csr, accessed) {
+        (FCSR, Some(FRM)) => vec![FRM],
+        (FCSR, Some(FCSR)) => vec![FRM, FFLAGS],
+        // Floating point operations only accrue flags
+        (FCSR, _) => vec![FFLAGS],
+        _ => vec![csr],
+    }
+}
+
+fn lmul_name(lmul: Lmul) -> &'static str {
+    match lmul {
+        Lmul::MF8 => "mf8",
+        Lmul::MF4 => "mf4",
+        Lmul::MF2 => "mf2",
+        Lmul::M1 => "m1",
+        Lmul::M2 => "m2",
+        Lmul::M4 => "m4",
+        Lmul::M8 => "m8",
+    }
+}
+
+/// Little-endian bytes as a single hex number, most significant digit first
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .rev()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn json_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rv_core::{
+        instruction::{
+            encoder::compress,
+            format::{self, Csri, Csrr, Opivi, Rfp, I, S},
+        },
+        memory::Memory,
+        registers::aliases::csr::MSCRATCH,
+        softfloat::RoundingMode,
+        vector_engine::{sew::BaseSew, AgnosticFill, VectorEngineBuilder},
+        RvCoreBuilder,
+    };
+
+    #[test]
+    fn retired_instructions_are_logged() {
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .trace(Trace::enabled())
+            .build();
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 3,
+            }),
+            Sd(S {
+                rs1: 0,
+                rs2: 5,
+                imm12: 0x10,
+            }),
+            Ld(I {
+                rd: 6,
+                rs1: 0,
+                imm12: 0x10,
+            }),
+            Csrrw(Csrr {
+                rd: 0,
+                rs1: 5,
+                csr: MSCRATCH,
+            }),
+            Vsetvli(format::Vsetvli {
+                rd: 7,
+                rs1: 5,
+                vtypei: 0,
+            }),
+            Vaddvi(Opivi {
+                dest: 1,
+                imm5: 5,
+                vs2: 0,
+                vm: false,
+            }),
+            compress(&Addi(I {
+                rd: 8,
+                rs1: 0,
+                imm12: 1,
+            })),
+        ])
+        .unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let log = core.trace.commit_log();
+        let lines: Vec<_> = log.lines().collect();
+
+        assert_eq!(
+            lines,
+            [
+                "core   0: 3 0x0000000000000100 (0x00300293) x5  0x0000000000000003",
+                "core   0: 3 0x0000000000000104 (0x00503823) mem 0x0000000000000010 0x0000000000000003",
+                "core   0: 3 0x0000000000000108 (0x01003303) x6  0x0000000000000003 mem 0x0000000000000010",
+                "core   0: 3 0x000000000000010c (0x34029073) c832_mscratch 0x0000000000000003",
+                "core   0: 3 0x0000000000000110 (0x0002f3d7) x7  0x0000000000000003 c3105_vtype 0x0000000000000000 c3104_vl 0x0000000000000003 c8_vstart 0x0000000000000000",
+                "core   0: 3 0x0000000000000114 (0x0202b0d7) e8 m1 l3 v1  0x00000000000000000000000000050505 c8_vstart 0x0000000000000000",
+                "core   0: 3 0x0000000000000118 (0x4405) x8  0x0000000000000001",
+            ]
+        );
+
+        assert_eq!(
+            core.trace.entries()[2].json(),
+            r#"{"pc":"0x108","mode":3,"raw":"0x01003303","disasm":"ld t1, 16(zero)","x":[[6,"0x3"]],"f":[],"v":[],"vtype":{"sew":8,"lmul":"m1","vl":0},"csr":[],"mem":[{"op":"load","address":"0x10","data":"0x0000000000000003"}]}"#
+        );
+        assert_eq!(core.trace.json_lines().lines().count(), 7);
+        assert_eq!(core.trace.take().len(), 7);
+        assert!(core.trace.entries().is_empty());
+    }
+
+    #[test]
+    fn float_csrs_are_logged_as_fflags_and_frm() {
+        use Instruction::*;
+
+        let dynamic = |rd, rs1, rs2| Rfp {
+            rd,
+            rs1,
+            rs2,
+            rm: RoundingMode::DYN,
+        };
+
+        let mut core = RvCoreBuilder::default().trace(Trace::enabled()).build();
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 1,
+            }),
+            Fcvtsw(dynamic(0, 5, 0)),
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 3,
+            }),
+            Fcvtsw(dynamic(1, 5, 0)),
+            Fdivs(dynamic(2, 0, 1)),
+            Csrrwi(Csri {
+                rd: 0,
+                uimm: 1,
+                csr: FRM,
+            }),
+            Csrrw(Csrr {
+                rd: 0,
+                rs1: 0,
+                csr: FCSR,
+            }),
+        ])
+        .unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let csrs: Vec<_> = core
+            .trace
+            .entries()
+            .iter()
+            .map(|entry| entry.c.clone())
+            .collect();
+
+        assert_eq!(
+            csrs,
+            [
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![(FFLAGS, 1)],
+                vec![(FRM, 1)],
+                vec![(FRM, 0), (FFLAGS, 0)],
+            ]
+        );
+        assert!(core
+            .trace
+            .commit_log()
+            .contains(" c1_fflags 0x0000000000000001"));
+    }
+
+    #[test]
+    fn masked_loads_log_active_elements_only() {
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .agnostic_fill(AgnosticFill::Ones)
+                    .build()
+                    .unwrap(),
+            )
+            .trace(Trace::enabled())
+            .build();
+        core.load_program(&[
+            // e8, m1, ta, ma
+            Vsetivli(format::Vsetivli {
+                rd: 0,
+                uimm: 4,
+                vtypei: 0b1100_0000,
+            }),
+            Vlv {
+                data: format::Vl {
+                    vd: 1,
+                    rs1: 0,
+                    vm: true,
+                },
+                eew: BaseSew::E8,
+            },
+        ])
+        .unwrap();
+        core.memory.load(0, &[1, 2, 3, 4]);
+        core.registers.v.bytes_mut(0..1)[0] = 0b0101;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let entry = &core.trace.entries()[1];
+
+        assert_eq!(
+            entry.memory,
+            [
+                MemoryAccess::Load {
+                    address: 0,
+                    data: vec![1],
+                },
+                MemoryAccess::Load {
+                    address: 2,
+                    data: vec![3],
+                },
+            ]
+        );
+        assert_eq!(entry.v.len(), 1);
+        assert_eq!(entry.v[0].1[..4], [1, 0xff, 3, 0xff]);
+    }
+
+    #[test]
+    fn empty_vector_ops_and_traps_write_nothing() {
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .trace(Trace::enabled())
+            .build();
+        core.load_program(&[
+            // vl = 0
+            Vsetivli(format::Vsetivli {
+                rd: 0,
+                uimm: 0,
+                vtypei: 0b1100_0000,
+            }),
+            Vaddvi(Opivi {
+                dest: 1,
+                imm5: 5,
+                vs2: 0,
+                vm: false,
+            }),
+            Ld(I {
+                rd: 5,
+                rs1: 0,
+                imm12: -8,
+            }),
+        ])
+        .unwrap();
+
+        assert!(core.run().any(|result| result.is_err()));
+
+        // The faulting load didn't retire
+        let entries = core.trace.entries();
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[1].v.is_empty());
+        assert!(entries[1].memory.is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use super::registers::{
+    aliases::{
+        csr::{MIDELEG, MIE, MIP},
+        interrupt, mstatus,
+    },
+    CsrRegisters, PrivilegeMode,
+};
+
+/// Synchronous exception raised by an instruction, payload is the value reported in mtval
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Exception {
+    InstructionAddressMisaligned(u64),
+    InstructionAccessFault(u64),
+    /// Holds raw instruction bits, 0 when they aren't known
+    IllegalInstruction(u64),
+    Breakpoint,
+    LoadAddressMisaligned(u64),
+    LoadAccessFault(u64),
+    StoreAddressMisaligned(u64),
+    StoreAccessFault(u64),
+    /// Holds the mode the call was made from
+    EnvironmentCall(PrivilegeMode),
+    InstructionPageFault(u64),
+    LoadPageFault(u64),
+    StorePageFault(u64),
+}
+
+impl Exception {
+    /// Exception code written to mcause
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::InstructionAddressMisaligned(_) => 0,
+            Self::InstructionAccessFault(_) => 1,
+            Self::IllegalInstruction(_) => 2,
+            Self::Breakpoint => 3,
+            Self::LoadAddressMisaligned(_) => 4,
+            Self::LoadAccessFault(_) => 5,
+            Self::StoreAddressMisaligned(_) => 6,
+            Self::StoreAccessFault(_) => 7,
+            Self::EnvironmentCall(mode) => 8 + *mode as u64,
+            Self::InstructionPageFault(_) => 12,
+            Self::LoadPageFault(_) => 13,
+            Self::StorePageFault(_) => 15,
+        }
+    }
+
+    pub fn at(self, pc: u64) -> Trap {
+        Trap {
+            exception: self,
+            pc,
+        }
+    }
+}
+
+impl fmt::Display for Exception {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InstructionAddressMisaligned(address) => {
+                write!(f, "Misaligned instruction address {:#x}", address)
+            }
+            Self::InstructionAccessFault(address) => {
+                write!(f, "Instruction access fault at {:#x}", address)
+            }
+            Self::IllegalInstruction(0) => write!(f, "Illegal instruction"),
+            Self::IllegalInstruction(raw) => write!(f, "Illegal instruction {:#010x}", raw),
+            Self::Breakpoint => write!(f, "Breakpoint"),
+            Self::LoadAddressMisaligned(address) => {
+                write!(f, "Misaligned load from {:#x}", address)
+            }
+            Self::LoadAccessFault(address) => {
+                write!(f, "Load access fault at {:#x}", address)
+            }
+            Self::StoreAddressMisaligned(address) => {
+                write!(f, "Misaligned store to {:#x}", address)
+            }
+            Self::StoreAccessFault(address) => {
+                write!(f, "Store access fault at {:#x}", address)
+            }
+            Self::EnvironmentCall(mode) => write!(f, "Environment call from {:?} mode", mode),
+            Self::InstructionPageFault(address) => {
+                write!(f, "Instruction page fault at {:#x}", address)
+            }
+            Self::LoadPageFault(address) => write!(f, "Load page fault at {:#x}", address),
+            Self::StorePageFault(address) => write!(f, "Store page fault at {:#x}", address),
+        }
+    }
+}
+
+/// Asynchronous interrupt, identified by its bit in mip and mie
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interrupt {
+    SupervisorSoftware,
+    MachineSoftware,
+    SupervisorTimer,
+    MachineTimer,
+    SupervisorExternal,
+    MachineExternal,
+}
+
+impl Interrupt {
+    /// Exception code written to mcause, next to the interrupt bit
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::SupervisorSoftware => 1,
+            Self::MachineSoftware => 3,
+            Self::SupervisorTimer => 5,
+            Self::MachineTimer => 7,
+            Self::SupervisorExternal => 9,
+            Self::MachineExternal => 11,
+        }
+    }
+
+    /// Highest priority interrupt that is pending, enabled in mie and globally enabled for `mode`.
+    /// Interrupts delegated through mideleg are taken in S-mode and never preempt M-mode.
+    pub fn pending(c: &CsrRegisters, mode: PrivilegeMode) -> Option<Self> {
+        let pending = c[MIP].read() & c[MIE].read();
+        let delegated = c[MIDELEG].read();
+
+        let mut enabled = 0;
+
+        if mode < PrivilegeMode::Machine || c.status(mstatus::MIE) {
+            enabled |= pending & !delegated;
+        }
+        if mode < PrivilegeMode::Supervisor
+            || mode == PrivilegeMode::Supervisor && c.status(mstatus::SIE)
+        {
+            enabled |= pending & delegated;
+        }
+
+        [
+            (Self::MachineExternal, interrupt::MEI),
+            (Self::MachineSoftware, interrupt::MSI),
+            (Self::MachineTimer, interrupt::MTI),
+            (Self::SupervisorExternal, interrupt::SEI),
+            (Self::SupervisorSoftware, interrupt::SSI),
+            (Self::SupervisorTimer, interrupt::STI),
+        ]
+        .into_iter()
+        .find(|(_, bit)| enabled & bit != 0)
+        .map(|(interrupt, _)| interrupt)
+    }
+}
+
+/// Exception together with the address of the instruction that raised it (mepc)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Trap {
+    pub exception: Exception,
+    pub pc: u64,
+}
+
+impl Trap {
+    pub fn code(&self) -> u64 {
+        self.exception.code()
+    }
+
+    /// Value reported in mtval, breakpoints report their own address
+    pub fn tval(&self) -> u64 {
+        match self.exception {
+            Exception::InstructionAddressMisaligned(tval)
+            | Exception::InstructionAccessFault(tval)
+            | Exception::IllegalInstruction(tval)
+            | Exception::LoadAddressMisaligned(tval)
+            | Exception::LoadAccessFault(tval)
+            | Exception::StoreAddressMisaligned(tval)
+            | Exception::StoreAccessFault(tval)
+            | Exception::InstructionPageFault(tval)
+            | Exception::LoadPageFault(tval)
+            | Exception::StorePageFault(tval) => tval,
+            Exception::Breakpoint => self.pc,
+            Exception::EnvironmentCall(_) => 0,
+        }
+    }
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at pc = {:#x}", self.exception, self.pc)
+    }
+}
+
+impl std::error::Error for Trap {}
@@ -1,3 +1,5 @@
+mod agnostic_fill;
+mod extension;
 mod lmul;
 mod mask_behaviour;
 pub mod sew;
@@ -6,22 +8,25 @@ mod vlen;
 use derive_builder::Builder;
 pub use mask_behaviour::MaskBehavior;
 
+pub use agnostic_fill::AgnosticFill;
+pub use extension::VectorExtension;
+
 pub use lmul::Lmul;
-use sew::BaseSew;
+use sew::{BaseSew, Sew};
 pub use vlen::Vlen;
 
-use super::snapshot::Snapshotable;
+use super::{snapshot::Snapshotable, trap::Exception};
 
 #[derive(Builder, Clone, Copy, Default, PartialEq, Debug)]
 #[builder(build_fn(skip))]
 pub struct VectorEngine {
     pub lmul: Lmul,
     pub vlen: Vlen,
+    pub extension: VectorExtension,
     pub sew: BaseSew,
-    #[allow(dead_code)]
     pub tail_elements: MaskBehavior,
-    #[allow(dead_code)]
     pub inactive_elements: MaskBehavior,
+    pub agnostic_fill: AgnosticFill,
 }
 
 impl VectorEngine {
@@ -35,22 +40,59 @@ impl VectorEngine {
         Self {
             lmul,
             vlen,
+            extension: VectorExtension::default(),
             sew,
             tail_elements,
             inactive_elements,
+            agnostic_fill: AgnosticFill::default(),
+        }
+    }
+
+    /// Checks that the extension supports `eew`-bit elements, `fp` for floating point ones
+    pub fn check_eew(&self, eew: usize, fp: bool) -> Result<(), Exception> {
+        let elen = match fp {
+            true => self.extension.fp_elen(),
+            false => self.extension.elen(),
+        };
+
+        if eew > elen {
+            return Err(Exception::IllegalInstruction(0));
         }
+
+        Ok(())
+    }
+
+    /// Checks that SEW and LMUL are supported together, fractional LMUL needs SEW <= LMUL * ELEN
+    pub fn supports_vtype(&self, sew: BaseSew, lmul: Lmul) -> bool {
+        let sew = sew.bit_length();
+
+        sew <= self.extension.elen() && lmul.multiply(self.extension.elen()) >= sew
     }
 }
 
 impl VectorEngineBuilder {
-    pub fn build(&self) -> VectorEngine {
-        VectorEngine {
+    pub fn build(&self) -> Result<VectorEngine, String> {
+        let vlen = self.vlen.unwrap_or_default();
+        let extension = self.extension.unwrap_or_default();
+
+        if vlen.bit_length() < extension.min_vlen() {
+            return Err(format!(
+                "{:?} requires VLEN >= {}b, got VLEN = {}b",
+                extension,
+                extension.min_vlen(),
+                vlen.bit_length()
+            ));
+        }
+
+        Ok(VectorEngine {
             lmul: self.lmul.unwrap_or_default(),
-            vlen: self.vlen.unwrap_or_default(),
+            vlen,
+            extension,
             sew: self.sew.unwrap_or_default(),
             tail_elements: self.tail_elements.unwrap_or_default(),
             inactive_elements: self.inactive_elements.unwrap_or_default(),
-        }
+            agnostic_fill: self.agnostic_fill.unwrap_or_default(),
+        })
     }
 }
 
@@ -61,9 +103,11 @@ impl Snapshotable for VectorEngine {
         Self {
             lmul: self.lmul,
             vlen: self.vlen,
+            extension: self.extension,
             sew: self.sew,
             tail_elements: self.tail_elements,
             inactive_elements: self.inactive_elements,
+            agnostic_fill: self.agnostic_fill,
         }
     }
 }
@@ -74,6 +118,30 @@ mod tests {
 
     #[test]
     fn default_works() {
-        VectorEngineBuilder::default().build();
+        VectorEngineBuilder::default().build().unwrap();
+    }
+
+    #[test]
+    fn vlen_is_checked_against_the_extension() {
+        let zve32 = VectorEngineBuilder::default()
+            .vlen(Vlen::new(32).unwrap())
+            .extension(VectorExtension::Zve32x)
+            .build();
+        let v = VectorEngineBuilder::default().vlen(Vlen::V64).build();
+
+        assert!(zve32.is_ok());
+        assert!(v.is_err());
+    }
+
+    #[test]
+    fn element_widths_follow_the_extension() {
+        let engine = VectorEngineBuilder::default()
+            .extension(VectorExtension::Zve64f)
+            .build()
+            .unwrap();
+
+        assert!(engine.check_eew(64, false).is_ok());
+        assert!(engine.check_eew(32, true).is_ok());
+        assert!(engine.check_eew(64, true).is_err());
     }
 }
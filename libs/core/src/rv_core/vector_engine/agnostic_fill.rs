@@ -0,0 +1,28 @@
+/// What agnostic tail and masked-off elements are overwritten with
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum AgnosticFill {
+    /// Leave the old values, agnostic then behaves like undisturbed
+    #[default]
+    Keep,
+    /// Set every bit, as the spec suggests
+    Ones,
+    /// Pseudo-random bytes, the value is the generator state and doubles as the seed
+    Random(u64),
+}
+
+impl AgnosticFill {
+    pub fn fill(&mut self, bytes: &mut [u8]) {
+        match self {
+            Self::Keep => {}
+            Self::Ones => bytes.fill(0xff),
+            Self::Random(state) => bytes.iter_mut().for_each(|byte| {
+                // SplitMix64
+                *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = *state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                *byte = (z ^ (z >> 31)) as u8;
+            }),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/// Vector extension the core implements, Zve* are the embedded subsets of V
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum VectorExtension {
+    #[default]
+    V,
+    Zve64d,
+    Zve64f,
+    Zve64x,
+    Zve32f,
+    Zve32x,
+}
+
+impl VectorExtension {
+    /// Widest supported element in bits
+    pub fn elen(&self) -> usize {
+        match self {
+            Self::V | Self::Zve64d | Self::Zve64f | Self::Zve64x => 64,
+            Self::Zve32f | Self::Zve32x => 32,
+        }
+    }
+
+    /// Widest floating point element in bits, zero when FP vector ops aren't supported
+    pub fn fp_elen(&self) -> usize {
+        match self {
+            Self::V | Self::Zve64d => 64,
+            Self::Zve64f | Self::Zve32f => 32,
+            Self::Zve64x | Self::Zve32x => 0,
+        }
+    }
+
+    pub fn min_vlen(&self) -> usize {
+        match self {
+            Self::V => 128,
+            Self::Zve64d | Self::Zve64f | Self::Zve64x => 64,
+            Self::Zve32f | Self::Zve32x => 32,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::rv_core::trap::Exception;
+
 /// Vector length multiplier
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
 pub enum Lmul {
@@ -39,7 +41,8 @@ impl Lmul {
         }
     }
 
-    pub fn double(self) -> Result<Self, &'static str> {
+    /// Doubling LMUL=8 is reserved
+    pub fn double(self) -> Result<Self, Exception> {
         let doubled = match self {
             Self::MF8 => Self::MF4,
             Self::MF4 => Self::MF2,
@@ -47,7 +50,7 @@ impl Lmul {
             Self::M1 => Self::M2,
             Self::M2 => Self::M4,
             Self::M4 => Self::M8,
-            Self::M8 => return Err("Doubling LMUL=8 is reserved"),
+            Self::M8 => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(doubled)
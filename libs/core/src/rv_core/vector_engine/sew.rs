@@ -1,3 +1,5 @@
+use crate::rv_core::trap::Exception;
+
 pub trait Sew {
     fn bit_length(&self) -> usize;
     fn byte_length(&self) -> usize {
@@ -45,61 +47,42 @@ impl BaseSew {
         }
     }
 
-    pub fn half(self) -> Result<HalfSew, String> {
+    pub fn half(self) -> Result<HalfSew, Exception> {
         let halved = match self {
             Self::E64 => HalfSew::E32,
             Self::E32 => HalfSew::E16,
             Self::E16 => HalfSew::E8,
-            _ => {
-                return Err(format!(
-                    "SEW has to be equal 16b, 32b or 64b for halving operations, got SEW = {}b",
-                    self.bit_length()
-                ))
-            }
+            _ => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(halved)
     }
 
-    pub fn fourth(self) -> Result<FourthSew, String> {
+    pub fn fourth(self) -> Result<FourthSew, Exception> {
         let fourthed = match self {
             Self::E64 => FourthSew::E16,
             Self::E32 => FourthSew::E8,
-            _ => {
-                return Err(format!(
-                    "SEW has to be equal 32b or 64b for fourthing operations, got SEW = {}b",
-                    self.bit_length()
-                ))
-            }
+            _ => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(fourthed)
     }
 
-    pub fn eighth(self) -> Result<EighthSew, String> {
+    pub fn eighth(self) -> Result<EighthSew, Exception> {
         let eighted = match self {
             Self::E64 => EighthSew::E8,
-            _ => {
-                return Err(format!(
-                    "SEW has to be equal 64b for eighthing operations, got SEW = {}b",
-                    self.bit_length()
-                ))
-            }
+            _ => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(eighted)
     }
 
-    pub fn fp(self) -> Result<FpSew, String> {
+    pub fn fp(self) -> Result<FpSew, Exception> {
         let fp_sew = match self {
+            Self::E16 => FpSew::E16,
             Self::E32 => FpSew::E32,
             Self::E64 => FpSew::E64,
-            _ => {
-                return Err(format!(
-                "When using FP vector operations, SEW has to be either 32b or 64b, got SEW = {}b",
-                self.bit_length()
-            ))
-            }
+            _ => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(fp_sew)
@@ -126,15 +109,12 @@ pub enum DoubleSew {
 }
 
 impl DoubleSew {
-    pub fn fp(self) -> Result<DoubleFpSew, String> {
+    pub fn fp(self) -> Result<DoubleFpSew, Exception> {
         let fp_sew = match self {
+            Self::E16 => DoubleFpSew::E16,
+            Self::E32 => DoubleFpSew::E32,
             Self::E64 => DoubleFpSew::E64,
-            _ => {
-                return Err(format!(
-                "When using widening FP vector operations, SEW has to be either 32b, got SEW = {}b",
-                self.bit_length() / 2
-            ))
-            }
+            _ => return Err(Exception::IllegalInstruction(0)),
         };
 
         Ok(fp_sew)
@@ -199,6 +179,7 @@ impl Sew for EighthSew {
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum FpSew {
+    E16,
     E32,
     E64,
 }
@@ -206,6 +187,7 @@ pub enum FpSew {
 impl Sew for FpSew {
     fn bit_length(&self) -> usize {
         match self {
+            Self::E16 => 16,
             Self::E32 => 32,
             Self::E64 => 64,
         }
@@ -214,12 +196,16 @@ impl Sew for FpSew {
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum DoubleFpSew {
+    E16,
+    E32,
     E64,
 }
 
 impl Sew for DoubleFpSew {
     fn bit_length(&self) -> usize {
         match self {
+            Self::E16 => 16,
+            Self::E32 => 32,
             Self::E64 => 64,
         }
     }
@@ -1,24 +1,49 @@
-/// Vector unit size of microarchitecture
-#[derive(Clone, Copy, Default, PartialEq, Debug)]
-pub enum Vlen {
-    V64,
-    #[default]
-    V128,
-    V256,
-    V512,
-}
+/// Vector unit size of microarchitecture, a power of two between 32 and 65536 bits
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Vlen(usize);
 
 impl Vlen {
-    pub fn bit_length(&self) -> usize {
-        match self {
-            Self::V64 => 64,
-            Self::V128 => 128,
-            Self::V256 => 256,
-            Self::V512 => 512,
+    pub const V64: Self = Self(64);
+    pub const V128: Self = Self(128);
+    pub const V256: Self = Self(256);
+    pub const V512: Self = Self(512);
+
+    pub fn new(bit_length: usize) -> Result<Self, String> {
+        if !bit_length.is_power_of_two() || !(32..=65536).contains(&bit_length) {
+            return Err(format!(
+                "VLEN has to be a power of two between 32b and 65536b, got VLEN = {}b",
+                bit_length
+            ));
         }
+
+        Ok(Self(bit_length))
+    }
+
+    pub fn bit_length(&self) -> usize {
+        self.0
     }
 
     pub fn byte_length(&self) -> usize {
         self.bit_length() / 8
     }
 }
+
+impl Default for Vlen {
+    fn default() -> Self {
+        Self::V128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vlen_is_validated() {
+        assert_eq!(Vlen::new(32).map(|vlen| vlen.byte_length()), Ok(4));
+        assert_eq!(Vlen::new(65536).map(|vlen| vlen.byte_length()), Ok(8192));
+        assert!(Vlen::new(16).is_err());
+        assert!(Vlen::new(96).is_err());
+        assert!(Vlen::new(131072).is_err());
+    }
+}
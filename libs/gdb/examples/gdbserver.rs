@@ -0,0 +1,20 @@
+//! Loads an ELF and waits for gdb, e.g.
+//! `cargo run -p eeric-gdb --example gdbserver -- program.elf localhost:1234`
+//! followed by `target remote localhost:1234` in riscv64 gdb.
+
+use eeric_core::prelude::*;
+use eeric_gdb::prelude::*;
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().ok_or("Usage: gdbserver <elf> [address]")?;
+    let address = args.next().unwrap_or_else(|| "localhost:1234".to_owned());
+
+    let bytes = std::fs::read(&path).map_err(|error| format!("{}: {}", path, error))?;
+    let mut core = RvCore::default();
+    core.load_elf(&Elf::parse(&bytes)?)?;
+
+    println!("Waiting for gdb on {}", address);
+
+    serve_tcp(&mut core, address).map_err(|error| error.to_string())
+}
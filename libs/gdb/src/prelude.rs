@@ -0,0 +1,3 @@
+#[cfg(unix)]
+pub use crate::server::serve_unix;
+pub use crate::server::{serve_tcp, Connection, GdbServer};
@@ -0,0 +1,570 @@
+mod packet;
+mod target;
+
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use eeric_core::prelude::*;
+
+use packet::{decode_hex, encode_hex, escape, parse_hex, unescape, Incoming};
+use target::{Register, PC};
+
+/// Steps a continue runs between checks for an interrupt from gdb
+const SLICE: usize = 4096;
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+const SIGBUS: u8 = 7;
+const SIGSEGV: u8 = 11;
+
+/// Malformed or unsupported request
+const EINVAL: u8 = 0x16;
+/// Memory that isn't mapped or can't be translated
+const EFAULT: u8 = 0x0e;
+
+/// Byte stream to a gdb client
+pub trait Connection: Read + Write {
+    /// Checks without blocking whether gdb asked to stop a running program
+    fn interrupted(&mut self) -> io::Result<bool>;
+}
+
+impl Connection for TcpStream {
+    fn interrupted(&mut self) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        let interrupted = poll_interrupt(self);
+        self.set_nonblocking(false)?;
+
+        interrupted
+    }
+}
+
+#[cfg(unix)]
+impl Connection for std::os::unix::net::UnixStream {
+    fn interrupted(&mut self) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        let interrupted = poll_interrupt(self);
+        self.set_nonblocking(false)?;
+
+        interrupted
+    }
+}
+
+/// Only the interrupt byte can arrive while the program runs, a closed connection stops it as well
+fn poll_interrupt(stream: &mut impl Read) -> io::Result<bool> {
+    let mut byte = [0];
+
+    match stream.read(&mut byte) {
+        Ok(0) => Ok(true),
+        Ok(_) => Ok(byte[0] == packet::INTERRUPT),
+        Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Waits for a single gdb client on a TCP address, e.g. `localhost:1234`, and serves it until it detaches
+pub fn serve_tcp(core: &mut RvCore, address: impl ToSocketAddrs) -> io::Result<()> {
+    let (mut stream, _) = TcpListener::bind(address)?.accept()?;
+    stream.set_nodelay(true)?;
+
+    GdbServer::new(core).serve(&mut stream)
+}
+
+/// Waits for a single gdb client on a Unix socket and serves it until it detaches
+#[cfg(unix)]
+pub fn serve_unix(core: &mut RvCore, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+    let (mut stream, _) = std::os::unix::net::UnixListener::bind(path)?.accept()?;
+
+    GdbServer::new(core).serve(&mut stream)
+}
+
+/// GDB remote serial protocol stub presenting the core as a single thread in all-stop mode.
+/// Registers are described by the standard RISC-V target description, memory is accessed by virtual address at the current privilege mode.
+/// Breakpoints and watchpoints are set on the core's debugger.
+pub struct GdbServer<'core> {
+    core: &'core mut RvCore,
+    /// Debugger ids of the points gdb inserted, keyed by the type, address and kind of their Z packet
+    points: HashMap<(u8, u64, u64), usize>,
+    /// Stop reply `?` repeats
+    last_stop: Vec<u8>,
+    ack: bool,
+}
+
+impl<'core> GdbServer<'core> {
+    pub fn new(core: &'core mut RvCore) -> Self {
+        Self {
+            core,
+            points: HashMap::new(),
+            last_stop: format!("S{:02x}", SIGTRAP).into_bytes(),
+            ack: true,
+        }
+    }
+
+    /// Answers packets until gdb detaches, kills the program or hangs up.
+    /// Points gdb inserted are removed from the debugger on the way out.
+    pub fn serve(&mut self, connection: &mut impl Connection) -> io::Result<()> {
+        let result = self.session(connection);
+
+        for (_, id) in self.points.drain() {
+            self.core.debugger.remove(id);
+        }
+
+        result
+    }
+
+    fn session(&mut self, connection: &mut impl Connection) -> io::Result<()> {
+        while let Some(incoming) = packet::receive(connection, self.ack)? {
+            let packet = match incoming {
+                // Program isn't running, there's nothing to interrupt
+                Incoming::Interrupt => {
+                    packet::send(connection, format!("S{:02x}", SIGINT).as_bytes())?;
+                    continue;
+                }
+                Incoming::Packet(packet) => packet,
+            };
+
+            let reply = match packet.first() {
+                Some(b'k') => return Ok(()),
+                Some(b'c' | b's') => self.resume(&packet, connection)?,
+                _ => self
+                    .command(&packet)
+                    .unwrap_or_else(|error| format!("E{:02x}", error).into_bytes()),
+            };
+
+            packet::send(connection, &reply)?;
+
+            match packet.as_slice() {
+                b"D" => return Ok(()),
+                b"QStartNoAckMode" => self.ack = false,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replies to everything but resuming, unknown packets get the empty reply
+    fn command(&mut self, packet: &[u8]) -> Result<Vec<u8>, u8> {
+        let (&kind, arguments) = packet.split_first().ok_or(EINVAL)?;
+
+        let reply = match kind {
+            b'?' => self.last_stop.clone(),
+            b'g' => {
+                let registers: Vec<u8> = (0..=PC)
+                    .filter_map(Register::from_number)
+                    .flat_map(|register| register.read(self.core))
+                    .collect();
+
+                encode_hex(&registers)
+            }
+            b'G' => {
+                let bytes = decode_hex(arguments).ok_or(EINVAL)?;
+
+                for (number, value) in bytes.chunks(8).take(PC + 1).enumerate() {
+                    Register::from_number(number)
+                        .ok_or(EINVAL)?
+                        .write(self.core, value)
+                        .map_err(|_| EINVAL)?;
+                }
+
+                b"OK".to_vec()
+            }
+            b'p' => {
+                let register = register(arguments)?;
+
+                encode_hex(&register.read(self.core))
+            }
+            b'P' => {
+                let (number, value) = split(arguments, b'=')?;
+                let value = decode_hex(value).ok_or(EINVAL)?;
+
+                register(number)?
+                    .write(self.core, &value)
+                    .map_err(|_| EINVAL)?;
+
+                b"OK".to_vec()
+            }
+            b'm' => {
+                let (address, len) = address_and_len(arguments)?;
+
+                encode_hex(&self.read_memory(address, len)?)
+            }
+            b'M' | b'X' => {
+                let (location, data) = split(arguments, b':')?;
+                let (address, len) = address_and_len(location)?;
+                let data = match kind {
+                    b'M' => decode_hex(data).ok_or(EINVAL)?,
+                    _ => unescape(data),
+                };
+
+                if data.len() != len {
+                    return Err(EINVAL);
+                }
+
+                self.write_memory(address, &data)?;
+
+                b"OK".to_vec()
+            }
+            b'Z' | b'z' => {
+                let mut fields = arguments.split(|byte| *byte == b',');
+                let mut field = || fields.next().and_then(parse_hex).ok_or(EINVAL);
+                let point = (field()? as u8, field()?, field()?);
+
+                if kind == b'Z' {
+                    self.insert_point(point)?;
+                } else if let Some(id) = self.points.remove(&point) {
+                    self.core.debugger.remove(id);
+                }
+
+                b"OK".to_vec()
+            }
+            b'H' | b'T' | b'D' => b"OK".to_vec(),
+            b'q' | b'Q' => self.query(packet)?,
+            _ => Vec::new(),
+        };
+
+        Ok(reply)
+    }
+
+    fn query(&mut self, packet: &[u8]) -> Result<Vec<u8>, u8> {
+        const FEATURES: &[u8] = b"qXfer:features:read:target.xml:";
+
+        let reply: &[u8] = match packet {
+            _ if packet.starts_with(b"qSupported") => {
+                b"PacketSize=4000;qXfer:features:read+;swbreak+;hwbreak+;QStartNoAckMode+"
+            }
+            _ if packet.starts_with(FEATURES) => {
+                let (offset, len) = address_and_len(&packet[FEATURES.len()..])?;
+                let description = target::description(self.core).into_bytes();
+                let start = (offset as usize).min(description.len());
+                let end = start.saturating_add(len).min(description.len());
+
+                // `m` marks that more data follows, `l` that this is the last part
+                let more = if end < description.len() { b'm' } else { b'l' };
+
+                return Ok([&[more], escape(&description[start..end]).as_slice()].concat());
+            }
+            b"QStartNoAckMode" => b"OK",
+            b"qAttached" => b"1",
+            b"qC" => b"QC1",
+            b"qfThreadInfo" => b"m1",
+            b"qsThreadInfo" => b"l",
+            _ => b"",
+        };
+
+        Ok(reply.to_vec())
+    }
+
+    /// Runs `s` and `c` packets, either can carry an address to resume at
+    fn resume(&mut self, packet: &[u8], connection: &mut impl Connection) -> io::Result<Vec<u8>> {
+        if let Some(pc) = parse_hex(&packet[1..]) {
+            self.core.registers.pc = pc;
+        }
+
+        let stop = match packet[0] {
+            b's' => self.core.run_until(StopCondition::Steps(1)),
+            _ => loop {
+                match self.core.run_until(StopCondition::Steps(SLICE)) {
+                    StopReason::Condition if connection.interrupted()? => {
+                        self.last_stop = format!("T{:02x}", SIGINT).into_bytes();
+                        return Ok(self.last_stop.clone());
+                    }
+                    StopReason::Condition => continue,
+                    reason => break reason,
+                }
+            },
+        };
+
+        self.last_stop = self.stop_reply(&stop);
+
+        Ok(self.last_stop.clone())
+    }
+
+    fn stop_reply(&self, stop: &StopReason) -> Vec<u8> {
+        let reply = match stop {
+            StopReason::Finished => format!("W{:02x}", self.core.exit_code.unwrap_or(0) as u8),
+            StopReason::Trap(trap) => format!("T{:02x}", signal(trap.exception)),
+            StopReason::Breakpoint(id) => match self.point(*id) {
+                Some((1, _, _)) => format!("T{:02x}hwbreak:;", SIGTRAP),
+                _ => format!("T{:02x}swbreak:;", SIGTRAP),
+            },
+            StopReason::Watchpoint { id, hit } => {
+                let (kind, start, _) = self.point(*id).unwrap_or((2, 0, 0));
+                let name = match kind {
+                    3 => "rwatch",
+                    4 => "awatch",
+                    _ => "watch",
+                };
+                // Reported address has to fall in the watched range
+                let address = match hit {
+                    WatchHit::Memory(
+                        MemoryAccess::Load { address, .. } | MemoryAccess::Store { address, .. },
+                    ) => (*address).max(start),
+                    WatchHit::Register(_) => start,
+                };
+
+                format!("T{:02x}{}:{:x};", SIGTRAP, name, address)
+            }
+            StopReason::Condition => format!("T{:02x}", SIGTRAP),
+        };
+
+        reply.into_bytes()
+    }
+
+    fn point(&self, id: usize) -> Option<(u8, u64, u64)> {
+        self.points
+            .iter()
+            .find(|(_, point_id)| **point_id == id)
+            .map(|(point, _)| *point)
+    }
+
+    /// Breakpoints of both types are the same to the core, watchpoints cover `kind` bytes
+    fn insert_point(&mut self, point: (u8, u64, u64)) -> Result<(), u8> {
+        if self.points.contains_key(&point) {
+            return Ok(());
+        }
+
+        let (kind, address, len) = point;
+        let debugger = &mut self.core.debugger;
+        let range = address..address.saturating_add(len);
+
+        let id = match kind {
+            0 | 1 => debugger.add_breakpoint(address),
+            2 => debugger.watch_memory(range, WatchKind::Write),
+            3 => debugger.watch_memory(range, WatchKind::Read),
+            4 => debugger.watch_memory(range, WatchKind::ReadWrite),
+            _ => return Err(EINVAL),
+        };
+        self.points.insert(point, id);
+
+        Ok(())
+    }
+
+    /// Reads up to the first byte that can't be accessed, failing only when that's the first one
+    fn read_memory(&mut self, address: u64, len: usize) -> Result<Vec<u8>, u8> {
+        let mut bytes = Vec::with_capacity(len);
+
+        for offset in 0..len as u64 {
+            match self.translate(address.wrapping_add(offset)) {
+                Ok(physical) => bytes.extend(self.core.memory.bytes(physical, 1)),
+                Err(_) if !bytes.is_empty() => break,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Writes bypass permissions, so breakpoints could be patched into read-only text
+    fn write_memory(&mut self, address: u64, data: &[u8]) -> Result<(), u8> {
+        let physical: Vec<u64> = (0..data.len() as u64)
+            .map(|offset| self.translate(address.wrapping_add(offset)))
+            .collect::<Result<_, _>>()?;
+
+        for (address, byte) in physical.into_iter().zip(data) {
+            self.core.memory.load(address, &[*byte]);
+        }
+
+        Ok(())
+    }
+
+    /// Devices are left out, reading a register could change their state
+    fn translate(&mut self, address: u64) -> Result<u64, u8> {
+        let physical = self
+            .core
+            .mmu()
+            .translate(address, Access::Load)
+            .map_err(|_| EFAULT)?;
+
+        let memory = &self.core.memory;
+
+        match memory.region_at(physical) {
+            Some(region) if memory.device(&region.name).is_none() => Ok(physical),
+            _ => Err(EFAULT),
+        }
+    }
+}
+
+fn signal(exception: Exception) -> u8 {
+    match exception {
+        Exception::IllegalInstruction(_) => SIGILL,
+        Exception::Breakpoint | Exception::EnvironmentCall(_) => SIGTRAP,
+        Exception::InstructionAddressMisaligned(_)
+        | Exception::LoadAddressMisaligned(_)
+        | Exception::StoreAddressMisaligned(_) => SIGBUS,
+        _ => SIGSEGV,
+    }
+}
+
+fn register(number: &[u8]) -> Result<Register, u8> {
+    parse_hex(number)
+        .and_then(|number| Register::from_number(number as usize))
+        .ok_or(EINVAL)
+}
+
+fn split(arguments: &[u8], separator: u8) -> Result<(&[u8], &[u8]), u8> {
+    let position = arguments
+        .iter()
+        .position(|byte| *byte == separator)
+        .ok_or(EINVAL)?;
+
+    Ok((&arguments[..position], &arguments[position + 1..]))
+}
+
+fn address_and_len(arguments: &[u8]) -> Result<(u64, usize), u8> {
+    let (address, len) = split(arguments, b',')?;
+
+    Ok((
+        parse_hex(address).ok_or(EINVAL)?,
+        parse_hex(len).ok_or(EINVAL)? as usize,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Plays back packets from gdb and collects the replies, gdb never interrupts
+    struct Session {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Session {
+        fn new(packets: &[&str]) -> Self {
+            let mut input = Vec::new();
+
+            for packet in packets {
+                input.push(b'+');
+                packet::send(&mut input, packet.as_bytes()).unwrap();
+            }
+
+            Self {
+                input: Cursor::new(input),
+                output: Vec::new(),
+            }
+        }
+
+        fn replies(&self) -> Vec<String> {
+            String::from_utf8_lossy(&self.output)
+                .split('$')
+                .skip(1)
+                .map(|packet| packet.rsplit_once('#').unwrap().0.to_owned())
+                .collect()
+        }
+    }
+
+    impl Read for Session {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Session {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Connection for Session {
+        fn interrupted(&mut self) -> io::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    #[test]
+    fn gdb_session_drives_the_core() {
+        use format::{I, S};
+        use Instruction::*;
+
+        let mut core = RvCoreBuilder::default()
+            .memory(Memory::new(vec![0; 0x100].into_iter()))
+            .build();
+        core.load_program(&[
+            Addi(I {
+                rd: 5,
+                rs1: 0,
+                imm12: 3,
+            }),
+            Sd(S {
+                rs1: 0,
+                rs2: 5,
+                imm12: 0x10,
+            }),
+            Addi(I {
+                rd: 5,
+                rs1: 5,
+                imm12: 1,
+            }),
+            Sd(S {
+                rs1: 0,
+                rs2: 5,
+                imm12: 0x18,
+            }),
+        ])
+        .unwrap();
+
+        let mut session = Session::new(&[
+            "qSupported:multiprocess+;swbreak+",
+            "qXfer:features:read:target.xml:0,5",
+            "Z0,104,4",
+            "c",
+            "p20",
+            "p5",
+            "z0,104,4",
+            "Z2,10,8",
+            "c",
+            "m10,8",
+            "M10,1:07",
+            "X11,1:}\x03",
+            "m10,2",
+            "z2,10,8",
+            "s",
+            "P5=0900000000000000",
+            "mfff0,4",
+            "c",
+            "?",
+            "D",
+        ]);
+        GdbServer::new(&mut core).serve(&mut session).unwrap();
+
+        assert_eq!(
+            session.replies(),
+            [
+                "PacketSize=4000;qXfer:features:read+;swbreak+;hwbreak+;QStartNoAckMode+",
+                "m<?xml",
+                "OK",
+                "T05swbreak:;",
+                "0401000000000000",
+                "0300000000000000",
+                "OK",
+                "OK",
+                "T05watch:10;",
+                "0300000000000000",
+                "OK",
+                "OK",
+                "0723",
+                "OK",
+                "T05",
+                "OK",
+                "E0e",
+                "W00",
+                "W00",
+                "OK",
+            ]
+        );
+        assert!(session.output.starts_with(b"+$"));
+        assert_eq!(core.registers.x[5], 9);
+        assert_eq!(core.memory.bytes(0x18, 1), [9]);
+        assert_eq!(core.debugger.breakpoints().count(), 0);
+    }
+}
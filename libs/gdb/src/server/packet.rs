@@ -0,0 +1,186 @@
+use std::io::{self, Read, Write};
+
+/// Byte gdb sends outside of any packet to interrupt a running program
+pub const INTERRUPT: u8 = 0x03;
+
+pub enum Incoming {
+    Packet(Vec<u8>),
+    Interrupt,
+}
+
+/// Reads the next packet, acknowledging it while acks are on. Returns None once the client hangs up.
+/// Acks of our own packets are skipped, packets are never retransmitted.
+pub fn receive(stream: &mut (impl Read + Write), ack: bool) -> io::Result<Option<Incoming>> {
+    loop {
+        match read_byte(stream)? {
+            None => return Ok(None),
+            Some(b'$') => {}
+            Some(INTERRUPT) => return Ok(Some(Incoming::Interrupt)),
+            Some(_) => continue,
+        }
+
+        let mut data = Vec::new();
+
+        loop {
+            match read_byte(stream)? {
+                None => return Ok(None),
+                Some(b'#') => break,
+                Some(byte) => data.push(byte),
+            }
+        }
+
+        let mut received = [0; 2];
+        stream.read_exact(&mut received)?;
+
+        let valid = parse_hex(&received) == Some(checksum(&data) as u64);
+
+        if ack {
+            stream.write_all(if valid { b"+" } else { b"-" })?;
+            stream.flush()?;
+        }
+
+        // Without acks there's no way to ask for a retransmission
+        if valid || !ack {
+            return Ok(Some(Incoming::Packet(data)));
+        }
+    }
+}
+
+pub fn send(stream: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(data.len() + 4);
+
+    packet.push(b'$');
+    packet.extend_from_slice(data);
+    packet.extend_from_slice(format!("#{:02x}", checksum(data)).as_bytes());
+
+    stream.write_all(&packet)?;
+    stream.flush()
+}
+
+fn read_byte(stream: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+
+    match stream.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+pub fn parse_hex(digits: &[u8]) -> Option<u64> {
+    let digits = std::str::from_utf8(digits).ok()?;
+
+    u64::from_str_radix(digits, 16).ok()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| format!("{:02x}", byte).into_bytes())
+        .collect()
+}
+
+pub fn decode_hex(digits: &[u8]) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| parse_hex(pair).map(|byte| byte as u8))
+        .collect()
+}
+
+/// Escapes binary data, bytes that mean something to the framing are sent as `}` and the byte xor 0x20
+pub fn escape(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+
+    for &byte in data {
+        match byte {
+            b'#' | b'$' | b'}' | b'*' => escaped.extend([b'}', byte ^ 0x20]),
+            _ => escaped.push(byte),
+        }
+    }
+
+    escaped
+}
+
+pub fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut bytes = data.iter();
+    let mut unescaped = Vec::with_capacity(data.len());
+
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'}' => unescaped.extend(bytes.next().map(|byte| byte ^ 0x20)),
+            _ => unescaped.push(byte),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Reads from the input and collects whatever is written back
+    struct Stream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn packets_are_checked_and_acknowledged() {
+        let mut stream = Stream {
+            input: Cursor::new(b"+$g#00$g#67\x03".to_vec()),
+            output: Vec::new(),
+        };
+
+        // Corrupted packet is rejected and skipped
+        assert!(matches!(
+            receive(&mut stream, true).unwrap(),
+            Some(Incoming::Packet(data)) if data == b"g"
+        ));
+        assert_eq!(stream.output, b"-+");
+        assert!(matches!(
+            receive(&mut stream, true).unwrap(),
+            Some(Incoming::Interrupt)
+        ));
+        assert!(receive(&mut stream, true).unwrap().is_none());
+
+        let mut output = Vec::new();
+        send(&mut output, b"OK").unwrap();
+        assert_eq!(output, b"$OK#9a");
+    }
+
+    #[test]
+    fn binary_data_is_escaped() {
+        let data = b"a#b$c}d*e";
+
+        assert_eq!(escape(data), b"a}\x03b}\x04c}]d}\x0ae");
+        assert_eq!(unescape(&escape(data)), data);
+        assert_eq!(decode_hex(&encode_hex(data)).unwrap(), data);
+        assert_eq!(decode_hex(b"abc"), None);
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::Write;
+
+use eeric_core::prelude::{alias::*, *};
+
+/// Registers are numbered the way gdb numbers them internally for RISC-V
+pub const PC: usize = 32;
+pub const F0: usize = 33;
+/// CSRs follow at their own address
+pub const CSR0: usize = 65;
+pub const PRIV: usize = CSR0 + 4096;
+pub const V0: usize = PRIV + 1;
+
+const X_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const F_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+/// Floating point CSRs belong to the fpu feature
+const FPU_CSRS: [(&str, usize); 3] = [("fflags", FFLAGS), ("frm", FRM), ("fcsr", FCSR)];
+
+const CSRS: [(&str, usize); 34] = [
+    ("vstart", VSTART),
+    ("vxsat", VXSAT),
+    ("vxrm", VXRM),
+    ("vcsr", VCSR),
+    ("sstatus", SSTATUS),
+    ("sie", SIE),
+    ("stvec", STVEC),
+    ("sscratch", SSCRATCH),
+    ("sepc", SEPC),
+    ("scause", SCAUSE),
+    ("stval", STVAL),
+    ("sip", SIP),
+    ("satp", SATP),
+    ("mstatus", MSTATUS),
+    ("misa", MISA),
+    ("medeleg", MEDELEG),
+    ("mideleg", MIDELEG),
+    ("mie", MIE),
+    ("mtvec", MTVEC),
+    ("mscratch", MSCRATCH),
+    ("mepc", MEPC),
+    ("mcause", MCAUSE),
+    ("mtval", MTVAL),
+    ("mip", MIP),
+    ("cycle", CYCLE),
+    ("time", TIME),
+    ("instret", INSTRET),
+    ("vl", VL),
+    ("vtype", VTYPE),
+    ("vlenb", VLENB),
+    ("mvendorid", MVENDORID),
+    ("marchid", MARCHID),
+    ("mimpid", MIMPID),
+    ("mhartid", MHARTID),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Register {
+    X(usize),
+    Pc,
+    F(usize),
+    Csr(usize),
+    /// Privilege mode the hart runs in
+    Priv,
+    V(usize),
+}
+
+impl Register {
+    pub fn from_number(number: usize) -> Option<Self> {
+        match number {
+            0..PC => Some(Self::X(number)),
+            PC => Some(Self::Pc),
+            F0..CSR0 => Some(Self::F(number - F0)),
+            CSR0..PRIV => Some(Self::Csr(number - CSR0)),
+            PRIV => Some(Self::Priv),
+            _ => (V0..V0 + 32)
+                .contains(&number)
+                .then(|| Self::V(number - V0)),
+        }
+    }
+
+    /// Little-endian contents
+    pub fn read(self, core: &RvCore) -> Vec<u8> {
+        let registers = &core.registers;
+
+        let value = match self {
+            Self::X(index) => registers.x[index],
+            Self::Pc => registers.pc,
+            Self::F(index) => registers.f[index],
+            Self::Csr(csr) => registers.c.read(csr),
+            Self::Priv => registers.mode as u64,
+            Self::V(index) => {
+                let vlenb = vlenb(core);
+                return registers.v.bytes()[index * vlenb..][..vlenb].to_vec();
+            }
+        };
+
+        value.to_le_bytes().to_vec()
+    }
+
+    /// Writes little-endian contents, CSRs are written the way instructions write them
+    pub fn write(self, core: &mut RvCore, bytes: &[u8]) -> Result<(), String> {
+        if let Self::V(index) = self {
+            let vlenb = vlenb(core);
+
+            if bytes.len() != vlenb {
+                return Err(format!("v{} takes {} bytes", index, vlenb));
+            }
+
+            core.registers
+                .v
+                .bytes_mut(index * vlenb..(index + 1) * vlenb)
+                .copy_from_slice(bytes);
+
+            return Ok(());
+        }
+
+        let value = <[u8; 8]>::try_from(bytes)
+            .map(u64::from_le_bytes)
+            .map_err(|_| format!("{:?} takes 8 bytes", self))?;
+        let registers = &mut core.registers;
+
+        match self {
+            // Writes to x0 are discarded
+            Self::X(0) => {}
+            Self::X(index) => registers.x[index] = value,
+            Self::Pc => registers.pc = value,
+            Self::F(index) => registers.f[index] = value,
+            Self::Csr(csr) => registers
+                .c
+                .write(csr, value)
+                .map_err(|exception| exception.to_string())?,
+            Self::Priv => registers.mode = PrivilegeMode::from_bits(value),
+            Self::V(_) => unreachable!(),
+        }
+
+        Ok(())
+    }
+}
+
+fn vlenb(core: &RvCore) -> usize {
+    core.registers.v.bytes().len() / 32
+}
+
+/// Target description gdb picks registers and their layout from
+pub fn description(core: &RvCore) -> String {
+    let vlenb = vlenb(core);
+    let mut xml = String::from(concat!(
+        "<?xml version=\"1.0\"?>\n",
+        "<!DOCTYPE target SYSTEM \"gdb-target.dtd\">\n",
+        "<target version=\"1.0\">\n",
+        "<architecture>riscv:rv64</architecture>\n",
+        "<feature name=\"org.gnu.gdb.riscv.cpu\">\n",
+    ));
+
+    for (number, name) in X_NAMES.iter().enumerate() {
+        let kind = match number {
+            RA => "code_ptr",
+            SP | GP | TP | S0 => "data_ptr",
+            _ => "int",
+        };
+        reg(&mut xml, name, 64, kind, number);
+    }
+    reg(&mut xml, "pc", 64, "code_ptr", PC);
+
+    xml.push_str(concat!(
+        "</feature>\n",
+        "<feature name=\"org.gnu.gdb.riscv.fpu\">\n",
+        "<union id=\"riscv_double\">",
+        "<field name=\"float\" type=\"ieee_single\"/>",
+        "<field name=\"double\" type=\"ieee_double\"/>",
+        "</union>\n",
+    ));
+
+    for (index, name) in F_NAMES.iter().enumerate() {
+        reg(&mut xml, name, 64, "riscv_double", F0 + index);
+    }
+    for (name, csr) in FPU_CSRS {
+        reg(&mut xml, name, 64, "int", CSR0 + csr);
+    }
+
+    xml.push_str("</feature>\n<feature name=\"org.gnu.gdb.riscv.csr\">\n");
+
+    for (name, csr) in CSRS {
+        reg(&mut xml, name, 64, "int", CSR0 + csr);
+    }
+
+    xml.push_str("</feature>\n<feature name=\"org.gnu.gdb.riscv.virtual\">\n");
+    reg(&mut xml, "priv", 64, "int", PRIV);
+    xml.push_str("</feature>\n<feature name=\"org.gnu.gdb.riscv.vector\">\n");
+
+    // Zve* can have registers narrower than a quadword
+    let elements = [
+        ("b", "bytes", 1),
+        ("s", "shorts", 2),
+        ("w", "words", 4),
+        ("l", "longs", 8),
+        ("q", "quads", 16),
+    ];
+    let elements = elements.iter().filter(|(_, _, bytes)| *bytes <= vlenb);
+
+    for (_, id, bytes) in elements.clone() {
+        let _ = writeln!(
+            xml,
+            "<vector id=\"{}\" type=\"uint{}\" count=\"{}\"/>",
+            id,
+            bytes * 8,
+            vlenb / bytes
+        );
+    }
+    xml.push_str("<union id=\"riscv_vector\">");
+    for (field, id, _) in elements {
+        let _ = write!(xml, "<field name=\"{}\" type=\"{}\"/>", field, id);
+    }
+    xml.push_str("</union>\n");
+
+    for index in 0..32 {
+        reg(
+            &mut xml,
+            &format!("v{}", index),
+            vlenb * 8,
+            "riscv_vector",
+            V0 + index,
+        );
+    }
+
+    xml.push_str("</feature>\n</target>\n");
+
+    xml
+}
+
+fn reg(xml: &mut String, name: &str, bitsize: usize, kind: &str, number: usize) {
+    let _ = writeln!(
+        xml,
+        "<reg name=\"{}\" bitsize=\"{}\" type=\"{}\" regnum=\"{}\"/>",
+        name, bitsize, kind, number
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_round_trip() {
+        let mut core = RvCore::default();
+
+        for (number, value) in [(5, 7u64), (PC, 0x200), (F0 + 1, 0x3ff0_0000_0000_0000)] {
+            let register = Register::from_number(number).unwrap();
+
+            register.write(&mut core, &value.to_le_bytes()).unwrap();
+            assert_eq!(register.read(&core), value.to_le_bytes());
+        }
+        assert_eq!(core.registers.x[T0], 7);
+        assert_eq!(core.registers.pc, 0x200);
+
+        let fcsr = Register::from_number(CSR0 + FCSR).unwrap();
+        fcsr.write(&mut core, &0x21u64.to_le_bytes()).unwrap();
+        assert_eq!(core.registers.c.read(FRM), 1);
+
+        let v2 = Register::from_number(V0 + 2).unwrap();
+        assert_eq!(v2, Register::V(2));
+        v2.write(&mut core, &[0xab; 16]).unwrap();
+        assert_eq!(v2.read(&core), [0xab; 16]);
+        assert!(v2.write(&mut core, &[0; 8]).is_err());
+
+        assert_eq!(Register::from_number(V0 + 32), None);
+        assert!(description(&core)
+            .contains("<reg name=\"v31\" bitsize=\"128\" type=\"riscv_vector\" regnum=\"4193\"/>"));
+    }
+}
@@ -9,22 +9,92 @@ use self::decoder::{AssemblerDirective, Section};
 
 pub struct Interpreter;
 
+#[derive(Clone, Copy, Default, Debug)]
+pub struct CompileOptions {
+    /// Compress every instruction that has a 16-bit form
+    pub compress: bool,
+    /// Zba and Zbb are available, sext.b, sext.h, zext.h and zext.w assemble to single instructions
+    pub bitmanip: bool,
+}
+
 pub struct CompilationResult {
     pub instructions: Vec<Instruction>,
     pub instructions_addresses: Vec<usize>,
+    /// Address of every instruction once loaded at `text_base`
+    pub instructions_pcs: Vec<u64>,
+    /// Text is placed right after the data memory, like `RvCoreBuilder` does by default
+    pub text_base: u64,
+    /// Constants in a data region at 0, followed by the stack up to the memory size and the text region
     pub memory: Memory,
 }
 
+impl CompilationResult {
+    /// Encoded words of every instruction, errors are keyed by source line like in `Interpreter::compile`
+    pub fn machine_code(&self) -> Result<Vec<Vec<u32>>, HashMap<usize, String>> {
+        self.encode_each(encode_fused)
+    }
+
+    /// Raw little-endian text section, compressed instructions take a halfword
+    pub fn binary(&self) -> Result<Vec<u8>, HashMap<usize, String>> {
+        Ok(self.encode_each(encode_bytes)?.concat())
+    }
+
+    fn encode_each<T>(
+        &self,
+        encode: impl Fn(&Instruction) -> Result<T, EncodeError>,
+    ) -> Result<Vec<T>, HashMap<usize, String>> {
+        let mut encoded = Vec::new();
+        let mut errors = HashMap::new();
+
+        for (instruction, line) in self.instructions.iter().zip(&self.instructions_addresses) {
+            match encode(instruction) {
+                Ok(code) => encoded.push(code),
+                Err(error) => {
+                    errors.insert(*line, error.to_string());
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(encoded)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 impl Interpreter {
     pub fn compile(
         program: String,
         memory_size: usize,
     ) -> Result<CompilationResult, HashMap<usize, String>> {
-        let mut instruction_labels = HashMap::new();
+        Self::compile_with(program, memory_size, CompileOptions::default())
+    }
+
+    /// Like [`Interpreter::compile`], but every instruction with a 16-bit form is compressed
+    pub fn compile_compressed(
+        program: String,
+        memory_size: usize,
+    ) -> Result<CompilationResult, HashMap<usize, String>> {
+        Self::compile_with(
+            program,
+            memory_size,
+            CompileOptions {
+                compress: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn compile_with(
+        program: String,
+        memory_size: usize,
+        options: CompileOptions,
+    ) -> Result<CompilationResult, HashMap<usize, String>> {
+        // Text labels point at the index of the instruction following them until the layout is known
+        let mut text_labels = HashMap::new();
         let mut memory_labels = HashMap::new();
-        let mut instructions = Vec::new();
         let mut lines_addresses = Vec::new();
-        let mut program_line_address = 0;
         let mut memory_data_address = 0;
 
         let mut to_decode = Vec::new();
@@ -52,14 +122,13 @@ impl Interpreter {
                         constants.push(vec);
                     }
                     LineClassification::Instruction(decodable) => {
-                        program_line_address += 4;
                         to_decode.push(decodable);
                         lines_addresses.push(line_address);
                         lines_sections.push(section_ctx.clone());
                     }
                     LineClassification::Label(label) => match section_ctx {
                         Section::Text => {
-                            instruction_labels.insert(label, program_line_address);
+                            text_labels.insert(label, to_decode.len());
                         }
                         Section::Data => {
                             memory_labels.insert(label, memory_data_address);
@@ -73,24 +142,100 @@ impl Interpreter {
             }
         }
 
-        for (decodable_line_index, decodable_line) in to_decode.into_iter().enumerate() {
-            let maybe_instruction = Decoder::decode_text_section(
-                &decodable_line,
-                &instruction_labels,
-                &memory_labels,
-                decodable_line_index * 4,
-            );
+        // Constants that don't fit in memory push the text further up
+        let text_base = memory_size.max(memory_data_address).next_multiple_of(4);
 
-            match maybe_instruction {
-                Ok(instruction) => instructions.push(instruction),
-                Err(msg) => {
-                    errors.insert(lines_addresses[decodable_line_index], msg);
+        let mut sizes: Vec<_> = to_decode
+            .iter()
+            .map(|line| Decoder::instruction_size(line, options.bitmanip))
+            .collect();
+
+        // Compressing shortens offsets between instructions, which may let more of them be compressed.
+        // Instructions are never expanded back, so laying out again eventually stops changing anything.
+        let (instructions, instructions_pcs) = loop {
+            let instructions_pcs: Vec<_> = sizes
+                .iter()
+                .scan(text_base, |address, size| {
+                    let pc = *address;
+                    *address += size;
+                    Some(pc)
+                })
+                .collect();
+            let text_end = text_base + sizes.iter().sum::<usize>();
+
+            let instruction_labels = text_labels
+                .iter()
+                .map(|(label, &index)| {
+                    let address = instructions_pcs.get(index).copied().unwrap_or(text_end);
+                    (label.clone(), address)
+                })
+                .collect();
+
+            let mut instructions = Vec::new();
+
+            for (decodable_line_index, decodable_line) in to_decode.iter().enumerate() {
+                let maybe_instruction = Decoder::decode_text_section(
+                    decodable_line,
+                    &instruction_labels,
+                    &memory_labels,
+                    instructions_pcs[decodable_line_index],
+                    options.bitmanip,
+                )
+                .and_then(|instruction| match encode_fused(&instruction) {
+                    Ok(_) => Ok(instruction),
+                    Err(error) => Err(error.to_string()),
+                });
+
+                match maybe_instruction {
+                    Ok(instruction) => instructions.push(instruction),
+                    Err(msg) => {
+                        errors.insert(lines_addresses[decodable_line_index], msg);
+                    }
+                };
+            }
+
+            if !options.compress || !errors.is_empty() {
+                break (instructions, instructions_pcs);
+            }
+
+            let mut shrunk = false;
+
+            for (instruction, size) in instructions.iter_mut().zip(&mut sizes) {
+                *instruction = Self::compress(instruction);
+
+                if (instruction.size() as usize) < *size {
+                    *size = instruction.size() as usize;
+                    shrunk = true;
                 }
-            };
-        }
+            }
+
+            if !shrunk {
+                break (instructions, instructions_pcs);
+            }
+        };
 
         if errors.is_empty() {
-            let mut memory = Memory::new((0..memory_size).map(|_| 0));
+            let text_end = text_base + sizes.iter().sum::<usize>();
+            let sections = [
+                ("data", 0, memory_data_address, Permissions::RW),
+                (
+                    "stack",
+                    memory_data_address,
+                    memory_size.saturating_sub(memory_data_address),
+                    Permissions::RW,
+                ),
+                ("text", text_base, text_end - text_base, Permissions::RX),
+            ];
+            let mut memory = Memory::empty();
+
+            // Empty sections aren't mapped
+            for (name, start, size, permissions) in sections {
+                if size > 0 {
+                    memory
+                        .map(Region::new(name, start as u64, size as u64, permissions))
+                        .expect("sections are laid out one after another");
+                }
+            }
 
             for constant in constants {
                 memory.assign(&constant);
@@ -99,12 +244,28 @@ impl Interpreter {
             Ok(CompilationResult {
                 instructions,
                 instructions_addresses: lines_addresses,
+                instructions_pcs: instructions_pcs.into_iter().map(|pc| pc as u64).collect(),
+                text_base: text_base as u64,
                 memory,
             })
         } else {
             Err(errors)
         }
     }
+
+    /// Pairs built around auipc are left uncompressed, their offsets change whenever code before them shrinks
+    fn compress(instruction: &Instruction) -> Instruction {
+        match instruction {
+            Instruction::Fusion(instructions)
+                if instructions
+                    .iter()
+                    .any(|instruction| matches!(instruction, Instruction::Auipc(_))) =>
+            {
+                instruction.clone()
+            }
+            instruction => compress(instruction),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -172,15 +333,11 @@ mod tests {
                     imm12: 7
                 }),
                 fuse![
-                    Instruction::Addi(format::I {
-                        rd: 0,
-                        rs1: 0,
-                        imm12: 0
-                    }),
+                    Instruction::Auipc(format::U { rd: 11, imm20: 0 }),
                     Instruction::Addi(format::I {
                         rd: 11,
-                        rs1: 0,
-                        imm12: 4
+                        rs1: 11,
+                        imm12: -32
                     })
                 ]
             ]
@@ -191,14 +348,38 @@ mod tests {
             vec![1, 4, 5, 6, 7, 8, 9]
         );
 
+        assert_eq!(compilation_result.text_base, 12);
+        assert_eq!(
+            compilation_result.instructions_pcs,
+            vec![12, 16, 20, 24, 28, 32, 36]
+        );
+
         assert_eq!(
-            compilation_result
-                .memory
-                .snapshot()
-                .into_iter()
-                .collect::<Vec<_>>(),
+            compilation_result.memory.bytes(0, 12),
             vec![37, 6, 73, 64, 97, 98, 99, 100, 101, 102, 0, 0]
         );
+
+        let regions: Vec<_> = compilation_result
+            .memory
+            .regions()
+            .iter()
+            .map(|region| {
+                (
+                    region.name.as_str(),
+                    region.start,
+                    region.size,
+                    region.permissions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            regions,
+            [
+                ("data", 0, 11, Permissions::RW),
+                ("stack", 11, 1, Permissions::RW),
+                ("text", 12, 32, Permissions::RX),
+            ]
+        );
     }
 
     #[test]
@@ -243,15 +424,11 @@ mod tests {
                     imm12: 16
                 }),
                 fuse![
-                    Instruction::Addi(format::I {
-                        rd: 0,
-                        rs1: 0,
-                        imm12: 0,
-                    }),
+                    Instruction::Auipc(format::U { rd: 11, imm20: 0 }),
                     Instruction::Addi(format::I {
                         rd: 11,
-                        rs1: 0,
-                        imm12: 0
+                        rs1: 11,
+                        imm12: -20
                     })
                 ],
                 Instruction::Addi(format::I {
@@ -264,7 +441,7 @@ mod tests {
                     Instruction::Jalr(format::I {
                         rd: 1,
                         rs1: 1,
-                        imm12: 8
+                        imm12: 12
                     })
                 ],
                 Instruction::Jal(format::U { rd: 0, imm20: 36 }),
@@ -317,12 +494,812 @@ mod tests {
         );
 
         assert_eq!(
-            compilation_result
-                .memory
-                .snapshot()
-                .into_iter()
-                .collect::<Vec<_>>(),
+            compilation_result.memory.bytes(0, 14),
             vec![72, 101, 108, 108, 111, 44, 32, 119, 111, 114, 108, 100, 33, 0]
         );
     }
+
+    #[test]
+    fn machine_code_emission() {
+        let input = r#"
+        .text
+        addi x1, x0, 123
+        loop:
+            add x1, x1, x1
+            bnez x1, loop
+            vsetvli t0, a0, e32, m1, ta, ma
+            vadd.vv v1, v2, v3
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0).unwrap();
+
+        assert_eq!(
+            compilation_result.machine_code(),
+            Ok(vec![
+                vec![0x07b00093],
+                vec![0x001080b3],
+                vec![0xfe009ee3],
+                vec![0x0d0572d7],
+                vec![0x022180d7]
+            ])
+        );
+
+        assert_eq!(
+            compilation_result.binary().unwrap()[..8],
+            [0x93, 0x00, 0xb0, 0x07, 0xb3, 0x80, 0x10, 0x00]
+        );
+    }
+
+    #[test]
+    fn program_runs_from_text_section() {
+        let input = r#"
+        .text
+            la a0, numbers
+            ld a1, 0(a0)
+            li a2, 0x12345
+            call add
+            j finish
+        add:
+            add a0, a1, a2
+            ret
+        finish:
+        .data
+        numbers:
+            .dword 100
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        assert_eq!(
+            compilation_result.instructions_pcs,
+            vec![0x40, 0x48, 0x4c, 0x54, 0x5c, 0x60, 0x64]
+        );
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[10], 100 + 0x12345);
+        assert_eq!(core.registers.x[1], 0x5c);
+        assert_eq!(core.registers.pc, 0x68);
+    }
+
+    #[test]
+    fn compression_shrinks_text_section() {
+        let input = r#"
+        .text
+            li a0, 0x20
+            la a1, to_copy
+            li a2, 10
+            call memcpy
+            j finish
+        memcpy:
+            mv a3, a0
+        loop:
+            lb t0, 0(a1)
+            sb t0, 0(a3)
+            addi a1, a1, 1
+            addi a3, a3, 1
+            addi a2, a2, -1
+            bnez a2, loop
+            c.jr ra
+        finish:
+        .data
+        to_copy:
+            .asciz "Hello, world!"
+        "#
+        .trim_start();
+
+        let uncompressed = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+        let compressed = Interpreter::compile_compressed(input.to_owned(), 0x40).unwrap();
+
+        assert_eq!(uncompressed.binary().unwrap().len(), 58);
+        assert_eq!(compressed.binary().unwrap().len(), 44);
+        assert_eq!(
+            compressed.instructions_pcs,
+            vec![0x40, 0x44, 0x4c, 0x4e, 0x56, 0x58, 0x5a, 0x5e, 0x62, 0x64, 0x66, 0x68, 0x6a]
+        );
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compressed.memory)
+            .text_base(compressed.text_base)
+            .build();
+        core.load_program(&compressed.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.memory.get(0x20).unwrap(), *b"Hello, wor");
+        assert_eq!(core.registers.pc, 0x6c);
+    }
+
+    #[test]
+    fn bitmanip_replaces_extension_pseudo_instructions() {
+        let input = r#"
+        .text
+            li a1, 0x1ff80
+            sext.b a2, a1
+            sext.h a3, a1
+            zext.w a4, a2
+            rev8 a5, a1
+        "#
+        .trim_start();
+
+        let run = |options| {
+            let compilation_result =
+                Interpreter::compile_with(input.to_owned(), 0, options).unwrap();
+            let mut core = RvCoreBuilder::default()
+                .text_base(compilation_result.text_base)
+                .build();
+            core.load_program(&compilation_result.instructions).unwrap();
+
+            for result in core.run() {
+                result.unwrap();
+            }
+
+            (compilation_result.instructions_pcs, core.registers.x)
+        };
+
+        let (fused_pcs, fused) = run(CompileOptions::default());
+        let (bitmanip_pcs, bitmanip) = run(CompileOptions {
+            bitmanip: true,
+            ..Default::default()
+        });
+
+        assert_eq!(fused_pcs, vec![0x0, 0x8, 0x10, 0x18, 0x20]);
+        assert_eq!(bitmanip_pcs, vec![0x0, 0x8, 0xc, 0x10, 0x14]);
+        assert_eq!(fused, bitmanip);
+        assert_eq!(fused[12] as i64, -0x80);
+        assert_eq!(fused[13] as i64, -0x80);
+        assert_eq!(fused[14], 0xffff_ff80);
+        assert_eq!(fused[15], 0x80ff_0100_0000_0000);
+    }
+
+    #[test]
+    fn hello_world_runs_with_rars_environment() {
+        let input = r#"
+        .text
+            li a7, 4
+            la a0, greeting
+            ecall
+            li a7, 1
+            li a0, 2024
+            ecall
+            li a7, 10
+            ecall
+            li a0, 1
+        .data
+        greeting:
+            .asciz "Hello, world! "
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+        let environment = std::rc::Rc::new(std::cell::RefCell::new(RarsEnvironment::default()));
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .environment(environment.clone())
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(environment.borrow().output, "Hello, world! 2024");
+        assert_eq!(core.exit_code, Some(0));
+        assert_eq!(core.registers.x[10], 2024);
+    }
+
+    #[test]
+    fn half_precision_kernel_runs() {
+        let input = r#"
+        .text
+            la a0, halves
+            la a1, singles
+            flh fa0, 0(a0)
+            flh fa1, 2(a0)
+            fmul.h fa2, fa0, fa1
+            fcvt.s.h fa3, fa2
+            fmv.x.w t0, fa3
+            vsetivli zero, 4, e16, m1, ta, ma
+            vle16.v v1, (a0)
+            vfadd.vf v2, v1, fa1
+            vfwcvt.f.f.v v4, v2
+            vsetivli zero, 4, e32, m1, ta, ma
+            vse32.v v4, (a1)
+            vsetivli zero, 4, e16, m1, ta, ma
+            vfncvt.f.f.w v6, v4
+            vse16.v v6, (a0)
+        .data
+        halves:
+            .half 15360, 16640, 47104, 31743
+        singles:
+            .word 0, 0, 0, 0
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let singles: [u8; 16] = core.memory.get(8).unwrap();
+        let singles = singles
+            .chunks(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(core.registers.x[5], 2.5f32.to_bits() as u64);
+        // 65504 + 2.5 rounds back to the largest half
+        assert_eq!(singles, vec![3.5, 5.0, 2.0, 65504.0]);
+        assert_eq!(
+            core.memory.get(0).unwrap(),
+            [0x00, 0x43, 0x00, 0x45, 0x00, 0x40, 0xff, 0x7b]
+        );
+    }
+
+    #[test]
+    fn rounding_modes_and_flags_are_observable() {
+        let input = r#"
+        .text
+            li t0, 1
+            li t1, 3
+            fcvt.s.w fa0, t0
+            fcvt.s.w fa1, t1
+            fdiv.s fa2, fa0, fa1
+            fsrmi 3
+            fdiv.s fa3, fa0, fa1
+            fdiv.s fa4, fa0, fa1, rtz
+            frflags t2
+            fsflags zero
+            fmv.w.x ft0, zero
+            fdiv.s fa5, fa0, ft0
+            frcsr t3
+            fmv.x.w a0, fa2
+            fmv.x.w a1, fa3
+            fmv.x.w a2, fa4
+            fcvt.w.s a3, fa3
+            fcvt.w.s a4, fa3, rdn
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        // 1/3 rounded to nearest and up agree, towards zero is one ulp below
+        assert_eq!(core.registers.x[10], 0x3eaa_aaab);
+        assert_eq!(core.registers.x[11], 0x3eaa_aaab);
+        assert_eq!(core.registers.x[12], 0x3eaa_aaaa);
+        // Only the inexact flag, then divide by zero under frm = RUP
+        assert_eq!(core.registers.x[7], alias::fflags::NX);
+        assert_eq!(core.registers.x[28], 0b011 << 5 | alias::fflags::DZ);
+        assert_eq!(core.registers.x[13], 1);
+        assert_eq!(core.registers.x[14], 0);
+    }
+
+    #[test]
+    fn fixed_point_ops_follow_vxrm() {
+        let input = r#"
+        .text
+            vsetivli zero, 4, e8, m1, ta, ma
+            li t0, -5
+            vmv.v.x v1, t0
+            vmv.v.i v2, 0
+            vaadd.vv v3, v1, v2
+            vmv.x.s a0, v3
+            csrrwi zero, vxrm, 2
+            vaadd.vv v3, v1, v2
+            vmv.x.s a1, v3
+            vsetivli zero, 4, e16, m2, ta, ma
+            li t0, 1000
+            vmv.v.x v4, t0
+            vsetivli zero, 4, e8, m1, ta, ma
+            vnclip.wi v6, v4, 2
+            vmv.x.s a2, v6
+            csrr a3, vcsr
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        // -5 / 2 rounds up to -2 under rnu and down to -3 under rdn
+        assert_eq!(core.registers.x[10] as i8, -2);
+        assert_eq!(core.registers.x[11] as i8, -3);
+        // 1000 >> 2 doesn't fit in a byte and saturates
+        assert_eq!(core.registers.x[12], 127);
+        assert_eq!(core.registers.x[13], 0b10 << 1 | 1);
+    }
+
+    #[test]
+    fn agnostic_elements_use_the_configured_fill() {
+        let input = r#"
+        .text
+            vsetvli t0, zero, e8, m1, tu, mu
+            vmv.v.i v2, 3
+            li t1, 0b0101
+            vmv.s.x v0, t1
+            vsetivli zero, 4, e8, m1, ta, ma
+            vadd.vi v2, v2, 1, v0.t
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        for (fill, agnostic) in [(AgnosticFill::Keep, 3), (AgnosticFill::Ones, 0xff)] {
+            let mut core = RvCoreBuilder::default()
+                .memory(compilation_result.memory.clone())
+                .text_base(compilation_result.text_base)
+                .vec_engine(
+                    VectorEngineBuilder::default()
+                        .agnostic_fill(fill)
+                        .build()
+                        .unwrap(),
+                )
+                .build();
+            core.load_program(&compilation_result.instructions).unwrap();
+
+            for result in core.run() {
+                result.unwrap();
+            }
+
+            let vlenb = Vlen::default().byte_length();
+            let v2 = &core.registers.snapshot().v[2 * vlenb..3 * vlenb];
+
+            // Elements 1 and 3 are masked off, everything from 4 on is tail
+            assert_eq!(v2[..4], [4, agnostic, 4, agnostic]);
+            assert!(v2[4..].iter().all(|&byte| byte == agnostic));
+        }
+    }
+
+    #[test]
+    fn mask_and_tail_policies_apply_independently() {
+        for (policy, masked, tail) in [
+            ("tu, mu", 3, 3),
+            ("tu, ma", 0xff, 3),
+            ("ta, mu", 3, 0xff),
+            ("ta, ma", 0xff, 0xff),
+        ] {
+            let input = format!(
+                r#"
+        .text
+            vsetvli t0, zero, e8, m1, tu, mu
+            vmv.v.i v2, 3
+            li t1, 0b0101
+            vmv.s.x v0, t1
+            vsetivli zero, 4, e8, m1, {policy}
+            vadd.vi v2, v2, 1, v0.t
+        "#
+            );
+
+            let compilation_result =
+                Interpreter::compile(input.trim_start().to_owned(), 0x40).unwrap();
+
+            let mut core = RvCoreBuilder::default()
+                .memory(compilation_result.memory)
+                .text_base(compilation_result.text_base)
+                .vec_engine(
+                    VectorEngineBuilder::default()
+                        .agnostic_fill(AgnosticFill::Ones)
+                        .build()
+                        .unwrap(),
+                )
+                .build();
+            core.load_program(&compilation_result.instructions).unwrap();
+
+            for result in core.run() {
+                result.unwrap();
+            }
+
+            let vlenb = Vlen::default().byte_length();
+            let v2 = &core.registers.snapshot().v[2 * vlenb..3 * vlenb];
+
+            assert_eq!(v2[..4], [4, masked, 4, masked], "{policy}");
+            assert!(v2[4..].iter().all(|&byte| byte == tail), "{policy}");
+        }
+    }
+
+    #[test]
+    fn vstart_past_vl_updates_no_elements() {
+        let input = r#"
+        .text
+            vsetvli t0, zero, e8, m1, tu, mu
+            vmv.v.i v2, 3
+            vsetivli zero, 0, e8, m1, ta, ma
+            vadd.vi v2, v2, 1
+            vsetivli zero, 2, e8, m1, ta, ma
+            csrrwi zero, vstart, 2
+            vadd.vi v2, v2, 1
+            li t1, -1
+            csrw vstart, t1
+            csrr t2, vstart
+            vadd.vi v2, v2, 1
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .agnostic_fill(AgnosticFill::Ones)
+                    .build()
+                    .unwrap(),
+            )
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let vlen = Vlen::default();
+        let vlenb = vlen.byte_length();
+
+        // vstart only holds element indices below VLEN
+        assert_eq!(core.registers.x[7], vlen.bit_length() as u64 - 1);
+        assert!(core.registers.snapshot().v[2 * vlenb..3 * vlenb]
+            .iter()
+            .all(|&byte| byte == 3));
+    }
+
+    #[test]
+    fn unsupported_vtype_sets_vill() {
+        let input = r#"
+        .text
+            vsetvli t0, zero, e64, m1, ta, ma
+            vsetvli t1, zero, e32, m1, ta, ma
+            vsetvli t2, zero, e8, mf8, ta, ma
+            vadd.vv v1, v1, v1
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .vec_engine(
+                VectorEngineBuilder::default()
+                    .vlen(Vlen::new(32).unwrap())
+                    .extension(VectorExtension::Zve32x)
+                    .build()
+                    .unwrap(),
+            )
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        let trap = core.run().find_map(Result::err).unwrap();
+
+        // SEW=64 is wider than ELEN and LMUL=1/8 leaves no room for an e8 element
+        assert_eq!(core.registers.x[5], 0);
+        assert_eq!(core.registers.x[6], 1);
+        assert_eq!(core.registers.x[7], 0);
+        assert_eq!(core.registers.c[alias::VTYPE].read(), alias::vtype::VILL);
+        assert!(matches!(trap.exception, Exception::IllegalInstruction(_)));
+    }
+
+    #[test]
+    fn vector_loads_resume_from_vstart() {
+        let input = r#"
+        .text
+            vsetivli zero, 8, e8, m1, ta, mu
+            vle8.v v1, (a0)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        // Only the first three elements fit in memory
+        let base = core.memory.len();
+        let end = base + 3;
+        core.memory.resize(end);
+        core.memory.load(base, &[1, 2, 3]);
+        core.registers.x[10] = base;
+
+        let trap = core.run().find_map(Result::err).unwrap();
+        let vlenb = Vlen::default().byte_length();
+
+        assert_eq!(trap.exception, Exception::LoadAccessFault(end));
+        assert_eq!(core.registers.c[alias::VSTART].read(), 3);
+        assert_eq!(core.registers.snapshot().v[vlenb..vlenb + 3], [1, 2, 3]);
+
+        core.memory.resize(end + 5);
+        core.memory.load(end, &[4, 5, 6, 7, 8]);
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.c[alias::VSTART].read(), 0);
+        assert_eq!(
+            core.registers.snapshot().v[vlenb..vlenb + 8],
+            [1, 2, 3, 4, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn whole_register_loads_resume_from_vstart() {
+        let input = r#"
+        .text
+            vsetivli zero, 0, e8, m1, ta, ma
+            vl2re16.v v2, (a0)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        let vlenb = Vlen::default().byte_length();
+        let data: Vec<u8> = (1..=2 * vlenb as u8).collect();
+
+        // The group spans two registers, only the first one and two more elements fit in memory
+        let base = core.memory.len();
+        let end = base + vlenb as u64 + 4;
+        core.memory.resize(end);
+        core.memory.load(base, &data[..vlenb + 4]);
+        core.registers.x[10] = base;
+
+        let trap = core.run().find_map(Result::err).unwrap();
+
+        assert_eq!(trap.exception, Exception::LoadAccessFault(end));
+        assert_eq!(
+            core.registers.c[alias::VSTART].read(),
+            (vlenb as u64 + 4) / 2
+        );
+
+        core.memory.resize(base + 2 * vlenb as u64);
+        core.memory.load(end, &data[vlenb + 4..]);
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.c[alias::VSTART].read(), 0);
+        assert_eq!(core.registers.snapshot().v[2 * vlenb..4 * vlenb], data[..]);
+    }
+
+    #[test]
+    fn whole_register_stores_resume_from_vstart() {
+        let input = r#"
+        .text
+            vsetvli t0, zero, e8, m1, ta, ma
+            vmv.v.i v2, 3
+            vsetivli zero, 1, e8, m1, ta, ma
+            vs1r.v v2, (a0)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        // Only the first three elements fit in the buffer
+        let base = 0x1000;
+        let end = base + 3;
+        let buffer = |size| Region::new("buffer", base, size, Permissions::RW);
+        core.memory.map(buffer(3)).unwrap();
+        core.registers.x[10] = base;
+
+        let trap = core.run().find_map(Result::err).unwrap();
+
+        assert_eq!(trap.exception, Exception::StoreAccessFault(end));
+        assert_eq!(core.registers.c[alias::VSTART].read(), 3);
+
+        // Elements below vstart aren't stored again
+        let vlenb = Vlen::default().byte_length();
+        core.memory.unmap("buffer");
+        core.memory.map(buffer(vlenb as u64)).unwrap();
+        core.memory.load(base, &[0; 3]);
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.c[alias::VSTART].read(), 0);
+        assert_eq!(core.memory.bytes(base, 3), [0; 3]);
+        assert!(core
+            .memory
+            .bytes(end, vlenb - 3)
+            .iter()
+            .all(|&byte| byte == 3));
+    }
+
+    #[test]
+    fn mask_loads_and_stores_cover_vl_bits() {
+        let input = r#"
+        .text
+            vsetivli zero, 10, e8, m1, ta, ma
+            vlm.v v1, (a0)
+            vsm.v v1, (a1)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        let base = 0x10;
+        core.memory.load(base, &[0xaa, 0xbb, 0xcc]);
+        core.registers.x[10] = base;
+        core.registers.x[11] = base + 3;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let vlenb = Vlen::default().byte_length();
+
+        // Ten mask bits take up two bytes
+        assert_eq!(
+            core.registers.snapshot().v[vlenb..vlenb + 3],
+            [0xaa, 0xbb, 0]
+        );
+        assert_eq!(core.memory.bytes(base + 3, 3), [0xaa, 0xbb, 0]);
+    }
+
+    #[test]
+    fn fault_only_first_loads_raise_page_faults_on_the_first_element() {
+        let input = r#"
+        .text
+            vsetivli zero, 8, e8, m1, ta, mu
+            vle8ff.v v1, (a0)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        // Sv39 root table at 0x2000 maps the first gigapage to itself, the second one is unmapped
+        core.memory.resize(0x3000);
+        core.memory.load(0x2000, &0xcfu64.to_le_bytes());
+        core.registers.x[10] = 0x4000_0000;
+
+        let c = &mut core.registers.c;
+        c[alias::SATP].write(8 << 60 | 0x2).unwrap();
+        c[alias::MTVEC].write(0x1000).unwrap();
+        core.registers.mode = PrivilegeMode::Supervisor;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.c[alias::MCAUSE].read(), 13);
+        assert_eq!(core.registers.c[alias::MTVAL].read(), 0x4000_0000);
+        assert_eq!(core.registers.c[alias::VL].read(), 8);
+        assert_eq!(core.registers.c[alias::VSTART].read(), 0);
+    }
+
+    #[test]
+    fn fault_only_first_loads_trim_vl_on_later_faults() {
+        let input = r#"
+        .text
+            vsetivli zero, 8, e8, m1, ta, mu
+            vle8ff.v v1, (a0)
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        let base = core.memory.len();
+        core.memory.resize(base + 3);
+        core.memory.load(base, &[1, 2, 3]);
+        core.registers.x[10] = base;
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        let vlenb = Vlen::default().byte_length();
+
+        assert_eq!(core.registers.c[alias::VL].read(), 3);
+        assert_eq!(core.registers.c[alias::VSTART].read(), 0);
+        assert_eq!(core.registers.snapshot().v[vlenb..vlenb + 3], [1, 2, 3]);
+    }
+
+    #[test]
+    fn atomics_update_memory() {
+        let input = r#"
+        .text
+            la a0, counter
+            li t1, 5
+        retry:
+            lr.d t0, (a0)
+            add t0, t0, t1
+            sc.d t2, t0, (a0)
+            bnez t2, retry
+            li t1, 3
+            amoadd.d.aqrl t3, t1, (a0)
+            ld t4, 0(a0)
+        .data
+        counter:
+            .dword 10
+        "#
+        .trim_start();
+
+        let compilation_result = Interpreter::compile(input.to_owned(), 0x40).unwrap();
+
+        let mut core = RvCoreBuilder::default()
+            .memory(compilation_result.memory)
+            .text_base(compilation_result.text_base)
+            .build();
+        core.load_program(&compilation_result.instructions).unwrap();
+
+        for result in core.run() {
+            result.unwrap();
+        }
+
+        assert_eq!(core.registers.x[7], 0);
+        assert_eq!(core.registers.x[28], 15);
+        assert_eq!(core.registers.x[29], 18);
+    }
 }
@@ -1,7 +1,7 @@
 mod data;
 mod operand;
 
-use std::{ascii, cmp::Ordering, collections::HashMap};
+use std::{ascii, collections::HashMap};
 
 use eeric_core::{
     fuse,
@@ -39,7 +39,7 @@ impl From<Data> for Vec<u8> {
             Data::Float(floats) => floats.into_iter().flat_map(f32::to_le_bytes).collect(),
             Data::Double(doubles) => doubles.into_iter().flat_map(f64::to_le_bytes).collect(),
             Data::Ascii(string) => string.into_iter().map(|char| char as u8).collect(),
-            Data::Zero(length) => std::iter::repeat(0).take(length).collect(),
+            Data::Zero(length) => std::iter::repeat_n(0, length).collect(),
         }
     }
 }
@@ -48,12 +48,15 @@ pub enum AssemblerDirective {
     Section(Section),
     Data(Data),
 }
+// Preprocessor directives are recognized but not supported yet
+#[allow(dead_code)]
 pub enum PreprocDirective {
     Define(String, String),
 }
 
 pub enum LineClassification {
     AssemblerDirective(AssemblerDirective),
+    #[allow(dead_code)]
     PreprocDirective(PreprocDirective),
     Instruction(String),
     Label(String),
@@ -99,30 +102,49 @@ impl Decoder {
         })
     }
 
+    /// With `bitmanip`, pseudo-instructions that have Zba/Zbb counterparts are decoded into them
     pub fn decode_text_section(
         instruction_line: &str,
         instruction_labels: &HashMap<String, usize>,
         memory_labels: &HashMap<String, usize>,
         current_address: usize,
+        bitmanip: bool,
     ) -> Result<Instruction, String> {
         let (mnemonic, op) = Self::split_instruction(instruction_line);
 
+        if mnemonic.starts_with("c.") {
+            return Self::decode_compressed(
+                mnemonic,
+                op,
+                instruction_labels,
+                memory_labels,
+                current_address,
+            );
+        }
+
         use integer::{
-            parse_branch_format as b, parse_i_format as i, parse_load_format as l,
-            parse_r_format as r, parse_s_format as s, parse_u_format as u,
+            parse_amo_format as amo, parse_branch_format as b, parse_i_format as i,
+            parse_load_format as l, parse_lr_format as lr, parse_r_format as r,
+            parse_r_unary_format as r_unary, parse_s_format as s, parse_u_format as u,
         };
 
         use csr::{parse_csri_format as csri, parse_csrr_format as csrr};
 
         use float::{
             parse_load_format as fl, parse_r4_format as r4, parse_r_format as fr,
-            parse_r_single_reg_format as frs, parse_r_single_reg_to_f_format as frs_to_f,
-            parse_r_single_reg_to_x_format as frs_to_x, parse_r_to_x_format as frx,
+            parse_r_rounding_format as fr_rm, parse_r_single_reg_exact_format as frs_exact,
+            parse_r_single_reg_rounding_format as frs_rm,
+            parse_r_single_reg_to_f_exact_format as frs_to_f_exact,
+            parse_r_single_reg_to_f_format as frs_to_f,
+            parse_r_single_reg_to_f_rounding_format as frs_to_f_rm,
+            parse_r_single_reg_to_x_format as frs_to_x,
+            parse_r_single_reg_to_x_rounding_format as frs_to_x_rm, parse_r_to_x_format as frx,
             parse_store_format as fs,
         };
 
         use vector::{
             parse_opfvf_fma_format as opfvf_fma, parse_opfvf_format as opfvf,
+            parse_opfvf_v0_format as opfvf_v0, parse_opfvf_vmv_format as opfvf_vmv,
             parse_opfvv_fma_format as opfvv_fma, parse_opfvv_format as opfvv,
             parse_opivi_format as opivi, parse_opivi_maskless_format as opivi_maskless,
             parse_opivi_v0_format as opivi_v0, parse_opivi_vmv_format as opivi_vmv,
@@ -136,15 +158,17 @@ impl Decoder {
             parse_vfunary1_format as vfunary1, parse_vl_format as vl, parse_vlm_format as vlm,
             parse_vlr_format as vlr, parse_vls_format as vls, parse_vlx_format as vlx,
             parse_vmunary0_format as vmunary0, parse_vmunary0_vidv_format as vidv,
-            parse_vrfunary0_format as vrfunary0, parse_vrxunary0_format as vrxunary0,
-            parse_vs_format as vs, parse_vsetivli_format as vsetivli,
-            parse_vsetvl_format as vsetvl, parse_vsetvli_format as vsetvli,
-            parse_vsm_format as vsm, parse_vsr_format as vsr, parse_vss_format as vss,
-            parse_vsx_format as vsx, parse_vwfunary0_format as vwfunary0,
+            parse_vmvnr_format as vmvnr, parse_vrfunary0_format as vrfunary0,
+            parse_vrxunary0_format as vrxunary0, parse_vs_format as vs,
+            parse_vsetivli_format as vsetivli, parse_vsetvl_format as vsetvl,
+            parse_vsetvli_format as vsetvli, parse_vsm_format as vsm, parse_vsr_format as vsr,
+            parse_vss_format as vss, parse_vsx_format as vsx, parse_vwfunary0_format as vwfunary0,
             parse_vwxunary0_format as vwxunary0, parse_vwxunary0_vmvxs_format as vmvxs,
             parse_vxunary0_format as vxunary0,
         };
 
+        let (mnemonic, aq, rl) = Self::split_ordering(mnemonic);
+
         let instruction = match Self::rename(mnemonic) {
             "add" => Add(r(op)?),
             "addw" => Addw(r(op)?),
@@ -196,8 +220,12 @@ impl Decoder {
             "bgeu" => Bgeu(b(op, instruction_labels, current_address)?),
             "blt" => Blt(b(op, instruction_labels, current_address)?),
             "bltu" => Bltu(b(op, instruction_labels, current_address)?),
-            "jal" => match u(op) {
-                Ok(instruction) => Jal(instruction),
+            "jal" => match integer::pseudo::parse_op_label_format(
+                op,
+                instruction_labels,
+                current_address,
+            ) {
+                Ok((rd, diff)) => Jal(U { rd, imm20: diff }),
                 Err(fst_err) => {
                     match integer::pseudo::parse_label_format(
                         op,
@@ -221,6 +249,22 @@ impl Decoder {
                 },
             },
 
+            "ecall" => Ecall,
+            "ebreak" => Ebreak,
+            "fence" => Fence(integer::parse_fence_format(op)?),
+            "fence.tso" => Fence(format::Fence {
+                fm: 0b1000,
+                pred: 0b0011,
+                succ: 0b0011,
+                rs1: 0,
+                rd: 0,
+            }),
+            "fence.i" => FenceI(I {
+                rd: 0,
+                rs1: 0,
+                imm12: 0,
+            }),
+
             "csrrw" => Csrrw(csrr(op)?),
             "csrrs" => Csrrs(csrr(op)?),
             "csrrc" => Csrrc(csrr(op)?),
@@ -228,6 +272,19 @@ impl Decoder {
             "csrrsi" => Csrrsi(csri(op)?),
             "csrrci" => Csrrci(csri(op)?),
 
+            "mret" => Mret,
+            "wfi" => Wfi,
+            "sret" => Sret,
+            "sfence.vma" => {
+                let (rs1, rs2) = match op {
+                    "" => (0, 0),
+                    _ => integer::pseudo::parse_op_op_format(op)
+                        .or_else(|_| integer::pseudo::parse_op_format(op).map(|rs1| (rs1, 0)))?,
+                };
+
+                SfenceVma(R { rd: 0, rs1, rs2 })
+            }
+
             "mul" => Mul(r(op)?),
             "mulh" => Mulh(r(op)?),
             "mulhsu" => Mulhsu(r(op)?),
@@ -242,36 +299,100 @@ impl Decoder {
             "remw" => Remw(r(op)?),
             "remuw" => Remuw(r(op)?),
 
+            "add.uw" => Adduw(r(op)?),
+            "sh1add" => Sh1add(r(op)?),
+            "sh2add" => Sh2add(r(op)?),
+            "sh3add" => Sh3add(r(op)?),
+            "sh1add.uw" => Sh1adduw(r(op)?),
+            "sh2add.uw" => Sh2adduw(r(op)?),
+            "sh3add.uw" => Sh3adduw(r(op)?),
+            "slli.uw" => Slliuw(i(op)?),
+
+            "andn" => Andn(r(op)?),
+            "orn" => Orn(r(op)?),
+            "xnor" => Xnor(r(op)?),
+            "clz" => Clz(r_unary(op)?),
+            "clzw" => Clzw(r_unary(op)?),
+            "ctz" => Ctz(r_unary(op)?),
+            "ctzw" => Ctzw(r_unary(op)?),
+            "cpop" => Cpop(r_unary(op)?),
+            "cpopw" => Cpopw(r_unary(op)?),
+            "max" => Max(r(op)?),
+            "maxu" => Maxu(r(op)?),
+            "min" => Min(r(op)?),
+            "minu" => Minu(r(op)?),
+            "rol" => Rol(r(op)?),
+            "rolw" => Rolw(r(op)?),
+            "ror" => Ror(r(op)?),
+            "rori" => Rori(i(op)?),
+            "roriw" => Roriw(i(op)?),
+            "rorw" => Rorw(r(op)?),
+            "orc.b" => Orcb(r_unary(op)?),
+            "rev8" => Rev8(r_unary(op)?),
+
+            "bclr" => Bclr(r(op)?),
+            "bclri" => Bclri(i(op)?),
+            "bext" => Bext(r(op)?),
+            "bexti" => Bexti(i(op)?),
+            "binv" => Binv(r(op)?),
+            "binvi" => Binvi(i(op)?),
+            "bset" => Bset(r(op)?),
+            "bseti" => Bseti(i(op)?),
+
+            "lr.w" => Lrw(lr(op, aq, rl)?),
+            "sc.w" => Scw(amo(op, aq, rl)?),
+            "amoswap.w" => Amoswapw(amo(op, aq, rl)?),
+            "amoadd.w" => Amoaddw(amo(op, aq, rl)?),
+            "amoxor.w" => Amoxorw(amo(op, aq, rl)?),
+            "amoand.w" => Amoandw(amo(op, aq, rl)?),
+            "amoor.w" => Amoorw(amo(op, aq, rl)?),
+            "amomin.w" => Amominw(amo(op, aq, rl)?),
+            "amomax.w" => Amomaxw(amo(op, aq, rl)?),
+            "amominu.w" => Amominuw(amo(op, aq, rl)?),
+            "amomaxu.w" => Amomaxuw(amo(op, aq, rl)?),
+
+            "lr.d" => Lrd(lr(op, aq, rl)?),
+            "sc.d" => Scd(amo(op, aq, rl)?),
+            "amoswap.d" => Amoswapd(amo(op, aq, rl)?),
+            "amoadd.d" => Amoaddd(amo(op, aq, rl)?),
+            "amoxor.d" => Amoxord(amo(op, aq, rl)?),
+            "amoand.d" => Amoandd(amo(op, aq, rl)?),
+            "amoor.d" => Amoord(amo(op, aq, rl)?),
+            "amomin.d" => Amomind(amo(op, aq, rl)?),
+            "amomax.d" => Amomaxd(amo(op, aq, rl)?),
+            "amominu.d" => Amominud(amo(op, aq, rl)?),
+            "amomaxu.d" => Amomaxud(amo(op, aq, rl)?),
+
             "flw" => Flw(fl(op, memory_labels)?),
             "fsw" => Fsw(fs(op, memory_labels)?),
             "fmadd.s" => Fmadds(r4(op)?),
             "fmsub.s" => Fmsubs(r4(op)?),
             "fnmsub.s" => Fnmsubs(r4(op)?),
             "fnmadd.s" => Fnmadds(r4(op)?),
-            "fadd.s" => Fadds(fr(op)?),
-            "fsub.s" => Fsubs(fr(op)?),
-            "fmul.s" => Fmuls(fr(op)?),
-            "fdiv.s" => Fdivs(frs(op)?),
-            "fsqrt.s" => Fsqrts(fr(op)?),
+            "fadd.s" => Fadds(fr_rm(op)?),
+            "fsub.s" => Fsubs(fr_rm(op)?),
+            "fmul.s" => Fmuls(fr_rm(op)?),
+            "fdiv.s" => Fdivs(fr_rm(op)?),
+            "fsqrt.s" => Fsqrts(frs_rm(op)?),
             "fsgnj.s" => Fsgnjs(fr(op)?),
             "fsgnjn.s" => Fsgnjns(fr(op)?),
             "fsgnjx.s" => Fsgnjxs(fr(op)?),
             "fmin.s" => Fmins(fr(op)?),
             "fmax.s" => Fmaxs(fr(op)?),
-            "fcvt.w.s" => Fcvtws(frs_to_x(op)?),
-            "fcvt.wu.s" => Fcvtwus(frs_to_x(op)?),
+            "fcvt.w.s" => Fcvtws(frs_to_x_rm(op)?),
+            "fcvt.wu.s" => Fcvtwus(frs_to_x_rm(op)?),
             "fmv.x.w" => Fmvxw(frs_to_x(op)?),
             "feq.s" => Feqs(frx(op)?),
             "flt.s" => Flts(frx(op)?),
             "fle.s" => Fles(frx(op)?),
             "fclass.s" => Fclasss(frs_to_x(op)?),
-            "fcvt.s.w" => Fcvtsw(frs_to_f(op)?),
-            "fcvt.s.wu" => Fcvtswu(frs_to_f(op)?),
+            "fcvt.s.w" => Fcvtsw(frs_to_f_rm(op)?),
+            "fcvt.s.wu" => Fcvtswu(frs_to_f_rm(op)?),
             "fmv.w.x" => Fmvwx(frs_to_f(op)?),
-            "fcvt.l.s" => Fcvtls(frs_to_x(op)?),
-            "fcvt.lu.s" => Fcvtlus(frs_to_x(op)?),
-            "fcvt.s.l" => Fcvtsl(frs_to_f(op)?),
-            "fcvt.s.lu" => Fcvtslu(frs_to_f(op)?),
+            "fcvt.l.s" => Fcvtls(frs_to_x_rm(op)?),
+            "fcvt.lu.s" => Fcvtlus(frs_to_x_rm(op)?),
+            "fcvt.s.l" => Fcvtsl(frs_to_f_rm(op)?),
+            "fcvt.s.lu" => Fcvtslu(frs_to_f_rm(op)?),
 
             "fld" => Fld(fl(op, memory_labels)?),
             "fsd" => Fsd(fs(op, memory_labels)?),
@@ -279,33 +400,68 @@ impl Decoder {
             "fmsub.d" => Fmsubd(r4(op)?),
             "fnmsub.d" => Fnmsubd(r4(op)?),
             "fnmadd.d" => Fnmaddd(r4(op)?),
-            "fadd.d" => Faddd(fr(op)?),
-            "fsub.d" => Fsubd(fr(op)?),
-            "fmul.d" => Fmuld(fr(op)?),
-            "fdiv.d" => Fdivd(frs(op)?),
-            "fsqrt.d" => Fsqrtd(fr(op)?),
+            "fadd.d" => Faddd(fr_rm(op)?),
+            "fsub.d" => Fsubd(fr_rm(op)?),
+            "fmul.d" => Fmuld(fr_rm(op)?),
+            "fdiv.d" => Fdivd(fr_rm(op)?),
+            "fsqrt.d" => Fsqrtd(frs_rm(op)?),
             "fsgnj.d" => Fsgnjd(fr(op)?),
             "fsgnjn.d" => Fsgnjnd(fr(op)?),
             "fsgnjx.d" => Fsgnjxd(fr(op)?),
             "fmin.d" => Fmind(fr(op)?),
             "fmax.d" => Fmaxd(fr(op)?),
-            "fcvt.s.d" => Fcvtsd(fr(op)?),
-            "fcvt.d.s" => Fcvtds(fr(op)?),
+            "fcvt.s.d" => Fcvtsd(frs_rm(op)?),
+            "fcvt.d.s" => Fcvtds(frs_exact(op)?),
             "feq.d" => Feqd(frx(op)?),
             "flt.d" => Fltd(frx(op)?),
             "fle.d" => Fled(frx(op)?),
             "fclass.d" => Fclassd(frs_to_x(op)?),
-            "fcvt.w.d" => Fcvtwd(frs_to_x(op)?),
-            "fcvt.wu.d" => Fcvtwud(frs_to_x(op)?),
-            "fcvt.d.w" => Fcvtdw(frs_to_f(op)?),
-            "fcvt.d.wu" => Fcvtdwu(frs_to_f(op)?),
-            "fcvt.l.d" => Fcvtld(frs_to_x(op)?),
-            "fcvt.lu.d" => Fcvtlud(frs_to_x(op)?),
+            "fcvt.w.d" => Fcvtwd(frs_to_x_rm(op)?),
+            "fcvt.wu.d" => Fcvtwud(frs_to_x_rm(op)?),
+            "fcvt.d.w" => Fcvtdw(frs_to_f_exact(op)?),
+            "fcvt.d.wu" => Fcvtdwu(frs_to_f_exact(op)?),
+            "fcvt.l.d" => Fcvtld(frs_to_x_rm(op)?),
+            "fcvt.lu.d" => Fcvtlud(frs_to_x_rm(op)?),
             "fmv.x.d" => Fmvxd(frs_to_x(op)?),
-            "fcvt.d.l" => Fcvtdl(frs_to_f(op)?),
-            "fcvt.d.lu" => Fcvtdlu(frs_to_f(op)?),
+            "fcvt.d.l" => Fcvtdl(frs_to_f_rm(op)?),
+            "fcvt.d.lu" => Fcvtdlu(frs_to_f_rm(op)?),
             "fmv.d.x" => Fmvdx(frs_to_f(op)?),
 
+            "flh" => Flh(fl(op, memory_labels)?),
+            "fsh" => Fsh(fs(op, memory_labels)?),
+            "fmadd.h" => Fmaddh(r4(op)?),
+            "fmsub.h" => Fmsubh(r4(op)?),
+            "fnmsub.h" => Fnmsubh(r4(op)?),
+            "fnmadd.h" => Fnmaddh(r4(op)?),
+            "fadd.h" => Faddh(fr_rm(op)?),
+            "fsub.h" => Fsubh(fr_rm(op)?),
+            "fmul.h" => Fmulh(fr_rm(op)?),
+            "fdiv.h" => Fdivh(fr_rm(op)?),
+            "fsqrt.h" => Fsqrth(frs_rm(op)?),
+            "fsgnj.h" => Fsgnjh(fr(op)?),
+            "fsgnjn.h" => Fsgnjnh(fr(op)?),
+            "fsgnjx.h" => Fsgnjxh(fr(op)?),
+            "fmin.h" => Fminh(fr(op)?),
+            "fmax.h" => Fmaxh(fr(op)?),
+            "fcvt.s.h" => Fcvtsh(frs_exact(op)?),
+            "fcvt.h.s" => Fcvths(frs_rm(op)?),
+            "fcvt.d.h" => Fcvtdh(frs_exact(op)?),
+            "fcvt.h.d" => Fcvthd(frs_rm(op)?),
+            "fcvt.w.h" => Fcvtwh(frs_to_x_rm(op)?),
+            "fcvt.wu.h" => Fcvtwuh(frs_to_x_rm(op)?),
+            "fmv.x.h" => Fmvxh(frs_to_x(op)?),
+            "feq.h" => Feqh(frx(op)?),
+            "flt.h" => Flth(frx(op)?),
+            "fle.h" => Fleh(frx(op)?),
+            "fclass.h" => Fclassh(frs_to_x(op)?),
+            "fcvt.h.w" => Fcvthw(frs_to_f_rm(op)?),
+            "fcvt.h.wu" => Fcvthwu(frs_to_f_rm(op)?),
+            "fmv.h.x" => Fmvhx(frs_to_f(op)?),
+            "fcvt.l.h" => Fcvtlh(frs_to_x_rm(op)?),
+            "fcvt.lu.h" => Fcvtluh(frs_to_x_rm(op)?),
+            "fcvt.h.l" => Fcvthl(frs_to_f_rm(op)?),
+            "fcvt.h.lu" => Fcvthlu(frs_to_f_rm(op)?),
+
             "vsetvli" => Vsetvli(vsetvli(op)?),
             "vsetivli" => Vsetivli(vsetivli(op)?),
             "vsetvl" => Vsetvl(vsetvl(op)?),
@@ -432,19 +588,19 @@ impl Decoder {
                 data: vsx(op)?,
             },
 
-            "vsuxeix8.v" => Vsuxv {
+            "vsoxei8.v" => Vsoxv {
                 eew: BaseSew::E8,
                 data: vsx(op)?,
             },
-            "vsuxeix16.v" => Vsuxv {
+            "vsoxei16.v" => Vsoxv {
                 eew: BaseSew::E16,
                 data: vsx(op)?,
             },
-            "vsuxeix32.v" => Vsuxv {
+            "vsoxei32.v" => Vsoxv {
                 eew: BaseSew::E32,
                 data: vsx(op)?,
             },
-            "vsuxeix64.v" => Vsuxv {
+            "vsoxei64.v" => Vsoxv {
                 eew: BaseSew::E64,
                 data: vsx(op)?,
             },
@@ -628,6 +784,167 @@ impl Decoder {
                 data: vl(op)?,
             },
 
+            "vlseg1e8ff.v" => Vlsegffv {
+                nf: 1,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg1e16ff.v" => Vlsegffv {
+                nf: 1,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg1e32ff.v" => Vlsegffv {
+                nf: 1,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg1e64ff.v" => Vlsegffv {
+                nf: 1,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg2e8ff.v" => Vlsegffv {
+                nf: 2,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg2e16ff.v" => Vlsegffv {
+                nf: 2,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg2e32ff.v" => Vlsegffv {
+                nf: 2,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg2e64ff.v" => Vlsegffv {
+                nf: 2,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg3e8ff.v" => Vlsegffv {
+                nf: 3,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg3e16ff.v" => Vlsegffv {
+                nf: 3,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg3e32ff.v" => Vlsegffv {
+                nf: 3,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg3e64ff.v" => Vlsegffv {
+                nf: 3,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg4e8ff.v" => Vlsegffv {
+                nf: 4,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg4e16ff.v" => Vlsegffv {
+                nf: 4,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg4e32ff.v" => Vlsegffv {
+                nf: 4,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg4e64ff.v" => Vlsegffv {
+                nf: 4,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg5e8ff.v" => Vlsegffv {
+                nf: 5,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg5e16ff.v" => Vlsegffv {
+                nf: 5,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg5e32ff.v" => Vlsegffv {
+                nf: 5,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg5e64ff.v" => Vlsegffv {
+                nf: 5,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg6e8ff.v" => Vlsegffv {
+                nf: 6,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg6e16ff.v" => Vlsegffv {
+                nf: 6,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg6e32ff.v" => Vlsegffv {
+                nf: 6,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg6e64ff.v" => Vlsegffv {
+                nf: 6,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg7e8ff.v" => Vlsegffv {
+                nf: 7,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg7e16ff.v" => Vlsegffv {
+                nf: 7,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg7e32ff.v" => Vlsegffv {
+                nf: 7,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg7e64ff.v" => Vlsegffv {
+                nf: 7,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+            "vlseg8e8ff.v" => Vlsegffv {
+                nf: 8,
+                eew: BaseSew::E8,
+                data: vl(op)?,
+            },
+            "vlseg8e16ff.v" => Vlsegffv {
+                nf: 8,
+                eew: BaseSew::E16,
+                data: vl(op)?,
+            },
+            "vlseg8e32ff.v" => Vlsegffv {
+                nf: 8,
+                eew: BaseSew::E32,
+                data: vl(op)?,
+            },
+            "vlseg8e64ff.v" => Vlsegffv {
+                nf: 8,
+                eew: BaseSew::E64,
+                data: vl(op)?,
+            },
+
             "vsseg1e8.v" => Vssegv {
                 nf: 1,
                 eew: BaseSew::E8,
@@ -1891,7 +2208,7 @@ impl Decoder {
             "vrgather.vx" => Vrgathervx(opivx(op)?),
             "vrgather.vi" => Vrgathervi(opivi(op)?),
 
-            "vrgatherei16.v" => Vrgatherei16vv(opivv(op)?),
+            "vrgatherei16.vv" => Vrgatherei16vv(opivv(op)?),
 
             "vslideup.vx" => Vslideupvx(opivx(op)?),
             "vslideup.vi" => Vslideupvi(opivi(op)?),
@@ -1910,15 +2227,17 @@ impl Decoder {
             "vmadc.vx" => Vmadcvx(opivx_maskless(op)?),
             "vmadc.vi" => Vmadcvi(opivi_maskless(op)?),
 
-            "vsbc.vvm" => Vsbcvvm(opivv(op)?),
-            "vsbc.vxm" => Vsbcvxm(opivx(op)?),
+            "vsbc.vvm" => Vsbcvvm(opivv_v0(op)?),
+            "vsbc.vxm" => Vsbcvxm(opivx_v0(op)?),
 
-            "vmsbc.vv" => Vmsbcvv(opivv(op)?),
-            "vmsbc.vx" => Vmsbcvx(opivx(op)?),
+            "vmsbc.vvm" => Vmsbcvvm(opivv_v0(op)?),
+            "vmsbc.vxm" => Vmsbcvxm(opivx_v0(op)?),
+            "vmsbc.vv" => Vmsbcvv(opivv_maskless(op)?),
+            "vmsbc.vx" => Vmsbcvx(opivx_maskless(op)?),
 
-            "vmerge.vvm" => Vmergevvm(opivv(op)?),
-            "vmerge.vxm" => Vmergevxm(opivx(op)?),
-            "vmerge.vim" => Vmergevim(opivi(op)?),
+            "vmerge.vvm" => Vmergevvm(opivv_v0(op)?),
+            "vmerge.vxm" => Vmergevxm(opivx_v0(op)?),
+            "vmerge.vim" => Vmergevim(opivi_v0(op)?),
 
             "vmv.v.v" => Vmvvv(opivv_vmv(op)?),
             "vmv.v.x" => Vmvvx(opivx_vmv(op)?),
@@ -1973,10 +2292,10 @@ impl Decoder {
             "vsmul.vv" => Vsmulvv(opivv(op)?),
             "vsmul.vx" => Vsmulvx(opivx(op)?),
 
-            "vmv1r.v" => Vmv1rv(opivi(op)?),
-            "vmv2r.v" => Vmv2rv(opivi(op)?),
-            "vmv4r.v" => Vmv4rv(opivi(op)?),
-            "vmv8r.v" => Vmv8rv(opivi(op)?),
+            "vmv1r.v" => Vmv1rv(vmvnr(op, 1)?),
+            "vmv2r.v" => Vmv2rv(vmvnr(op, 2)?),
+            "vmv4r.v" => Vmv4rv(vmvnr(op, 4)?),
+            "vmv8r.v" => Vmv8rv(vmvnr(op, 8)?),
 
             "vsrl.vv" => Vsrlvv(opivv(op)?),
             "vsrl.vx" => Vsrlvx(opivx(op)?),
@@ -2215,8 +2534,8 @@ impl Decoder {
             "vfrec7.v" => Vfrec7v(vfunary1(op)?),
             "vfclass.v" => Vfclassv(vfunary1(op)?),
 
-            "vfmerge.vfm" => Vfmergevfm(opfvf(op)?),
-            "vfmv.v.f" => Vfmvvf(opfvf(op)?),
+            "vfmerge.vfm" => Vfmergevfm(opfvf_v0(op)?),
+            "vfmv.v.f" => Vfmvvf(opfvf_vmv(op)?),
 
             "vmfeq.vv" => Vmfeqvv(opfvv(op)?),
             "vmfeq.vf" => Vmfeqvf(opfvf(op)?),
@@ -2303,21 +2622,16 @@ impl Decoder {
             "la" => {
                 let (rd, mem_addr) =
                     integer::pseudo::parse_op_memory_label_format(op, memory_labels)?;
+                let (hi, lo) = Self::split_immediate(mem_addr.wrapping_sub(current_address as i32));
 
-                // Needs to have nop to preserve correct PC behavior
                 fuse![
-                    Addi(I {
-                        rd: 0,
-                        rs1: 0,
-                        imm12: 0,
-                    }),
+                    Auipc(U { rd, imm20: hi }),
                     Addi(I {
                         rd,
-                        rs1: 0,
-                        imm12: mem_addr,
+                        rs1: rd,
+                        imm12: lo,
                     })
                 ]
-                
             }
             "nop" => Addi(I {
                 rd: 0,
@@ -2327,27 +2641,23 @@ impl Decoder {
             "li" => {
                 let (reg, imm) = integer::pseudo::parse_op_imm_format(op)?;
 
-                match imm.cmp(&4096) {
-                    Ordering::Less => Addi(I {
+                if Self::fits_imm12(imm) {
+                    Addi(I {
                         rd: reg,
                         rs1: 0,
                         imm12: imm,
-                    }),
-                    Ordering::Equal => Lui(U {
-                        rd: reg,
-                        imm20: imm,
-                    }),
-                    Ordering::Greater => fuse![
-                        Lui(U {
-                            rd: reg,
-                            imm20: imm >> 12,
-                        }),
-                        Addi(I {
+                    })
+                } else {
+                    let (hi, lo) = Self::split_immediate(imm);
+
+                    fuse![
+                        Lui(U { rd: reg, imm20: hi }),
+                        Addiw(I {
                             rd: reg,
                             rs1: reg,
-                            imm12: imm & 0xfff,
+                            imm12: lo,
                         }),
-                    ],
+                    ]
                 }
             }
             "mv" => {
@@ -2366,6 +2676,13 @@ impl Decoder {
                 let (rd, rs2) = integer::pseudo::parse_op_op_format(op)?;
                 Subw(R { rd, rs1: 0, rs2 })
             }
+            "sext.b" if bitmanip => Sextb(r_unary(op)?),
+            "sext.h" if bitmanip => Sexth(r_unary(op)?),
+            "zext.h" if bitmanip => Zexth(r_unary(op)?),
+            "zext.w" if bitmanip => {
+                let (rd, rs1) = integer::pseudo::parse_op_op_format(op)?;
+                Adduw(R { rd, rs1, rs2: 0 })
+            }
             "sext.b" => {
                 let (rd, rs1) = integer::pseudo::parse_op_op_format(op)?;
 
@@ -2377,7 +2694,7 @@ impl Decoder {
                     }),
                     Srai(I {
                         rd,
-                        rs1,
+                        rs1: rd,
                         imm12: 64 - 8,
                     }),
                 ]
@@ -2392,7 +2709,7 @@ impl Decoder {
                     }),
                     Srai(I {
                         rd,
-                        rs1,
+                        rs1: rd,
                         imm12: 64 - 16,
                     }),
                 ]
@@ -2485,6 +2802,18 @@ impl Decoder {
                 let (rd, rs1) = float::pseudo::parse_op_op_format(op)?;
                 Fsgnjnd(R { rd, rs1, rs2: rs1 })
             }
+            "fmv.h" => {
+                let (rd, rs1) = float::pseudo::parse_op_op_format(op)?;
+                Fsgnjh(R { rd, rs1, rs2: rs1 })
+            }
+            "fabs.h" => {
+                let (rd, rs1) = float::pseudo::parse_op_op_format(op)?;
+                Fsgnjxh(R { rd, rs1, rs2: rs1 })
+            }
+            "fneg.h" => {
+                let (rd, rs1) = float::pseudo::parse_op_op_format(op)?;
+                Fsgnjnh(R { rd, rs1, rs2: rs1 })
+            }
             "beqz" => {
                 let (rs1, diff) = integer::pseudo::parse_op_label_format(
                     op,
@@ -2627,15 +2956,14 @@ impl Decoder {
                 let diff =
                     integer::pseudo::parse_label_format(op, instruction_labels, current_address)?;
 
+                let (hi, lo) = Self::split_immediate(diff);
+
                 fuse![
-                    Auipc(U {
-                        rd: 1,
-                        imm20: diff >> 12,
-                    }),
+                    Auipc(U { rd: 1, imm20: hi }),
                     Jalr(I {
                         rd: 1,
                         rs1: 1,
-                        imm12: diff & 0xfff,
+                        imm12: lo,
                     }),
                 ]
             }
@@ -2643,15 +2971,14 @@ impl Decoder {
                 let diff =
                     integer::pseudo::parse_label_format(op, instruction_labels, current_address)?;
 
+                let (hi, lo) = Self::split_immediate(diff);
+
                 fuse![
-                    Auipc(U {
-                        rd: 6,
-                        imm20: diff >> 12,
-                    }),
+                    Auipc(U { rd: 6, imm20: hi }),
                     Jalr(I {
                         rd: 0,
                         rs1: 6,
-                        imm12: diff & 0xfff,
+                        imm12: lo,
                     }),
                 ]
             }
@@ -2921,7 +3248,7 @@ impl Decoder {
                         vm: false,
                     }),
                     Vmnandmm(Opmvv {
-                        dest: dest,
+                        dest,
                         vs1: dest,
                         vs2: dest,
                         vm: false,
@@ -2936,7 +3263,7 @@ impl Decoder {
                             vm: true,
                         }),
                         Vmxormm(Opmvv {
-                            dest: dest,
+                            dest,
                             vs1: 0,
                             vs2: dest,
                             vm: false,
@@ -2953,7 +3280,7 @@ impl Decoder {
                                         vm: false,
                                     }),
                                     Vmandnmm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: vt,
                                         vs2: dest,
                                         vm: false,
@@ -2974,13 +3301,13 @@ impl Decoder {
                                         vm: false,
                                     }),
                                     Vmandnmm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: 0,
                                         vs2: dest,
                                         vm: false,
                                     }),
                                     Vmormm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: dest,
                                         vs2: vt,
                                         vm: false,
@@ -3003,7 +3330,7 @@ impl Decoder {
                         vm: false,
                     }),
                     Vmnandmm(Opmvv {
-                        dest: dest,
+                        dest,
                         vs1: dest,
                         vs2: dest,
                         vm: false,
@@ -3018,7 +3345,7 @@ impl Decoder {
                             vm: true,
                         }),
                         Vmxormm(Opmvv {
-                            dest: dest,
+                            dest,
                             vs1: 0,
                             vs2: dest,
                             vm: false,
@@ -3035,7 +3362,7 @@ impl Decoder {
                                         vm: false,
                                     }),
                                     Vmandnmm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: vt,
                                         vs2: dest,
                                         vm: false,
@@ -3056,13 +3383,13 @@ impl Decoder {
                                         vm: false,
                                     }),
                                     Vmandnmm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: 0,
                                         vs2: dest,
                                         vm: false,
                                     }),
                                     Vmormm(Opmvv {
-                                        dest: dest,
+                                        dest,
                                         vs1: dest,
                                         vs2: vt,
                                         vm: false,
@@ -3079,7 +3406,7 @@ impl Decoder {
             "vfneg.v" => {
                 let (dest, vs2, vm) = vector::pseudo::parse_op_op_mask_format(op)?;
                 Vfsgnjnvv(Opfvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2,
                     vm,
@@ -3088,7 +3415,7 @@ impl Decoder {
             "vfabs.v" => {
                 let (dest, vs2, vm) = vector::pseudo::parse_op_op_mask_format(op)?;
                 Vfsgnjxvv(Opfvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2,
                     vm,
@@ -3097,7 +3424,7 @@ impl Decoder {
             "vmfgt.vv" => {
                 let (dest, vs2, vs1, vm) = vector::pseudo::parse_op_op_op_mask_format(op)?;
                 Vmfltvv(Opfvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2: vs1,
                     vm,
@@ -3106,7 +3433,7 @@ impl Decoder {
             "vmfge.vv" => {
                 let (dest, vs2, vs1, vm) = vector::pseudo::parse_op_op_op_mask_format(op)?;
                 Vmflevv(Opfvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2: vs1,
                     vm,
@@ -3115,7 +3442,7 @@ impl Decoder {
             "vmmv.m" => {
                 let (dest, vs2) = vector::pseudo::parse_op_op_format(op)?;
                 Vmandmm(Opmvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2,
                     vm: false,
@@ -3124,7 +3451,7 @@ impl Decoder {
             "vmclr.m" => {
                 let dest = vector::pseudo::parse_op_format(op)?;
                 Vmxormm(Opmvv {
-                    dest: dest,
+                    dest,
                     vs1: dest,
                     vs2: dest,
                     vm: false,
@@ -3133,7 +3460,7 @@ impl Decoder {
             "vmset.m" => {
                 let dest = vector::pseudo::parse_op_format(op)?;
                 Vmxnormm(Opmvv {
-                    dest: dest,
+                    dest,
                     vs1: dest,
                     vs2: dest,
                     vm: false,
@@ -3142,7 +3469,7 @@ impl Decoder {
             "vmnot.m" => {
                 let (dest, vs2) = vector::pseudo::parse_op_op_format(op)?;
                 Vmnandmm(Opmvv {
-                    dest: dest,
+                    dest,
                     vs1: vs2,
                     vs2,
                     vm: false,
@@ -3171,6 +3498,20 @@ impl Decoder {
         }
     }
 
+    /// Strips the `.aq`, `.rl` or `.aqrl` suffix of atomic instructions
+    fn split_ordering(mnemonic: &str) -> (&str, bool, bool) {
+        let atomic = ["lr.", "sc.", "amo"]
+            .iter()
+            .any(|prefix| mnemonic.starts_with(prefix));
+
+        match mnemonic.rsplit_once('.') {
+            Some((base, "aq")) if atomic => (base, true, false),
+            Some((base, "rl")) if atomic => (base, false, true),
+            Some((base, "aqrl")) if atomic => (base, true, true),
+            _ => (mnemonic, false, false),
+        }
+    }
+
     fn rename(old: &str) -> &str {
         match old {
             "vle1.v" => "vlm.v",
@@ -3183,6 +3524,7 @@ impl Decoder {
             "vfrsqrte7.v" => "vfrsqrt7.v",
             "vfrece7.v" => "vfrec7.v",
             "vmcpy.m" => "vmmv.m",
+            "vrgatherei16.v" => "vrgatherei16.vv",
 
             // Technically pseudoinstructions, but without custom parsers
             "vl1r.v" => "vl1re8.v",
@@ -3194,6 +3536,105 @@ impl Decoder {
         }
     }
 
+    /// Bytes taken by the line once assembled, pseudoinstructions may expand to several words
+    /// Parses compressed instruction in its expanded form, so it shares operand parsing with the 32-bit one
+    fn decode_compressed(
+        mnemonic: &str,
+        op: &str,
+        instruction_labels: &HashMap<String, usize>,
+        memory_labels: &HashMap<String, usize>,
+        current_address: usize,
+    ) -> Result<Instruction, String> {
+        let expanded = Self::expand_compressed(mnemonic, op)?;
+
+        let instruction = match Self::decode_text_section(
+            &expanded,
+            instruction_labels,
+            memory_labels,
+            current_address,
+            false,
+        )? {
+            // Upper immediate is written unsigned, but c.lui sign-extends its 6 bits
+            Lui(U { rd, imm20 }) => Lui(U {
+                rd,
+                imm20: imm20 << 12 >> 12,
+            }),
+            instruction => instruction,
+        };
+
+        let half = encode_compressed_as(&instruction, mnemonic)
+            .map_err(|error| format!("{} {}: {}", mnemonic, op, error))?;
+
+        Ok(Compressed(Box::new(instruction), half))
+    }
+
+    fn expand_compressed(mnemonic: &str, op: &str) -> Result<String, String> {
+        let operands: Vec<&str> = op.split(',').map(str::trim).collect();
+        let base = &mnemonic[2..];
+
+        Ok(match (mnemonic, operands.as_slice()) {
+            ("c.nop", [""]) => "addi zero, zero, 0".to_owned(),
+            ("c.ebreak", [""]) => "ebreak".to_owned(),
+            ("c.li", [rd, imm]) => format!("addi {}, zero, {}", rd, imm),
+            ("c.mv", [rd, rs2]) => format!("add {}, zero, {}", rd, rs2),
+            ("c.addi16sp", [rd, imm]) => format!("addi {}, {}, {}", rd, rd, imm),
+            (
+                "c.addi" | "c.addiw" | "c.slli" | "c.srli" | "c.srai" | "c.andi" | "c.add"
+                | "c.sub" | "c.xor" | "c.or" | "c.and" | "c.subw" | "c.addw",
+                [rd, operand],
+            ) => format!("{} {}, {}, {}", base, rd, rd, operand),
+            ("c.addi4spn", [_, _, _]) => format!("addi {}", op),
+            ("c.lui" | "c.lw" | "c.ld" | "c.fld" | "c.sw" | "c.sd" | "c.fsd", _) => {
+                format!("{} {}", base, op)
+            }
+            ("c.lwsp" | "c.ldsp" | "c.fldsp" | "c.swsp" | "c.sdsp" | "c.fsdsp", _) => {
+                format!("{} {}", base.trim_end_matches("sp"), op)
+            }
+            ("c.j", _) => format!("j {}", op),
+            ("c.jr", _) => format!("jr {}", op),
+            ("c.jalr", _) => format!("jalr {}", op),
+            ("c.beqz" | "c.bnez", _) => format!("{} {}", base, op),
+            _ => {
+                return Err(format!(
+                    "Invalid compressed instruction: {} {}",
+                    mnemonic, op
+                ))
+            }
+        })
+    }
+
+    pub fn instruction_size(instruction_line: &str, bitmanip: bool) -> usize {
+        let (mnemonic, op) = Self::split_instruction(instruction_line);
+
+        if mnemonic.starts_with("c.") {
+            return 2;
+        }
+
+        let words = match mnemonic {
+            "la" | "call" | "tail" => 2,
+            "sext.b" | "sext.h" | "zext.h" | "zext.w" if !bitmanip => 2,
+            "li" => match integer::pseudo::parse_op_imm_format(op) {
+                Ok((_, imm)) if !Self::fits_imm12(imm) => 2,
+                _ => 1,
+            },
+            _ => 1,
+        };
+
+        words * 4
+    }
+
+    fn fits_imm12(imm: i32) -> bool {
+        (-2048..2048).contains(&imm)
+    }
+
+    /// Splits value into upper immediate for lui/auipc and sign-extended lower 12 bits
+    fn split_immediate(value: i32) -> (i32, i32) {
+        let lo = (value << 20) >> 20;
+        let hi = value.wrapping_sub(lo) >> 12;
+
+        (hi, lo)
+    }
+
     fn split_instruction(instruction_line: &str) -> (&str, &str) {
         let mut lane = instruction_line.splitn(2, char::is_whitespace);
         let mnemonic = lane.next().unwrap_or_default().trim();
@@ -3219,6 +3660,7 @@ mod tests {
             "vfrsqrte7.v",
             "vfrece7.v",
             "vmcpy.m",
+            "vrgatherei16.v",
         ];
 
         old_mnemonics
@@ -3233,23 +3675,193 @@ mod tests {
 
         memory_labels.insert("to_copy".to_owned(), 12);
 
-        let instruction =
-            Decoder::decode_text_section("la x1, to_copy", &HashMap::new(), &memory_labels, 0);
+        let instruction = Decoder::decode_text_section(
+            "la x1, to_copy",
+            &HashMap::new(),
+            &memory_labels,
+            0x1000,
+            false,
+        );
 
         assert_eq!(
             instruction,
             Ok(fuse![
-                Addi(I {
-                    rd: 0,
-                    rs1: 0,
-                    imm12: 0
-                }),
+                Auipc(U { rd: 1, imm20: -1 }),
                 Addi(I {
                     rd: 1,
-                    rs1: 0,
+                    rs1: 1,
                     imm12: 12
                 })
             ])
         );
     }
+
+    #[test]
+    fn li_works() {
+        let decode =
+            |line| Decoder::decode_text_section(line, &HashMap::new(), &HashMap::new(), 0, false);
+
+        assert_eq!(
+            decode("li a0, -2048"),
+            Ok(Addi(I {
+                rd: 10,
+                rs1: 0,
+                imm12: -2048
+            }))
+        );
+        assert_eq!(
+            decode("li a0, 0x12345fff"),
+            Ok(fuse![
+                Lui(U {
+                    rd: 10,
+                    imm20: 0x12346
+                }),
+                Addiw(I {
+                    rd: 10,
+                    rs1: 10,
+                    imm12: -1
+                })
+            ])
+        );
+        assert_eq!(Decoder::instruction_size("li a0, 2047", false), 4);
+        assert_eq!(Decoder::instruction_size("li a0, 2048", false), 8);
+        assert_eq!(Decoder::instruction_size("zext.w a0, a1", false), 8);
+        assert_eq!(Decoder::instruction_size("zext.w a0, a1", true), 4);
+    }
+
+    #[test]
+    fn compressed_forms_are_kept() {
+        let assemble = |line| {
+            let instruction =
+                Decoder::decode_text_section(line, &HashMap::new(), &HashMap::new(), 0, false);
+            instruction.map(|instruction| encode(&instruction))
+        };
+
+        assert_eq!(assemble("c.addi sp, 16"), Ok(Ok(0x0141)));
+        assert_eq!(assemble("c.addi16sp sp, 16"), Ok(Ok(0x6141)));
+        // HINTs are assembled only when spelled out as compressed
+        assert_eq!(assemble("c.li zero, 10"), Ok(Ok(0x4029)));
+        assert_eq!(assemble("c.slli a0, 0"), Ok(Ok(0x0502)));
+    }
+
+    #[test]
+    fn disassembly_round_trips() {
+        let lines = [
+            "addi a0, a0, -1",
+            "lui t0, 0x12345",
+            "sd s0, -8(sp)",
+            "jalr ra, 0(a1)",
+            "csrrs a0, vlenb, zero",
+            "csrrwi zero, vxrm, 2",
+            "csrrc a0, 0x7c0, a1",
+            "beq a0, a1, 0x10",
+            "bltu t0, t1, 0xfffffffffffffff8",
+            "jal ra, 0x800",
+            "call 0x12345",
+            "tail 0xfffffffffffff000",
+            "fence rw, w",
+            "fence",
+            "fence.tso",
+            "fence.i",
+            "ecall",
+            "lr.d a0, (a1)",
+            "sc.w.aqrl a0, a2, (a1)",
+            "amomaxu.d.aq t0, t1, (sp)",
+            "fmadd.d fa0, fa1, fa2, fa3",
+            "fmsub.s fa0, fa1, fa2, fa3, rup",
+            "fdiv.d fa0, fa1, fa2, rdn",
+            "fcvt.w.s a0, ft0",
+            "fcvt.l.d a0, fa0, rtz",
+            "fmv.d.x fs1, t6",
+            "flh ft0, 6(a0)",
+            "fnmadd.h fa0, fa1, fa2, fa3",
+            "fcvt.h.s fa0, fa1",
+            "fcvt.wu.h a0, fa1",
+            "fcvt.d.s fa0, fa1",
+            "fcvt.d.s fa0, fa1, rtz",
+            "fcvt.d.w fa0, a0, dyn",
+            "fcvt.d.h fa0, fa1, rdn",
+            "fclass.h a0, fa1",
+            "fmv.h.x fa0, a1",
+            "feq.s a0, fa0, fa1",
+            "vsetvli t0, a0, e64, mf2, tu, ma",
+            "vsetivli zero, 16, e8, m8, ta, mu",
+            "vle32.v v8, (a0), v0.t",
+            "vlse64.v v8, (a0), t1",
+            "vsoxei16.v v4, (a1), v12, v0.t",
+            "vle8ff.v v2, (a3)",
+            "vlseg2e32ff.v v4, (a0)",
+            "vlsseg4e16.v v4, (a0), a2",
+            "vluxseg2ei32.v v4, (a0), v8, v0.t",
+            "vl2re32.v v2, (a0)",
+            "vs4r.v v4, (a0)",
+            "vadd.vi v1, v2, -3, v0.t",
+            "vsll.vi v1, v2, 31",
+            "vadc.vvm v1, v2, v3, v0",
+            "vmadc.vx v1, v2, a0",
+            "vmerge.vim v1, v2, 5, v0",
+            "vmv.v.x v1, a0",
+            "vmv4r.v v4, v8",
+            "vmacc.vx v1, a0, v2, v0.t",
+            "vredsum.vs v1, v2, v3",
+            "vmv.x.s a0, v1",
+            "vmv.s.x v1, a0",
+            "vcpop.m a0, v1, v0.t",
+            "vzext.vf4 v1, v2",
+            "vmsbf.m v1, v2",
+            "vid.v v1, v0.t",
+            "vfmerge.vfm v1, v2, fa0, v0",
+            "vfmv.v.f v1, fa0",
+            "vfnmsac.vf v1, fa0, v2",
+            "vfmv.f.s fa0, v1",
+            "vfmv.s.f v1, fa0",
+            "vfsqrt.v v1, v2, v0.t",
+            "vfwcvt.f.x.v v2, v1",
+            "c.addi4spn s0, sp, 1020",
+            "c.lw a0, 4(a1)",
+            "c.fsdsp fa0, 8(sp)",
+            "c.ldsp ra, 504(sp)",
+            "c.addi16sp sp, -64",
+            "c.addi sp, 16",
+            "c.j 0xfffffffffffffffe",
+            "c.beqz a0, 0x20",
+            "c.add zero, a6",
+            "c.lui zero, 0xffff5",
+            "vsetvli t2, s10, 0x252",
+            "vsetivli t2, 18, 0x329",
+            "c.lui a0, 0xfffe0",
+            "c.srai a0, 63",
+            "c.and s1, a5",
+            "c.mv a0, a1",
+            "c.add a0, a1",
+            "c.jr ra",
+            "c.jalr a0",
+            "c.nop",
+            "c.ebreak",
+            "add.uw a0, a1, zero",
+            "sh3add.uw a0, a1, a2",
+            "slli.uw a0, a1, 63",
+            "xnor a0, a1, a2",
+            "clz a0, a1",
+            "cpopw a0, a1",
+            "sext.b a0, a1",
+            "zext.h a0, a1",
+            "minu a0, a1, a2",
+            "rori a0, a1, 63",
+            "roriw a0, a1, 31",
+            "orc.b a0, a1",
+            "rev8 a0, a1",
+            "bexti a0, a1, 63",
+            "binv a0, a1, a2",
+        ];
+
+        for line in lines {
+            let instruction =
+                Decoder::decode_text_section(line, &HashMap::new(), &HashMap::new(), 0, true)
+                    .map_err(|e| format!("{line}: {e}"))
+                    .unwrap();
+
+            assert_eq!(instruction.to_string(), line);
+        }
+    }
 }
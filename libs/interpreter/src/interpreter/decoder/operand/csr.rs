@@ -52,7 +52,29 @@ fn parse_operand(op: &str) -> Result<usize, String> {
         "fcsr" => alias::FCSR,
         "fflags" => alias::FFLAGS,
         "frm" => alias::FRM,
+        "sstatus" => alias::SSTATUS,
+        "sie" => alias::SIE,
+        "stvec" => alias::STVEC,
+        "sscratch" => alias::SSCRATCH,
+        "sepc" => alias::SEPC,
+        "scause" => alias::SCAUSE,
+        "stval" => alias::STVAL,
+        "sip" => alias::SIP,
+        "satp" => alias::SATP,
         "mstatus" => alias::MSTATUS,
+        "misa" => alias::MISA,
+        "medeleg" => alias::MEDELEG,
+        "mideleg" => alias::MIDELEG,
+        "mie" => alias::MIE,
+        "mtvec" => alias::MTVEC,
+        "mscratch" => alias::MSCRATCH,
+        "mepc" => alias::MEPC,
+        "mcause" => alias::MCAUSE,
+        "mtval" => alias::MTVAL,
+        "mip" => alias::MIP,
+        "mvendorid" => alias::MVENDORID,
+        "mimpid" => alias::MIMPID,
+        "mhartid" => alias::MHARTID,
         "vsstatus" => alias::VSSTATUS,
         "vtype" => alias::VTYPE,
         "vl" => alias::VL,
@@ -61,7 +83,10 @@ fn parse_operand(op: &str) -> Result<usize, String> {
         "vxrm" => alias::VXRM,
         "vxsat" => alias::VXSAT,
         "vcsr" => alias::VCSR,
-        _ => return Err(format!("Incorrect or unsupported CSR operand: {}", op)),
+        _ => match integer::parse_immediate(op) {
+            Ok(csr @ 0..=0xfff) => csr as usize,
+            _ => return Err(format!("Incorrect or unsupported CSR operand: {}", op)),
+        },
     };
 
     Ok(operand)
@@ -5,9 +5,9 @@ use eeric_core::prelude::*;
 pub fn parse_r4_format(r4: &str) -> Result<format::R4, String> {
     let tokens: Vec<&str> = r4.split(',').map(str::trim).collect();
 
-    if tokens.len() != 4 {
+    if !(4..=5).contains(&tokens.len()) {
         return Err(format!(
-            "Expected format: 'rd, rs1, rs2, rs3', got {} instead",
+            "Expected format: 'rd, rs1, rs2, rs3[, rm]', got {} instead",
             r4
         ));
     }
@@ -16,8 +16,15 @@ pub fn parse_r4_format(r4: &str) -> Result<format::R4, String> {
     let rs1 = parse_operand(tokens[1])?;
     let rs2 = parse_operand(tokens[2])?;
     let rs3 = parse_operand(tokens[3])?;
+    let rm = parse_rounding_mode(tokens.get(4))?;
 
-    Ok(format::R4 { rd, rs1, rs2, rs3 })
+    Ok(format::R4 {
+        rd,
+        rs1,
+        rs2,
+        rs3,
+        rm,
+    })
 }
 
 pub fn parse_load_format(
@@ -83,19 +90,6 @@ pub fn parse_r_format(r: &str) -> Result<format::R, String> {
     Ok(format::R { rd, rs1, rs2 })
 }
 
-pub fn parse_r_single_reg_format(r: &str) -> Result<format::R, String> {
-    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
-
-    if tokens.len() != 2 {
-        return Err(format!("Expected format: 'fd, fs1', got {} instead", r));
-    }
-
-    let rd = parse_operand(tokens[0])?;
-    let rs1 = parse_operand(tokens[1])?;
-
-    Ok(format::R { rd, rs1, rs2: 0 })
-}
-
 pub fn parse_r_to_x_format(r: &str) -> Result<format::R, String> {
     let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
 
@@ -139,6 +133,158 @@ pub fn parse_r_single_reg_to_f_format(r: &str) -> Result<format::R, String> {
     Ok(format::R { rd, rs1, rs2: 0 })
 }
 
+pub fn parse_r_rounding_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(3..=4).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'fd, fs1, fs2[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = parse_operand(tokens[1])?;
+    let rs2 = parse_operand(tokens[2])?;
+    let rm = parse_rounding_mode(tokens.get(3))?;
+
+    Ok(format::Rfp { rd, rs1, rs2, rm })
+}
+
+pub fn parse_r_single_reg_rounding_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(2..=3).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'fd, fs1[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = parse_operand(tokens[1])?;
+    let rm = parse_rounding_mode(tokens.get(2))?;
+
+    Ok(format::Rfp {
+        rd,
+        rs1,
+        rs2: 0,
+        rm,
+    })
+}
+
+pub fn parse_r_single_reg_to_x_rounding_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(2..=3).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'rd, fs1[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = super::integer::parse_operand(tokens[0])?;
+    let rs1 = parse_operand(tokens[1])?;
+    let rm = parse_rounding_mode(tokens.get(2))?;
+
+    Ok(format::Rfp {
+        rd,
+        rs1,
+        rs2: 0,
+        rm,
+    })
+}
+
+pub fn parse_r_single_reg_to_f_rounding_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(2..=3).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'fd, rs1[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = super::integer::parse_operand(tokens[1])?;
+    let rm = parse_rounding_mode(tokens.get(2))?;
+
+    Ok(format::Rfp {
+        rd,
+        rs1,
+        rs2: 0,
+        rm,
+    })
+}
+
+/// Conversions that are always exact, the rounding mode defaults to rne
+pub fn parse_r_single_reg_exact_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(2..=3).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'fd, fs1[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = parse_operand(tokens[1])?;
+    let rm = parse_exact_rounding_mode(tokens.get(2))?;
+
+    Ok(format::Rfp {
+        rd,
+        rs1,
+        rs2: 0,
+        rm,
+    })
+}
+
+pub fn parse_r_single_reg_to_f_exact_format(r: &str) -> Result<format::Rfp, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if !(2..=3).contains(&tokens.len()) {
+        return Err(format!(
+            "Expected format: 'fd, rs1[, rm]', got {} instead",
+            r
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = super::integer::parse_operand(tokens[1])?;
+    let rm = parse_exact_rounding_mode(tokens.get(2))?;
+
+    Ok(format::Rfp {
+        rd,
+        rs1,
+        rs2: 0,
+        rm,
+    })
+}
+
+/// Rounding mode of exact conversions is unused, assemblers encode rne when it's left out
+fn parse_exact_rounding_mode(rm: Option<&&str>) -> Result<u32, String> {
+    match rm {
+        None => Ok(RoundingMode::Rne as u32),
+        Some(_) => parse_rounding_mode(rm),
+    }
+}
+
+/// Optional trailing rounding mode operand, instructions without it use frm
+fn parse_rounding_mode(rm: Option<&&str>) -> Result<u32, String> {
+    let rm = match rm {
+        None | Some(&"dyn") => return Ok(RoundingMode::DYN),
+        Some(&"rne") => RoundingMode::Rne,
+        Some(&"rtz") => RoundingMode::Rtz,
+        Some(&"rdn") => RoundingMode::Rdn,
+        Some(&"rup") => RoundingMode::Rup,
+        Some(&"rmm") => RoundingMode::Rmm,
+        Some(other) => return Err(format!("Incorrect rounding mode: {}", other)),
+    };
+
+    Ok(rm as u32)
+}
+
 pub fn parse_operand(op_str: &str) -> Result<usize, String> {
     let op = match op_str {
         "f0" | "ft0" => 0,
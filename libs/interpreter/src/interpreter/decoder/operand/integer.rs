@@ -19,6 +19,19 @@ pub fn parse_r_format(r: &str) -> Result<format::R, String> {
     Ok(format::R { rd, rs1, rs2 })
 }
 
+pub fn parse_r_unary_format(r: &str) -> Result<format::R, String> {
+    let tokens: Vec<&str> = r.split(',').map(str::trim).collect();
+
+    if tokens.len() != 2 {
+        return Err(format!("Expected format: 'rd, rs1', got {} instead", r));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = parse_operand(tokens[1])?;
+
+    Ok(format::R { rd, rs1, rs2: 0 })
+}
+
 pub fn parse_i_format(i: &str) -> Result<format::I, String> {
     let tokens: Vec<&str> = i.split(',').map(str::trim).collect();
 
@@ -124,6 +137,108 @@ pub fn parse_u_format(u: &str) -> Result<format::U, String> {
     Ok(format::U { rd, imm20: imm })
 }
 
+pub fn parse_lr_format(lr: &str, aq: bool, rl: bool) -> Result<format::Atomic, String> {
+    let tokens: Vec<&str> = lr.split(',').map(str::trim).collect();
+
+    if tokens.len() != 2 {
+        return Err(format!("Expected format: 'rd, (rs1)', got {} instead", lr));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs1 = parse_addr_operand(tokens[1])?;
+
+    Ok(format::Atomic {
+        rd,
+        rs1,
+        rs2: 0,
+        aq,
+        rl,
+    })
+}
+
+pub fn parse_amo_format(amo: &str, aq: bool, rl: bool) -> Result<format::Atomic, String> {
+    let tokens: Vec<&str> = amo.split(',').map(str::trim).collect();
+
+    if tokens.len() != 3 {
+        return Err(format!(
+            "Expected format: 'rd, rs2, (rs1)', got {} instead",
+            amo
+        ));
+    }
+
+    let rd = parse_operand(tokens[0])?;
+    let rs2 = parse_operand(tokens[1])?;
+    let rs1 = parse_addr_operand(tokens[2])?;
+
+    Ok(format::Atomic {
+        rd,
+        rs1,
+        rs2,
+        aq,
+        rl,
+    })
+}
+
+/// Empty operands order all accesses, like a plain `fence` does
+pub fn parse_fence_format(fence: &str) -> Result<format::Fence, String> {
+    if fence.is_empty() {
+        return Ok(format::Fence {
+            fm: 0,
+            pred: 0b1111,
+            succ: 0b1111,
+            rs1: 0,
+            rd: 0,
+        });
+    }
+
+    let tokens: Vec<&str> = fence.split(',').map(str::trim).collect();
+
+    if tokens.len() != 2 {
+        return Err(format!(
+            "Expected format: 'pred, succ', got {} instead",
+            fence
+        ));
+    }
+
+    let pred = parse_fence_set(tokens[0])?;
+    let succ = parse_fence_set(tokens[1])?;
+
+    Ok(format::Fence {
+        fm: 0,
+        pred,
+        succ,
+        rs1: 0,
+        rd: 0,
+    })
+}
+
+fn parse_fence_set(set: &str) -> Result<u32, String> {
+    if set == "0" {
+        return Ok(0);
+    }
+
+    set.chars()
+        .try_fold((0, 0b10000), |(bits, previous), letter| {
+            let bit = match letter {
+                'i' => 0b1000,
+                'o' => 0b0100,
+                'r' => 0b0010,
+                'w' => 0b0001,
+                _ => return None,
+            };
+
+            (bit < previous).then_some((bits | bit, bit))
+        })
+        .filter(|(bits, _)| *bits != 0)
+        .map(|(bits, _)| bits)
+        .ok_or_else(|| {
+            format!(
+                "Fence set has to be made of letters from 'iorw' in order, got {} instead",
+                set
+            )
+        })
+}
+
 pub fn parse_offset_addr_operand(
     op: &str,
     memory_labels: &HashMap<String, usize>,
@@ -238,12 +353,23 @@ pub fn parse_instruction_label(
     map: &HashMap<String, usize>,
     current_line: usize,
 ) -> Result<i32, String> {
-    map.get(label)
-        .cloned()
-        .map(|addr| addr.wrapping_sub(current_line) as i32)
+    if let Some(addr) = map.get(label) {
+        return Ok(addr.wrapping_sub(current_line) as i32);
+    }
+
+    // Numeric targets are absolute addresses, as GNU as reads them
+    parse_address(label)
+        .and_then(|addr| i32::try_from(addr.wrapping_sub(current_line as u64) as i64).ok())
         .ok_or(format!("Did not find instruction label {}", label))
 }
 
+fn parse_address(addr: &str) -> Option<u64> {
+    match addr.strip_prefix("0x").or(addr.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => addr.parse().ok(),
+    }
+}
+
 pub mod pseudo {
     use std::collections::HashMap;
 
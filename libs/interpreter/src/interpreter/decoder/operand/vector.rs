@@ -73,29 +73,28 @@ fn parse_vtype(vtype: &[&str]) -> Result<(BaseSew, Lmul, MaskBehavior, MaskBehav
 
 pub fn parse_vsetvli_format(vsetvli: &str) -> Result<format::Vsetvli, String> {
     let tokens: Vec<&str> = vsetvli.split(',').map(str::trim).collect();
-    if tokens.len() != 6 {
+    if tokens.len() != 6 && tokens.len() != 3 {
         return Err(format!(
-            "Expected format: 'rd, rs1, BaseSew, Lmul, ta/tu, ma/mu', got {} instead",
+            "Expected format: 'rd, rs1, BaseSew, Lmul, ta/tu, ma/mu' or 'rd, rs1, vtypei', got {} instead",
             vsetvli
         ));
     }
 
     let rd = integer::parse_operand(tokens[0])?;
     let rs1 = integer::parse_operand(tokens[1])?;
-    let vtype = parse_vtype(&tokens[2..])?;
 
     Ok(format::Vsetvli {
         rd,
         rs1,
-        vtypei: construct_vtype(vtype)?,
+        vtypei: parse_vtypei(&tokens[2..])?,
     })
 }
 
 pub fn parse_vsetivli_format(vsetivli: &str) -> Result<format::Vsetivli, String> {
     let tokens: Vec<&str> = vsetivli.split(',').map(str::trim).collect();
-    if tokens.len() != 6 {
+    if tokens.len() != 6 && tokens.len() != 3 {
         return Err(format!(
-            "Expected format: 'rd, uimm5, BaseSew, Lmul, ta/tu, ma/mu', got {} instead",
+            "Expected format: 'rd, uimm5, BaseSew, Lmul, ta/tu, ma/mu' or 'rd, uimm5, vtypei', got {} instead",
             vsetivli
         ));
     }
@@ -103,15 +102,21 @@ pub fn parse_vsetivli_format(vsetivli: &str) -> Result<format::Vsetivli, String>
     let rd = integer::parse_operand(tokens[0])?;
     let uimm = integer::parse_immediate(tokens[1])? as u32;
 
-    let vtype = parse_vtype(&tokens[2..])?;
-
     Ok(format::Vsetivli {
         rd,
         uimm,
-        vtypei: construct_vtype(vtype)?,
+        vtypei: parse_vtypei(&tokens[2..])?,
     })
 }
 
+/// Reserved vtype encodings can only be written as a raw immediate
+fn parse_vtypei(vtype: &[&str]) -> Result<u32, String> {
+    match vtype {
+        [vtypei] => Ok(integer::parse_immediate(vtypei)? as u32),
+        _ => construct_vtype(parse_vtype(vtype)?),
+    }
+}
+
 pub fn parse_vsetvl_format(vsetvl: &str) -> Result<format::Vsetvl, String> {
     let tokens: Vec<&str> = vsetvl.split(',').map(str::trim).collect();
     if tokens.len() != 3 {
@@ -379,7 +384,7 @@ pub fn parse_opivv_maskless_format(opivv: &str) -> Result<format::Opivv, String>
         dest,
         vs2,
         vs1,
-        vm: true,
+        vm: false,
     })
 }
 
@@ -445,7 +450,7 @@ pub fn parse_opivx_v0_format(opivx: &str) -> Result<format::Opivx, String> {
         dest,
         vs2,
         rs1,
-        vm: false,
+        vm: true,
     })
 }
 
@@ -537,7 +542,7 @@ pub fn parse_opivi_v0_format(opivi: &str) -> Result<format::Opivi, String> {
         dest,
         vs2,
         imm5: imm,
-        vm: false,
+        vm: true,
     })
 }
 
@@ -582,6 +587,23 @@ pub fn parse_opivi_vmv_format(opivi_vmv: &str) -> Result<format::Opivi, String>
     })
 }
 
+pub fn parse_vmvnr_format(vmvnr: &str, nr: i32) -> Result<format::Opivi, String> {
+    let tokens: Vec<&str> = vmvnr.split(',').map(str::trim).collect();
+    if tokens.len() != 2 {
+        return Err(format!("Expected format: 'vd, vs2', got {} instead", vmvnr));
+    }
+
+    let dest = parse_operand(tokens[0])?.as_register()?;
+    let vs2 = parse_operand(tokens[1])?.as_register()?;
+
+    Ok(format::Opivi {
+        dest,
+        vs2,
+        imm5: nr - 1,
+        vm: false,
+    })
+}
+
 pub fn parse_opmvv_format(opmvv: &str) -> Result<format::Opmvv, String> {
     let tokens: Vec<&str> = opmvv.split(',').map(str::trim).collect();
     if tokens.len() != 3 && tokens.len() != 4 {
@@ -903,6 +925,50 @@ pub fn parse_opfvf_format(opfvf: &str) -> Result<format::Opfvf, String> {
     Ok(format::Opfvf { vd, vs2, rs1, vm })
 }
 
+pub fn parse_opfvf_v0_format(opfvf: &str) -> Result<format::Opfvf, String> {
+    let tokens: Vec<&str> = opfvf.split(',').map(str::trim).collect();
+    if tokens.len() != 4 {
+        return Err(format!(
+            "Expected format: 'vd, vs2, rs1, v0', got {} instead",
+            opfvf
+        ));
+    }
+
+    let vd = parse_operand(tokens[0])?.as_register()?;
+    let vs2 = parse_operand(tokens[1])?.as_register()?;
+    let rs1 = float::parse_operand(tokens[2])?;
+    if parse_operand(tokens[3])?.as_register()? != 0 {
+        return Err("Expected last operand to be v0".to_owned());
+    }
+
+    Ok(format::Opfvf {
+        vd,
+        vs2,
+        rs1,
+        vm: true,
+    })
+}
+
+pub fn parse_opfvf_vmv_format(opfvf_vmv: &str) -> Result<format::Opfvf, String> {
+    let tokens: Vec<&str> = opfvf_vmv.split(',').map(str::trim).collect();
+    if tokens.len() != 2 {
+        return Err(format!(
+            "Expected format: 'vd, rs1', got {} instead",
+            opfvf_vmv
+        ));
+    }
+
+    let vd = parse_operand(tokens[0])?.as_register()?;
+    let rs1 = float::parse_operand(tokens[1])?;
+
+    Ok(format::Opfvf {
+        vd,
+        vs2: 0,
+        rs1,
+        vm: false,
+    })
+}
+
 pub fn parse_opfvv_fma_format(opfvv: &str) -> Result<format::Opfvv, String> {
     let tokens: Vec<&str> = opfvv.split(',').map(str::trim).collect();
     if tokens.len() != 3 && tokens.len() != 4 {
@@ -1 +1 @@
-pub use crate::interpreter::Interpreter;
+pub use crate::interpreter::{CompileOptions, Interpreter};